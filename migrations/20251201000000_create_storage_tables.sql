@@ -0,0 +1,17 @@
+-- The config and the sandbox's staging copy for each scope (the deployment, a tenant or a
+-- campaign). `revision` goes up on every write so concurrent donations notice each other.
+CREATE TABLE IF NOT EXISTS thermometer_configs (
+    scope TEXT NOT NULL,
+    id TEXT NOT NULL,
+    data TEXT NOT NULL,
+    revision BIGINT NOT NULL,
+    PRIMARY KEY (scope, id)
+);
+
+-- Everything else, as JSON documents in collections named like the Firestore ones
+CREATE TABLE IF NOT EXISTS thermometer_documents (
+    collection TEXT NOT NULL,
+    id TEXT NOT NULL,
+    data TEXT NOT NULL,
+    PRIMARY KEY (collection, id)
+);
@@ -0,0 +1,283 @@
+use axum::{
+    extract::{Query, State},
+    http::{HeaderMap, StatusCode},
+    response::{IntoResponse, Json, Response},
+};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use utoipa::{IntoParams, ToSchema};
+
+use crate::ledger::{Donation, DonationSource};
+use crate::sources::source_label;
+use crate::{error_response, require_admin, AppState, ErrorResponse, ThermometerConfig};
+
+/// How donations map onto the bookkeeper's chart of accounts in the accounting export
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[serde(default)]
+pub struct AccountingSettings {
+    /// Account each gift is deposited to (the debit side)
+    pub deposit_account: String,
+    /// Income account each gift is credited to, unless its source has one in `source_accounts`
+    pub income_account: String,
+    /// Income account per ledger source, e.g. `{"stripe": "Online Contributions"}`
+    pub source_accounts: HashMap<DonationSource, String>,
+    /// QuickBooks class per team, e.g. `{"PUP ALL NIGHT: THE PM PACK": "Events"}`
+    pub team_classes: HashMap<String, String>,
+    /// Class for teams not in `team_classes`; none when unset
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub default_class: Option<String>,
+}
+
+impl Default for AccountingSettings {
+    fn default() -> Self {
+        Self {
+            deposit_account: "Undeposited Funds".to_string(),
+            income_account: "Contributions Income".to_string(),
+            source_accounts: HashMap::new(),
+            team_classes: HashMap::new(),
+            default_class: None,
+        }
+    }
+}
+
+/// One gift as the bookkeeper sees it
+struct Entry<'a> {
+    date: chrono::NaiveDate,
+    donation: &'a Donation,
+    donor: String,
+    income_account: &'a str,
+    class: &'a str,
+    memo: String,
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct AccountingQuery {
+    /// `iif` (QuickBooks Desktop) or `csv` (default)
+    format: Option<String>,
+    /// First day to include, `YYYY-MM-DD` in the campaign timezone
+    from: Option<String>,
+    /// Last day to include, `YYYY-MM-DD` in the campaign timezone
+    to: Option<String>,
+}
+
+fn parse_day(name: &str, value: Option<&str>) -> Result<Option<chrono::NaiveDate>, String> {
+    value
+        .map(|v| chrono::NaiveDate::parse_from_str(v, "%Y-%m-%d").map_err(|_| format!("Invalid {}: expected YYYY-MM-DD", name)))
+        .transpose()
+}
+
+/// Gifts in the date range, oldest first. Spreadsheet syncs and adjustments correct totals rather
+/// than record money received, so they're left out.
+fn entries<'a>(
+    config: &'a ThermometerConfig,
+    donations: &'a [Donation],
+    from: Option<chrono::NaiveDate>,
+    to: Option<chrono::NaiveDate>,
+) -> Vec<Entry<'a>> {
+    let tz = config.tz();
+    let settings = &config.accounting;
+    donations
+        .iter()
+        .filter(|d| d.source.is_gift())
+        .filter_map(|donation| {
+            let date = chrono::DateTime::parse_from_rfc3339(&donation.created_at)
+                .ok()?
+                .with_timezone(&tz)
+                .date_naive();
+            if from.is_some_and(|from| date < from) || to.is_some_and(|to| date > to) {
+                return None;
+            }
+            let donor = donation
+                .donor_name
+                .as_deref()
+                .map(str::trim)
+                .filter(|n| !n.is_empty())
+                .unwrap_or("Anonymous donor")
+                .to_string();
+            Some(Entry {
+                date,
+                donation,
+                donor,
+                income_account: settings
+                    .source_accounts
+                    .get(&donation.source)
+                    .unwrap_or(&settings.income_account),
+                class: settings
+                    .team_classes
+                    .get(&donation.team)
+                    .or(settings.default_class.as_ref())
+                    .map_or("", String::as_str),
+                memo: memo(config, donation),
+            })
+        })
+        .collect()
+}
+
+/// e.g. `PayPal gift for UnderDogs (Gift Aid)`
+fn memo(config: &ThermometerConfig, donation: &Donation) -> String {
+    let mut memo = format!("{} gift for {}", source_label(donation.source), donation.team);
+    let mut notes = vec![];
+    if !config.receipts.is_deductible(donation) {
+        notes.push("not tax-deductible");
+    }
+    if donation.gift_aid.is_some() {
+        notes.push("Gift Aid");
+    }
+    if !notes.is_empty() {
+        memo.push_str(&format!(" ({})", notes.join(", ")));
+    }
+    memo
+}
+
+/// IIF fields are tab-separated, one record per line
+fn iif_field(value: &str) -> String {
+    value.replace(['\t', '\r', '\n'], " ")
+}
+
+/// Each gift as a QuickBooks Desktop deposit: the deposit account debited and the income account
+/// credited, split by class
+fn to_iif(config: &ThermometerConfig, entries: &[Entry]) -> String {
+    let mut out = String::from(
+        "!TRNS\tTRNSID\tTRNSTYPE\tDATE\tACCNT\tNAME\tCLASS\tAMOUNT\tDOCNUM\tMEMO\n\
+         !SPL\tSPLID\tTRNSTYPE\tDATE\tACCNT\tNAME\tCLASS\tAMOUNT\tDOCNUM\tMEMO\n\
+         !ENDTRNS\n",
+    );
+    for entry in entries {
+        let date = entry.date.format("%m/%d/%Y");
+        let name = iif_field(&entry.donor);
+        let class = iif_field(entry.class);
+        let memo = iif_field(&entry.memo);
+        let id = iif_field(&entry.donation.id);
+        out.push_str(&format!(
+            "TRNS\t\tDEPOSIT\t{}\t{}\t{}\t{}\t{:.2}\t{}\t{}\n",
+            date,
+            iif_field(&config.accounting.deposit_account),
+            name,
+            class,
+            entry.donation.amount,
+            id,
+            memo
+        ));
+        out.push_str(&format!(
+            "SPL\t\tDEPOSIT\t{}\t{}\t{}\t{}\t{:.2}\t{}\t{}\n",
+            date,
+            iif_field(entry.income_account),
+            name,
+            class,
+            -entry.donation.amount,
+            id,
+            memo
+        ));
+        out.push_str("ENDTRNS\n");
+    }
+    out
+}
+
+fn to_csv(config: &ThermometerConfig, entries: &[Entry]) -> Result<String, csv::Error> {
+    let mut writer = csv::Writer::from_writer(vec![]);
+    writer.write_record([
+        "date",
+        "donation_id",
+        "donor",
+        "donor_email",
+        "team",
+        "source",
+        "debit_account",
+        "credit_account",
+        "class",
+        "amount",
+        "tax_deductible",
+        "gift_aid_declared_at",
+        "gift_aid_home_address",
+        "gift_aid_postcode",
+        "memo",
+    ])?;
+    for entry in entries {
+        let gift_aid = entry.donation.gift_aid.as_ref();
+        writer.write_record([
+            entry.date.to_string().as_str(),
+            &entry.donation.id,
+            &entry.donor,
+            entry.donation.donor_email.as_deref().unwrap_or(""),
+            &entry.donation.team,
+            source_label(entry.donation.source),
+            &config.accounting.deposit_account,
+            entry.income_account,
+            entry.class,
+            &format!("{:.2}", entry.donation.amount),
+            if config.receipts.is_deductible(entry.donation) { "yes" } else { "no" },
+            gift_aid.map_or("", |g| g.declared_at.as_str()),
+            gift_aid.map_or("", |g| g.home_address.as_str()),
+            gift_aid.map_or("", |g| g.postcode.as_str()),
+            &entry.memo,
+        ])?;
+    }
+    let bytes = writer.into_inner().map_err(|e| e.into_error())?;
+    Ok(String::from_utf8_lossy(&bytes).into_owned())
+}
+
+#[utoipa::path(
+    get,
+    path = "/admin/export/accounting",
+    tag = "Admin",
+    security(("edit_key" = [])),
+    params(AccountingQuery),
+    responses(
+        (status = 200, description = "Gifts in the date range mapped to the configured accounts and classes, as a QuickBooks IIF or CSV download", content_type = "text/csv", body = String),
+        (status = 400, description = "Unknown format or invalid date", body = ErrorResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 500, description = "Failed to load the ledger", body = ErrorResponse)
+    )
+)]
+pub async fn export_accounting(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Query(query): Query<AccountingQuery>,
+) -> Result<Response, (StatusCode, Json<ErrorResponse>)> {
+    require_admin(&headers, &state)?;
+
+    let iif = match query.format.as_deref() {
+        None | Some("csv") => false,
+        Some("iif") => true,
+        Some(other) => {
+            return Err(error_response(StatusCode::BAD_REQUEST, format!("Unknown format: {} (expected iif or csv)", other)))
+        }
+    };
+    let bad_request = |e: String| error_response(StatusCode::BAD_REQUEST, e);
+    let from = parse_day("from", query.from.as_deref()).map_err(bad_request)?;
+    let to = parse_day("to", query.to.as_deref()).map_err(bad_request)?;
+
+    let config = state.storage.load_config().await.map_err(|e| {
+        error_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to load config: {}", e))
+    })?;
+    let donations = state.storage.list_donations().await.map_err(|e| {
+        error_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to load ledger: {}", e))
+    })?;
+    let entries = entries(&config, &donations, from, to);
+
+    let range = match (from, to) {
+        (Some(from), Some(to)) => format!("-{}-to-{}", from, to),
+        (Some(from), None) => format!("-from-{}", from),
+        (None, Some(to)) => format!("-to-{}", to),
+        (None, None) => String::new(),
+    };
+    let (content_type, extension, body) = if iif {
+        ("application/octet-stream", "iif", to_iif(&config, &entries))
+    } else {
+        let csv = to_csv(&config, &entries).map_err(|e| {
+            error_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to write CSV: {}", e))
+        })?;
+        ("text/csv", "csv", csv)
+    };
+
+    tracing::info!("Exported {} donation(s) for accounting", entries.len());
+    Ok((
+        [
+            ("Content-Type", content_type.to_string()),
+            ("Content-Disposition", format!("attachment; filename=\"donations{}.{}\"", range, extension)),
+        ],
+        body,
+    )
+        .into_response())
+}
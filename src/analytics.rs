@@ -0,0 +1,323 @@
+use axum::{
+    extract::{Query, Request, State},
+    http::{HeaderMap, StatusCode},
+    middleware::Next,
+    response::{Json, Response},
+};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Mutex;
+use std::time::Duration;
+use utoipa::{IntoParams, ToSchema};
+
+use crate::experiment::Variant;
+use crate::scheduler::Job;
+use crate::{error_response, require_admin, AppState, ErrorResponse, ThermometerConfig};
+
+/// Days summarized when `days` isn't given
+const DEFAULT_DAYS: i64 = 30;
+/// Referrer shown for image loads without a `Referer` header (email clients, direct links)
+const DIRECT: &str = "direct";
+/// Page views are best-effort, so a slow analytics server is given up on quickly
+const PAGE_VIEW_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Optional traffic counting
+#[derive(Debug, Clone, Default, Serialize, Deserialize, ToSchema)]
+#[serde(default)]
+pub struct AnalyticsSettings {
+    /// Count loads of the root image routes by referring site, experiment variant and theme
+    pub count_embeds: bool,
+    /// Send page views of `/`, `/donors` and `/teams` to Plausible or umami from the server
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub page_views: Option<PageViewSettings>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum PageViewProvider {
+    Plausible,
+    Umami,
+}
+
+/// Where page views are reported. They're sent by the server, so donor-facing pages load no
+/// third-party script.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct PageViewSettings {
+    pub provider: PageViewProvider,
+    /// Event API URL, e.g. `https://plausible.io/api/event` or `https://umami.example.org/api/send`
+    pub endpoint: String,
+    /// Plausible site domain or umami website ID
+    pub site: String,
+}
+
+/// Image loads for one day, referring site, variant and theme
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct EmbedHits {
+    /// `date|referrer|variant|theme`
+    pub id: String,
+    /// `YYYY-MM-DD` in the campaign timezone
+    pub date: String,
+    /// Domain of the page showing the image, or `direct`
+    pub referrer: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub variant: Option<Variant>,
+    /// `light`, `dark` or `thumbnail`
+    pub theme: String,
+    pub hits: u64,
+}
+
+type HitKey = (String, String, Option<Variant>, &'static str);
+
+/// Hits not yet written to storage. Counting in memory keeps image loads from each costing a
+/// storage write; the flush job saves them every few minutes.
+#[derive(Default)]
+pub struct EmbedCounter {
+    pending: Mutex<HashMap<HitKey, u64>>,
+}
+
+impl EmbedCounter {
+    pub fn record(&self, config: &ThermometerConfig, headers: &HeaderMap, variant: Option<Variant>, theme: &'static str) {
+        let date = chrono::Utc::now().with_timezone(&config.tz()).date_naive().to_string();
+        let key = (date, referrer_domain(headers), variant, theme);
+        *self.pending.lock().unwrap().entry(key).or_default() += 1;
+    }
+
+    fn take(&self) -> HashMap<HitKey, u64> {
+        std::mem::take(&mut *self.pending.lock().unwrap())
+    }
+
+    fn restore(&self, hits: HashMap<HitKey, u64>) {
+        let mut pending = self.pending.lock().unwrap();
+        for (key, count) in hits {
+            *pending.entry(key).or_default() += count;
+        }
+    }
+
+    fn snapshot(&self) -> Vec<EmbedHits> {
+        self.pending.lock().unwrap().iter().map(|(key, hits)| to_record(key, *hits)).collect()
+    }
+}
+
+/// Host of the `Referer` header without a leading `www.`, or `direct`
+fn referrer_domain(headers: &HeaderMap) -> String {
+    headers
+        .get("referer")
+        .and_then(|v| v.to_str().ok())
+        .and_then(|r| reqwest::Url::parse(r).ok())
+        .and_then(|url| url.host_str().map(|h| h.trim_start_matches("www.").to_lowercase()))
+        .unwrap_or_else(|| DIRECT.to_string())
+}
+
+fn to_record((date, referrer, variant, theme): &HitKey, hits: u64) -> EmbedHits {
+    EmbedHits {
+        id: format!("{}|{}|{}|{}", date, referrer, variant.map_or("none", Variant::as_str), theme),
+        date: date.clone(),
+        referrer: referrer.clone(),
+        variant: *variant,
+        theme: theme.to_string(),
+        hits,
+    }
+}
+
+/// Add pending hits to the stored counts. On failure the hits go back to pending for the next
+/// flush.
+pub async fn flush(state: &AppState) -> Result<usize, String> {
+    let pending = state.embed_hits.take();
+    if pending.is_empty() {
+        return Ok(0);
+    }
+    let stored: HashMap<String, u64> = match state.storage.list_embed_hits().await {
+        Ok(stored) => stored.into_iter().map(|h| (h.id, h.hits)).collect(),
+        Err(e) => {
+            state.embed_hits.restore(pending);
+            return Err(format!("Failed to load embed counts: {}", e));
+        }
+    };
+
+    let mut unsaved = HashMap::new();
+    let mut error = None;
+    let mut saved = 0;
+    for (key, hits) in pending {
+        let mut record = to_record(&key, hits);
+        record.hits += stored.get(&record.id).copied().unwrap_or(0);
+        match state.storage.save_embed_hits(&record).await {
+            Ok(()) => saved += 1,
+            Err(e) => {
+                error = Some(format!("Failed to save embed counts: {}", e));
+                unsaved.insert(key, hits);
+            }
+        }
+    }
+    let kept = unsaved.len();
+    state.embed_hits.restore(unsaved);
+    match error {
+        Some(e) => Err(format!("{} ({} counter(s) kept for the next flush)", e, kept)),
+        None => Ok(saved),
+    }
+}
+
+/// Scheduled job that saves pending embed counts
+pub fn job() -> Job {
+    Job::new("embed-analytics", "*/5 * * * *", |state| async move {
+        let saved = flush(&state).await?;
+        Ok(format!("Saved {} embed counter(s)", saved))
+    })
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct EmbedAnalyticsQuery {
+    /// Days to cover, counting today (default 30)
+    days: Option<i64>,
+}
+
+/// Image loads grouped one way, most first
+#[derive(Debug, Serialize, ToSchema)]
+pub struct HitCount {
+    pub key: String,
+    pub hits: u64,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct EmbedAnalytics {
+    /// First day covered (`YYYY-MM-DD`, campaign timezone)
+    pub since: String,
+    pub total_hits: u64,
+    /// By the domain of the page showing the image; `direct` for loads without a referrer
+    pub by_referrer: Vec<HitCount>,
+    /// By experiment variant, or `none` when no experiment was on
+    pub by_variant: Vec<HitCount>,
+    pub by_theme: Vec<HitCount>,
+    /// By day, oldest first
+    pub by_day: Vec<HitCount>,
+}
+
+fn ranked(counts: HashMap<String, u64>) -> Vec<HitCount> {
+    let mut counts: Vec<HitCount> = counts.into_iter().map(|(key, hits)| HitCount { key, hits }).collect();
+    counts.sort_by(|a, b| b.hits.cmp(&a.hits).then_with(|| a.key.cmp(&b.key)));
+    counts
+}
+
+#[utoipa::path(
+    get,
+    path = "/admin/analytics/embeds",
+    tag = "Admin",
+    security(("edit_key" = [])),
+    params(EmbedAnalyticsQuery),
+    responses(
+        (status = 200, description = "Image loads by referring site, experiment variant, theme and day", body = EmbedAnalytics),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 500, description = "Failed to load counts", body = ErrorResponse)
+    )
+)]
+pub async fn embed_analytics(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Query(query): Query<EmbedAnalyticsQuery>,
+) -> Result<Json<EmbedAnalytics>, (StatusCode, Json<ErrorResponse>)> {
+    require_admin(&headers, &state)?;
+
+    let load_error = |e: crate::storage::StorageError| {
+        error_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to load embed counts: {}", e))
+    };
+    let config = state.storage.load_config().await.map_err(load_error)?;
+    let mut records = state.storage.list_embed_hits().await.map_err(load_error)?;
+    // Include what hasn't been flushed yet so the report is current
+    records.extend(state.embed_hits.snapshot());
+
+    let days = query.days.unwrap_or(DEFAULT_DAYS).clamp(1, 366);
+    let today = chrono::Utc::now().with_timezone(&config.tz()).date_naive();
+    let since = (today - chrono::Duration::days(days - 1)).to_string();
+
+    let mut by_referrer = HashMap::new();
+    let mut by_variant = HashMap::new();
+    let mut by_theme = HashMap::new();
+    let mut by_day = BTreeMap::new();
+    let mut total_hits = 0;
+    // `YYYY-MM-DD` strings sort by date
+    for record in records.iter().filter(|r| r.date >= since) {
+        total_hits += record.hits;
+        *by_referrer.entry(record.referrer.clone()).or_default() += record.hits;
+        *by_variant
+            .entry(record.variant.map_or("none", Variant::as_str).to_string())
+            .or_default() += record.hits;
+        *by_theme.entry(record.theme.clone()).or_default() += record.hits;
+        *by_day.entry(record.date.clone()).or_default() += record.hits;
+    }
+
+    Ok(Json(EmbedAnalytics {
+        since,
+        total_hits,
+        by_referrer: ranked(by_referrer),
+        by_variant: ranked(by_variant),
+        by_theme: ranked(by_theme),
+        by_day: by_day.into_iter().map(|(key, hits)| HitCount { key, hits }).collect(),
+    }))
+}
+
+/// Middleware on the public pages that reports each successful view to the configured analytics
+/// server after the response is sent
+pub async fn track_page_view(State(state): State<AppState>, request: Request, next: Next) -> Response {
+    let headers = request.headers().clone();
+    // Built now, while the tenant prefix for this request is still known
+    let url = format!("{}{}", crate::base_url_from_headers(&headers), request.uri().path());
+    let response = next.run(request).await;
+    if response.status().is_success() {
+        tokio::spawn(async move {
+            if let Err(e) = send_page_view(&state, &url, &headers).await {
+                tracing::warn!("Failed to report page view of {}: {}", url, e);
+            }
+        });
+    }
+    response
+}
+
+async fn send_page_view(state: &AppState, url: &str, headers: &HeaderMap) -> Result<(), String> {
+    let config = state.storage.load_config().await.map_err(|e| format!("Failed to load config: {}", e))?;
+    let Some(settings) = config.analytics.page_views else {
+        return Ok(());
+    };
+
+    let header = |name: &str| headers.get(name).and_then(|v| v.to_str().ok()).unwrap_or("");
+    let referrer = header("referer");
+    let body = match settings.provider {
+        PageViewProvider::Plausible => serde_json::json!({
+            "name": "pageview",
+            "url": url,
+            "domain": settings.site,
+            "referrer": referrer,
+        }),
+        PageViewProvider::Umami => {
+            let parsed = reqwest::Url::parse(url).map_err(|e| format!("Invalid page URL: {}", e))?;
+            serde_json::json!({
+                "type": "event",
+                "payload": {
+                    "website": settings.site,
+                    "hostname": parsed.host_str().unwrap_or(""),
+                    "url": parsed.path(),
+                    "referrer": referrer,
+                    "language": header("accept-language").split(',').next().unwrap_or("").trim(),
+                },
+            })
+        }
+    };
+
+    // Both services need the visitor's address and browser to tell unique visitors apart; they
+    // hash them with a daily salt rather than storing them, and nothing is kept here
+    let mut request = state
+        .http_client
+        .post(&settings.endpoint)
+        .timeout(PAGE_VIEW_TIMEOUT)
+        .header("User-Agent", header("user-agent"))
+        .json(&body);
+    let address = header("x-forwarded-for").split(',').next().unwrap_or("").trim();
+    if !address.is_empty() {
+        request = request.header("X-Forwarded-For", address);
+    }
+
+    match request.send().await {
+        Ok(response) if response.status().is_success() => Ok(()),
+        Ok(response) => Err(format!("Analytics server returned {}", response.status())),
+        Err(e) => Err(format!("Request failed: {}", e)),
+    }
+}
@@ -0,0 +1,181 @@
+use axum::{
+    extract::{Query, State},
+    http::{HeaderMap, StatusCode},
+    response::Json,
+};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::color_constants;
+use crate::sandbox::EditTarget;
+use crate::versions::ChangeNote;
+use crate::{error_response, require_admin, AppState, ErrorResponse, SuccessResponse, ThermometerConfig};
+
+/// Longest announcement accepted; images shorten anything past `IMAGE_TEXT_LIMIT`
+const TEXT_LIMIT: usize = 280;
+const IMAGE_TEXT_LIMIT: usize = 60;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum AnnouncementLevel {
+    #[default]
+    Info,
+    Success,
+    Warning,
+    Urgent,
+}
+
+impl AnnouncementLevel {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            AnnouncementLevel::Info => "info",
+            AnnouncementLevel::Success => "success",
+            AnnouncementLevel::Warning => "warning",
+            AnnouncementLevel::Urgent => "urgent",
+        }
+    }
+
+    /// Banner color on thermometer images
+    pub fn color(self) -> &'static str {
+        match self {
+            AnnouncementLevel::Info => color_constants::announcement::INFO,
+            AnnouncementLevel::Success => color_constants::announcement::SUCCESS,
+            AnnouncementLevel::Warning => color_constants::announcement::WARNING,
+            AnnouncementLevel::Urgent => color_constants::announcement::URGENT,
+        }
+    }
+}
+
+/// A banner message like "Matching gift active until midnight!" shown on the home page, the
+/// thermometer images and `/summary` until it expires or is removed
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct Announcement {
+    pub text: String,
+    #[serde(default)]
+    pub level: AnnouncementLevel,
+    /// RFC 3339 time after which the announcement stops showing
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expires_at: Option<String>,
+}
+
+impl Announcement {
+    /// Whether it hasn't expired yet. An unparseable expiry counts as expired.
+    pub fn is_active(&self) -> bool {
+        match &self.expires_at {
+            Some(expires_at) => chrono::DateTime::parse_from_rfc3339(expires_at).is_ok_and(|t| t > chrono::Utc::now()),
+            None => true,
+        }
+    }
+
+    /// The text cut down to fit across an image, e.g. `Matching gift active until midnight!`
+    pub fn image_text(&self) -> String {
+        if self.text.chars().count() <= IMAGE_TEXT_LIMIT {
+            return self.text.clone();
+        }
+        let cut: String = self.text.chars().take(IMAGE_TEXT_LIMIT - 1).collect();
+        format!("{}…", cut.trim_end())
+    }
+}
+
+/// The announcement to show right now, if any
+pub fn active(config: &ThermometerConfig) -> Option<&Announcement> {
+    config.announcement.as_ref().filter(|a| a.is_active())
+}
+
+fn validate(announcement: &mut Announcement) -> Result<(), String> {
+    announcement.text = announcement.text.trim().to_string();
+    if announcement.text.is_empty() {
+        return Err("Announcement text is required".to_string());
+    }
+    if announcement.text.chars().count() > TEXT_LIMIT {
+        return Err(format!("Announcement text is limited to {} characters", TEXT_LIMIT));
+    }
+    if let Some(expires_at) = &announcement.expires_at {
+        let expires_at = chrono::DateTime::parse_from_rfc3339(expires_at)
+            .map_err(|e| format!("Invalid expires_at: {}", e))?;
+        if expires_at <= chrono::Utc::now() {
+            return Err("expires_at must be in the future".to_string());
+        }
+    }
+    Ok(())
+}
+
+#[utoipa::path(
+    put,
+    path = "/admin/announcement",
+    tag = "Admin",
+    security(("edit_key" = [])),
+    params(ChangeNote),
+    request_body = Announcement,
+    responses(
+        (status = 200, description = "Announcement posted, replacing any current one", body = SuccessResponse),
+        (status = 400, description = "Missing or too long text, or an expiry that isn't in the future", body = ErrorResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse)
+    )
+)]
+pub async fn set_announcement(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Query(change): Query<ChangeNote>,
+    Json(mut announcement): Json<Announcement>,
+) -> Result<Json<SuccessResponse>, (StatusCode, Json<ErrorResponse>)> {
+    require_admin(&headers, &state)?;
+    validate(&mut announcement).map_err(|e| error_response(StatusCode::BAD_REQUEST, e))?;
+
+    let _guard = state.config_lock.lock().await;
+    let (target, previous) = EditTarget::load(&state).await.map_err(|e| {
+        error_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to load config: {}", e))
+    })?;
+
+    // Not a figures update, so `last_updated` is left alone
+    let mut config = previous.clone();
+    config.announcement = Some(announcement);
+    target.commit(&state, &previous, &mut config, change.text()).await.map_err(|e| {
+        error_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to save config: {}", e))
+    })?;
+
+    tracing::info!("Posted announcement");
+    Ok(Json(SuccessResponse {
+        message: target.message("Announcement posted"),
+        config,
+    }))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/admin/announcement",
+    tag = "Admin",
+    security(("edit_key" = [])),
+    params(ChangeNote),
+    responses(
+        (status = 200, description = "Announcement removed", body = SuccessResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 404, description = "No announcement is set", body = ErrorResponse)
+    )
+)]
+pub async fn clear_announcement(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Query(change): Query<ChangeNote>,
+) -> Result<Json<SuccessResponse>, (StatusCode, Json<ErrorResponse>)> {
+    require_admin(&headers, &state)?;
+
+    let _guard = state.config_lock.lock().await;
+    let (target, previous) = EditTarget::load(&state).await.map_err(|e| {
+        error_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to load config: {}", e))
+    })?;
+
+    let mut config = previous.clone();
+    if config.announcement.take().is_none() {
+        return Err(error_response(StatusCode::NOT_FOUND, "No announcement is set"));
+    }
+    target.commit(&state, &previous, &mut config, change.text()).await.map_err(|e| {
+        error_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to save config: {}", e))
+    })?;
+
+    tracing::info!("Removed announcement");
+    Ok(Json(SuccessResponse {
+        message: target.message("Announcement removed"),
+        config,
+    }))
+}
@@ -0,0 +1,217 @@
+use axum::{
+    extract::{Path, State},
+    http::{HeaderMap, StatusCode},
+    response::Json,
+};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::{
+    commit_config, error_response, require_admin, scheduled_changes, verify_auth, AppState, ErrorResponse,
+    SuccessResponse, ThermometerConfig,
+};
+
+/// Fields that aren't compared when working out what an editor changed: `last_updated` is set on
+/// every save, and editors can't change the approval settings themselves
+const UNCOMPARED_FIELDS: [&str; 2] = ["last_updated", "approvals"];
+
+/// Sign-off on config changes made with `EDITOR_KEY`, e.g. so the treasurer approves goal changes
+#[derive(Debug, Clone, Default, Serialize, Deserialize, ToSchema)]
+#[serde(default)]
+pub struct ApprovalSettings {
+    /// Hold editor changes until an admin approves them; when off they go live right away
+    pub required: bool,
+}
+
+/// An editor's config change waiting for an admin
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct PendingChange {
+    pub id: String,
+    /// JSON merge patch of the top-level fields the editor changed, merged into the live config
+    /// when approved
+    #[schema(value_type = Object)]
+    pub change: serde_json::Value,
+    /// The editor's change note, saved with the config version the approval creates
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub note: Option<String>,
+    pub submitted_at: String,
+}
+
+/// Whether the request carries the editor key (and not the admin key)
+pub fn is_editor(headers: &HeaderMap, state: &AppState) -> bool {
+    verify_auth(headers, &state.edit_key).is_err()
+        && state
+            .editor_key
+            .as_deref()
+            .is_some_and(|key| verify_auth(headers, key).is_ok())
+}
+
+/// Accept either the admin key or the editor key
+fn require_editor(headers: &HeaderMap, state: &AppState) -> Result<(), (StatusCode, Json<ErrorResponse>)> {
+    if is_editor(headers, state) {
+        return Ok(());
+    }
+    require_admin(headers, state)
+}
+
+/// The top-level fields of `proposed` that differ from `live`, as a JSON merge patch
+fn diff(live: &ThermometerConfig, proposed: &ThermometerConfig) -> Result<serde_json::Value, String> {
+    let fields = |config: &ThermometerConfig| match serde_json::to_value(config) {
+        Ok(serde_json::Value::Object(fields)) => Ok(fields),
+        Ok(_) => Err("Config didn't serialize to an object".to_string()),
+        Err(e) => Err(format!("Failed to serialize config: {}", e)),
+    };
+    let (before, after) = (fields(live)?, fields(proposed)?);
+
+    let mut patch = serde_json::Map::new();
+    for (key, value) in &after {
+        if !UNCOMPARED_FIELDS.contains(&key.as_str()) && before.get(key) != Some(value) {
+            patch.insert(key.clone(), value.clone());
+        }
+    }
+    for key in before.keys() {
+        if !UNCOMPARED_FIELDS.contains(&key.as_str()) && !after.contains_key(key) {
+            patch.insert(key.clone(), serde_json::Value::Null);
+        }
+    }
+    Ok(serde_json::Value::Object(patch))
+}
+
+/// Save an editor's `proposed` config as a change waiting for approval. Callers hold `config_lock`.
+pub async fn submit(
+    state: &AppState,
+    mut proposed: ThermometerConfig,
+    note: Option<&str>,
+) -> Result<PendingChange, (StatusCode, Json<ErrorResponse>)> {
+    // Approved changes always go to the live config, never the sandbox's staging copy
+    let live = state.storage.load_config().await.map_err(storage_error)?;
+    crate::retain_dropped_teams(&live, &mut proposed);
+    crate::stamp_team_changes(&live, &mut proposed);
+
+    let change = diff(&live, &proposed).map_err(|e| error_response(StatusCode::INTERNAL_SERVER_ERROR, e))?;
+    if change.as_object().is_some_and(|fields| fields.is_empty()) {
+        return Err(error_response(StatusCode::BAD_REQUEST, "No changes from the live configuration"));
+    }
+
+    let pending = PendingChange {
+        id: uuid::Uuid::new_v4().to_string(),
+        change,
+        note: note.map(str::to_string),
+        submitted_at: chrono::Utc::now().to_rfc3339(),
+    };
+    state.storage.save_pending_change(&pending).await.map_err(storage_error)?;
+
+    tracing::info!("Editor submitted config change {} for approval", pending.id);
+    Ok(pending)
+}
+
+fn storage_error(e: impl std::fmt::Display) -> (StatusCode, Json<ErrorResponse>) {
+    error_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to access pending changes: {}", e))
+}
+
+async fn find(state: &AppState, id: &str) -> Result<PendingChange, (StatusCode, Json<ErrorResponse>)> {
+    state
+        .storage
+        .list_pending_changes()
+        .await
+        .map_err(storage_error)?
+        .into_iter()
+        .find(|c| c.id == id)
+        .ok_or_else(|| error_response(StatusCode::NOT_FOUND, "Pending change not found"))
+}
+
+#[utoipa::path(
+    get,
+    path = "/admin/pending",
+    tag = "Admin",
+    security(("edit_key" = []), ("editor_key" = [])),
+    responses(
+        (status = 200, description = "Editor changes waiting for approval, oldest first", body = [PendingChange]),
+        (status = 401, description = "Unauthorized", body = ErrorResponse)
+    )
+)]
+pub async fn list_pending(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+) -> Result<Json<Vec<PendingChange>>, (StatusCode, Json<ErrorResponse>)> {
+    require_editor(&headers, &state)?;
+
+    let mut changes = state.storage.list_pending_changes().await.map_err(storage_error)?;
+    changes.sort_by(|a, b| a.submitted_at.cmp(&b.submitted_at));
+    Ok(Json(changes))
+}
+
+#[utoipa::path(
+    post,
+    path = "/admin/pending/{id}/approve",
+    tag = "Admin",
+    security(("edit_key" = [])),
+    params(("id" = String, Path, description = "Pending change ID")),
+    responses(
+        (status = 200, description = "Change merged into the live configuration", body = SuccessResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 404, description = "Pending change not found", body = ErrorResponse),
+        (status = 409, description = "The change no longer produces a valid config", body = ErrorResponse)
+    )
+)]
+pub async fn approve_pending(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path(id): Path<String>,
+) -> Result<Json<SuccessResponse>, (StatusCode, Json<ErrorResponse>)> {
+    require_admin(&headers, &state)?;
+
+    let _guard = state.config_lock.lock().await;
+    let pending = find(&state, &id).await?;
+    let previous = state.storage.load_config().await.map_err(|e| {
+        error_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to load config: {}", e))
+    })?;
+    // Merged into whatever is live now, so uploads since the change was submitted are kept
+    let mut config = scheduled_changes::apply(&previous, &pending.change)
+        .map_err(|e| error_response(StatusCode::CONFLICT, e))?;
+    crate::retain_dropped_teams(&previous, &mut config);
+    config.last_updated = chrono::Utc::now().to_rfc3339();
+
+    let note = match &pending.note {
+        Some(note) => format!("Approved: {}", note),
+        None => format!("Approved change {}", pending.id),
+    };
+    commit_config(&state, &previous, &mut config, Some(&note)).await.map_err(|e| {
+        error_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to save config: {}", e))
+    })?;
+    state.storage.delete_pending_change(&pending.id).await.map_err(storage_error)?;
+
+    tracing::info!("Approved pending change {}", pending.id);
+    Ok(Json(SuccessResponse {
+        message: "Change approved and live".to_string(),
+        config,
+    }))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/admin/pending/{id}",
+    tag = "Admin",
+    security(("edit_key" = [])),
+    params(("id" = String, Path, description = "Pending change ID")),
+    responses(
+        (status = 204, description = "Change rejected and discarded"),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 404, description = "Pending change not found", body = ErrorResponse)
+    )
+)]
+pub async fn reject_pending(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path(id): Path<String>,
+) -> Result<StatusCode, (StatusCode, Json<ErrorResponse>)> {
+    require_admin(&headers, &state)?;
+
+    // Held so a rejection can't race an approval of the same change
+    let _guard = state.config_lock.lock().await;
+    let pending = find(&state, &id).await?;
+    state.storage.delete_pending_change(&pending.id).await.map_err(storage_error)?;
+
+    tracing::info!("Rejected pending change {}", pending.id);
+    Ok(StatusCode::NO_CONTENT)
+}
@@ -0,0 +1,193 @@
+//! Team avatars for rendered images. resvg can't fetch remote `href`s, so avatars are downloaded
+//! here and inlined as `data:` URIs; one that can't be fetched is drawn as a silhouette instead.
+//! Teams without an `image_url` get a placeholder with their initials.
+
+use axum::http::header;
+use base64::Engine;
+use std::collections::HashMap;
+use std::sync::RwLock;
+use std::time::{Duration, Instant};
+use tokio::task::JoinSet;
+
+use crate::theme::is_hex_color;
+use crate::{AppState, ThermometerConfig};
+
+const FETCH_TIMEOUT: Duration = Duration::from_secs(3);
+/// Bigger avatars are skipped rather than inlined into every render
+const MAX_AVATAR_BYTES: usize = 512 * 1024;
+/// How long a fetched avatar is reused before it's downloaded again
+const FRESH_FOR: Duration = Duration::from_secs(60 * 60);
+/// How long a failed fetch is remembered, so a dead URL doesn't slow every render
+const RETRY_FAILED_AFTER: Duration = Duration::from_secs(5 * 60);
+/// Formats resvg can decode from a `data:` URI
+const IMAGE_TYPES: [&str; 4] = ["image/png", "image/jpeg", "image/gif", "image/webp"];
+
+/// Placeholder colors for teams without their own `color`, dark enough for white initials
+const PLACEHOLDER_COLORS: [&str; 8] = [
+    "#C92A2A", "#A61E4D", "#862E9C", "#5F3DC4", "#1864AB", "#0B7285", "#2B8A3E", "#D9480F",
+];
+
+/// Stand-in avatar for a team without an `image_url`
+#[derive(Debug, Clone)]
+pub struct Placeholder {
+    /// Up to two letters, or None to draw a paw print when the name has no letters or digits
+    pub initials: Option<String>,
+    /// `#rrggbb` or `#rgb`
+    pub color: String,
+}
+
+impl Placeholder {
+    /// The same name always gets the same placeholder. `color` is used when it's a valid hex
+    /// color; otherwise one is picked from the name.
+    pub fn for_team(name: &str, color: Option<&str>) -> Self {
+        let initials: String = name
+            .split_whitespace()
+            .filter_map(|word| word.chars().find(|c| c.is_alphanumeric()))
+            .take(2)
+            .flat_map(char::to_uppercase)
+            .collect();
+        let color = match color.map(str::trim) {
+            Some(color) if is_hex_color(color) => color.to_string(),
+            _ => PLACEHOLDER_COLORS[(name_hash(name) % PLACEHOLDER_COLORS.len() as u64) as usize].to_string(),
+        };
+        Self {
+            initials: (!initials.is_empty()).then_some(initials),
+            color,
+        }
+    }
+}
+
+/// FNV-1a of the lowercased name, stable across releases unlike `DefaultHasher`
+fn name_hash(name: &str) -> u64 {
+    name.trim().to_lowercase().bytes().fold(0xcbf29ce484222325, |hash, byte| {
+        (hash ^ byte as u64).wrapping_mul(0x100000001b3)
+    })
+}
+
+struct Entry {
+    /// None when the fetch failed
+    data_uri: Option<String>,
+    fetched_at: Instant,
+}
+
+/// Downloaded avatars by URL. Shared by the deployment, its tenants and campaigns.
+#[derive(Default)]
+pub struct AvatarCache {
+    entries: RwLock<HashMap<String, Entry>>,
+}
+
+impl AvatarCache {
+    /// The cached result for `url`, unless it's missing or stale
+    fn cached(&self, url: &str) -> Option<Option<String>> {
+        let entries = self.entries.read().unwrap();
+        let entry = entries.get(url)?;
+        let ttl = if entry.data_uri.is_some() { FRESH_FOR } else { RETRY_FAILED_AFTER };
+        (entry.fetched_at.elapsed() < ttl).then(|| entry.data_uri.clone())
+    }
+
+    /// Drop every cached avatar, returning how many there were
+    pub fn clear(&self) -> usize {
+        let mut entries = self.entries.write().unwrap();
+        let count = entries.len();
+        entries.clear();
+        count
+    }
+
+    fn store(&self, url: &str, data_uri: Option<String>) {
+        self.entries.write().unwrap().insert(
+            url.to_string(),
+            Entry {
+                data_uri,
+                fetched_at: Instant::now(),
+            },
+        );
+    }
+}
+
+/// Download an avatar as a `data:` URI
+async fn fetch(client: &reqwest::Client, url: &str) -> Result<String, String> {
+    let mut response = client
+        .get(url)
+        .timeout(FETCH_TIMEOUT)
+        .send()
+        .await
+        .and_then(|r| r.error_for_status())
+        .map_err(|e| e.to_string())?;
+
+    let content_type = response
+        .headers()
+        .get(header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.split(';').next())
+        .map(|v| v.trim().to_ascii_lowercase())
+        .unwrap_or_default();
+    if !IMAGE_TYPES.contains(&content_type.as_str()) {
+        return Err(format!("unsupported content type {:?}", content_type));
+    }
+
+    let mut body = Vec::new();
+    while let Some(chunk) = response.chunk().await.map_err(|e| e.to_string())? {
+        body.extend_from_slice(&chunk);
+        if body.len() > MAX_AVATAR_BYTES {
+            return Err(format!("larger than {} KB", MAX_AVATAR_BYTES / 1024));
+        }
+    }
+    Ok(format!(
+        "data:{};base64,{}",
+        content_type,
+        base64::engine::general_purpose::STANDARD.encode(body)
+    ))
+}
+
+/// Inlinable avatars for `urls`, fetching those not cached concurrently. A URL maps to None when
+/// it couldn't be fetched, so the image shows a silhouette.
+pub async fn resolve(state: &AppState, urls: &[String]) -> HashMap<String, Option<String>> {
+    let mut resolved = HashMap::new();
+    let mut fetches = JoinSet::new();
+    for url in urls {
+        if resolved.contains_key(url) {
+            continue;
+        }
+        if url.starts_with("data:image/") {
+            resolved.insert(url.clone(), Some(url.clone()));
+        } else if let Some(cached) = state.avatars.cached(url) {
+            resolved.insert(url.clone(), cached);
+        } else if url.starts_with("https://") || url.starts_with("http://") {
+            resolved.insert(url.clone(), None);
+            let (client, url) = (state.http_client.clone(), url.clone());
+            fetches.spawn(async move {
+                let result = fetch(&client, &url).await;
+                (url, result)
+            });
+        } else {
+            resolved.insert(url.clone(), None);
+        }
+    }
+
+    while let Some(joined) = fetches.join_next().await {
+        let Ok((url, result)) = joined else { continue };
+        let data_uri = result
+            .map_err(|e| tracing::warn!("Failed to fetch avatar {}: {}", url, e))
+            .ok();
+        state.avatars.store(&url, data_uri.clone());
+        resolved.insert(url, data_uri);
+    }
+    resolved
+}
+
+/// Fetch new or changed avatars in the background after a save, so the next render doesn't wait
+pub fn on_config_change(state: &AppState, config: &ThermometerConfig) {
+    let urls: Vec<String> = config
+        .active_teams()
+        .filter_map(|t| t.image_url.clone())
+        .filter(|url| state.avatars.cached(url).is_none())
+        .collect();
+    if urls.is_empty() {
+        return;
+    }
+
+    let state = state.clone();
+    tokio::spawn(async move {
+        resolve(&state, &urls).await;
+    });
+}
@@ -0,0 +1,105 @@
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap};
+use utoipa::ToSchema;
+
+use crate::ledger::Donation;
+use crate::ThermometerConfig;
+
+/// Amount a team has to raise first to earn `first_to_1k`
+const FIRST_TO_AMOUNT: f64 = 1000.0;
+/// Share of its own goal a team has to reach to earn `halfway`
+const HALFWAY_SHARE: f64 = 0.5;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum BadgeKind {
+    /// First team whose ledger entries added up to $1,000
+    #[serde(rename = "first_to_1k")]
+    FirstTo1k,
+    /// Raised the most of any team in a single day
+    BiggestDay,
+    /// Reached half of the team's own `goal`
+    Halfway,
+}
+
+impl BadgeKind {
+    /// Small icon shown next to the team name
+    pub fn icon(self) -> &'static str {
+        match self {
+            Self::FirstTo1k => "🥇",
+            Self::BiggestDay => "🚀",
+            Self::Halfway => "🎯",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct Badge {
+    pub kind: BadgeKind,
+    /// How the team earned it, e.g. `Raised $1,250.00 on 2025-11-02`
+    pub description: String,
+}
+
+/// Badges earned by each team, computed from the ledger and current totals. Deleted teams are
+/// left out.
+pub fn award(config: &ThermometerConfig, donations: &[Donation]) -> HashMap<String, Vec<Badge>> {
+    let tz = config.tz();
+    let mut ledger: Vec<&Donation> = donations.iter().collect();
+    ledger.sort_by(|a, b| a.created_at.cmp(&b.created_at));
+
+    let mut badges: HashMap<String, Vec<Badge>> = HashMap::new();
+
+    // Walk the ledger in order until some team's running total reaches the threshold
+    let mut running: HashMap<&str, f64> = HashMap::new();
+    for donation in &ledger {
+        let total = running.entry(donation.team.as_str()).or_default();
+        *total += donation.amount;
+        if *total >= FIRST_TO_AMOUNT {
+            let day = local_date(&donation.created_at, tz).map(|d| format!(" on {}", d)).unwrap_or_default();
+            badges.entry(donation.team.clone()).or_default().push(Badge {
+                kind: BadgeKind::FirstTo1k,
+                description: format!("First team to raise ${:.2}{}", FIRST_TO_AMOUNT, day),
+            });
+            break;
+        }
+    }
+
+    // Net amount per team per local day; every team tied for the best day shares the badge
+    let mut daily: BTreeMap<(chrono::NaiveDate, &str), f64> = BTreeMap::new();
+    for donation in &ledger {
+        if let Some(day) = local_date(&donation.created_at, tz) {
+            *daily.entry((day, donation.team.as_str())).or_default() += donation.amount;
+        }
+    }
+    let best = daily.values().copied().fold(0.0, f64::max);
+    if best > 0.0 {
+        for (&(day, team), &amount) in &daily {
+            if amount == best {
+                badges.entry(team.to_string()).or_default().push(Badge {
+                    kind: BadgeKind::BiggestDay,
+                    description: format!("Raised ${:.2} on {}, the biggest single day of any team", amount, day),
+                });
+            }
+        }
+    }
+
+    for team in config.active_teams() {
+        if let Some(goal) = team.goal.filter(|g| *g > 0.0) {
+            if team.total_raised >= goal * HALFWAY_SHARE {
+                badges.entry(team.name.clone()).or_default().push(Badge {
+                    kind: BadgeKind::Halfway,
+                    description: format!("Reached {:.0}% of the team goal of ${:.2}", HALFWAY_SHARE * 100.0, goal),
+                });
+            }
+        }
+    }
+
+    badges.retain(|name, _| config.active_teams().any(|t| &t.name == name));
+    badges
+}
+
+fn local_date(timestamp: &str, tz: chrono_tz::Tz) -> Option<chrono::NaiveDate> {
+    chrono::DateTime::parse_from_rfc3339(timestamp)
+        .ok()
+        .map(|t| t.with_timezone(&tz).date_naive())
+}
@@ -0,0 +1,212 @@
+use axum::{
+    extract::{Query, State},
+    http::{HeaderMap, StatusCode},
+    response::Json,
+};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use utoipa::ToSchema;
+
+use crate::integrations;
+use crate::ledger::{self, DonationSource};
+use crate::sandbox::EditTarget;
+use crate::versions::{ChangeNote, TeamTotalChange};
+use crate::{config_hash, error_response, require_admin, AppState, ErrorResponse, ThermometerConfig};
+
+/// A change applied to every active team at once (deleted teams are left alone)
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[serde(tag = "op", rename_all = "snake_case")]
+pub enum BulkOperation {
+    /// Add `amount` to every team; negative amounts subtract, and totals stop at zero
+    Add { amount: f64 },
+    /// Multiply every team's total by `factor`
+    Multiply { factor: f64 },
+    /// Set every team's total to zero
+    Zero,
+    /// Rename team `from` to `to`, keeping its total
+    Rename { from: String, to: String },
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct BulkRequest {
+    operation: BulkOperation,
+    /// `confirm_token` from a preview of the same operation; omit to preview
+    confirm: Option<String>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct BulkResult {
+    /// `false` for a preview
+    applied: bool,
+    /// Send back as `confirm` to apply exactly this preview. Stops working once the config changes.
+    confirm_token: String,
+    message: String,
+    /// Team totals the operation changes
+    changes: Vec<TeamTotalChange>,
+    /// The config with the operation applied
+    #[serde(serialize_with = "integrations::serialize_masked")]
+    config: ThermometerConfig,
+}
+
+fn round_cents(amount: f64) -> f64 {
+    (amount * 100.0).round() / 100.0
+}
+
+/// Ties a confirmation to the operation and the exact config it was previewed against
+fn confirm_token(config: &ThermometerConfig, operation: &BulkOperation) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(config_hash(config));
+    hasher.update(serde_json::to_vec(operation).unwrap_or_default());
+    hex::encode(&hasher.finalize()[..8])
+}
+
+/// Apply `operation` to a copy of `config`, describing what it did
+fn apply(
+    config: &ThermometerConfig,
+    operation: &BulkOperation,
+) -> Result<(ThermometerConfig, String), (StatusCode, Json<ErrorResponse>)> {
+    let mut config = config.clone();
+    let active = config.teams.iter_mut().filter(|t| t.deleted_at.is_none());
+
+    let message = match operation {
+        BulkOperation::Add { amount } => {
+            if !amount.is_finite() {
+                return Err(error_response(StatusCode::BAD_REQUEST, "amount must be a number"));
+            }
+            for team in active {
+                team.total_raised = round_cents(team.total_raised + amount).max(0.0);
+            }
+            format!("Added ${:.2} to every team", amount)
+        }
+        BulkOperation::Multiply { factor } => {
+            if !factor.is_finite() || *factor < 0.0 {
+                return Err(error_response(StatusCode::BAD_REQUEST, "factor must be zero or positive"));
+            }
+            for team in active {
+                team.total_raised = round_cents(team.total_raised * factor);
+            }
+            format!("Multiplied every team's total by {}", factor)
+        }
+        BulkOperation::Zero => {
+            for team in active {
+                team.total_raised = 0.0;
+            }
+            "Reset every team's total to zero".to_string()
+        }
+        BulkOperation::Rename { from, to } => {
+            let to = to.trim();
+            if to.is_empty() {
+                return Err(error_response(StatusCode::BAD_REQUEST, "New team name is required"));
+            }
+            if config.teams.iter().any(|t| t.name == to) {
+                return Err(error_response(StatusCode::CONFLICT, format!("Team already exists: {}", to)));
+            }
+            let team = config
+                .teams
+                .iter_mut()
+                .find(|t| t.name == *from && t.deleted_at.is_none())
+                .ok_or_else(|| error_response(StatusCode::NOT_FOUND, format!("Team not found: {}", from)))?;
+            team.name = to.to_string();
+            format!("Renamed team {} to {}", from, to)
+        }
+    };
+    Ok((config, message))
+}
+
+/// Per-team total changes, with a renamed team showing as its old name going to zero and its new
+/// name starting from zero, the same way the ledger records it
+fn changes(before: &ThermometerConfig, after: &ThermometerConfig) -> Vec<TeamTotalChange> {
+    let total_of = |config: &ThermometerConfig, name: &str| {
+        config.active_teams().find(|t| t.name == name).map(|t| t.total_raised).unwrap_or(0.0)
+    };
+
+    let mut names: Vec<&str> = before.active_teams().map(|t| t.name.as_str()).collect();
+    for team in after.active_teams() {
+        if !names.contains(&team.name.as_str()) {
+            names.push(&team.name);
+        }
+    }
+    names
+        .into_iter()
+        .filter_map(|name| {
+            let (b, a) = (total_of(before, name), total_of(after, name));
+            (b != a).then(|| TeamTotalChange {
+                name: name.to_string(),
+                before: b,
+                after: a,
+                delta: round_cents(a - b),
+            })
+        })
+        .collect()
+}
+
+#[utoipa::path(
+    post,
+    path = "/admin/teams/bulk",
+    tag = "Admin",
+    security(("edit_key" = [])),
+    params(ChangeNote),
+    request_body = BulkRequest,
+    responses(
+        (status = 200, description = "Preview (without `confirm`) or the applied result", body = BulkResult),
+        (status = 400, description = "Invalid operation", body = ErrorResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 404, description = "Team to rename not found", body = ErrorResponse),
+        (status = 409, description = "Confirmation token doesn't match the current config, or the new team name is taken", body = ErrorResponse)
+    )
+)]
+pub async fn bulk_update(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Query(change): Query<ChangeNote>,
+    Json(request): Json<BulkRequest>,
+) -> Result<Json<BulkResult>, (StatusCode, Json<ErrorResponse>)> {
+    require_admin(&headers, &state)?;
+
+    let _guard = state.config_lock.lock().await;
+    let (target, previous) = EditTarget::load(&state).await.map_err(|e| {
+        error_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to load config: {}", e))
+    })?;
+
+    let (mut config, message) = apply(&previous, &request.operation)?;
+    let changes = changes(&previous, &config);
+    let confirm_token = confirm_token(&previous, &request.operation);
+
+    let Some(confirm) = request.confirm else {
+        return Ok(Json(BulkResult {
+            applied: false,
+            confirm_token,
+            message: format!("Preview: {}", message),
+            changes,
+            config,
+        }));
+    };
+    if confirm != confirm_token {
+        return Err(error_response(
+            StatusCode::CONFLICT,
+            "Confirmation token doesn't match this operation on the current config; preview it again",
+        ));
+    }
+
+    config.last_updated = chrono::Utc::now().to_rfc3339();
+    target.commit(&state, &previous, &mut config, change.text()).await.map_err(|e| {
+        error_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to save config: {}", e))
+    })?;
+    // Audit trail: one ledger entry per changed team. Sandbox changes are recorded on promote.
+    if target == EditTarget::Live {
+        ledger::record_total_changes(&state, &previous.teams, &config.teams, DonationSource::Adjustment)
+            .await
+            .map_err(|e| {
+                error_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to record adjustments: {}", e))
+            })?;
+    }
+
+    tracing::info!("Bulk team update: {}", message);
+    Ok(Json(BulkResult {
+        applied: true,
+        confirm_token,
+        message: target.message(message),
+        changes,
+        config,
+    }))
+}
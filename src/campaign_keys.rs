@@ -0,0 +1,183 @@
+use axum::{
+    extract::{Path, State},
+    http::{HeaderMap, HeaderValue, Method, StatusCode},
+    response::Json,
+};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::campaigns::Campaign;
+use crate::{error_response, require_admin, verify_auth, AppState, ErrorResponse};
+
+/// Admin endpoints that record gifts
+const DONATION_PATHS: [&str; 4] = ["/admin/upload", "/admin/donation", "/admin/quick-donation", "/admin/pledges"];
+/// Admin endpoints (and prefixes) that change the config
+const CONFIG_PATHS: [&str; 4] = ["/admin/config", "/admin/teams/", "/admin/announcement", "/admin/scheduled-changes"];
+
+/// A key that only works for one campaign, e.g. for a partner rescue's volunteers running their
+/// own appeal on this deployment. It can't touch the deployment's config or any other campaign.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct CampaignKey {
+    pub id: String,
+    /// Who the key was issued to
+    pub name: String,
+    /// Sent as `Authorization: Bearer <key>` to the campaign's admin endpoints
+    pub key: String,
+    /// What the key may do; anything else is answered with `403`
+    pub scopes: Vec<KeyScope>,
+    pub created_at: String,
+}
+
+/// An action a campaign key may be allowed
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum KeyScope {
+    /// `GET` any admin endpoint: reports, versions, exports, pending changes
+    Read,
+    /// Record gifts: CSV uploads, donations, quick donations and pledges
+    Donations,
+    /// Change the config, teams, announcement and scheduled changes
+    Config,
+    /// Everything the admin key can do in the campaign
+    Admin,
+}
+
+impl KeyScope {
+    /// Whether a request to the campaign's `path` (without the `/campaigns/<id>` prefix) is allowed
+    fn covers(self, method: &Method, path: &str) -> bool {
+        let is_read = matches!(*method, Method::GET | Method::HEAD);
+        match self {
+            KeyScope::Read => is_read,
+            KeyScope::Donations => !is_read && DONATION_PATHS.contains(&path),
+            KeyScope::Config => !is_read && CONFIG_PATHS.iter().any(|p| path.starts_with(p)),
+            KeyScope::Admin => true,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct CreateCampaignKeyRequest {
+    name: String,
+    scopes: Vec<KeyScope>,
+}
+
+/// What a request carrying one of `campaign`'s keys may do
+pub enum KeyCheck {
+    /// No campaign key; the campaign's handlers check the admin or other keys as usual
+    NotCampaignKey,
+    /// The key covers the request, which is passed on as the admin's
+    Allowed,
+    /// The key doesn't cover the request
+    Denied(String),
+}
+
+/// Check a request to `campaign`'s `path` against the campaign's keys. Only admin endpoints are
+/// checked; the public pages don't need a key.
+pub fn check(campaign: &Campaign, headers: &HeaderMap, method: &Method, path: &str) -> KeyCheck {
+    if !path.starts_with("/admin") {
+        return KeyCheck::NotCampaignKey;
+    }
+    let Some(key) = campaign.keys.iter().find(|k| verify_auth(headers, &k.key).is_ok()) else {
+        return KeyCheck::NotCampaignKey;
+    };
+    if key.scopes.iter().any(|scope| scope.covers(method, path)) {
+        KeyCheck::Allowed
+    } else {
+        KeyCheck::Denied(format!("Key {} isn't allowed to {} {}", key.name, method, path))
+    }
+}
+
+/// Replace a campaign key in `headers` with the admin key, so handlers accept the request
+pub fn authorize_as_admin(headers: &mut HeaderMap, edit_key: &str) {
+    if let Ok(value) = HeaderValue::from_str(&format!("Bearer {}", edit_key)) {
+        headers.insert("Authorization", value);
+    }
+}
+
+fn storage_error(e: impl std::fmt::Display) -> (StatusCode, Json<ErrorResponse>) {
+    error_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to save campaign: {}", e))
+}
+
+#[utoipa::path(
+    post,
+    path = "/admin/campaigns/{id}/keys",
+    tag = "Admin",
+    security(("edit_key" = [])),
+    params(("id" = String, Path, description = "Campaign id")),
+    request_body = CreateCampaignKeyRequest,
+    responses(
+        (status = 201, description = "Key issued; share `key` with the campaign's volunteers", body = CampaignKey),
+        (status = 400, description = "Missing name or scopes", body = ErrorResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 404, description = "Campaign not found", body = ErrorResponse)
+    )
+)]
+pub async fn create_key(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path(id): Path<String>,
+    Json(request): Json<CreateCampaignKeyRequest>,
+) -> Result<(StatusCode, Json<CampaignKey>), (StatusCode, Json<ErrorResponse>)> {
+    require_admin(&headers, &state)?;
+
+    let name = request.name.trim();
+    if name.is_empty() {
+        return Err(error_response(StatusCode::BAD_REQUEST, "Name is required"));
+    }
+    if request.scopes.is_empty() {
+        return Err(error_response(StatusCode::BAD_REQUEST, "At least one scope is required"));
+    }
+    let Some(mut campaign) = state.campaigns.campaign(&id) else {
+        return Err(error_response(StatusCode::NOT_FOUND, "Campaign not found"));
+    };
+    let key = CampaignKey {
+        id: Uuid::new_v4().to_string(),
+        name: name.to_string(),
+        key: Uuid::new_v4().to_string(),
+        scopes: request.scopes,
+        created_at: chrono::Utc::now().to_rfc3339(),
+    };
+    campaign.keys.push(key.clone());
+    state.storage.save_campaign(&campaign).await.map_err(storage_error)?;
+    state.campaigns.update(&campaign);
+
+    tracing::info!("Issued key {} for campaign {} to {}", key.id, campaign.id, key.name);
+    Ok((StatusCode::CREATED, Json(key)))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/admin/campaigns/{id}/keys/{key_id}",
+    tag = "Admin",
+    security(("edit_key" = [])),
+    params(
+        ("id" = String, Path, description = "Campaign id"),
+        ("key_id" = String, Path, description = "Key ID (not the key itself)")
+    ),
+    responses(
+        (status = 204, description = "Key revoked"),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 404, description = "Campaign or key not found", body = ErrorResponse)
+    )
+)]
+pub async fn revoke_key(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path((id, key_id)): Path<(String, String)>,
+) -> Result<StatusCode, (StatusCode, Json<ErrorResponse>)> {
+    require_admin(&headers, &state)?;
+
+    let Some(mut campaign) = state.campaigns.campaign(&id) else {
+        return Err(error_response(StatusCode::NOT_FOUND, "Campaign not found"));
+    };
+    if !campaign.keys.iter().any(|k| k.id == key_id) {
+        return Err(error_response(StatusCode::NOT_FOUND, "Key not found"));
+    }
+    campaign.keys.retain(|k| k.id != key_id);
+    state.storage.save_campaign(&campaign).await.map_err(storage_error)?;
+    state.campaigns.update(&campaign);
+
+    tracing::info!("Revoked key {} of campaign {}", key_id, campaign.id);
+    Ok(StatusCode::NO_CONTENT)
+}
@@ -0,0 +1,397 @@
+use axum::{
+    extract::{Path, Request, State},
+    http::{HeaderMap, Method, StatusCode, Uri},
+    middleware::Next,
+    response::{IntoResponse, Json, Response},
+    routing::{delete, get, post},
+    Router,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, Mutex, RwLock};
+use tower::ServiceExt;
+use utoipa::ToSchema;
+
+use crate::campaign_keys::{self, CampaignKey, KeyCheck};
+use crate::read_tokens;
+use crate::scheduler::{self, Scheduler};
+use crate::tenants;
+use crate::{error_response, require_admin, AppState, ErrorResponse, ThermometerConfig};
+
+/// Path prefix that selects a campaign, e.g. `/campaigns/spring-gala/thermometer-light.png`
+pub const CAMPAIGN_PATH_PREFIX: &str = "/campaigns/";
+
+/// A fundraiser run alongside the main campaign, with its own config, ledger and scheduled jobs.
+/// It shares the keys of the deployment or tenant it belongs to, and can have keys of its own.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct Campaign {
+    /// URL id, lowercase letters, digits and dashes
+    pub id: String,
+    pub title: String,
+    /// Left out of `GET /campaigns`, and only readable with a read token or the admin key
+    #[serde(default)]
+    pub unlisted: bool,
+    pub created_at: String,
+    /// Set once the campaign is over; its pages and images are still served but nothing changes
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub archived_at: Option<String>,
+    /// Keys that only work for this campaign, each limited to some actions
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub keys: Vec<CampaignKey>,
+}
+
+/// An active campaign as listed publicly
+#[derive(Debug, Serialize, ToSchema)]
+pub struct CampaignListing {
+    pub id: String,
+    pub title: String,
+    pub goal: f64,
+    pub total_raised: f64,
+    pub progress_percent: f64,
+    /// Path of the campaign's home page, e.g. `/campaigns/spring-gala/`
+    pub path: String,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct CreateCampaignRequest {
+    title: String,
+    /// Starting goal; defaults to the default config's goal
+    goal: Option<f64>,
+    /// Only readable with a read token or the admin key
+    #[serde(default)]
+    unlisted: bool,
+}
+
+/// Routers for every campaign of one deployment or tenant, keyed by id
+#[derive(Default)]
+pub struct CampaignRegistry {
+    campaigns: RwLock<HashMap<String, (Campaign, Router, AppState)>>,
+}
+
+impl CampaignRegistry {
+    fn get(&self, id: &str) -> Option<(Campaign, Router)> {
+        self.campaigns
+            .read()
+            .unwrap()
+            .get(id)
+            .map(|(campaign, router, _)| (campaign.clone(), router.clone()))
+    }
+
+    /// State of every registered campaign
+    pub fn states(&self) -> Vec<AppState> {
+        self.campaigns.read().unwrap().values().map(|(_, _, state)| state.clone()).collect()
+    }
+
+    /// State of one registered campaign
+    pub fn state(&self, id: &str) -> Option<AppState> {
+        self.campaigns.read().unwrap().get(id).map(|(_, _, state)| state.clone())
+    }
+
+    /// One registered campaign
+    pub fn campaign(&self, id: &str) -> Option<Campaign> {
+        self.campaigns.read().unwrap().get(id).map(|(campaign, _, _)| campaign.clone())
+    }
+
+    /// Replace a registered campaign's record after it was saved, e.g. with new keys
+    pub fn update(&self, campaign: &Campaign) {
+        if let Some((stored, _, _)) = self.campaigns.write().unwrap().get_mut(&campaign.id) {
+            *stored = campaign.clone();
+        }
+    }
+
+    fn contains(&self, id: &str) -> bool {
+        self.campaigns.read().unwrap().contains_key(id)
+    }
+
+    /// Build the campaign's state and routes, starting its scheduled jobs unless it's archived
+    fn register(&self, owner: &AppState, campaign: &Campaign) -> AppState {
+        let state = AppState {
+            storage: owner.storage.for_campaign(&campaign.id),
+            in_flight_keys: Arc::new(Mutex::new(HashSet::new())),
+            scheduler: Arc::new(Scheduler::new(crate::scheduled_jobs())),
+            config_lock: Arc::new(tokio::sync::Mutex::new(())),
+            exports: owner.exports.as_ref().map(|e| Arc::new(e.for_campaign(&campaign.id))),
+            momentum: crate::momentum::channel(),
+            config_updates: crate::live_updates::channel(),
+            experiment: Arc::new(crate::experiment::Counters::default()),
+            embed_hits: Arc::new(crate::analytics::EmbedCounter::default()),
+            notifications: Arc::new(crate::notifications::DeliveryLog::default()),
+            // Campaigns don't have campaigns of their own
+            campaigns: Arc::new(CampaignRegistry::default()),
+            scope: format!("{}{}{}", owner.scope, CAMPAIGN_PATH_PREFIX, campaign.id),
+            ..owner.clone()
+        };
+        if campaign.archived_at.is_none() {
+            scheduler::spawn_scheduler(state.clone());
+        }
+
+        let router = crate::routes(&state).with_state(state.clone());
+        if let Some((_, _, previous)) = self
+            .campaigns
+            .write()
+            .unwrap()
+            .insert(campaign.id.clone(), (campaign.clone(), router, state.clone()))
+        {
+            previous.scheduler.stop();
+        }
+        state
+    }
+
+    fn archive(&self, campaign: &Campaign) {
+        if let Some((stored, _, state)) = self.campaigns.write().unwrap().get_mut(&campaign.id) {
+            *stored = campaign.clone();
+            state.scheduler.stop();
+        }
+    }
+
+    fn remove(&self, id: &str) {
+        if let Some((_, _, state)) = self.campaigns.write().unwrap().remove(id) {
+            state.scheduler.stop();
+        }
+    }
+}
+
+/// Register every stored campaign of a deployment or tenant; called once at startup
+pub async fn load(state: &AppState) {
+    match state.storage.list_campaigns().await {
+        Ok(campaigns) => {
+            for campaign in &campaigns {
+                state.campaigns.register(state, campaign);
+            }
+            if !campaigns.is_empty() {
+                tracing::info!("Serving {} campaign(s)", campaigns.len());
+            }
+        }
+        Err(e) => tracing::error!("Failed to load campaigns: {}", e),
+    }
+}
+
+/// Listing and management routes, served by the deployment and each tenant but not by campaigns
+pub fn routes() -> Router<AppState> {
+    Router::new()
+        .route("/campaigns", get(list_active_campaigns))
+        .route("/admin/campaigns", get(list_campaigns))
+        .route("/admin/campaigns/:id", post(create_campaign))
+        .route("/admin/campaigns/:id/archive", post(archive_campaign))
+        .route("/admin/campaigns/:id/keys", post(campaign_keys::create_key))
+        .route("/admin/campaigns/:id/keys/:key_id", delete(campaign_keys::revoke_key))
+}
+
+/// Route `/campaigns/<id>/...` to that campaign's router with the prefix stripped. Archived
+/// campaigns only answer reads, and the public pages of unlisted ones look like they don't exist
+/// without a read token or the admin key. Requests with one of the campaign's own keys only reach
+/// the admin endpoints its scopes cover, and are passed on as the admin's.
+pub async fn dispatch(State(state): State<AppState>, mut request: Request, next: Next) -> Response {
+    let Some(rest) = request.uri().path().strip_prefix(CAMPAIGN_PATH_PREFIX) else {
+        return next.run(request).await;
+    };
+    let (id, path) = match rest.find('/') {
+        Some(i) => (&rest[..i], &rest[i..]),
+        None => (rest, "/"),
+    };
+    let (id, path) = (id.to_string(), path.to_string());
+
+    let is_read = matches!(*request.method(), Method::GET | Method::HEAD);
+    // Admin pages and writes have their own authentication (the admin portal page itself is
+    // loaded without a key), so only public reads of an unlisted campaign need a token
+    let public_read = is_read && !path.starts_with("/admin");
+    let Some((campaign, router)) = state.campaigns.get(&id).filter(|(campaign, _)| {
+        !campaign.unlisted || !public_read || read_tokens::can_read_unlisted(request.headers(), &state)
+    }) else {
+        return error_response(StatusCode::NOT_FOUND, format!("Unknown campaign: {}", id)).into_response();
+    };
+    if campaign.archived_at.is_some() && !is_read {
+        return error_response(StatusCode::CONFLICT, format!("Campaign {} is archived", id)).into_response();
+    }
+    match campaign_keys::check(&campaign, request.headers(), request.method(), &path) {
+        KeyCheck::NotCampaignKey => {}
+        KeyCheck::Allowed => campaign_keys::authorize_as_admin(request.headers_mut(), &state.edit_key),
+        KeyCheck::Denied(message) => return error_response(StatusCode::FORBIDDEN, message).into_response(),
+    }
+
+    let path_and_query = match request.uri().query() {
+        Some(query) => format!("{}?{}", path, query),
+        None => path.to_string(),
+    };
+    let Ok(uri) = path_and_query.parse::<Uri>() else {
+        return error_response(StatusCode::BAD_REQUEST, "Invalid request path").into_response();
+    };
+    *request.uri_mut() = uri;
+
+    let base_path = format!("{}{}{}", tenants::base_path(), CAMPAIGN_PATH_PREFIX, id);
+    match tenants::with_base_path(base_path, router.oneshot(request)).await {
+        Ok(response) => response,
+        Err(never) => match never {},
+    }
+}
+
+fn storage_error(e: impl std::fmt::Display) -> (StatusCode, Json<ErrorResponse>) {
+    error_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to access campaigns: {}", e))
+}
+
+#[utoipa::path(
+    get,
+    path = "/campaigns",
+    tag = "Public",
+    responses(
+        (status = 200, description = "Active campaigns with their progress, oldest first. Unlisted ones are only included for a read token or the admin key", body = [CampaignListing])
+    )
+)]
+pub async fn list_active_campaigns(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+) -> Result<Json<Vec<CampaignListing>>, (StatusCode, Json<ErrorResponse>)> {
+    let unlisted = read_tokens::can_read_unlisted(&headers, &state);
+    let mut campaigns = state.storage.list_campaigns().await.map_err(storage_error)?;
+    campaigns.retain(|c| c.archived_at.is_none() && (unlisted || !c.unlisted));
+    campaigns.sort_by(|a, b| a.created_at.cmp(&b.created_at));
+
+    let mut listings = vec![];
+    for campaign in campaigns {
+        let Some(campaign_state) = state.campaigns.state(&campaign.id) else {
+            continue;
+        };
+        let config = match campaign_state.storage.load_config().await {
+            Ok(config) => config,
+            Err(e) => {
+                tracing::warn!("Failed to load campaign {} for listing: {}", campaign.id, e);
+                continue;
+            }
+        };
+        listings.push(CampaignListing {
+            path: format!("{}{}{}/", tenants::base_path(), CAMPAIGN_PATH_PREFIX, campaign.id),
+            id: campaign.id,
+            title: config.title.clone(),
+            goal: config.goal,
+            total_raised: config.total_raised(),
+            progress_percent: config.progress_percent(),
+        });
+    }
+    Ok(Json(listings))
+}
+
+#[utoipa::path(
+    get,
+    path = "/admin/campaigns",
+    tag = "Admin",
+    security(("edit_key" = [])),
+    responses(
+        (status = 200, description = "Every campaign, including archived ones, oldest first", body = [Campaign]),
+        (status = 401, description = "Unauthorized", body = ErrorResponse)
+    )
+)]
+pub async fn list_campaigns(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+) -> Result<Json<Vec<Campaign>>, (StatusCode, Json<ErrorResponse>)> {
+    require_admin(&headers, &state)?;
+
+    let mut campaigns = state.storage.list_campaigns().await.map_err(storage_error)?;
+    campaigns.sort_by(|a, b| a.created_at.cmp(&b.created_at));
+    Ok(Json(campaigns))
+}
+
+#[utoipa::path(
+    post,
+    path = "/admin/campaigns/{id}",
+    tag = "Admin",
+    security(("edit_key" = [])),
+    params(("id" = String, Path, description = "Campaign id, used in its URLs")),
+    request_body = CreateCampaignRequest,
+    responses(
+        (status = 201, description = "Campaign created and served under `/campaigns/{id}`", body = Campaign),
+        (status = 400, description = "Invalid id", body = ErrorResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 409, description = "Id already in use", body = ErrorResponse)
+    )
+)]
+pub async fn create_campaign(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path(id): Path<String>,
+    Json(request): Json<CreateCampaignRequest>,
+) -> Result<(StatusCode, Json<Campaign>), (StatusCode, Json<ErrorResponse>)> {
+    require_admin(&headers, &state)?;
+
+    let id = id.trim().to_string();
+    if !tenants::is_valid_slug(&id) {
+        return Err(error_response(
+            StatusCode::BAD_REQUEST,
+            "Campaign id must be lowercase letters, digits or dashes, not starting with a dash",
+        ));
+    }
+    if state.campaigns.contains(&id) {
+        return Err(error_response(StatusCode::CONFLICT, format!("Campaign {} already exists", id)));
+    }
+
+    let owner_config = state.storage.load_config().await.map_err(|e| {
+        error_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to load config: {}", e))
+    })?;
+    let campaign = Campaign {
+        id,
+        title: request.title.trim().to_string(),
+        unlisted: request.unlisted,
+        created_at: chrono::Utc::now().to_rfc3339(),
+        archived_at: None,
+        keys: vec![],
+    };
+    let defaults = ThermometerConfig::default();
+    let config = ThermometerConfig {
+        organization_name: owner_config.organization_name,
+        title: campaign.title.clone(),
+        goal: request.goal.unwrap_or(defaults.goal),
+        timezone: owner_config.timezone,
+        ..defaults
+    };
+
+    let campaign_state = state.campaigns.register(&state, &campaign);
+    let saved = async {
+        campaign_state.storage.save_config(&config).await?;
+        state.storage.save_campaign(&campaign).await
+    }
+    .await;
+    if let Err(e) = saved {
+        state.campaigns.remove(&campaign.id);
+        return Err(error_response(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("Failed to create campaign: {}", e),
+        ));
+    }
+
+    tracing::info!("Created campaign {}", campaign.id);
+    Ok((StatusCode::CREATED, Json(campaign)))
+}
+
+#[utoipa::path(
+    post,
+    path = "/admin/campaigns/{id}/archive",
+    tag = "Admin",
+    security(("edit_key" = [])),
+    params(("id" = String, Path, description = "Campaign id")),
+    responses(
+        (status = 200, description = "Campaign archived: still served, but read-only and with its jobs stopped", body = Campaign),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 404, description = "Campaign not found", body = ErrorResponse),
+        (status = 409, description = "Campaign already archived", body = ErrorResponse)
+    )
+)]
+pub async fn archive_campaign(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path(id): Path<String>,
+) -> Result<Json<Campaign>, (StatusCode, Json<ErrorResponse>)> {
+    require_admin(&headers, &state)?;
+
+    let Some((mut campaign, _)) = state.campaigns.get(&id) else {
+        return Err(error_response(StatusCode::NOT_FOUND, "Campaign not found"));
+    };
+    if campaign.archived_at.is_some() {
+        return Err(error_response(StatusCode::CONFLICT, format!("Campaign {} is already archived", id)));
+    }
+    campaign.archived_at = Some(chrono::Utc::now().to_rfc3339());
+    state.storage.save_campaign(&campaign).await.map_err(storage_error)?;
+    state.campaigns.archive(&campaign);
+
+    tracing::info!("Archived campaign {}", campaign.id);
+    Ok(Json(campaign))
+}
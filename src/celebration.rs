@@ -0,0 +1,53 @@
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::ThermometerConfig;
+
+const DEFAULT_HEADLINE: &str = "We did it!";
+/// Longest headline that fits in place of "of goal" on the link-preview thumbnail
+const THUMBNAIL_HEADLINE_LIMIT: usize = 16;
+
+/// Switches the home page and link previews to a thank-you once the goal is reached, so nobody
+/// has to push a change the moment it happens
+#[derive(Debug, Clone, Default, Serialize, Deserialize, ToSchema)]
+#[serde(default)]
+pub struct CelebrationSettings {
+    pub enabled: bool,
+    /// Defaults to "We did it!"
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub headline: Option<String>,
+    /// Thank-you paragraph under the headline on the home page
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct Celebration {
+    pub headline: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+}
+
+impl Celebration {
+    /// The headline cut down to fit the thumbnail
+    pub fn thumbnail_text(&self) -> String {
+        if self.headline.chars().count() <= THUMBNAIL_HEADLINE_LIMIT {
+            return self.headline.clone();
+        }
+        let cut: String = self.headline.chars().take(THUMBNAIL_HEADLINE_LIMIT - 1).collect();
+        format!("{}…", cut.trim_end())
+    }
+}
+
+/// The celebration to show, once it's enabled and the total has reached the goal
+pub fn active(config: &ThermometerConfig) -> Option<Celebration> {
+    let settings = &config.celebration;
+    if !settings.enabled || config.goal <= 0.0 || config.total_raised() < config.goal {
+        return None;
+    }
+    let non_empty = |s: &Option<String>| s.as_deref().map(str::trim).filter(|s| !s.is_empty()).map(str::to_string);
+    Some(Celebration {
+        headline: non_empty(&settings.headline).unwrap_or_else(|| DEFAULT_HEADLINE.to_string()),
+        message: non_empty(&settings.message),
+    })
+}
@@ -0,0 +1,375 @@
+//! Celebrates milestones in the shelter's Slack and Discord: when a save takes the total past one
+//! of the configured thresholds, a message goes out with the thermometer attached. Announced
+//! thresholds are stored, so a restart or a second instance never posts the same one twice.
+
+use axum::{
+    extract::{Path, State},
+    http::{HeaderMap, StatusCode},
+    response::Json,
+};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::time::Duration;
+use utoipa::ToSchema;
+
+use crate::storage::StorageError;
+use crate::thermometer::{generate_thermometer_svg, svg_to_png, ThermometerStyle};
+use crate::{error_response, require_admin, AppState, ErrorResponse, ThermometerConfig};
+
+const POST_TIMEOUT: Duration = Duration::from_secs(15);
+/// Pixels per SVG pixel of the attached image
+const IMAGE_SCALE: f32 = 1.5;
+const IMAGE_FILENAME: &str = "thermometer.png";
+const SLACK_API: &str = "https://slack.com/api";
+
+/// The `chat_announcements` section of the config. Posts go to every chat set up in
+/// `integrations`: Discord, and Slack through the bot (with the image) or the incoming webhook
+/// (text only). While this is on, `milestone` notifications stay out of Slack.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[serde(default)]
+pub struct ChatAnnouncementSettings {
+    pub enabled: bool,
+    /// Percentages of the goal to announce
+    pub percents: Vec<f64>,
+    /// Also announce the `milestones` marked on the thermometer
+    pub milestones: bool,
+    /// Attach the dark thermometer instead of the light one
+    pub dark_mode: bool,
+}
+
+impl Default for ChatAnnouncementSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            percents: vec![25.0, 50.0, 75.0, 100.0],
+            milestones: true,
+            dark_mode: false,
+        }
+    }
+}
+
+/// A threshold that has been posted
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct AnnouncedThreshold {
+    /// `percent-<percent>-of-<goal>` or `milestone-<amount>`. Percentages are per goal, so raising
+    /// the goal lets them be announced again.
+    pub id: String,
+    /// What was announced, e.g. `50% of the $10000 goal`
+    pub label: String,
+    pub amount: f64,
+    /// Campaign total when it was announced
+    pub total_raised: f64,
+    pub announced_at: String,
+}
+
+struct Threshold {
+    id: String,
+    label: String,
+    amount: f64,
+}
+
+/// Every threshold the settings ask for, lowest first
+fn thresholds(config: &ThermometerConfig) -> Vec<Threshold> {
+    let settings = &config.chat_announcements;
+    let mut thresholds: Vec<Threshold> = settings
+        .percents
+        .iter()
+        .filter(|p| **p > 0.0 && config.goal > 0.0)
+        .map(|p| Threshold {
+            id: format!("percent-{}-of-{}", p, config.goal),
+            label: format!("{}% of the ${:.0} goal", p, config.goal),
+            amount: config.goal * p / 100.0,
+        })
+        .collect();
+    if settings.milestones {
+        thresholds.extend(config.milestones.iter().filter(|m| m.amount > 0.0).map(|m| Threshold {
+            id: format!("milestone-{}", m.amount),
+            label: format!("{} (${:.0})", m.label, m.amount),
+            amount: m.amount,
+        }));
+    }
+    thresholds.sort_by(|a, b| a.amount.total_cmp(&b.amount));
+    thresholds
+}
+
+/// Post the thresholds a save took the total past
+pub fn on_config_change(state: &AppState, previous: &ThermometerConfig, config: &ThermometerConfig) {
+    if !config.chat_announcements.enabled {
+        return;
+    }
+    let (before, after) = (previous.total_raised(), config.total_raised());
+    let crossed: Vec<Threshold> = thresholds(config)
+        .into_iter()
+        .filter(|t| before < t.amount && after >= t.amount)
+        .collect();
+    if crossed.is_empty() {
+        return;
+    }
+
+    let state = state.clone();
+    let config = config.clone();
+    tokio::spawn(async move {
+        if let Err(e) = announce(&state, &config, crossed).await {
+            tracing::error!("Failed to announce milestones: {}", e);
+        }
+    });
+}
+
+async fn announce(state: &AppState, config: &ThermometerConfig, crossed: Vec<Threshold>) -> Result<(), String> {
+    // Claimed with a create-only write before posting, so when two saves (or instances) cross the
+    // same threshold only one posts it; a threshold missed after a failed post beats one posted twice
+    let now = chrono::Utc::now().to_rfc3339();
+    let mut fresh = Vec::new();
+    for threshold in crossed {
+        let record = AnnouncedThreshold {
+            id: threshold.id.clone(),
+            label: threshold.label.clone(),
+            amount: threshold.amount,
+            total_raised: config.total_raised(),
+            announced_at: now.clone(),
+        };
+        let inserted = state
+            .storage
+            .insert_announced_threshold(&record)
+            .await
+            .map_err(|e| format!("Failed to record announced threshold {}: {}", record.id, e))?;
+        if inserted {
+            fresh.push(threshold);
+        }
+    }
+    if fresh.is_empty() {
+        return Ok(());
+    }
+
+    let labels: Vec<&str> = fresh.iter().map(|t| t.label.as_str()).collect();
+    let image = render_image(config).await;
+    if let Err(e) = &image {
+        tracing::warn!("Announcing milestones without the image: {}", e);
+    }
+    let image = image.ok();
+
+    let mut posted = false;
+    if let Some(url) = config.integrations.discord_webhook_url(state) {
+        let message = announcement_text(config, &labels, str::to_string);
+        match post_discord(state, &url, &message, image.as_deref()).await {
+            Ok(()) => posted = true,
+            Err(e) => tracing::error!("Failed to announce milestones on Discord: {}", e),
+        }
+    }
+    let message = announcement_text(config, &labels, slack_escape);
+    let slack = match (config.integrations.slack_bot_token(state), &config.integrations.slack_channel) {
+        (Some(token), Some(channel)) => Some(post_slack_file(state, &token, channel, &message, image.as_deref()).await),
+        _ => match config.integrations.slack_webhook_url(state) {
+            Some(url) => Some(post_slack_webhook(state, &url, &message).await),
+            None => None,
+        },
+    };
+    match slack {
+        Some(Ok(())) => posted = true,
+        Some(Err(e)) => tracing::error!("Failed to announce milestones on Slack: {}", e),
+        None => {}
+    }
+
+    if posted {
+        tracing::info!("Announced {}", labels.join(", "));
+    } else {
+        tracing::warn!("Crossed {} but no chat could be posted to", labels.join(", "));
+    }
+    Ok(())
+}
+
+/// e.g. `🎉 Animal Shelter Donation Drive just passed 50% of the $10000 goal! $5250 raised so far.`
+fn announcement_text(config: &ThermometerConfig, labels: &[&str], escape: impl Fn(&str) -> String) -> String {
+    format!(
+        "🎉 {} just passed {}! ${:.0} raised so far.",
+        escape(&config.title),
+        labels.iter().map(|l| escape(l)).collect::<Vec<_>>().join(" and "),
+        config.total_raised()
+    )
+}
+
+/// Slack treats `<...>` as links and mentions
+fn slack_escape(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;")
+}
+
+/// The thermometer as it stands, at the campaign's default width
+async fn render_image(config: &ThermometerConfig) -> Result<Vec<u8>, String> {
+    let config = config.clone();
+    tokio::task::spawn_blocking(move || {
+        let style = ThermometerStyle::new(config.image.default_width, config.chat_announcements.dark_mode);
+        let svg = generate_thermometer_svg(&config, &style, &[], &[])
+            .map_err(|e| format!("Failed to render thermometer: {}", e))?;
+        svg_to_png(&svg, IMAGE_SCALE)
+    })
+    .await
+    .map_err(|e| format!("Image rendering task failed: {}", e))?
+}
+
+async fn check_response(service: &str, response: reqwest::Response) -> Result<reqwest::Response, String> {
+    let status = response.status();
+    if status.is_success() {
+        return Ok(response);
+    }
+    let body = response.text().await.unwrap_or_default();
+    Err(format!("{} returned {}: {}", service, status, body.trim()))
+}
+
+/// A Discord webhook message with the image as an attachment. Mentions are switched off, so a team
+/// named `@everyone` can't ping the server.
+async fn post_discord(state: &AppState, url: &str, message: &str, image: Option<&[u8]>) -> Result<(), String> {
+    let payload = json!({ "content": message, "allowed_mentions": { "parse": [] } });
+    let request = state.http_client.post(url).timeout(POST_TIMEOUT);
+    let request = match image {
+        Some(image) => {
+            let file = reqwest::multipart::Part::bytes(image.to_vec())
+                .file_name(IMAGE_FILENAME)
+                .mime_str("image/png")
+                .map_err(|e| format!("Failed to attach image: {}", e))?;
+            let form = reqwest::multipart::Form::new()
+                .text("payload_json", payload.to_string())
+                .part("files[0]", file);
+            request.multipart(form)
+        }
+        None => request.json(&payload),
+    };
+    let response = request.send().await.map_err(|e| format!("Discord request failed: {}", e))?;
+    check_response("Discord", response).await?;
+    Ok(())
+}
+
+/// A text-only message through the Slack incoming webhook, which can't carry files
+async fn post_slack_webhook(state: &AppState, url: &str, message: &str) -> Result<(), String> {
+    let response = state
+        .http_client
+        .post(url)
+        .timeout(POST_TIMEOUT)
+        .json(&json!({ "text": message }))
+        .send()
+        .await
+        .map_err(|e| format!("Slack request failed: {}", e))?;
+    check_response("Slack", response).await?;
+    Ok(())
+}
+
+/// Call a Slack Web API method. Slack answers 200 with `ok: false` for most errors.
+async fn slack_api(request: reqwest::RequestBuilder, method: &str) -> Result<serde_json::Value, String> {
+    let response = request
+        .timeout(POST_TIMEOUT)
+        .send()
+        .await
+        .map_err(|e| format!("Slack {} failed: {}", method, e))?;
+    let body: serde_json::Value = check_response("Slack", response)
+        .await?
+        .json()
+        .await
+        .map_err(|e| format!("Slack {} returned an unreadable response: {}", method, e))?;
+    if body["ok"].as_bool() != Some(true) {
+        return Err(format!("Slack {} failed: {}", method, body["error"].as_str().unwrap_or("unknown error")));
+    }
+    Ok(body)
+}
+
+/// Upload the image to `channel` with the message as its comment, through the bot. Without an
+/// image, the message is posted on its own.
+async fn post_slack_file(
+    state: &AppState,
+    token: &str,
+    channel: &str,
+    message: &str,
+    image: Option<&[u8]>,
+) -> Result<(), String> {
+    let client = &state.http_client;
+    let Some(image) = image else {
+        let request = client
+            .post(format!("{}/chat.postMessage", SLACK_API))
+            .bearer_auth(token)
+            .json(&json!({ "channel": channel, "text": message }));
+        slack_api(request, "chat.postMessage").await?;
+        return Ok(());
+    };
+
+    let length = image.len().to_string();
+    let request = client
+        .post(format!("{}/files.getUploadURLExternal", SLACK_API))
+        .bearer_auth(token)
+        .form(&[("filename", IMAGE_FILENAME), ("length", length.as_str())]);
+    let upload = slack_api(request, "files.getUploadURLExternal").await?;
+    let (Some(upload_url), Some(file_id)) = (upload["upload_url"].as_str(), upload["file_id"].as_str()) else {
+        return Err("Slack files.getUploadURLExternal returned no upload URL".to_string());
+    };
+
+    let response = client
+        .post(upload_url)
+        .timeout(POST_TIMEOUT)
+        .body(image.to_vec())
+        .send()
+        .await
+        .map_err(|e| format!("Slack upload failed: {}", e))?;
+    check_response("Slack", response).await?;
+
+    let request = client
+        .post(format!("{}/files.completeUploadExternal", SLACK_API))
+        .bearer_auth(token)
+        .json(&json!({
+            "files": [{ "id": file_id, "title": "Thermometer" }],
+            "channel_id": channel,
+            "initial_comment": message,
+        }));
+    slack_api(request, "files.completeUploadExternal").await?;
+    Ok(())
+}
+
+fn storage_error(e: StorageError) -> (StatusCode, Json<ErrorResponse>) {
+    error_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to access announced thresholds: {}", e))
+}
+
+#[utoipa::path(
+    get,
+    path = "/admin/chat-announcements",
+    tag = "Admin",
+    security(("edit_key" = [])),
+    responses(
+        (status = 200, description = "Thresholds already posted to Slack and Discord, lowest first", body = [AnnouncedThreshold]),
+        (status = 401, description = "Unauthorized", body = ErrorResponse)
+    )
+)]
+pub async fn list_announced(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+) -> Result<Json<Vec<AnnouncedThreshold>>, (StatusCode, Json<ErrorResponse>)> {
+    require_admin(&headers, &state)?;
+
+    let mut announced = state.storage.list_announced_thresholds().await.map_err(storage_error)?;
+    announced.sort_by(|a, b| a.amount.total_cmp(&b.amount));
+    Ok(Json(announced))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/admin/chat-announcements/{id}",
+    tag = "Admin",
+    security(("edit_key" = [])),
+    params(("id" = String, Path, description = "Announced threshold ID, e.g. `percent-50-of-10000`")),
+    responses(
+        (status = 204, description = "Forgotten; the threshold is announced again the next time the total passes it"),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 404, description = "Threshold hasn't been announced", body = ErrorResponse)
+    )
+)]
+pub async fn forget_announced(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path(id): Path<String>,
+) -> Result<StatusCode, (StatusCode, Json<ErrorResponse>)> {
+    require_admin(&headers, &state)?;
+
+    let announced = state.storage.list_announced_thresholds().await.map_err(storage_error)?;
+    if !announced.iter().any(|a| a.id == id) {
+        return Err(error_response(StatusCode::NOT_FOUND, format!("Threshold hasn't been announced: {}", id)));
+    }
+    state.storage.delete_announced_threshold(&id).await.map_err(storage_error)?;
+
+    tracing::info!("Forgot announced threshold {}", id);
+    Ok(StatusCode::NO_CONTENT)
+}
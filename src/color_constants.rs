@@ -24,6 +24,10 @@ pub mod light {
     // Percentage markers
     pub const MARKER_STROKE: &str = "#888";
     pub const MARKER_TEXT: &str = "#888";
+
+    // Leaderboard silhouette for avatars that can't be fetched
+    pub const AVATAR_BACKGROUND: &str = "#E6E6E6";
+    pub const AVATAR_FIGURE: &str = "#B0B0B0";
 }
 
 // Dark mode colors
@@ -50,4 +54,42 @@ pub mod dark {
     // Percentage markers
     pub const MARKER_STROKE: &str = "#AAAAAA";
     pub const MARKER_TEXT: &str = "#AAAAAA";
+
+    // Leaderboard silhouette for avatars that can't be fetched
+    pub const AVATAR_BACKGROUND: &str = "#3A3A3A";
+    pub const AVATAR_FIGURE: &str = "#707070";
+}
+
+// Per-source colors for the stacked breakdown (shared by both themes)
+pub mod sources {
+    pub const STRIPE: &str = "#635BFF";
+    pub const PAYPAL: &str = "#0070BA";
+    pub const FACEBOOK: &str = "#1877F2";
+    pub const PHONEBANK: &str = "#F5A623";
+    pub const CASH: &str = "#2E8B57";
+    pub const MANUAL: &str = "#8FBC8F";
+    pub const SHEET_SYNC: &str = "#DC143C";
+    pub const ADJUSTMENT: &str = "#B0A8B9";
+    pub const OTHER: &str = "#9B9B9B";
+}
+
+// Announcement banner backgrounds by level, under white text (shared by both themes)
+pub mod announcement {
+    pub const INFO: &str = "#1976D2";
+    pub const SUCCESS: &str = "#2E7D32";
+    pub const WARNING: &str = "#E65100";
+    pub const URGENT: &str = "#DC143C";
+}
+
+// Gift-match countdown badge on thermometer images (shared by both themes)
+pub mod matching {
+    pub const BADGE: &str = "#F5A623";
+    pub const BADGE_TEXT: &str = "#1a1a1a";
+}
+
+// Heatmap calendar cells, from no donations to the busiest days (shared by both themes)
+pub mod heatmap {
+    pub const LEVELS: [&str; 5] = ["#EBEDF0", "#FFCDD2", "#FF8A80", "#FF5252", "#DC143C"];
+    /// Outline around the biggest day
+    pub const HIGHLIGHT: &str = "#4A4A4A";
 }
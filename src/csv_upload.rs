@@ -0,0 +1,173 @@
+use axum::{
+    body::Bytes,
+    extract::multipart::Field,
+    http::StatusCode,
+    response::Json,
+};
+use std::io::Read;
+use tokio::sync::mpsc;
+use utoipa::ToSchema;
+
+use crate::{error_response, ErrorResponse, Team};
+
+/// Chunks buffered between the upload and the parser; bounds memory to a few multipart chunks
+const CHUNK_QUEUE_DEPTH: usize = 4;
+
+/// Declared file types accepted for upload. Excel registers itself as the handler for `.csv` on
+/// Windows, so browsers there report CSV files as `application/vnd.ms-excel`.
+const ACCEPTED_CONTENT_TYPES: [&str; 4] = [
+    "text/csv",
+    "application/csv",
+    "text/comma-separated-values",
+    "application/vnd.ms-excel",
+];
+
+/// Multipart form accepted by `/admin/upload` (documentation only; the field is streamed)
+#[derive(ToSchema)]
+#[allow(dead_code)]
+pub struct CsvUploadForm {
+    /// CSV with `name,image_url,total_raised` columns, declared as `text/csv`
+    #[schema(value_type = String, format = Binary)]
+    file: Vec<u8>,
+}
+
+/// Limits for `/admin/upload`, read from `CSV_UPLOAD_MAX_BYTES` and `CSV_UPLOAD_MAX_ROWS`
+#[derive(Debug, Clone, Copy)]
+pub struct CsvUploadLimits {
+    pub max_bytes: usize,
+    pub max_rows: usize,
+}
+
+impl CsvUploadLimits {
+    pub fn from_env() -> Self {
+        let env_or = |name: &str, default: usize| {
+            std::env::var(name)
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(default)
+        };
+        Self {
+            max_bytes: env_or("CSV_UPLOAD_MAX_BYTES", 10 * 1024 * 1024), // 10MB
+            max_rows: env_or("CSV_UPLOAD_MAX_ROWS", 5000),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum CsvUploadError {
+    TooLarge(usize),
+    TooManyRows(usize),
+    UnsupportedType(String),
+    Invalid(String),
+}
+
+impl From<CsvUploadError> for (StatusCode, Json<ErrorResponse>) {
+    fn from(err: CsvUploadError) -> Self {
+        match err {
+            CsvUploadError::TooLarge(max) => error_response(
+                StatusCode::PAYLOAD_TOO_LARGE,
+                format!("CSV file exceeds the {} byte upload limit", max),
+            ),
+            CsvUploadError::TooManyRows(max) => error_response(
+                StatusCode::PAYLOAD_TOO_LARGE,
+                format!("CSV file has more than {} rows", max),
+            ),
+            CsvUploadError::UnsupportedType(content_type) => error_response(
+                StatusCode::UNSUPPORTED_MEDIA_TYPE,
+                format!("Unsupported file type '{}'; upload a .csv file (text/csv)", content_type),
+            ),
+            CsvUploadError::Invalid(msg) => error_response(StatusCode::BAD_REQUEST, msg),
+        }
+    }
+}
+
+/// Blocking `Read` over chunks handed across from the async upload stream
+struct ChunkReader {
+    chunks: mpsc::Receiver<Bytes>,
+    current: Bytes,
+}
+
+impl Read for ChunkReader {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        while self.current.is_empty() {
+            match self.chunks.blocking_recv() {
+                Some(chunk) => self.current = chunk,
+                None => return Ok(0),
+            }
+        }
+
+        let n = buf.len().min(self.current.len());
+        buf[..n].copy_from_slice(&self.current.split_to(n));
+        Ok(n)
+    }
+}
+
+fn parse_rows(reader: ChunkReader, max_rows: usize) -> Result<Vec<Team>, CsvUploadError> {
+    let mut reader = csv::Reader::from_reader(reader);
+    let mut teams: Vec<Team> = Vec::new();
+
+    for result in reader.deserialize() {
+        if teams.len() >= max_rows {
+            return Err(CsvUploadError::TooManyRows(max_rows));
+        }
+        let team: Team = result.map_err(|e| CsvUploadError::Invalid(format!("Failed to parse CSV: {}", e)))?;
+        teams.push(team);
+    }
+
+    Ok(teams)
+}
+
+/// Reject file parts that don't declare a CSV content type
+fn check_content_type(field: &Field<'_>) -> Result<(), CsvUploadError> {
+    let declared = field.content_type().unwrap_or("none");
+    // Ignore parameters such as "; charset=utf-8"
+    let essence = declared.split(';').next().unwrap_or_default().trim().to_ascii_lowercase();
+
+    if ACCEPTED_CONTENT_TYPES.contains(&essence.as_str()) {
+        Ok(())
+    } else {
+        Err(CsvUploadError::UnsupportedType(declared.to_string()))
+    }
+}
+
+/// Stream a multipart file field through the CSV parser row by row. The parser runs on a blocking
+/// thread fed by a small channel, so the whole file is never held in memory and a bad row or the row
+/// limit stops the upload early.
+pub async fn read_teams(mut field: Field<'_>, limits: CsvUploadLimits) -> Result<Vec<Team>, CsvUploadError> {
+    check_content_type(&field)?;
+
+    let (tx, rx) = mpsc::channel::<Bytes>(CHUNK_QUEUE_DEPTH);
+    let parser = tokio::task::spawn_blocking(move || {
+        parse_rows(ChunkReader { chunks: rx, current: Bytes::new() }, limits.max_rows)
+    });
+
+    let mut received = 0usize;
+    let mut upload_result = Ok(());
+    loop {
+        match field.chunk().await {
+            Ok(Some(chunk)) => {
+                received += chunk.len();
+                if received > limits.max_bytes {
+                    upload_result = Err(CsvUploadError::TooLarge(limits.max_bytes));
+                    break;
+                }
+                // A closed channel means the parser already stopped on an error
+                if tx.send(chunk).await.is_err() {
+                    break;
+                }
+            }
+            Ok(None) => break,
+            Err(e) => {
+                upload_result = Err(CsvUploadError::Invalid(format!("Failed to read file data: {}", e)));
+                break;
+            }
+        }
+    }
+    drop(tx);
+
+    let parsed = parser
+        .await
+        .map_err(|e| CsvUploadError::Invalid(format!("CSV parser failed: {}", e)))?;
+    upload_result?;
+    parsed
+}
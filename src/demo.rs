@@ -0,0 +1,155 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use crate::ledger::{Donation, DonationSource, ModerationStatus, PaymentMethod};
+use crate::storage::{ConfigStorage, StorageError};
+use crate::{Team, ThermometerConfig};
+
+/// Days of donation history to generate
+const HISTORY_DAYS: i64 = 30;
+
+static ACTIVE: AtomicBool = AtomicBool::new(false);
+
+/// Whether the server is running with seeded demo data; pages show a banner when it is
+pub fn is_active() -> bool {
+    ACTIVE.load(Ordering::Relaxed)
+}
+
+/// Demo mode is enabled with `--demo` or `DEMO_MODE=1`
+pub fn requested() -> bool {
+    std::env::args().skip(1).any(|arg| arg == "--demo")
+        || std::env::var("DEMO_MODE").is_ok_and(|v| v == "1" || v.eq_ignore_ascii_case("true"))
+}
+
+/// Team names and the group each belongs to
+const TEAMS: [(&str, &str); 6] = [
+    ("Kitten Cuddlers", "Volunteers"),
+    ("Dog Walkers", "Volunteers"),
+    ("Front Desk Crew", "Staff"),
+    ("Foster Families", "Volunteers"),
+    ("Weekend Warriors", "Volunteers"),
+    ("Board of Directors", "Staff"),
+];
+
+const DONORS: [&str; 16] = [
+    "Maria Gonzalez",
+    "James Whitfield",
+    "Priya Raman",
+    "The Okafor Family",
+    "Sam Lee",
+    "Hannah Brooks",
+    "Diego Alvarez",
+    "Corner Bakery",
+    "Evelyn Park",
+    "Marcus Hill",
+    "Grace Nakamura",
+    "Tom & Rita Novak",
+    "Aisha Bello",
+    "Paws & Claws Pet Supply",
+    "Oliver Grant",
+    "Lucy Fernandez",
+];
+
+const MESSAGES: [&str; 6] = [
+    "For all the good boys and girls waiting for a home!",
+    "In memory of Biscuit, the best cat ever.",
+    "Thank you for everything you do.",
+    "Adopted our pup here last spring. Happy to give back!",
+    "Go team!",
+    "Every animal deserves a warm bed.",
+];
+
+const AMOUNTS: [f64; 10] = [10.0, 20.0, 25.0, 25.0, 50.0, 50.0, 75.0, 100.0, 150.0, 250.0];
+
+/// Small deterministic generator so every demo run shows the same campaign
+struct Lcg(u64);
+
+impl Lcg {
+    fn next(&mut self, bound: usize) -> usize {
+        self.0 = self.0.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+        ((self.0 >> 33) % bound as u64) as usize
+    }
+}
+
+/// Fill `storage` with a realistic campaign: teams, a month of donations that picks up toward
+/// the end (the usual campaign curve), named and anonymous donors, and approved messages
+pub async fn seed(storage: &dyn ConfigStorage) -> Result<(), StorageError> {
+    ACTIVE.store(true, Ordering::Relaxed);
+
+    let mut rng = Lcg(2024);
+    let now = chrono::Utc::now();
+    let mut totals = [0.0; TEAMS.len()];
+    let mut count = 0;
+
+    for day in 0..HISTORY_DAYS {
+        // A few gifts a day at the start, ramping up as the deadline approaches
+        let gifts = 2 + (day as usize * day as usize) / 60 + rng.next(3);
+        for _ in 0..gifts {
+            let team = rng.next(TEAMS.len());
+            let amount = AMOUNTS[rng.next(AMOUNTS.len())];
+            let created_at = now - chrono::Duration::days(HISTORY_DAYS - 1 - day)
+                - chrono::Duration::minutes(rng.next(12 * 60) as i64);
+            let offline = rng.next(5) == 0;
+            let message = (rng.next(4) == 0).then(|| MESSAGES[rng.next(MESSAGES.len())].to_string());
+
+            storage
+                .append_donation(&Donation {
+                    id: uuid::Uuid::new_v4().to_string(),
+                    team: TEAMS[team].0.to_string(),
+                    amount,
+                    donor_name: Some(DONORS[rng.next(DONORS.len())].to_string()),
+                    donor_email: None,
+                    source: if offline { DonationSource::Cash } else { DonationSource::Stripe },
+                    created_at: created_at.to_rfc3339(),
+                    pledge_id: None,
+                    method: offline.then_some(PaymentMethod::Cash),
+                    moderation: if message.is_some() { ModerationStatus::Approved } else { ModerationStatus::Pending },
+                    message,
+                    anonymous: Some(rng.next(6) == 0),
+                    display_name: None,
+                    caller: None,
+                    tax_deductible: None,
+                    gift_aid: None,
+                    note: None,
+                })
+                .await?;
+            totals[team] += amount;
+            count += 1;
+        }
+    }
+
+    let config = ThermometerConfig {
+        organization_name: "Happy Tails Animal Rescue (Demo)".to_string(),
+        title: "Spring Adoption Drive".to_string(),
+        goal: 25000.0,
+        teams: TEAMS
+            .iter()
+            .zip(totals)
+            .map(|((name, group), total_raised)| Team {
+                name: name.to_string(),
+                image_url: None,
+                total_raised,
+                deleted_at: None,
+                sort_order: None,
+                last_changed: None,
+                goal: None,
+                group: Some(group.to_string()),
+                members: None,
+                score_multiplier: None,
+                color: None,
+                captain_name: None,
+                captain_email: None,
+                captain_phone: None,
+            })
+            .collect(),
+        last_updated: now.to_rfc3339(),
+        ..ThermometerConfig::default()
+    };
+    storage.save_config(&config).await?;
+
+    tracing::info!(
+        "Seeded demo campaign with {} donations totalling ${:.2}",
+        count,
+        config.total_raised()
+    );
+    Ok(())
+}
@@ -0,0 +1,242 @@
+use askama::Template;
+use chrono::{NaiveDate, NaiveTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Arc;
+use tokio::sync::Mutex;
+use utoipa::ToSchema;
+
+use crate::email::{InlineImage, OutgoingEmail};
+use crate::scheduler::Job;
+use crate::thermometer::{generate_thermometer_svg, svg_to_png, SizePreset, ThermometerStyle};
+use crate::{AppState, ThermometerConfig};
+
+const TOP_MOVERS: usize = 5;
+const THERMOMETER_CID: &str = "thermometer";
+
+/// Daily digest email settings
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[serde(default)]
+pub struct DigestSettings {
+    enabled: bool,
+    pub recipients: Vec<String>,
+    /// Local send time in 24-hour `HH:MM` format
+    send_at: String,
+    /// IANA timezone used to interpret `send_at` (e.g. `America/Chicago`); defaults to the
+    /// campaign timezone
+    #[serde(skip_serializing_if = "Option::is_none")]
+    timezone: Option<String>,
+}
+
+impl Default for DigestSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            recipients: vec![],
+            send_at: "08:00".to_string(),
+            timezone: None,
+        }
+    }
+}
+
+impl DigestSettings {
+    /// Returns today's local date if the digest is due (enabled and past the send time).
+    /// `campaign_tz` applies when the digest has no timezone of its own.
+    fn due_date(&self, campaign_tz: chrono_tz::Tz) -> Option<NaiveDate> {
+        if !self.enabled || self.recipients.is_empty() {
+            return None;
+        }
+
+        let send_at = NaiveTime::parse_from_str(&self.send_at, "%H:%M")
+            .map_err(|e| tracing::warn!("Invalid digest send_at {:?}: {}", self.send_at, e))
+            .ok()?;
+        let timezone = match &self.timezone {
+            Some(name) => name
+                .parse::<chrono_tz::Tz>()
+                .map_err(|e| tracing::warn!("Invalid digest timezone {:?}: {}", name, e))
+                .ok()?,
+            None => campaign_tz,
+        };
+
+        let now = Utc::now().with_timezone(&timezone);
+        (now.time() >= send_at).then(|| now.date_naive())
+    }
+}
+
+/// Totals captured when the previous digest was sent, used to compute deltas
+struct Baseline {
+    total: f64,
+    teams: HashMap<String, f64>,
+}
+
+impl Baseline {
+    fn from_config(config: &ThermometerConfig) -> Self {
+        Self {
+            total: config.total_raised(),
+            teams: config
+                .active_teams()
+                .map(|t| (t.name.clone(), t.total_raised))
+                .collect(),
+        }
+    }
+}
+
+struct Mover {
+    name: String,
+    delta: String,
+    total: String,
+}
+
+#[derive(Template)]
+#[template(path = "digest-email.html")]
+struct DigestEmailTemplate {
+    organization_name: String,
+    title: String,
+    date: String,
+    total_raised: String,
+    goal: String,
+    progress_percent: String,
+    delta: String,
+    movers: Vec<Mover>,
+    image_cid: &'static str,
+}
+
+/// Digest progress kept between scheduler runs
+struct DigestState {
+    baseline: Option<Baseline>,
+    last_sent: Option<NaiveDate>,
+    first_check: bool,
+}
+
+/// Scheduled job that sends the daily digest email. It checks every minute so `send_at` and
+/// `timezone` in the digest settings keep deciding when the email goes out.
+pub fn job() -> Job {
+    let digest = Arc::new(Mutex::new(DigestState {
+        baseline: None,
+        last_sent: None,
+        first_check: true,
+    }));
+    Job::new("daily-digest", "* * * * *", move |state| {
+        let digest = digest.clone();
+        async move { run_digest(&state, &mut *digest.lock().await).await }
+    })
+}
+
+async fn run_digest(state: &AppState, digest: &mut DigestState) -> Result<String, String> {
+    let config = state
+        .storage
+        .load_config()
+        .await
+        .map_err(|e| format!("Failed to load config for daily digest: {}", e))?;
+
+    let previous = digest.baseline.get_or_insert_with(|| Baseline::from_config(&config));
+
+    let Some(today) = config.digest.due_date(config.tz()) else {
+        digest.first_check = false;
+        return Ok("Not due".to_string());
+    };
+
+    // Don't resend today's digest just because the server restarted after the send time
+    if digest.first_check {
+        digest.first_check = false;
+        digest.last_sent = Some(today);
+        return Ok("Skipped today's digest after restart".to_string());
+    }
+
+    if digest.last_sent == Some(today) {
+        return Ok("Already sent today".to_string());
+    }
+
+    // Only attempt once per day; failures are reported rather than retried every minute
+    digest.last_sent = Some(today);
+    send_digest(state, &config, previous, today).await?;
+    digest.baseline = Some(Baseline::from_config(&config));
+
+    let message = format!("Sent daily digest to {} recipient(s)", config.digest.recipients.len());
+    tracing::info!("{}", message);
+    Ok(message)
+}
+
+async fn send_digest(
+    state: &AppState,
+    config: &ThermometerConfig,
+    baseline: &Baseline,
+    date: NaiveDate,
+) -> Result<(), String> {
+    let mailer = state
+        .mailer
+        .as_ref()
+        .ok_or_else(|| "Email is not configured (SMTP_HOST not set)".to_string())?;
+
+    let total_raised = config.total_raised();
+    let delta = total_raised - baseline.total;
+
+    let mut movers: Vec<(&str, f64, f64)> = config
+        .active_teams()
+        .map(|t| {
+            let before = baseline.teams.get(&t.name).copied().unwrap_or(0.0);
+            (t.name.as_str(), t.total_raised - before, t.total_raised)
+        })
+        .filter(|(_, delta, _)| *delta > 0.0)
+        .collect();
+    movers.sort_by(|a, b| b.1.total_cmp(&a.1));
+    movers.truncate(TOP_MOVERS);
+
+    let template = DigestEmailTemplate {
+        organization_name: config.organization_name.clone(),
+        title: config.title.clone(),
+        date: date.format("%A, %B %-d, %Y").to_string(),
+        total_raised: format!("{:.2}", total_raised),
+        goal: format!("{:.2}", config.goal),
+        progress_percent: format!("{:.0}", config.progress_percent()),
+        delta: format!("{:.2}", delta),
+        movers: movers
+            .iter()
+            .map(|(name, delta, total)| Mover {
+                name: name.to_string(),
+                delta: format!("{:.2}", delta),
+                total: format!("{:.2}", total),
+            })
+            .collect(),
+        image_cid: THERMOMETER_CID,
+    };
+
+    let html_body = template
+        .render()
+        .map_err(|e| format!("Failed to render digest template: {}", e))?;
+
+    let mut text_body = format!(
+        "{} - {}\n\nTotal raised: ${:.2} of ${:.2} ({:.0}%)\nSince the last digest: ${:.2}\n",
+        config.organization_name,
+        config.title,
+        total_raised,
+        config.goal,
+        config.progress_percent(),
+        delta
+    );
+    if !movers.is_empty() {
+        text_body.push_str("\nTop movers:\n");
+        for (name, delta, total) in &movers {
+            text_body.push_str(&format!("  {}: +${:.2} (now ${:.2})\n", name, delta, total));
+        }
+    }
+
+    let (width, scale) = SizePreset::Email.dimensions();
+    let svg = generate_thermometer_svg(config, &ThermometerStyle::new(width, false), &[], &[])
+        .map_err(|e| format!("Failed to render thermometer: {}", e))?;
+    let png_data = svg_to_png(&svg, scale)?;
+
+    mailer
+        .send(OutgoingEmail {
+            to: config.digest.recipients.clone(),
+            subject: format!("{} daily digest - {}", config.title, date.format("%b %-d")),
+            html_body,
+            text_body,
+            inline_images: vec![InlineImage {
+                content_id: THERMOMETER_CID.to_string(),
+                content_type: "image/png".to_string(),
+                data: png_data,
+            }],
+        })
+        .await
+}
@@ -0,0 +1,153 @@
+use askama::Template;
+use axum::{
+    extract::{Query, State},
+    http::{HeaderMap, StatusCode},
+    response::Json,
+};
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+
+use crate::ledger::{Donation, ModerationStatus};
+use crate::render::{Page, PageError};
+use crate::{error_response, require_admin, AppState, ErrorResponse};
+
+const RECENT_DONOR_LIMIT: usize = 20;
+
+/// Campaign-level defaults for how donors appear publicly
+#[derive(Debug, Clone, Default, Serialize, Deserialize, ToSchema)]
+#[serde(default)]
+pub struct DonorDisplaySettings {
+    /// Treat donors as anonymous unless they opt in to showing their name
+    pub anonymous_by_default: bool,
+}
+
+struct DonorEntry {
+    name: String,
+    team: String,
+    amount: String,
+    message: Option<String>,
+}
+
+#[derive(Template)]
+#[template(path = "donors.html")]
+pub struct DonorsTemplate {
+    organization_name: String,
+    title: String,
+    donors: Vec<DonorEntry>,
+}
+
+/// Public donor wall: most recent donors, with messages only once approved
+pub async fn donors_page(State(state): State<AppState>) -> Result<Page<DonorsTemplate>, PageError> {
+    let config = state.storage.load_config().await.map_err(PageError::internal)?;
+    let donations = state.storage.list_donations().await.map_err(PageError::internal)?;
+
+    // Spreadsheet adjustments aren't gifts from a donor
+    let donors = donations
+        .iter()
+        .rev()
+        .filter(|d| d.source.is_gift())
+        .take(RECENT_DONOR_LIMIT)
+        .map(|d| DonorEntry {
+            name: d.public_name(&config.donors),
+            team: d.team.clone(),
+            amount: format!("{:.2}", d.amount),
+            message: d.public_message().map(String::from),
+        })
+        .collect();
+
+    Ok(Page(DonorsTemplate {
+        organization_name: config.organization_name,
+        title: config.title,
+        donors,
+    }))
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct ModerationQuery {
+    /// Defaults to `pending`
+    status: Option<ModerationStatus>,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct ModerationDecision {
+    /// Donation ID
+    id: String,
+    status: ModerationStatus,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct ModerationResponse {
+    message: String,
+    donation: Donation,
+}
+
+#[utoipa::path(
+    get,
+    path = "/admin/moderation",
+    tag = "Admin",
+    security(("edit_key" = [])),
+    params(ModerationQuery),
+    responses(
+        (status = 200, description = "Donations with messages in the requested state", body = [Donation]),
+        (status = 401, description = "Unauthorized", body = ErrorResponse)
+    )
+)]
+pub async fn moderation_queue(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Query(query): Query<ModerationQuery>,
+) -> Result<Json<Vec<Donation>>, (StatusCode, Json<ErrorResponse>)> {
+    require_admin(&headers, &state)?;
+
+    let status = query.status.unwrap_or(ModerationStatus::Pending);
+    let donations = state.storage.list_donations().await.map_err(|e| {
+        error_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to load donations: {}", e))
+    })?;
+
+    Ok(Json(
+        donations
+            .into_iter()
+            .filter(|d| d.message.is_some() && d.moderation == status)
+            .collect(),
+    ))
+}
+
+#[utoipa::path(
+    post,
+    path = "/admin/moderation",
+    tag = "Admin",
+    security(("edit_key" = [])),
+    request_body = ModerationDecision,
+    responses(
+        (status = 200, description = "Moderation state updated", body = ModerationResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 404, description = "Donation not found", body = ErrorResponse)
+    )
+)]
+pub async fn moderate_message(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Json(decision): Json<ModerationDecision>,
+) -> Result<Json<ModerationResponse>, (StatusCode, Json<ErrorResponse>)> {
+    require_admin(&headers, &state)?;
+
+    let donations = state.storage.list_donations().await.map_err(|e| {
+        error_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to load donations: {}", e))
+    })?;
+
+    let mut donation = donations
+        .into_iter()
+        .find(|d| d.id == decision.id)
+        .ok_or_else(|| error_response(StatusCode::NOT_FOUND, "Donation not found"))?;
+
+    donation.moderation = decision.status;
+    state.storage.update_donation(&donation).await.map_err(|e| {
+        error_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to save donation: {}", e))
+    })?;
+
+    tracing::info!("Donation {} message marked {:?}", donation.id, donation.moderation);
+    Ok(Json(ModerationResponse {
+        message: "Moderation state updated".to_string(),
+        donation,
+    }))
+}
@@ -0,0 +1,107 @@
+use lettre::message::{header::ContentType, Attachment, Mailbox, MultiPart, SinglePart};
+use lettre::transport::smtp::authentication::Credentials;
+use lettre::{AsyncSmtpTransport, AsyncTransport, Message, Tokio1Executor};
+
+/// An inline image embedded in an HTML email and referenced via `cid:<content_id>`
+pub struct InlineImage {
+    pub content_id: String,
+    pub content_type: String,
+    pub data: Vec<u8>,
+}
+
+/// A single outgoing email
+pub struct OutgoingEmail {
+    pub to: Vec<String>,
+    pub subject: String,
+    pub html_body: String,
+    pub text_body: String,
+    pub inline_images: Vec<InlineImage>,
+}
+
+/// SMTP mailer configured from environment variables
+pub struct Mailer {
+    transport: AsyncSmtpTransport<Tokio1Executor>,
+    from: Mailbox,
+}
+
+impl Mailer {
+    /// Build a mailer from `SMTP_HOST`, `SMTP_PORT`, `SMTP_USERNAME`, `SMTP_PASSWORD` and `EMAIL_FROM`.
+    /// Returns `None` when `SMTP_HOST` is not set (email features are disabled).
+    pub fn from_env() -> Option<Self> {
+        let host = std::env::var("SMTP_HOST").ok()?;
+
+        let from = match std::env::var("EMAIL_FROM")
+            .unwrap_or_else(|_| "thermometer@localhost".to_string())
+            .parse::<Mailbox>()
+        {
+            Ok(mailbox) => mailbox,
+            Err(e) => {
+                tracing::error!("Invalid EMAIL_FROM address, email disabled: {}", e);
+                return None;
+            }
+        };
+
+        let mut builder = match AsyncSmtpTransport::<Tokio1Executor>::starttls_relay(&host) {
+            Ok(builder) => builder,
+            Err(e) => {
+                tracing::error!("Failed to configure SMTP relay {}, email disabled: {}", host, e);
+                return None;
+            }
+        };
+
+        if let Some(port) = std::env::var("SMTP_PORT").ok().and_then(|p| p.parse().ok()) {
+            builder = builder.port(port);
+        }
+
+        if let (Ok(username), Ok(password)) = (
+            std::env::var("SMTP_USERNAME"),
+            std::env::var("SMTP_PASSWORD"),
+        ) {
+            builder = builder.credentials(Credentials::new(username, password));
+        }
+
+        tracing::info!("Email enabled via SMTP relay {}", host);
+        Some(Self {
+            transport: builder.build(),
+            from,
+        })
+    }
+
+    /// Send an email to every recipient in `email.to`
+    pub async fn send(&self, email: OutgoingEmail) -> Result<(), String> {
+        let mut builder = Message::builder()
+            .from(self.from.clone())
+            .subject(email.subject);
+
+        for recipient in &email.to {
+            let mailbox = recipient
+                .parse::<Mailbox>()
+                .map_err(|e| format!("Invalid recipient {}: {}", recipient, e))?;
+            builder = builder.to(mailbox);
+        }
+
+        let mut related = MultiPart::related().singlepart(SinglePart::html(email.html_body));
+        for image in email.inline_images {
+            let content_type = ContentType::parse(&image.content_type)
+                .map_err(|e| format!("Invalid content type {}: {}", image.content_type, e))?;
+            related = related.singlepart(
+                Attachment::new_inline(image.content_id).body(image.data, content_type),
+            );
+        }
+
+        let body = MultiPart::alternative()
+            .singlepart(SinglePart::plain(email.text_body))
+            .multipart(related);
+
+        let message = builder
+            .multipart(body)
+            .map_err(|e| format!("Failed to build email: {}", e))?;
+
+        self.transport
+            .send(message)
+            .await
+            .map_err(|e| format!("Failed to send email: {}", e))?;
+
+        Ok(())
+    }
+}
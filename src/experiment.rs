@@ -0,0 +1,213 @@
+use axum::{
+    extract::State,
+    http::{HeaderMap, StatusCode},
+    response::Json,
+};
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use utoipa::ToSchema;
+
+use crate::{error_response, require_admin, AppState, ErrorResponse};
+
+/// Two framings of the thermometer image shown to different viewers, to see which one sends more
+/// people to the donate page
+#[derive(Debug, Clone, Default, Serialize, Deserialize, ToSchema)]
+#[serde(default)]
+pub struct ExperimentSettings {
+    pub enabled: bool,
+    pub a: RenderVariant,
+    pub b: RenderVariant,
+}
+
+impl ExperimentSettings {
+    pub fn variant(&self, variant: Variant) -> &RenderVariant {
+        match variant {
+            Variant::A => &self.a,
+            Variant::B => &self.b,
+        }
+    }
+}
+
+/// How one variant changes the thermometer image; unset fields render as usual
+#[derive(Debug, Clone, Default, Serialize, Deserialize, ToSchema)]
+#[serde(default)]
+pub struct RenderVariant {
+    /// Replaces "Our Goal" at the top of the image, e.g. `Help us reach 500 adoptions`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub headline: Option<String>,
+    /// Overrides the light or dark theme of the requested image
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub theme: Option<Theme>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum Theme {
+    Light,
+    Dark,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum Variant {
+    A,
+    B,
+}
+
+impl Variant {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Variant::A => "a",
+            Variant::B => "b",
+        }
+    }
+
+    fn index(self) -> usize {
+        match self {
+            Variant::A => 0,
+            Variant::B => 1,
+        }
+    }
+}
+
+/// The variant a viewer sees: the one they asked for with `variant=`, otherwise picked from a
+/// hash of their address and browser so the same viewer keeps seeing the same one
+pub fn assign(requested: Option<Variant>, headers: &HeaderMap) -> Variant {
+    use sha2::{Digest, Sha256};
+    if let Some(variant) = requested {
+        return variant;
+    }
+    let header = |name: &str| headers.get(name).and_then(|v| v.to_str().ok()).unwrap_or("");
+    let address = header("x-forwarded-for").split(',').next().unwrap_or("").trim();
+    let digest = Sha256::digest(format!("{}\0{}", address, header("user-agent")));
+    if digest[0] % 2 == 0 {
+        Variant::A
+    } else {
+        Variant::B
+    }
+}
+
+/// Impressions and donate-page visits per variant since the server started or the counts were
+/// reset. Kept in memory, like job run history.
+pub struct Counters {
+    impressions: [AtomicU64; 2],
+    clicks: [AtomicU64; 2],
+    since: Mutex<String>,
+}
+
+impl Default for Counters {
+    fn default() -> Self {
+        Self {
+            impressions: Default::default(),
+            clicks: Default::default(),
+            since: Mutex::new(chrono::Utc::now().to_rfc3339()),
+        }
+    }
+}
+
+impl Counters {
+    pub fn record_impression(&self, variant: Variant) {
+        self.impressions[variant.index()].fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn record_click(&self, variant: Variant) {
+        self.clicks[variant.index()].fetch_add(1, Ordering::Relaxed);
+    }
+
+    fn reset(&self) {
+        for counter in self.impressions.iter().chain(self.clicks.iter()) {
+            counter.store(0, Ordering::Relaxed);
+        }
+        *self.since.lock().unwrap() = chrono::Utc::now().to_rfc3339();
+    }
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ExperimentReport {
+    pub enabled: bool,
+    /// When counting started (server start or the last reset)
+    pub since: String,
+    pub variants: Vec<VariantReport>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct VariantReport {
+    pub variant: Variant,
+    #[serde(flatten)]
+    pub settings: RenderVariant,
+    /// Image loads through the root image routes
+    pub impressions: u64,
+    /// Donate page visits from viewers assigned this variant
+    pub donate_clicks: u64,
+    /// `donate_clicks` per 100 impressions, rounded to two decimals
+    pub click_through_percent: f64,
+}
+
+#[utoipa::path(
+    get,
+    path = "/admin/experiment",
+    tag = "Admin",
+    security(("edit_key" = [])),
+    responses(
+        (status = 200, description = "Impressions, donate page visits and click-through per variant", body = ExperimentReport),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 500, description = "Failed to load config", body = ErrorResponse)
+    )
+)]
+pub async fn get_experiment(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+) -> Result<Json<ExperimentReport>, (StatusCode, Json<ErrorResponse>)> {
+    require_admin(&headers, &state)?;
+
+    let config = state.storage.load_config().await.map_err(|e| {
+        error_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to load config: {}", e))
+    })?;
+    let counters = &state.experiment;
+    let variants = [Variant::A, Variant::B]
+        .into_iter()
+        .map(|variant| {
+            let impressions = counters.impressions[variant.index()].load(Ordering::Relaxed);
+            let donate_clicks = counters.clicks[variant.index()].load(Ordering::Relaxed);
+            let click_through_percent = if impressions > 0 {
+                (donate_clicks as f64 / impressions as f64 * 10000.0).round() / 100.0
+            } else {
+                0.0
+            };
+            VariantReport {
+                variant,
+                settings: config.experiment.variant(variant).clone(),
+                impressions,
+                donate_clicks,
+                click_through_percent,
+            }
+        })
+        .collect();
+
+    Ok(Json(ExperimentReport {
+        enabled: config.experiment.enabled,
+        since: counters.since.lock().unwrap().clone(),
+        variants,
+    }))
+}
+
+#[utoipa::path(
+    post,
+    path = "/admin/experiment/reset",
+    tag = "Admin",
+    security(("edit_key" = [])),
+    responses(
+        (status = 204, description = "Counts cleared"),
+        (status = 401, description = "Unauthorized", body = ErrorResponse)
+    )
+)]
+pub async fn reset_experiment(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+) -> Result<StatusCode, (StatusCode, Json<ErrorResponse>)> {
+    require_admin(&headers, &state)?;
+    state.experiment.reset();
+    tracing::info!("Reset experiment counts");
+    Ok(StatusCode::NO_CONTENT)
+}
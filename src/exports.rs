@@ -0,0 +1,428 @@
+use axum::{
+    extract::{Query, State},
+    http::{HeaderMap, StatusCode},
+    response::Json,
+};
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashSet};
+use utoipa::ToSchema;
+
+use crate::ledger::{Donation, RecurringPledge};
+use crate::presets::CampaignPreset;
+use crate::scheduler::Job;
+use crate::versions::{ChangeNote, ConfigVersion};
+use crate::{commit_config, error_response, require_admin, AppState, ErrorResponse, ThermometerConfig};
+
+const STORAGE_SCOPE: &str = "https://www.googleapis.com/auth/devstorage.read_write";
+const GCS_HOST: &str = "https://storage.googleapis.com";
+const DEFAULT_RETENTION_DAYS: i64 = 30;
+
+/// Bucket that daily exports are written to, from `EXPORT_BUCKET` and `EXPORT_RETENTION_DAYS`
+#[derive(Debug, Clone)]
+pub struct ExportBucket {
+    bucket: String,
+    /// Object name prefix; tenants export under `exports/tenants/<slug>/` and campaigns under
+    /// `exports/campaigns/<id>/`
+    prefix: String,
+    retention_days: i64,
+}
+
+impl ExportBucket {
+    /// Returns `None` when `EXPORT_BUCKET` is not set (exports are disabled)
+    pub fn from_env() -> Option<Self> {
+        let bucket = std::env::var("EXPORT_BUCKET").ok().filter(|b| !b.trim().is_empty())?;
+        let retention_days = std::env::var("EXPORT_RETENTION_DAYS")
+            .ok()
+            .and_then(|d| d.parse().ok())
+            .filter(|d| *d > 0)
+            .unwrap_or(DEFAULT_RETENTION_DAYS);
+        Some(Self {
+            bucket: bucket.trim().to_string(),
+            prefix: "exports/".to_string(),
+            retention_days,
+        })
+    }
+
+    pub fn for_tenant(&self, slug: &str) -> Self {
+        Self {
+            prefix: format!("{}tenants/{}/", self.prefix, slug),
+            ..self.clone()
+        }
+    }
+
+    pub fn for_campaign(&self, id: &str) -> Self {
+        Self {
+            prefix: format!("{}campaigns/{}/", self.prefix, id),
+            ..self.clone()
+        }
+    }
+
+    fn object_name(&self, date: NaiveDate) -> String {
+        format!("{}{}.json", self.prefix, date)
+    }
+
+    /// Export date encoded in an object name, or `None` for objects that aren't exports
+    fn export_date(&self, name: &str) -> Option<NaiveDate> {
+        let date = name.strip_prefix(&self.prefix)?.strip_suffix(".json")?;
+        NaiveDate::parse_from_str(date, "%Y-%m-%d").ok()
+    }
+}
+
+/// Everything needed to rebuild a campaign after data loss
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CampaignExport {
+    pub exported_at: String,
+    pub config: ThermometerConfig,
+    #[serde(default)]
+    pub versions: Vec<ConfigVersion>,
+    #[serde(default)]
+    pub donations: Vec<Donation>,
+    #[serde(default)]
+    pub pledges: Vec<RecurringPledge>,
+    #[serde(default)]
+    pub presets: Vec<CampaignPreset>,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct RestoreRequest {
+    /// Date of the export to restore (`YYYY-MM-DD`); omit to use the most recent one
+    date: Option<String>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct RestoreSummary {
+    /// Bucket object the data was restored from
+    pub object: String,
+    pub exported_at: String,
+    pub donations: usize,
+    pub pledges: usize,
+    pub presets: usize,
+    pub versions: usize,
+}
+
+/// Minimal Cloud Storage JSON API client. Honors `STORAGE_EMULATOR_HOST` for local testing.
+struct GcsClient<'a> {
+    http: &'a reqwest::Client,
+    host: String,
+    token: Option<String>,
+    bucket: &'a str,
+}
+
+#[derive(Deserialize)]
+struct ObjectList {
+    #[serde(default)]
+    items: Vec<ObjectItem>,
+    #[serde(rename = "nextPageToken")]
+    next_page_token: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct ObjectItem {
+    name: String,
+}
+
+impl<'a> GcsClient<'a> {
+    async fn connect(http: &'a reqwest::Client, bucket: &'a str) -> Result<Self, String> {
+        if let Ok(host) = std::env::var("STORAGE_EMULATOR_HOST") {
+            return Ok(Self {
+                http,
+                host: host.trim_end_matches('/').to_string(),
+                token: None,
+                bucket,
+            });
+        }
+
+        let provider = gcp_auth::provider()
+            .await
+            .map_err(|e| format!("No Google Cloud credentials for exports: {}", e))?;
+        let token = provider
+            .token(&[STORAGE_SCOPE])
+            .await
+            .map_err(|e| format!("Failed to get Cloud Storage access token: {}", e))?;
+        Ok(Self {
+            http,
+            host: GCS_HOST.to_string(),
+            token: Some(token.as_str().to_string()),
+            bucket,
+        })
+    }
+
+    /// `<host>/<base>/b/<bucket>/o[/<object>]`, with the object name escaped as a single segment
+    fn url(&self, base: &str, object: Option<&str>) -> Result<reqwest::Url, String> {
+        let mut url = reqwest::Url::parse(&self.host).map_err(|e| format!("Invalid storage host: {}", e))?;
+        {
+            let mut segments = url
+                .path_segments_mut()
+                .map_err(|_| "Invalid storage host".to_string())?;
+            segments.extend(base.split('/')).extend(["b", self.bucket, "o"]);
+            if let Some(object) = object {
+                segments.push(object);
+            }
+        }
+        Ok(url)
+    }
+
+    async fn send(&self, request: reqwest::RequestBuilder) -> Result<reqwest::Response, String> {
+        let request = match &self.token {
+            Some(token) => request.bearer_auth(token),
+            None => request,
+        };
+        request
+            .send()
+            .await
+            .map_err(|e| format!("Cloud Storage request failed: {}", e))
+    }
+
+    async fn check(response: reqwest::Response) -> Result<reqwest::Response, String> {
+        let status = response.status();
+        if status.is_success() {
+            return Ok(response);
+        }
+        let text = response.text().await.unwrap_or_default();
+        Err(format!("Cloud Storage returned {}: {}", status, text))
+    }
+
+    async fn upload(&self, name: &str, body: Vec<u8>) -> Result<(), String> {
+        let url = self.url("upload/storage/v1", None)?;
+        let request = self
+            .http
+            .post(url)
+            .query(&[("uploadType", "media"), ("name", name)])
+            .header("Content-Type", "application/json")
+            .body(body);
+        Self::check(self.send(request).await?).await?;
+        Ok(())
+    }
+
+    /// Object contents, or `None` if it doesn't exist
+    async fn download(&self, name: &str) -> Result<Option<Vec<u8>>, String> {
+        let url = self.url("storage/v1", Some(name))?;
+        let response = self.send(self.http.get(url).query(&[("alt", "media")])).await?;
+        if response.status() == reqwest::StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+        let bytes = Self::check(response)
+            .await?
+            .bytes()
+            .await
+            .map_err(|e| format!("Failed to read export: {}", e))?;
+        Ok(Some(bytes.to_vec()))
+    }
+
+    /// Names of objects directly under `prefix` (not in nested "folders")
+    async fn list(&self, prefix: &str) -> Result<Vec<String>, String> {
+        let mut names = Vec::new();
+        let mut page_token: Option<String> = None;
+        loop {
+            let mut query = vec![("prefix", prefix.to_string()), ("delimiter", "/".to_string())];
+            if let Some(token) = page_token.take() {
+                query.push(("pageToken", token));
+            }
+            let url = self.url("storage/v1", None)?;
+            let page: ObjectList = Self::check(self.send(self.http.get(url).query(&query)).await?)
+                .await?
+                .json()
+                .await
+                .map_err(|e| format!("Invalid object listing: {}", e))?;
+            names.extend(page.items.into_iter().map(|item| item.name));
+            match page.next_page_token {
+                Some(token) => page_token = Some(token),
+                None => return Ok(names),
+            }
+        }
+    }
+
+    async fn delete(&self, name: &str) -> Result<(), String> {
+        let url = self.url("storage/v1", Some(name))?;
+        Self::check(self.send(self.http.delete(url)).await?).await?;
+        Ok(())
+    }
+}
+
+async fn collect_export(state: &AppState) -> Result<CampaignExport, String> {
+    let storage = &state.storage;
+    let read = |what: &str, e: crate::storage::StorageError| format!("Failed to read {} for export: {}", what, e);
+    Ok(CampaignExport {
+        exported_at: chrono::Utc::now().to_rfc3339(),
+        config: storage.load_config().await.map_err(|e| read("config", e))?,
+        versions: storage.list_config_versions().await.map_err(|e| read("config versions", e))?,
+        donations: storage.list_donations().await.map_err(|e| read("ledger", e))?,
+        pledges: storage.list_pledges().await.map_err(|e| read("pledges", e))?,
+        presets: storage.list_presets().await.map_err(|e| read("presets", e))?,
+    })
+}
+
+/// Daily job writing a dated export to the bucket and pruning exports past the retention period
+pub fn job() -> Job {
+    Job::new("daily-export", "@daily", |state| async move { run_export(&state).await })
+}
+
+async fn run_export(state: &AppState) -> Result<String, String> {
+    let Some(exports) = state.exports.as_deref() else {
+        return Ok("Skipped: EXPORT_BUCKET not set".to_string());
+    };
+
+    let export = collect_export(state).await?;
+    let body = serde_json::to_vec(&export).map_err(|e| format!("Failed to serialize export: {}", e))?;
+
+    let today = chrono::Utc::now().date_naive();
+    let name = exports.object_name(today);
+    let gcs = GcsClient::connect(&state.http_client, &exports.bucket).await?;
+    gcs.upload(&name, body).await?;
+
+    // Today's export is already written if pruning fails; the failure only leaves extra exports behind
+    let cutoff = today - chrono::Duration::days(exports.retention_days);
+    let mut pruned = 0;
+    for old in gcs.list(&exports.prefix).await? {
+        if exports.export_date(&old).is_some_and(|date| date < cutoff) {
+            gcs.delete(&old).await?;
+            pruned += 1;
+        }
+    }
+
+    tracing::info!("Wrote export gs://{}/{} ({} donations)", exports.bucket, name, export.donations.len());
+    Ok(format!("Exported to gs://{}/{}, pruned {} old export(s)", exports.bucket, name, pruned))
+}
+
+fn upstream_error(e: String) -> (StatusCode, Json<ErrorResponse>) {
+    error_response(StatusCode::BAD_GATEWAY, e)
+}
+
+fn storage_error(e: impl std::fmt::Display) -> (StatusCode, Json<ErrorResponse>) {
+    error_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to restore export: {}", e))
+}
+
+/// Add donations recorded since the export to the restored team totals, so the totals still
+/// match the ledger
+fn carry_over_donations<'a>(
+    donations: impl Iterator<Item = &'a Donation>,
+    live: &ThermometerConfig,
+    config: &mut ThermometerConfig,
+) {
+    let mut deltas: BTreeMap<&str, f64> = BTreeMap::new();
+    for donation in donations {
+        *deltas.entry(donation.team.as_str()).or_default() += donation.amount;
+    }
+    for (name, delta) in deltas {
+        let delta = (delta * 100.0).round() / 100.0;
+        if delta == 0.0 {
+            continue;
+        }
+        match config.teams.iter_mut().find(|t| t.name == name) {
+            Some(team) => team.total_raised += delta,
+            None => match live.teams.iter().find(|t| t.name == name) {
+                Some(team) => config.teams.push(crate::Team {
+                    total_raised: delta,
+                    ..team.clone()
+                }),
+                None => {
+                    tracing::warn!("{} has ${:.2} in donations since the export but no team to carry it to", name, delta);
+                    continue;
+                }
+            },
+        }
+        tracing::info!("Carrying ${:.2} raised since the export over to {}", delta, name);
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/admin/restore-from-export",
+    tag = "Admin",
+    security(("edit_key" = [])),
+    params(ChangeNote),
+    request_body = RestoreRequest,
+    responses(
+        (status = 200, description = "Config replaced by the export (plus donations since), and exported records written back", body = RestoreSummary),
+        (status = 400, description = "Invalid date", body = ErrorResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 404, description = "No export for that date", body = ErrorResponse),
+        (status = 502, description = "Cloud Storage request failed", body = ErrorResponse),
+        (status = 503, description = "Exports are not configured", body = ErrorResponse)
+    )
+)]
+pub async fn restore_from_export(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Query(change): Query<ChangeNote>,
+    Json(request): Json<RestoreRequest>,
+) -> Result<Json<RestoreSummary>, (StatusCode, Json<ErrorResponse>)> {
+    require_admin(&headers, &state)?;
+
+    let Some(exports) = state.exports.as_deref() else {
+        return Err(error_response(
+            StatusCode::SERVICE_UNAVAILABLE,
+            "Exports are not configured (EXPORT_BUCKET not set)",
+        ));
+    };
+    let gcs = GcsClient::connect(&state.http_client, &exports.bucket)
+        .await
+        .map_err(upstream_error)?;
+
+    let name = match request.date {
+        Some(date) => {
+            let date = NaiveDate::parse_from_str(date.trim(), "%Y-%m-%d")
+                .map_err(|_| error_response(StatusCode::BAD_REQUEST, "date must be YYYY-MM-DD"))?;
+            exports.object_name(date)
+        }
+        None => {
+            let names = gcs.list(&exports.prefix).await.map_err(upstream_error)?;
+            names
+                .into_iter()
+                .filter_map(|name| exports.export_date(&name).map(|date| (date, name)))
+                .max()
+                .map(|(_, name)| name)
+                .ok_or_else(|| error_response(StatusCode::NOT_FOUND, "No exports found"))?
+        }
+    };
+
+    let body = gcs
+        .download(&name)
+        .await
+        .map_err(upstream_error)?
+        .ok_or_else(|| error_response(StatusCode::NOT_FOUND, format!("Export not found: {}", name)))?;
+    let export: CampaignExport = serde_json::from_slice(&body)
+        .map_err(|e| upstream_error(format!("Export {} is not readable: {}", name, e)))?;
+
+    // Records are upserted by ID, so anything created after the export is kept, and what those
+    // donations added is carried over onto the exported team totals
+    let _guard = state.config_lock.lock().await;
+    let storage = &state.storage;
+    for version in &export.versions {
+        storage.save_config_version(version).await.map_err(storage_error)?;
+    }
+    for preset in &export.presets {
+        storage.save_preset(preset).await.map_err(storage_error)?;
+    }
+    for pledge in &export.pledges {
+        storage.save_pledge(pledge).await.map_err(storage_error)?;
+    }
+    let current = storage.list_donations().await.map_err(storage_error)?;
+    let existing: HashSet<&str> = current.iter().map(|d| d.id.as_str()).collect();
+    let exported: HashSet<&str> = export.donations.iter().map(|d| d.id.as_str()).collect();
+    for donation in &export.donations {
+        if existing.contains(donation.id.as_str()) {
+            storage.update_donation(donation).await.map_err(storage_error)?;
+        } else {
+            storage.append_donation(donation).await.map_err(storage_error)?;
+        }
+    }
+
+    let previous = storage.load_config().await.map_err(storage_error)?;
+    let mut config = export.config;
+    let since_export = current.iter().filter(|d| !exported.contains(d.id.as_str()));
+    carry_over_donations(since_export, &previous, &mut config);
+    config.last_updated = chrono::Utc::now().to_rfc3339();
+    commit_config(&state, &previous, &mut config, change.text()).await.map_err(storage_error)?;
+
+    tracing::warn!("Restored campaign from export gs://{}/{}", exports.bucket, name);
+    Ok(Json(RestoreSummary {
+        object: format!("gs://{}/{}", exports.bucket, name),
+        exported_at: export.exported_at,
+        donations: export.donations.len(),
+        pledges: export.pledges.len(),
+        presets: export.presets.len(),
+        versions: export.versions.len(),
+    }))
+}
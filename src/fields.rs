@@ -0,0 +1,88 @@
+use serde::Deserialize;
+use serde_json::{Map, Value};
+use utoipa::IntoParams;
+
+use crate::summary;
+use crate::views::PublicConfig;
+use crate::ThermometerConfig;
+
+// Query parameters for trimming the config response
+#[derive(Debug, Default, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct ConfigQuery {
+    /// Comma-separated top-level fields to return, e.g. `goal,total`. Besides the config's own
+    /// fields, `total`, `progress_percent`, `remaining`, `days_left` and `animals_helped` are
+    /// computed from active teams. Fields that aren't set come back as `null`
+    fields: Option<String>,
+    /// `all` (default), `active`, `none`, or `topN` for the N highest-raising active teams
+    teams: Option<String>,
+}
+
+enum TeamFilter {
+    All,
+    Active,
+    None,
+    Top(usize),
+}
+
+impl TeamFilter {
+    fn parse(value: &str) -> Result<Self, String> {
+        match value {
+            "all" => Ok(Self::All),
+            "active" => Ok(Self::Active),
+            "none" => Ok(Self::None),
+            _ => value
+                .strip_prefix("top")
+                .and_then(|n| n.parse().ok())
+                .filter(|&n| n > 0)
+                .map(Self::Top)
+                .ok_or_else(|| format!("Invalid teams filter '{}': use all, active, none or topN", value)),
+        }
+    }
+
+    fn apply(&self, config: &mut ThermometerConfig) {
+        match self {
+            Self::All => {}
+            Self::Active => config.teams.retain(|t| t.deleted_at.is_none()),
+            Self::None => config.teams.clear(),
+            Self::Top(n) => {
+                config.teams.retain(|t| t.deleted_at.is_none());
+                config
+                    .teams
+                    .sort_by(|a, b| b.total_raised.total_cmp(&a.total_raised).then_with(|| a.name.cmp(&b.name)));
+                config.teams.truncate(*n);
+            }
+        }
+    }
+}
+
+/// The public config as JSON, with teams filtered and only the requested fields kept. With no
+/// query parameters this is the whole public config.
+pub fn select(config: &ThermometerConfig, query: &ConfigQuery) -> Result<Value, String> {
+    let filter = match query.teams.as_deref() {
+        Some(value) => TeamFilter::parse(value.trim())?,
+        None => TeamFilter::All,
+    };
+    let mut trimmed = config.clone();
+    filter.apply(&mut trimmed);
+    let full = serde_json::to_value(PublicConfig::from(&trimmed)).map_err(|e| e.to_string())?;
+
+    let Some(fields) = query.fields.as_deref() else {
+        return Ok(full);
+    };
+    // Totals always cover every active team, whatever the teams filter kept
+    let summary = summary::summarize(config);
+    let mut selected = Map::new();
+    for name in fields.split(',').map(str::trim).filter(|f| !f.is_empty()) {
+        let value = match name {
+            "total" => summary.total_raised.into(),
+            "progress_percent" => summary.progress_percent.into(),
+            "remaining" => summary.remaining.into(),
+            "days_left" => summary.days_left.into(),
+            "animals_helped" => summary.animals_helped.into(),
+            _ => full.get(name).cloned().unwrap_or(Value::Null),
+        };
+        selected.insert(name.to_string(), value);
+    }
+    Ok(Value::Object(selected))
+}
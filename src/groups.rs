@@ -0,0 +1,261 @@
+use askama::Template;
+use axum::{
+    extract::State,
+    http::StatusCode,
+    response::{IntoResponse, Response},
+};
+
+use crate::color_constants;
+use crate::render::{self, ImageAbout, ImageCache, ImageFormat};
+use crate::thermometer::PngOptions;
+use crate::{error_response, AppState, ThermometerConfig};
+
+/// Column for teams without a group, once any team has one
+const UNGROUPED: &str = "Other";
+const COLUMN_WIDTH: f64 = 160.0;
+const MARGIN: f64 = 20.0;
+/// Room left of the first column for the shared scale labels
+const SCALE_LABEL_WIDTH: f64 = 60.0;
+const TUBE_TOP: f64 = 70.0;
+const TUBE_HEIGHT: f64 = 260.0;
+const TUBE_WIDTH: f64 = 28.0;
+const BULB_RADIUS: f64 = 24.0;
+
+/// Amount raised by the active teams in one group
+#[derive(Debug, Clone)]
+pub struct GroupTotal {
+    pub name: String,
+    pub total_raised: f64,
+    pub teams: usize,
+}
+
+/// Totals per group in the order groups first appear in the team list, with ungrouped teams
+/// last under "Other". Empty when no active team has a group.
+pub fn group_totals(config: &ThermometerConfig) -> Vec<GroupTotal> {
+    if config.active_teams().all(|t| t.group.is_none()) {
+        return vec![];
+    }
+    let mut groups: Vec<GroupTotal> = Vec::new();
+    let mut ungrouped: Option<GroupTotal> = None;
+    for team in config.active_teams() {
+        let group = match team.group.as_deref() {
+            Some(name) => match groups.iter().position(|g| g.name == name) {
+                Some(i) => &mut groups[i],
+                None => {
+                    groups.push(GroupTotal { name: name.to_string(), total_raised: 0.0, teams: 0 });
+                    groups.last_mut().unwrap()
+                }
+            },
+            None => ungrouped.get_or_insert_with(|| GroupTotal {
+                name: UNGROUPED.to_string(),
+                total_raised: 0.0,
+                teams: 0,
+            }),
+        };
+        group.total_raised += team.total_raised;
+        group.teams += 1;
+    }
+    groups.extend(ungrouped);
+    groups
+}
+
+/// Round up to 1, 2, 2.5 or 5 times a power of ten, so the shared scale has readable markers
+fn nice_ceiling(amount: f64) -> f64 {
+    if amount <= 0.0 {
+        return 100.0;
+    }
+    let power = 10f64.powf(amount.log10().floor());
+    let step = [1.0, 2.0, 2.5, 5.0, 10.0]
+        .into_iter()
+        .find(|step| step * power >= amount)
+        .unwrap_or(10.0);
+    step * power
+}
+
+#[derive(Template)]
+#[template(path = "groups.svg")]
+struct GroupsTemplate {
+    width: u32,
+    height: u32,
+    title: String,
+    title_x: String,
+    title_y: String,
+    markers: Vec<ScaleMarker>,
+    marker_x1: String,
+    marker_x2: String,
+    marker_text_x: String,
+    columns: Vec<Column>,
+    tube_y: String,
+    tube_width: String,
+    tube_height: String,
+    tube_radius: String,
+    bulb_y: String,
+    bulb_radius: String,
+    bulb_fill_radius: String,
+    name_y: String,
+    amount_y: String,
+    teams_y: String,
+    // Color constants
+    background_color: &'static str,
+    title_text_color: &'static str,
+    text_primary_color: &'static str,
+    text_secondary_color: &'static str,
+    tube_fill_color: &'static str,
+    tube_stroke_color: &'static str,
+    fill_color: &'static str,
+    achieved_text_color: &'static str,
+    marker_stroke_color: &'static str,
+    marker_text_color: &'static str,
+}
+
+struct ScaleMarker {
+    y: String,
+    text_y: String,
+    label: String,
+}
+
+struct Column {
+    center_x: String,
+    tube_x: String,
+    fill_x: String,
+    fill_y: String,
+    fill_width: String,
+    fill_height: String,
+    name: String,
+    amount: String,
+    teams: String,
+}
+
+/// One mini-thermometer per group side by side, all filled against the same scale so the
+/// columns compare directly. The scale tops out just above the biggest group.
+pub fn generate_groups_svg(title: &str, groups: &[GroupTotal]) -> Result<String, askama::Error> {
+    let scale = nice_ceiling(groups.iter().map(|g| g.total_raised).fold(0.0, f64::max));
+    let left = MARGIN + SCALE_LABEL_WIDTH;
+    let tube_bottom = TUBE_TOP + TUBE_HEIGHT;
+    let bulb_y = tube_bottom + BULB_RADIUS * 0.7;
+    let inset = 3.0;
+
+    let markers = [1.0, 0.75, 0.5, 0.25, 0.0]
+        .into_iter()
+        .map(|share| {
+            let y = TUBE_TOP + TUBE_HEIGHT * (1.0 - share);
+            ScaleMarker {
+                y: format!("{:.2}", y),
+                text_y: format!("{:.2}", y + 4.0),
+                label: format!("${:.0}", scale * share),
+            }
+        })
+        .collect();
+
+    let columns = groups
+        .iter()
+        .enumerate()
+        .map(|(i, group)| {
+            let center_x = left + (i as f64 + 0.5) * COLUMN_WIDTH;
+            let fill_height = (TUBE_HEIGHT - inset) * (group.total_raised / scale).clamp(0.0, 1.0);
+            Column {
+                center_x: format!("{:.2}", center_x),
+                tube_x: format!("{:.2}", center_x - TUBE_WIDTH / 2.0),
+                fill_x: format!("{:.2}", center_x - TUBE_WIDTH / 2.0 + inset),
+                fill_y: format!("{:.2}", tube_bottom - fill_height),
+                fill_width: format!("{:.2}", TUBE_WIDTH - inset * 2.0),
+                // Runs down into the bulb so the fill joins it without a gap
+                fill_height: format!("{:.2}", fill_height + BULB_RADIUS),
+                name: group.name.clone(),
+                amount: format!("${:.0}", group.total_raised),
+                teams: format!("{} {}", group.teams, if group.teams == 1 { "team" } else { "teams" }),
+            }
+        })
+        .collect();
+
+    let width = left + groups.len() as f64 * COLUMN_WIDTH + MARGIN;
+    let name_y = bulb_y + BULB_RADIUS + 24.0;
+
+    GroupsTemplate {
+        width: width as u32,
+        height: (name_y + 56.0) as u32,
+        title: title.to_string(),
+        title_x: format!("{:.2}", width / 2.0),
+        title_y: format!("{:.2}", MARGIN + 20.0),
+        markers,
+        marker_x1: format!("{:.2}", left - 10.0),
+        marker_x2: format!("{:.2}", left + groups.len() as f64 * COLUMN_WIDTH),
+        marker_text_x: format!("{:.2}", left - 14.0),
+        columns,
+        tube_y: format!("{:.2}", TUBE_TOP),
+        tube_width: format!("{:.2}", TUBE_WIDTH),
+        tube_height: format!("{:.2}", TUBE_HEIGHT),
+        tube_radius: format!("{:.2}", TUBE_WIDTH / 2.0),
+        bulb_y: format!("{:.2}", bulb_y),
+        bulb_radius: format!("{:.2}", BULB_RADIUS),
+        bulb_fill_radius: format!("{:.2}", BULB_RADIUS - inset),
+        name_y: format!("{:.2}", name_y),
+        amount_y: format!("{:.2}", name_y + 24.0),
+        teams_y: format!("{:.2}", name_y + 42.0),
+        background_color: color_constants::light::BACKGROUND,
+        title_text_color: color_constants::light::TITLE_TEXT,
+        text_primary_color: color_constants::light::TEXT_PRIMARY,
+        text_secondary_color: color_constants::light::TEXT_SECONDARY,
+        tube_fill_color: color_constants::light::TUBE_FILL,
+        tube_stroke_color: color_constants::light::TUBE_STROKE,
+        fill_color: color_constants::light::FILL_COLOR_1,
+        achieved_text_color: color_constants::light::ACHIEVED_TEXT,
+        marker_stroke_color: color_constants::light::MARKER_STROKE,
+        marker_text_color: color_constants::light::MARKER_TEXT,
+    }
+    .render()
+}
+
+async fn groups_image(state: &AppState, format: ImageFormat) -> Response {
+    let config = match state.storage.load_config().await {
+        Ok(config) => config,
+        Err(e) => return render::error_image("Failed to load config for group thermometers", e, format, false, 1.0),
+    };
+    let groups = group_totals(&config);
+    if groups.is_empty() {
+        return error_response(StatusCode::NOT_FOUND, "No teams have a group").into_response();
+    }
+    match generate_groups_svg(&config.title, &groups) {
+        Ok(svg) => {
+            let about = ImageAbout::new(
+                format!("Totals by group: {}", config.title),
+                groups
+                    .iter()
+                    .map(|g| format!("{}: ${:.2}", g.name, g.total_raised))
+                    .collect::<Vec<_>>()
+                    .join(", "),
+                config.last_updated.clone(),
+            );
+            render::image_response(StatusCode::OK, svg, format, PngOptions::at_scale(1.0), ImageCache::NoCache, &about)
+        }
+        Err(e) => render::error_image("Failed to render group thermometers", e, format, false, 1.0),
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/groups.svg",
+    tag = "Public",
+    responses(
+        (status = 200, description = "One mini-thermometer per team group side by side on a shared scale; teams without a group are shown as \"Other\"", content_type = "image/svg+xml", body = String),
+        (status = 404, description = "No team has a group", body = ErrorResponse),
+        (status = 500, description = "Placeholder image shown when rendering fails", content_type = "image/svg+xml", body = String)
+    )
+)]
+pub async fn groups_svg(State(state): State<AppState>) -> Response {
+    groups_image(&state, ImageFormat::Svg).await
+}
+
+#[utoipa::path(
+    get,
+    path = "/groups.png",
+    tag = "Public",
+    responses(
+        (status = 200, description = "PNG version of `/groups.svg`", content_type = "image/png", body = String),
+        (status = 404, description = "No team has a group", body = ErrorResponse),
+        (status = 500, description = "Placeholder image shown when rendering fails", content_type = "image/png", body = String)
+    )
+)]
+pub async fn groups_png(State(state): State<AppState>) -> Response {
+    groups_image(&state, ImageFormat::Png).await
+}
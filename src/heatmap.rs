@@ -0,0 +1,210 @@
+use askama::Template;
+use axum::{extract::State, http::StatusCode, response::Response};
+use chrono::{Datelike, NaiveDate};
+
+use crate::color_constants;
+use crate::public_stats::{self, HistoryBucket};
+use crate::render::{self, ImageAbout, ImageCache, ImageFormat};
+use crate::thermometer::PngOptions;
+use crate::AppState;
+
+/// Most weeks drawn; older days are left off
+const MAX_WEEKS: i64 = 53;
+const CELL: f64 = 14.0;
+const GAP: f64 = 3.0;
+const MARGIN: f64 = 20.0;
+/// Room left of the grid for weekday labels
+const WEEKDAY_LABEL_WIDTH: f64 = 30.0;
+const MONTHS: [&str; 12] = ["Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec"];
+
+#[derive(Template)]
+#[template(path = "heatmap.svg")]
+struct HeatmapTemplate {
+    width: u32,
+    height: u32,
+    title: String,
+    title_y: String,
+    title_font_size: String,
+    label_font_size: String,
+    margin: String,
+    month_y: String,
+    months: Vec<MonthLabel>,
+    weekday_x: String,
+    weekdays: Vec<WeekdayLabel>,
+    cell_size: String,
+    cells: Vec<Cell>,
+    caption: String,
+    caption_y: String,
+    legend_y: String,
+    legend_less_x: String,
+    legend_more_x: String,
+    legend: Vec<Swatch>,
+    // Color constants
+    background_color: &'static str,
+    title_text_color: &'static str,
+    text_primary_color: &'static str,
+    text_secondary_color: &'static str,
+    highlight_color: &'static str,
+}
+
+struct MonthLabel {
+    x: String,
+    name: &'static str,
+}
+
+struct WeekdayLabel {
+    y: String,
+    name: &'static str,
+}
+
+struct Cell {
+    x: String,
+    y: String,
+    color: &'static str,
+    highlight: bool,
+    tooltip: String,
+}
+
+struct Swatch {
+    x: String,
+    color: &'static str,
+}
+
+/// Color level for a day's amount: 0 for nothing raised, then quarters of the biggest day
+fn level(amount: f64, max: f64) -> usize {
+    if amount <= 0.0 || max <= 0.0 {
+        return 0;
+    }
+    ((amount / max * 4.0).ceil() as usize).clamp(1, 4)
+}
+
+/// A GitHub-style calendar of daily amounts: one column per week (Sunday at the top), ending
+/// today, with the biggest day outlined and named underneath
+pub fn generate_heatmap_svg(title: &str, history: &[HistoryBucket], today: NaiveDate) -> Result<String, askama::Error> {
+    let first = history.first().map_or(today, |b| b.date);
+    let earliest = today - chrono::Duration::weeks(MAX_WEEKS - 1);
+    let start = first.max(earliest);
+    // Back up to the Sunday so every column is a full week
+    let start = start - chrono::Duration::days(start.weekday().num_days_from_sunday() as i64);
+    let weeks = (today - start).num_days() / 7 + 1;
+
+    let raised = |date: NaiveDate| history.iter().find(|b| b.date == date).map_or(0.0, |b| b.raised);
+    let biggest = history
+        .iter()
+        .filter(|b| b.date >= start && b.raised > 0.0)
+        .max_by(|a, b| a.raised.total_cmp(&b.raised));
+    let max = biggest.map_or(0.0, |b| b.raised);
+
+    let grid_x = MARGIN + WEEKDAY_LABEL_WIDTH;
+    let grid_y = MARGIN + 44.0;
+    let step = CELL + GAP;
+
+    let mut cells = Vec::new();
+    let mut months = Vec::new();
+    for (i, date) in start.iter_days().take_while(|d| *d <= today).enumerate() {
+        let column = (i / 7) as f64;
+        let row = date.weekday().num_days_from_sunday() as f64;
+        let amount = raised(date);
+        cells.push(Cell {
+            x: format!("{:.2}", grid_x + column * step),
+            y: format!("{:.2}", grid_y + row * step),
+            color: color_constants::heatmap::LEVELS[level(amount, max)],
+            highlight: biggest.is_some_and(|b| b.date == date),
+            tooltip: format!("{}: ${:.2}", date.format("%b %-d, %Y"), amount.max(0.0)),
+        });
+        // Label the first week that starts in each month
+        if row == 0.0 && date.day() <= 7 {
+            months.push(MonthLabel {
+                x: format!("{:.2}", grid_x + column * step),
+                name: MONTHS[date.month0() as usize],
+            });
+        }
+    }
+
+    let weekdays = [(1.0, "Mon"), (3.0, "Wed"), (5.0, "Fri")]
+        .into_iter()
+        .map(|(row, name)| WeekdayLabel {
+            y: format!("{:.2}", grid_y + row * step + CELL * 0.8),
+            name,
+        })
+        .collect();
+
+    let grid_bottom = grid_y + 7.0 * step;
+    let width = (grid_x + weeks as f64 * step + MARGIN).max(360.0);
+    let legend_right = width - MARGIN - 32.0;
+    let legend: Vec<Swatch> = color_constants::heatmap::LEVELS
+        .iter()
+        .enumerate()
+        .map(|(i, &color)| Swatch {
+            x: format!("{:.2}", legend_right - (5 - i) as f64 * step),
+            color,
+        })
+        .collect();
+
+    let caption = match biggest {
+        Some(day) => format!("Biggest day: {} (${:.2})", day.date.format("%B %-d, %Y"), day.raised),
+        None => "No donations yet".to_string(),
+    };
+
+    HeatmapTemplate {
+        width: width as u32,
+        height: (grid_bottom + 40.0) as u32,
+        title: title.to_string(),
+        title_y: format!("{:.2}", MARGIN + 14.0),
+        title_font_size: "16".to_string(),
+        label_font_size: "10".to_string(),
+        margin: format!("{:.2}", MARGIN),
+        month_y: format!("{:.2}", grid_y - 6.0),
+        months,
+        weekday_x: format!("{:.2}", grid_x - 6.0),
+        weekdays,
+        cell_size: format!("{:.2}", CELL),
+        cells,
+        caption,
+        caption_y: format!("{:.2}", grid_bottom + 20.0),
+        legend_y: format!("{:.2}", grid_bottom + 9.0),
+        legend_less_x: format!("{:.2}", legend_right - 5.0 * step - 4.0),
+        legend_more_x: format!("{:.2}", legend_right + 4.0),
+        legend,
+        background_color: color_constants::light::BACKGROUND,
+        title_text_color: color_constants::light::TITLE_TEXT,
+        text_primary_color: color_constants::light::TEXT_PRIMARY,
+        text_secondary_color: color_constants::light::TEXT_SECONDARY,
+        highlight_color: color_constants::heatmap::HIGHLIGHT,
+    }
+    .render()
+}
+
+#[utoipa::path(
+    get,
+    path = "/heatmap.svg",
+    tag = "Public",
+    responses(
+        (status = 200, description = "Calendar of daily amounts raised over the last year, with the biggest day called out", content_type = "image/svg+xml", body = String),
+        (status = 500, description = "Placeholder image shown when rendering fails", content_type = "image/svg+xml", body = String)
+    )
+)]
+pub async fn heatmap_svg(State(state): State<AppState>) -> Response {
+    let config = match state.storage.load_config().await {
+        Ok(config) => config,
+        Err(e) => return render::error_image("Failed to load config for heatmap", e, ImageFormat::Svg, false, 1.0),
+    };
+    let donations = match state.storage.list_donations().await {
+        Ok(donations) => donations,
+        Err(e) => return render::error_image("Failed to load ledger for heatmap", e, ImageFormat::Svg, false, 1.0),
+    };
+
+    let today = chrono::Utc::now().with_timezone(&config.tz()).date_naive();
+    let history = public_stats::history(&config, &donations, today);
+    match generate_heatmap_svg(&config.title, &history, today) {
+        Ok(svg) => {
+            let about = ImageAbout::new(
+                format!("Amount raised each day: {}", config.title),
+                "Calendar of the amount raised each day over the last year, darker for bigger days.",
+                config.last_updated.clone(),
+            );
+            render::image_response(StatusCode::OK, svg, ImageFormat::Svg, PngOptions::at_scale(1.0), ImageCache::NoCache, &about)
+        }
+        Err(e) => render::error_image("Failed to render heatmap template", e, ImageFormat::Svg, false, 1.0),
+    }
+}
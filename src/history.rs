@@ -0,0 +1,108 @@
+use axum::{
+    extract::{Query, State},
+    http::StatusCode,
+    response::Json,
+};
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+
+use crate::versions::TeamTotal;
+use crate::{error_response, AppState, ErrorResponse, ThermometerConfig};
+
+/// Campaign and team totals as they stood after a save
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct TotalsSnapshot {
+    /// RFC 3339, UTC
+    pub recorded_at: String,
+    pub total_raised: f64,
+    pub goal: f64,
+    /// Active teams and their totals
+    pub teams: Vec<TeamTotal>,
+}
+
+impl TotalsSnapshot {
+    fn of(config: &ThermometerConfig) -> Self {
+        Self {
+            recorded_at: chrono::Utc::now().to_rfc3339(),
+            total_raised: config.total_raised(),
+            goal: config.goal,
+            teams: team_totals(config),
+        }
+    }
+}
+
+fn team_totals(config: &ThermometerConfig) -> Vec<TeamTotal> {
+    config
+        .active_teams()
+        .map(|t| TeamTotal {
+            name: t.name.clone(),
+            total_raised: t.total_raised,
+        })
+        .collect()
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct HistoryQuery {
+    /// Only snapshots recorded at or after this RFC 3339 time, e.g. `2025-12-01T00:00:00Z`
+    since: Option<String>,
+    /// Only this team's total in each snapshot (case-insensitive); snapshots without it are left out
+    team: Option<String>,
+}
+
+/// Save the totals after a live save, unless the save left the goal and every total as they were
+pub async fn record(state: &AppState, previous: &ThermometerConfig, config: &ThermometerConfig) {
+    let unchanged = previous.goal == config.goal
+        && previous.total_raised() == config.total_raised()
+        && team_totals(previous)
+            .iter()
+            .map(|t| (&t.name, t.total_raised))
+            .eq(team_totals(config).iter().map(|t| (&t.name, t.total_raised)));
+    if unchanged {
+        return;
+    }
+
+    let snapshot = TotalsSnapshot::of(config);
+    if let Err(e) = state.storage.append_totals_snapshot(&snapshot).await {
+        tracing::error!("Failed to record totals history: {}", e);
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/history",
+    tag = "Public",
+    params(HistoryQuery),
+    responses(
+        (status = 200, description = "Totals after every save that changed them, oldest first", body = [TotalsSnapshot]),
+        (status = 400, description = "Invalid `since` time", body = ErrorResponse)
+    )
+)]
+pub async fn get_history(
+    State(state): State<AppState>,
+    Query(query): Query<HistoryQuery>,
+) -> Result<Json<Vec<TotalsSnapshot>>, (StatusCode, Json<ErrorResponse>)> {
+    // Normalized to UTC so it compares with the stored timestamps as a string
+    let since = query
+        .since
+        .as_deref()
+        .map(|since| {
+            chrono::DateTime::parse_from_rfc3339(since)
+                .map(|t| t.with_timezone(&chrono::Utc).to_rfc3339())
+                .map_err(|_| error_response(StatusCode::BAD_REQUEST, "Invalid since: expected an RFC 3339 time"))
+        })
+        .transpose()?;
+
+    let mut snapshots = state.storage.list_totals_snapshots(since.as_deref()).await.map_err(|e| {
+        error_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to load history: {}", e))
+    })?;
+    snapshots.sort_by(|a, b| a.recorded_at.cmp(&b.recorded_at));
+
+    if let Some(team) = query.team.as_deref().map(str::trim) {
+        snapshots.retain_mut(|snapshot| {
+            snapshot.teams.retain(|t| t.name.eq_ignore_ascii_case(team));
+            !snapshot.teams.is_empty()
+        });
+    }
+    Ok(Json(snapshots))
+}
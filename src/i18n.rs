@@ -0,0 +1,97 @@
+//! Translated text for rendered images, chosen with `lang=`. Each language is one catalog, so
+//! adding a language means adding a catalog and a `Lang` variant.
+
+use chrono::{DateTime, TimeZone};
+use std::fmt::Display;
+
+/// Languages images can be rendered in
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Lang {
+    #[default]
+    En,
+    Es,
+}
+
+impl Lang {
+    /// A language tag such as `es` or `es-MX`; None when there's no catalog for it
+    pub fn parse(tag: &str) -> Option<Self> {
+        let primary = tag.trim().split(['-', '_']).next()?.to_ascii_lowercase();
+        match primary.as_str() {
+            "en" => Some(Lang::En),
+            "es" => Some(Lang::Es),
+            _ => None,
+        }
+    }
+
+    pub fn catalog(self) -> &'static Catalog {
+        match self {
+            Lang::En => &EN,
+            Lang::Es => &ES,
+        }
+    }
+}
+
+/// Label strings of the thermometer image
+pub struct Catalog {
+    /// Headline at the top unless an experiment variant replaces it
+    pub our_goal: &'static str,
+    /// Under the amount raised
+    pub achieved: &'static str,
+    /// Under the goal amount
+    pub goal: &'static str,
+    /// Under the percentage
+    pub of_goal_reached: &'static str,
+    animal_helped: &'static str,
+    animals_helped: &'static str,
+    figures_as_of: &'static str,
+    /// chrono format of the date in the stale-figures notice
+    date_format: &'static str,
+    /// Between the amount raised and the goal in the comparison strip
+    of: &'static str,
+}
+
+static EN: Catalog = Catalog {
+    our_goal: "Our Goal",
+    achieved: "achieved",
+    goal: "goal",
+    of_goal_reached: "of our goal reached",
+    animal_helped: "animal helped",
+    animals_helped: "animals helped",
+    figures_as_of: "Figures as of",
+    date_format: "%b %-d, %Y",
+    of: "of",
+};
+
+static ES: Catalog = Catalog {
+    our_goal: "Nuestra meta",
+    achieved: "recaudado",
+    goal: "meta",
+    of_goal_reached: "de nuestra meta alcanzada",
+    animal_helped: "animal ayudado",
+    animals_helped: "animales ayudados",
+    figures_as_of: "Cifras al",
+    // chrono only has English month names
+    date_format: "%-d/%m/%Y",
+    of: "de",
+};
+
+impl Catalog {
+    /// e.g. `≈ 34 animals helped`
+    pub fn animals_helped(&self, count: u64) -> String {
+        let phrase = if count == 1 { self.animal_helped } else { self.animals_helped };
+        format!("≈ {} {}", count, phrase)
+    }
+
+    /// e.g. `Figures as of Dec 5, 2025`
+    pub fn figures_as_of<Tz: TimeZone>(&self, updated: &DateTime<Tz>) -> String
+    where
+        Tz::Offset: Display,
+    {
+        format!("{} {}", self.figures_as_of, updated.format(self.date_format))
+    }
+
+    /// e.g. `$1200 of $5000 (24%)`
+    pub fn amount_of_goal(&self, raised: f64, goal: f64, percent: f64) -> String {
+        format!("${:.0} {} ${:.0} ({:.0}%)", raised, self.of, goal, percent)
+    }
+}
@@ -0,0 +1,203 @@
+use axum::{
+    body::{to_bytes, Body},
+    extract::{Request, State},
+    http::StatusCode,
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use utoipa::IntoParams;
+
+use crate::{error_response, AppState};
+
+pub const IDEMPOTENCY_HEADER: &str = "Idempotency-Key";
+const REPLAYED_HEADER: &str = "Idempotent-Replayed";
+/// How long a key is remembered; retries after this re-run the request
+const KEY_TTL_HOURS: i64 = 24;
+const MAX_KEY_LENGTH: usize = 255;
+
+/// Documents the optional header on idempotent endpoints
+#[derive(Deserialize, IntoParams)]
+#[into_params(parameter_in = Header)]
+#[allow(dead_code)]
+pub struct IdempotencyKeyHeader {
+    /// Unique key for this request; a retry with the same key replays the original response
+    #[serde(rename = "Idempotency-Key")]
+    #[param(rename = "Idempotency-Key")]
+    idempotency_key: Option<String>,
+}
+
+/// A stored response for a previously seen `Idempotency-Key`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IdempotencyRecord {
+    /// Hash of the method, path, credentials and client key
+    pub id: String,
+    /// Hash of the original request body, to catch a key reused for a different request
+    pub request_hash: String,
+    pub status: u16,
+    pub content_type: Option<String>,
+    pub body: String,
+    pub created_at: String,
+}
+
+impl IdempotencyRecord {
+    pub fn is_expired(&self) -> bool {
+        chrono::DateTime::parse_from_rfc3339(&self.created_at)
+            .map(|created| chrono::Utc::now() - created.with_timezone(&chrono::Utc) > chrono::Duration::hours(KEY_TTL_HOURS))
+            .unwrap_or(true)
+    }
+
+    fn replay(&self) -> Response {
+        let mut response = Response::builder()
+            .status(StatusCode::from_u16(self.status).unwrap_or(StatusCode::OK))
+            .header(REPLAYED_HEADER, "true");
+        if let Some(content_type) = &self.content_type {
+            response = response.header("Content-Type", content_type);
+        }
+        response
+            .body(Body::from(self.body.clone()))
+            .unwrap_or_else(|_| StatusCode::INTERNAL_SERVER_ERROR.into_response())
+    }
+}
+
+fn sha256_hex(parts: &[&[u8]]) -> String {
+    let mut hasher = Sha256::new();
+    for part in parts {
+        hasher.update(part);
+        // Separator so ("ab", "c") and ("a", "bc") hash differently
+        hasher.update([0u8]);
+    }
+    hex::encode(hasher.finalize())
+}
+
+/// Removes a key from the in-flight set even if the handler panics
+struct InFlightGuard<'a> {
+    state: &'a AppState,
+    id: String,
+}
+
+impl Drop for InFlightGuard<'_> {
+    fn drop(&mut self) {
+        if let Ok(mut in_flight) = self.state.in_flight_keys.lock() {
+            in_flight.remove(&self.id);
+        }
+    }
+}
+
+/// Middleware for mutating endpoints: a request carrying an `Idempotency-Key` header that was
+/// already handled successfully gets the original response replayed instead of being applied again.
+/// Requests without the header pass straight through.
+pub async fn idempotent(State(state): State<AppState>, request: Request, next: Next) -> Response {
+    let Some(key) = request
+        .headers()
+        .get(IDEMPOTENCY_HEADER)
+        .and_then(|h| h.to_str().ok())
+        .map(|k| k.trim().to_string())
+        .filter(|k| !k.is_empty())
+    else {
+        return next.run(request).await;
+    };
+
+    if key.len() > MAX_KEY_LENGTH {
+        return error_response(
+            StatusCode::BAD_REQUEST,
+            format!("{} must be at most {} characters", IDEMPOTENCY_HEADER, MAX_KEY_LENGTH),
+        )
+        .into_response();
+    }
+
+    // Scope keys to the endpoint and caller so one client can't replay another's response
+    let authorization = request
+        .headers()
+        .get("Authorization")
+        .map(|h| h.as_bytes().to_vec())
+        .unwrap_or_default();
+    let id = sha256_hex(&[
+        request.method().as_str().as_bytes(),
+        request.uri().path().as_bytes(),
+        &authorization,
+        key.as_bytes(),
+    ]);
+
+    let (parts, body) = request.into_parts();
+    let body = match to_bytes(body, usize::MAX).await {
+        Ok(bytes) => bytes,
+        Err(e) => {
+            return error_response(StatusCode::BAD_REQUEST, format!("Failed to read request body: {}", e))
+                .into_response();
+        }
+    };
+    let request_hash = sha256_hex(&[&body]);
+
+    // Claimed before the lookup: a request that finished between an earlier lookup and the claim
+    // would otherwise have saved its record unseen and be applied a second time
+    let newly_in_flight = state
+        .in_flight_keys
+        .lock()
+        .map(|mut in_flight| in_flight.insert(id.clone()))
+        .unwrap_or(false);
+    if !newly_in_flight {
+        return error_response(
+            StatusCode::CONFLICT,
+            format!("A request with this {} is still being processed", IDEMPOTENCY_HEADER),
+        )
+        .into_response();
+    }
+    let _guard = InFlightGuard { state: &state, id: id.clone() };
+
+    match state.storage.load_idempotency_record(&id).await {
+        Ok(Some(record)) if !record.is_expired() => {
+            if record.request_hash != request_hash {
+                return error_response(
+                    StatusCode::UNPROCESSABLE_ENTITY,
+                    format!("{} was already used for a different request", IDEMPOTENCY_HEADER),
+                )
+                .into_response();
+            }
+            tracing::info!("Replaying response for idempotency key {}", key);
+            return record.replay();
+        }
+        Ok(_) => {}
+        Err(e) => {
+            // Running the request without being able to check the key risks applying it twice
+            tracing::error!("Failed to look up idempotency key: {}", e);
+            return error_response(StatusCode::SERVICE_UNAVAILABLE, "Failed to check idempotency key")
+                .into_response();
+        }
+    }
+
+    let response = next.run(Request::from_parts(parts, Body::from(body))).await;
+
+    // Only successful responses are remembered; a failed request changed nothing and may be retried
+    if !response.status().is_success() {
+        return response;
+    }
+
+    let (parts, body) = response.into_parts();
+    let body = match to_bytes(body, usize::MAX).await {
+        Ok(bytes) => bytes,
+        Err(e) => {
+            tracing::error!("Failed to buffer response for idempotency key {}: {}", key, e);
+            return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+        }
+    };
+
+    let record = IdempotencyRecord {
+        id,
+        request_hash,
+        status: parts.status.as_u16(),
+        content_type: parts
+            .headers
+            .get("Content-Type")
+            .and_then(|h| h.to_str().ok())
+            .map(String::from),
+        body: String::from_utf8_lossy(&body).into_owned(),
+        created_at: chrono::Utc::now().to_rfc3339(),
+    };
+    if let Err(e) = state.storage.save_idempotency_record(&record).await {
+        tracing::error!("Failed to store idempotency key {}: {}", key, e);
+    }
+
+    Response::from_parts(parts, Body::from(body))
+}
@@ -0,0 +1,57 @@
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::ThermometerConfig;
+
+/// Concrete outcomes the money raised pays for, shown as "This funds 120 vaccines"
+#[derive(Debug, Clone, Default, Serialize, Deserialize, ToSchema)]
+#[serde(default)]
+pub struct ImpactSettings {
+    /// In display order; the first one also goes on the share card
+    pub outcomes: Vec<ImpactOutcome>,
+    /// Average cost of helping one animal, for the "≈ 34 animals helped" counter
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cost_per_animal: Option<f64>,
+}
+
+/// What a fixed amount buys, e.g. `$25` = one vaccine
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct ImpactOutcome {
+    pub amount: f64,
+    /// Singular name, e.g. `vaccine`
+    pub item: String,
+    /// Plural name when adding an `s` isn't right, e.g. `spay/neuter surgeries`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub plural: Option<String>,
+}
+
+impl ImpactOutcome {
+    /// How many whole items `total` pays for, e.g. `120 vaccines`; `None` until it covers one
+    pub fn describe(&self, total: f64) -> Option<String> {
+        if self.amount <= 0.0 {
+            return None;
+        }
+        let count = (total / self.amount).floor();
+        if count < 1.0 {
+            return None;
+        }
+        let name = if count == 1.0 {
+            self.item.clone()
+        } else {
+            self.plural.clone().unwrap_or_else(|| format!("{}s", self.item))
+        };
+        Some(format!("{:.0} {}", count, name))
+    }
+}
+
+/// What the current total funds, one entry per outcome it covers at least once
+pub fn funded(config: &ThermometerConfig) -> Vec<String> {
+    let total = config.total_raised();
+    config.impact.outcomes.iter().filter_map(|o| o.describe(total)).collect()
+}
+
+/// Whole animals the total has helped at `cost_per_animal`; `None` when no cost is set
+pub fn animals_helped(config: &ThermometerConfig) -> Option<u64> {
+    let cost = config.impact.cost_per_animal.filter(|c| *c > 0.0)?;
+    Some((config.total_raised() / cost).floor().max(0.0) as u64)
+}
@@ -0,0 +1,268 @@
+//! Credentials for the services the thermometer talks to, kept in the `integrations` section of
+//! the config instead of the environment so each tenant and campaign can have its own. Secrets are
+//! write-only: every admin response shows them masked, and `POST /admin/config` can't change them.
+//! Only the deployment and its campaigns fall back to the environment variables; tenants never
+//! see the operator's credentials.
+
+use axum::{
+    extract::{Query, State},
+    http::{HeaderMap, StatusCode},
+    response::Json,
+};
+use serde::{Deserialize, Serialize, Serializer};
+use std::collections::BTreeMap;
+use utoipa::ToSchema;
+
+use crate::sandbox::EditTarget;
+use crate::versions::ChangeNote;
+use crate::{error_response, require_admin, AppState, ErrorResponse, ThermometerConfig};
+
+/// Characters of a secret left visible when it's masked
+const VISIBLE_CHARS: usize = 4;
+const MASK: &str = "••••";
+/// Shortest accepted webhook signing secret
+const MIN_SIGNING_SECRET_LEN: usize = 16;
+
+/// The `integrations` section of the config. Outside tenants, anything unset falls back to its
+/// environment variable.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, ToSchema)]
+#[serde(default)]
+pub struct IntegrationSettings {
+    /// Slack incoming webhook for staff notifications, instead of `SLACK_WEBHOOK_URL`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub slack_webhook_url: Option<String>,
+    /// Secret outgoing webhooks are signed with, instead of `WEBHOOK_SIGNING_SECRET`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub webhook_signing_secret: Option<String>,
+    /// Mailchimp API key for the milestone automation, instead of `MAILCHIMP_API_KEY`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mailchimp_api_key: Option<String>,
+    /// Discord webhook for milestone announcements, instead of `DISCORD_WEBHOOK_URL`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub discord_webhook_url: Option<String>,
+    /// Slack bot token (`xoxb-...`) with `files:write`, so milestone announcements can attach the
+    /// thermometer image; instead of `SLACK_BOT_TOKEN`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub slack_bot_token: Option<String>,
+    /// Slack channel ID the bot posts milestone announcements in. Not a secret, so never masked.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub slack_channel: Option<String>,
+    /// URL of each `webhooks.endpoints` entry by its ID. Endpoint URLs often carry a token, so they
+    /// live here with the other secrets.
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub webhook_urls: BTreeMap<String, String>,
+}
+
+/// A change to the `integrations` section. Fields left out (or null) keep their current value;
+/// an empty string removes it. The same goes for each endpoint ID in `webhook_urls`.
+#[derive(Debug, Default, Deserialize, ToSchema)]
+#[serde(default)]
+pub struct IntegrationsUpdate {
+    slack_webhook_url: Option<String>,
+    webhook_signing_secret: Option<String>,
+    mailchimp_api_key: Option<String>,
+    discord_webhook_url: Option<String>,
+    slack_bot_token: Option<String>,
+    slack_channel: Option<String>,
+    webhook_urls: BTreeMap<String, String>,
+}
+
+/// `••••` and the last few characters, enough to tell which secret is set
+pub fn mask(secret: &str) -> String {
+    let chars: Vec<char> = secret.chars().collect();
+    if chars.len() <= VISIBLE_CHARS * 2 {
+        return MASK.to_string();
+    }
+    let tail: String = chars[chars.len() - VISIBLE_CHARS..].iter().collect();
+    format!("{}{}", MASK, tail)
+}
+
+/// `var` from the environment, for the deployment's own states only
+fn env(state: &AppState, var: &str) -> Option<String> {
+    state.env_integrations.then(|| std::env::var(var).ok()).flatten()
+}
+
+/// Apply one field of an update
+fn apply(current: &mut Option<String>, update: Option<String>) {
+    match update.map(|value| value.trim().to_string()) {
+        Some(value) if value.is_empty() => *current = None,
+        Some(value) => *current = Some(value),
+        None => {}
+    }
+}
+
+impl IntegrationSettings {
+    pub fn is_empty(&self) -> bool {
+        self == &Self::default()
+    }
+
+    /// A copy with every secret masked, for admin responses
+    pub fn masked(&self) -> Self {
+        Self {
+            slack_webhook_url: self.slack_webhook_url.as_deref().map(mask),
+            webhook_signing_secret: self.webhook_signing_secret.as_deref().map(mask),
+            mailchimp_api_key: self.mailchimp_api_key.as_deref().map(mask),
+            discord_webhook_url: self.discord_webhook_url.as_deref().map(mask),
+            slack_bot_token: self.slack_bot_token.as_deref().map(mask),
+            slack_channel: self.slack_channel.clone(),
+            webhook_urls: self.webhook_urls.iter().map(|(id, url)| (id.clone(), mask(url))).collect(),
+        }
+    }
+
+    /// The Slack incoming webhook, from the config or `SLACK_WEBHOOK_URL`
+    pub fn slack_webhook_url(&self, state: &AppState) -> Option<String> {
+        self.slack_webhook_url.clone().or_else(|| env(state, "SLACK_WEBHOOK_URL"))
+    }
+
+    /// The webhook signing secret, from the config or `WEBHOOK_SIGNING_SECRET`
+    pub fn webhook_signing_secret(&self, state: &AppState) -> Option<String> {
+        self.webhook_signing_secret.clone().or_else(|| env(state, "WEBHOOK_SIGNING_SECRET"))
+    }
+
+    /// The Mailchimp API key, from the config or `MAILCHIMP_API_KEY`
+    pub fn mailchimp_api_key(&self, state: &AppState) -> Option<String> {
+        self.mailchimp_api_key.clone().or_else(|| env(state, "MAILCHIMP_API_KEY"))
+    }
+
+    /// The Discord webhook, from the config or `DISCORD_WEBHOOK_URL`
+    pub fn discord_webhook_url(&self, state: &AppState) -> Option<String> {
+        self.discord_webhook_url.clone().or_else(|| env(state, "DISCORD_WEBHOOK_URL"))
+    }
+
+    /// The Slack bot token, from the config or `SLACK_BOT_TOKEN`
+    pub fn slack_bot_token(&self, state: &AppState) -> Option<String> {
+        self.slack_bot_token.clone().or_else(|| env(state, "SLACK_BOT_TOKEN"))
+    }
+
+    /// Catch credentials that can't work before they're saved
+    pub fn validate(&self) -> Result<(), String> {
+        for (name, url) in [
+            ("slack_webhook_url", &self.slack_webhook_url),
+            ("discord_webhook_url", &self.discord_webhook_url),
+        ] {
+            let Some(url) = url else {
+                continue;
+            };
+            let valid = reqwest::Url::parse(url).is_ok_and(|u| u.scheme() == "https" && u.host_str().is_some());
+            if !valid {
+                return Err(format!("integrations.{} must be an https:// URL", name));
+            }
+        }
+        for (id, url) in &self.webhook_urls {
+            let valid = reqwest::Url::parse(url)
+                .is_ok_and(|u| matches!(u.scheme(), "http" | "https") && u.host_str().is_some());
+            if !valid {
+                return Err(format!("integrations.webhook_urls.{} must be an http:// or https:// URL", id));
+            }
+        }
+        if self.slack_bot_token.as_deref().is_some_and(|token| !token.starts_with("xoxb-")) {
+            return Err("integrations.slack_bot_token must be a bot token starting with xoxb-".to_string());
+        }
+        if self.slack_bot_token.is_some() && self.slack_channel.is_none() {
+            return Err("integrations.slack_channel is needed for the bot to know where to post".to_string());
+        }
+        if let Some(secret) = &self.webhook_signing_secret {
+            if secret.chars().count() < MIN_SIGNING_SECRET_LEN {
+                return Err(format!(
+                    "integrations.webhook_signing_secret must be at least {} characters",
+                    MIN_SIGNING_SECRET_LEN
+                ));
+            }
+        }
+        if let Some(key) = &self.mailchimp_api_key {
+            // Keys end in their datacenter, e.g. "abc123-us21"
+            if !key.rsplit_once('-').is_some_and(|(key, dc)| !key.is_empty() && !dc.is_empty()) {
+                return Err("integrations.mailchimp_api_key must end in its datacenter, e.g. -us21".to_string());
+            }
+        }
+        Ok(())
+    }
+}
+
+/// `config` with its integration secrets masked, for admin responses that include the config
+pub fn masked(config: &ThermometerConfig) -> ThermometerConfig {
+    ThermometerConfig {
+        integrations: config.integrations.masked(),
+        ..config.clone()
+    }
+}
+
+/// `serialize_with` for response fields holding a config
+pub fn serialize_masked<S: Serializer>(config: &ThermometerConfig, serializer: S) -> Result<S::Ok, S::Error> {
+    masked(config).serialize(serializer)
+}
+
+#[utoipa::path(
+    get,
+    path = "/admin/integrations",
+    tag = "Admin",
+    security(("edit_key" = [])),
+    responses(
+        (status = 200, description = "The `integrations` section (of the staging copy while the sandbox is on) with every secret masked to its last 4 characters", body = IntegrationSettings),
+        (status = 401, description = "Unauthorized", body = ErrorResponse)
+    )
+)]
+pub async fn get_integrations(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+) -> Result<Json<IntegrationSettings>, (StatusCode, Json<ErrorResponse>)> {
+    require_admin(&headers, &state)?;
+
+    let (_, config) = EditTarget::load(&state).await.map_err(|e| {
+        error_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to load config: {}", e))
+    })?;
+    Ok(Json(config.integrations.masked()))
+}
+
+#[utoipa::path(
+    put,
+    path = "/admin/integrations",
+    tag = "Admin",
+    security(("edit_key" = [])),
+    params(ChangeNote),
+    request_body = IntegrationsUpdate,
+    responses(
+        (status = 200, description = "Integrations saved; secrets come back masked", body = IntegrationSettings),
+        (status = 400, description = "A Slack or Discord webhook that isn't an https:// URL, a webhook endpoint URL that isn't http(s), a signing secret under 16 characters, a Mailchimp key without its datacenter, or a Slack bot token that isn't `xoxb-` or has no `slack_channel`", body = ErrorResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse)
+    )
+)]
+pub async fn update_integrations(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Query(change): Query<ChangeNote>,
+    Json(update): Json<IntegrationsUpdate>,
+) -> Result<Json<IntegrationSettings>, (StatusCode, Json<ErrorResponse>)> {
+    require_admin(&headers, &state)?;
+
+    let _guard = state.config_lock.lock().await;
+    let (target, previous) = EditTarget::load(&state).await.map_err(|e| {
+        error_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to load config: {}", e))
+    })?;
+
+    let mut integrations = previous.integrations.clone();
+    apply(&mut integrations.slack_webhook_url, update.slack_webhook_url);
+    apply(&mut integrations.webhook_signing_secret, update.webhook_signing_secret);
+    apply(&mut integrations.mailchimp_api_key, update.mailchimp_api_key);
+    apply(&mut integrations.discord_webhook_url, update.discord_webhook_url);
+    apply(&mut integrations.slack_bot_token, update.slack_bot_token);
+    apply(&mut integrations.slack_channel, update.slack_channel);
+    for (id, url) in update.webhook_urls {
+        let mut current = integrations.webhook_urls.remove(&id);
+        apply(&mut current, Some(url));
+        if let Some(url) = current {
+            integrations.webhook_urls.insert(id, url);
+        }
+    }
+    integrations.validate().map_err(|e| error_response(StatusCode::BAD_REQUEST, e))?;
+
+    // Not a figures update, so `last_updated` is left alone
+    let mut config = previous.clone();
+    config.integrations = integrations;
+    target.commit(&state, &previous, &mut config, change.text()).await.map_err(|e| {
+        error_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to save config: {}", e))
+    })?;
+
+    tracing::info!("Updated integrations");
+    Ok(Json(config.integrations.masked()))
+}
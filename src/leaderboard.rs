@@ -0,0 +1,277 @@
+use askama::Template;
+use axum::{
+    extract::State,
+    http::StatusCode,
+    response::{IntoResponse, Response},
+};
+use std::collections::HashMap;
+
+use crate::avatars::{self, Placeholder};
+use crate::color_constants;
+use crate::render::{self, ImageAbout, ImageCache, ImageFormat};
+use crate::summary::{self, RankedTeam};
+use crate::thermometer::PngOptions;
+use crate::{error_response, AppState};
+
+/// Teams shown on the image
+const MAX_TEAMS: usize = 10;
+const WIDTH: f64 = 600.0;
+const MARGIN: f64 = 20.0;
+const HEADER_HEIGHT: f64 = 60.0;
+const ROW_HEIGHT: f64 = 56.0;
+const AVATAR_RADIUS: f64 = 20.0;
+const BAR_HEIGHT: f64 = 6.0;
+
+#[derive(Template)]
+#[template(path = "leaderboard.svg")]
+struct LeaderboardTemplate {
+    width: u32,
+    height: u32,
+    title: String,
+    title_x: String,
+    title_y: String,
+    rows: Vec<Row>,
+    rank_x: String,
+    avatar_cx: String,
+    avatar_x: String,
+    avatar_radius: String,
+    avatar_size: String,
+    initials_size: String,
+    pad_rx: String,
+    pad_ry: String,
+    toe_radius: String,
+    name_x: String,
+    amount_x: String,
+    bar_width: String,
+    bar_height: String,
+    // Color constants
+    background_color: &'static str,
+    title_text_color: &'static str,
+    text_primary_color: &'static str,
+    text_secondary_color: &'static str,
+    tube_fill_color: &'static str,
+    tube_stroke_color: &'static str,
+    fill_color: &'static str,
+    achieved_text_color: &'static str,
+    placeholder_background: &'static str,
+    placeholder_figure: &'static str,
+}
+
+struct Row {
+    rank: usize,
+    name: String,
+    amount: String,
+    /// Inlined avatar, or None for the placeholder or silhouette
+    avatar: Option<String>,
+    /// Initials or paw print for a team without an `image_url`
+    placeholder: Option<Placeholder>,
+    center_y: String,
+    avatar_y: String,
+    text_y: String,
+    bar_y: String,
+    bar_fill_width: String,
+    head_y: String,
+    head_radius: String,
+    shoulders_y: String,
+    shoulders_rx: String,
+    shoulders_ry: String,
+    initials_y: String,
+    pad_y: String,
+    toes: Vec<Point>,
+}
+
+struct Point {
+    x: String,
+    y: String,
+}
+
+/// The top teams with their avatars, each with a bar against the leading team. `avatars` maps
+/// image URLs to `data:` URIs; a URL without one gets a silhouette, and a team without a URL gets
+/// its placeholder.
+fn generate_leaderboard_svg(
+    title: &str,
+    teams: &[RankedTeam],
+    avatars: &HashMap<String, Option<String>>,
+    dark_mode: bool,
+) -> Result<String, askama::Error> {
+    let rank_x = MARGIN + 14.0;
+    let avatar_cx = MARGIN + 50.0;
+    let name_x = avatar_cx + AVATAR_RADIUS + 14.0;
+    let amount_x = WIDTH - MARGIN;
+    let bar_width = amount_x - name_x;
+    let top_score = teams.iter().map(|t| t.score).fold(0.0, f64::max);
+
+    let rows = teams
+        .iter()
+        .enumerate()
+        .map(|(i, team)| {
+            let center_y = HEADER_HEIGHT + (i as f64 + 0.5) * ROW_HEIGHT;
+            let share = if top_score > 0.0 { (team.score / top_score).clamp(0.0, 1.0) } else { 0.0 };
+            Row {
+                rank: team.rank,
+                name: team.name.clone(),
+                amount: format!("${:.0}", team.total_raised),
+                avatar: team.image_url.as_ref().and_then(|url| avatars.get(url).cloned().flatten()),
+                placeholder: team.image_url.is_none().then(|| team.placeholder()),
+                center_y: format!("{:.2}", center_y),
+                avatar_y: format!("{:.2}", center_y - AVATAR_RADIUS),
+                text_y: format!("{:.2}", center_y - 2.0),
+                bar_y: format!("{:.2}", center_y + 8.0),
+                bar_fill_width: format!("{:.2}", bar_width * share),
+                head_y: format!("{:.2}", center_y - AVATAR_RADIUS * 0.25),
+                head_radius: format!("{:.2}", AVATAR_RADIUS * 0.38),
+                shoulders_y: format!("{:.2}", center_y + AVATAR_RADIUS * 0.8),
+                shoulders_rx: format!("{:.2}", AVATAR_RADIUS * 0.7),
+                shoulders_ry: format!("{:.2}", AVATAR_RADIUS * 0.5),
+                initials_y: format!("{:.2}", center_y + AVATAR_RADIUS * 0.3),
+                pad_y: format!("{:.2}", center_y + AVATAR_RADIUS * 0.22),
+                toes: [(-0.45, -0.08), (-0.17, -0.4), (0.17, -0.4), (0.45, -0.08)]
+                    .into_iter()
+                    .map(|(dx, dy)| Point {
+                        x: format!("{:.2}", avatar_cx + AVATAR_RADIUS * dx),
+                        y: format!("{:.2}", center_y + AVATAR_RADIUS * dy),
+                    })
+                    .collect(),
+            }
+        })
+        .collect::<Vec<_>>();
+
+    let template = LeaderboardTemplate {
+        width: WIDTH as u32,
+        height: (HEADER_HEIGHT + rows.len() as f64 * ROW_HEIGHT + MARGIN) as u32,
+        title: title.to_string(),
+        title_x: format!("{:.2}", WIDTH / 2.0),
+        title_y: format!("{:.2}", MARGIN + 20.0),
+        rows,
+        rank_x: format!("{:.2}", rank_x),
+        avatar_cx: format!("{:.2}", avatar_cx),
+        avatar_x: format!("{:.2}", avatar_cx - AVATAR_RADIUS),
+        avatar_radius: format!("{:.2}", AVATAR_RADIUS),
+        avatar_size: format!("{:.2}", AVATAR_RADIUS * 2.0),
+        initials_size: format!("{:.2}", AVATAR_RADIUS * 0.85),
+        pad_rx: format!("{:.2}", AVATAR_RADIUS * 0.36),
+        pad_ry: format!("{:.2}", AVATAR_RADIUS * 0.3),
+        toe_radius: format!("{:.2}", AVATAR_RADIUS * 0.14),
+        name_x: format!("{:.2}", name_x),
+        amount_x: format!("{:.2}", amount_x),
+        bar_width: format!("{:.2}", bar_width),
+        bar_height: format!("{:.2}", BAR_HEIGHT),
+        background_color: color_constants::light::BACKGROUND,
+        title_text_color: color_constants::light::TITLE_TEXT,
+        text_primary_color: color_constants::light::TEXT_PRIMARY,
+        text_secondary_color: color_constants::light::TEXT_SECONDARY,
+        tube_fill_color: color_constants::light::TUBE_FILL,
+        tube_stroke_color: color_constants::light::TUBE_STROKE,
+        fill_color: color_constants::light::FILL_COLOR_1,
+        achieved_text_color: color_constants::light::ACHIEVED_TEXT,
+        placeholder_background: color_constants::light::AVATAR_BACKGROUND,
+        placeholder_figure: color_constants::light::AVATAR_FIGURE,
+    };
+
+    if dark_mode {
+        LeaderboardTemplate {
+            background_color: color_constants::dark::BACKGROUND,
+            title_text_color: color_constants::dark::TITLE_TEXT,
+            text_primary_color: color_constants::dark::TEXT_PRIMARY,
+            text_secondary_color: color_constants::dark::TEXT_SECONDARY,
+            tube_fill_color: color_constants::dark::TUBE_FILL,
+            tube_stroke_color: color_constants::dark::TUBE_STROKE,
+            fill_color: color_constants::dark::FILL_COLOR_1,
+            achieved_text_color: color_constants::dark::ACHIEVED_TEXT,
+            placeholder_background: color_constants::dark::AVATAR_BACKGROUND,
+            placeholder_figure: color_constants::dark::AVATAR_FIGURE,
+            ..template
+        }
+        .render()
+    } else {
+        template.render()
+    }
+}
+
+async fn leaderboard_image(state: &AppState, format: ImageFormat, dark_mode: bool) -> Response {
+    let config = match state.storage.load_config().await {
+        Ok(config) => config,
+        Err(e) => {
+            return render::error_image("Failed to load config for the leaderboard image", e, format, dark_mode, 1.0)
+        }
+    };
+    let mut teams = summary::summarize(&config).teams;
+    if teams.is_empty() {
+        return error_response(StatusCode::NOT_FOUND, "No active teams").into_response();
+    }
+    teams.truncate(MAX_TEAMS);
+
+    let urls: Vec<String> = teams.iter().filter_map(|t| t.image_url.clone()).collect();
+    let avatars = avatars::resolve(state, &urls).await;
+    match generate_leaderboard_svg(&config.title, &teams, &avatars, dark_mode) {
+        Ok(svg) => {
+            let about = ImageAbout::new(
+                format!("Top teams: {}", config.title),
+                teams
+                    .iter()
+                    .map(|t| format!("{}. {}: ${:.2}", t.rank, t.name, t.total_raised))
+                    .collect::<Vec<_>>()
+                    .join(", "),
+                config.last_updated.clone(),
+            );
+            render::image_response(StatusCode::OK, svg, format, PngOptions::at_scale(1.0), ImageCache::NoCache, &about)
+        }
+        Err(e) => render::error_image("Failed to render the leaderboard image", e, format, dark_mode, 1.0),
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/leaderboard.svg",
+    tag = "Public",
+    responses(
+        (status = 200, description = "The top 10 teams by score with their avatars inlined; teams without an avatar get their initials on their color, and avatars that can't be fetched are shown as a silhouette", content_type = "image/svg+xml", body = String),
+        (status = 404, description = "No active teams", body = ErrorResponse),
+        (status = 500, description = "Placeholder image shown when rendering fails", content_type = "image/svg+xml", body = String)
+    )
+)]
+pub async fn leaderboard_svg(State(state): State<AppState>) -> Response {
+    leaderboard_image(&state, ImageFormat::Svg, false).await
+}
+
+#[utoipa::path(
+    get,
+    path = "/leaderboard.png",
+    tag = "Public",
+    responses(
+        (status = 200, description = "PNG version of `/leaderboard.svg`", content_type = "image/png", body = String),
+        (status = 404, description = "No active teams", body = ErrorResponse),
+        (status = 500, description = "Placeholder image shown when rendering fails", content_type = "image/png", body = String)
+    )
+)]
+pub async fn leaderboard_png(State(state): State<AppState>) -> Response {
+    leaderboard_image(&state, ImageFormat::Png, false).await
+}
+
+#[utoipa::path(
+    get,
+    path = "/leaderboard-dark.svg",
+    tag = "Public",
+    responses(
+        (status = 200, description = "`/leaderboard.svg` in the dark theme", content_type = "image/svg+xml", body = String),
+        (status = 404, description = "No active teams", body = ErrorResponse),
+        (status = 500, description = "Placeholder image shown when rendering fails", content_type = "image/svg+xml", body = String)
+    )
+)]
+pub async fn leaderboard_dark_svg(State(state): State<AppState>) -> Response {
+    leaderboard_image(&state, ImageFormat::Svg, true).await
+}
+
+#[utoipa::path(
+    get,
+    path = "/leaderboard-dark.png",
+    tag = "Public",
+    responses(
+        (status = 200, description = "PNG version of `/leaderboard-dark.svg`", content_type = "image/png", body = String),
+        (status = 404, description = "No active teams", body = ErrorResponse),
+        (status = 500, description = "Placeholder image shown when rendering fails", content_type = "image/png", body = String)
+    )
+)]
+pub async fn leaderboard_dark_png(State(state): State<AppState>) -> Response {
+    leaderboard_image(&state, ImageFormat::Png, true).await
+}
@@ -0,0 +1,584 @@
+use axum::{
+    extract::State,
+    http::{HeaderMap, StatusCode},
+    response::Json,
+};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::donors::DonorDisplaySettings;
+use crate::momentum;
+use crate::receipts;
+use crate::webhooks;
+use crate::storage::StorageError;
+use crate::idempotency::IdempotencyKeyHeader;
+use crate::sandbox::EditTarget;
+use crate::{
+    config_committed, error_response, require_admin, AppState, ErrorResponse, SuccessResponse, Team, ThermometerConfig,
+};
+
+/// Team credited when a donation doesn't name one
+pub const GENERAL_TEAM: &str = "General";
+
+/// Where a ledger entry came from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "kebab-case")]
+pub enum DonationSource {
+    Stripe,
+    Paypal,
+    Facebook,
+    Cash,
+    /// Checks and other hand-entered donations
+    Manual,
+    Phonebank,
+    /// Adjustments from a spreadsheet (CSV) upload
+    SheetSync,
+    /// Bulk adjustments from `POST /admin/teams/bulk`
+    Adjustment,
+}
+
+impl DonationSource {
+    /// Whether entries from this source are gifts from a donor rather than corrections to totals
+    pub fn is_gift(self) -> bool {
+        !matches!(self, DonationSource::SheetSync | DonationSource::Adjustment)
+    }
+}
+
+/// How an offline donation was paid
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum PaymentMethod {
+    Cash,
+    Check,
+}
+
+/// Review state of a donor's public message
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum ModerationStatus {
+    #[default]
+    Pending,
+    Approved,
+    Rejected,
+}
+
+/// A single donation recorded in the ledger
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct Donation {
+    pub id: String,
+    pub team: String,
+    pub amount: f64,
+    pub donor_name: Option<String>,
+    pub donor_email: Option<String>,
+    pub source: DonationSource,
+    pub created_at: String,
+    /// Recurring pledge this payment belongs to, if any
+    #[serde(default)]
+    pub pledge_id: Option<String>,
+    /// Payment method for offline donations
+    #[serde(default)]
+    pub method: Option<PaymentMethod>,
+    /// Public message from the donor, shown on the donor wall once approved
+    #[serde(default)]
+    pub message: Option<String>,
+    #[serde(default)]
+    pub moderation: ModerationStatus,
+    /// Hide the donor's name publicly; `None` uses the campaign default
+    #[serde(default)]
+    pub anonymous: Option<bool>,
+    /// Name to show publicly instead of `donor_name`
+    #[serde(default)]
+    pub display_name: Option<String>,
+    /// Phone bank volunteer who took the pledge
+    #[serde(default)]
+    pub caller: Option<String>,
+    /// Whether the gift is tax-deductible; `None` uses the campaign default
+    #[serde(default)]
+    pub tax_deductible: Option<bool>,
+    /// UK Gift Aid declaration, if the donor made one
+    #[serde(default)]
+    pub gift_aid: Option<GiftAidDeclaration>,
+    /// Staff note kept with the ledger entry, never shown publicly
+    #[serde(default)]
+    pub note: Option<String>,
+}
+
+/// A UK donor's declaration that they pay enough tax for the charity to claim Gift Aid on the gift.
+/// HMRC claims need the donor's home address and postcode alongside their name.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct GiftAidDeclaration {
+    /// RFC 3339 time the donor made the declaration
+    pub declared_at: String,
+    pub home_address: String,
+    pub postcode: String,
+}
+
+impl Donation {
+    /// Name to show on public surfaces (donor wall, feeds, notifications), honoring anonymity
+    pub fn public_name(&self, settings: &DonorDisplaySettings) -> String {
+        if self.anonymous.unwrap_or(settings.anonymous_by_default) {
+            return "Anonymous".to_string();
+        }
+
+        self.display_name
+            .as_ref()
+            .or(self.donor_name.as_ref())
+            .filter(|name| !name.trim().is_empty())
+            .cloned()
+            .unwrap_or_else(|| "A generous donor".to_string())
+    }
+
+    /// The donor's message, only if it has been approved for public display
+    pub fn public_message(&self) -> Option<&str> {
+        match self.moderation {
+            ModerationStatus::Approved => self.message.as_deref(),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct QuickDonationRequest {
+    team: String,
+    amount: f64,
+    method: PaymentMethod,
+    /// Channel the gift came through; defaults to `cash` for cash and `manual` for checks
+    source: Option<DonationSource>,
+    donor: Option<String>,
+    donor_email: Option<String>,
+    /// Public message (held for moderation)
+    message: Option<String>,
+    anonymous: Option<bool>,
+    display_name: Option<String>,
+    /// Omitted uses the campaign default
+    tax_deductible: Option<bool>,
+    gift_aid: Option<GiftAidDeclaration>,
+}
+
+/// A single gift to add to a team's total without re-uploading the spreadsheet
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct DonationRequest {
+    /// Created if no team has this name yet
+    team: String,
+    amount: f64,
+    donor_name: Option<String>,
+    /// Staff note kept with the ledger entry, never shown publicly
+    note: Option<String>,
+}
+
+/// Compact response for phone entry: the recorded donation plus updated totals
+#[derive(Serialize, ToSchema)]
+pub struct QuickDonationResponse {
+    donation: Donation,
+    team_total: f64,
+    total_raised: f64,
+}
+
+/// A recurring monthly gift. Each payment is recorded as a separate `Donation` linked by `pledge_id`.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct RecurringPledge {
+    pub id: String,
+    pub team: String,
+    pub monthly_amount: f64,
+    pub donor_name: Option<String>,
+    pub donor_email: Option<String>,
+    /// First month of the pledge (`YYYY-MM-DD`)
+    pub start_date: String,
+    /// Payment processor subscription ID (e.g. Stripe `sub_...`) used to match future payments
+    pub subscription_id: Option<String>,
+    pub source: DonationSource,
+    pub active: bool,
+    /// Anonymity preference applied to each payment; `None` uses the campaign default
+    #[serde(default)]
+    pub anonymous: Option<bool>,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct CreatePledgeRequest {
+    team: String,
+    monthly_amount: f64,
+    donor_name: Option<String>,
+    donor_email: Option<String>,
+    /// Defaults to today
+    start_date: Option<String>,
+    subscription_id: Option<String>,
+    anonymous: Option<bool>,
+}
+
+/// Aggregate recurring revenue, shown separately from one-time totals
+#[derive(Serialize, ToSchema)]
+pub struct RecurringSummary {
+    pub active_pledges: usize,
+    /// Sum of monthly amounts across active pledges
+    pub projected_monthly: f64,
+    /// Total already received from recurring pledges (included in team totals)
+    pub collected_to_date: f64,
+}
+
+/// Credit a donation's amount to its team's total, creating the team if it doesn't exist yet.
+/// Storage backends call this inside the transaction that appends the ledger entry.
+pub fn credit_donation(config: &mut ThermometerConfig, donation: &Donation) {
+    let now = chrono::Utc::now().to_rfc3339();
+    match config.teams.iter_mut().find(|t| t.name == donation.team) {
+        Some(team) => {
+            team.total_raised += donation.amount;
+            team.last_changed = Some(now.clone());
+        }
+        None => config.teams.push(Team {
+            name: donation.team.clone(),
+            image_url: None,
+            total_raised: donation.amount,
+            deleted_at: None,
+            sort_order: None,
+            last_changed: Some(now.clone()),
+            goal: None,
+            group: None,
+            members: None,
+            score_multiplier: None,
+            color: None,
+            captain_name: None,
+            captain_email: None,
+            captain_phone: None,
+        }),
+    }
+    config.last_updated = now;
+}
+
+/// Append a donation to the ledger and credit its amount to the team's total,
+/// creating the team if it doesn't exist yet, then send the donor a receipt. A donation whose
+/// ID is already in the ledger (a retried payment webhook) changes nothing and returns the
+/// current config.
+pub async fn record_donation(
+    state: &AppState,
+    donation: Donation,
+) -> Result<ThermometerConfig, StorageError> {
+    let _guard = state.config_lock.lock().await;
+    // The ledger entry and the new total are written atomically, so concurrent Stripe webhooks
+    // handled by other instances can't overwrite each other's increments
+    let Some((previous, config)) = state.storage.record_donation(&donation).await? else {
+        tracing::info!("Ignoring donation {} already in the ledger", donation.id);
+        return state.storage.load_config().await;
+    };
+    config_committed(state, &previous, &config, None).await;
+    receipts::send_receipt(state, &config, &donation);
+    webhooks::on_donation(state, &config, &donation);
+    momentum::on_donation(state, &config, &donation).await;
+
+    tracing::info!(
+        "Recorded {:?} donation {} of ${:.2} for {}",
+        donation.source,
+        donation.id,
+        donation.amount,
+        donation.team
+    );
+    Ok(config)
+}
+
+/// Record an admin-entered donation. While the sandbox is on it only credits the staging copy:
+/// nothing reaches the ledger, receipts or webhooks.
+async fn record_quick_donation(state: &AppState, donation: &Donation) -> Result<ThermometerConfig, StorageError> {
+    {
+        let _guard = state.config_lock.lock().await;
+        if let (EditTarget::Staging, previous) = EditTarget::load(state).await? {
+            let mut config = previous.clone();
+            credit_donation(&mut config, donation);
+            EditTarget::Staging.commit(state, &previous, &mut config, None).await?;
+            tracing::info!("Credited sandbox donation of ${:.2} to {}", donation.amount, donation.team);
+            return Ok(config);
+        }
+    }
+    record_donation(state, donation.clone()).await
+}
+
+/// Append a ledger entry from `source` (`sheet-sync` or `adjustment`) for every team whose total
+/// changed between `before` and `after`. The config itself is replaced by the caller, so team
+/// totals are not touched here. Call it once that save has succeeded, so a failed save leaves no
+/// entries behind.
+pub async fn record_total_changes(
+    state: &AppState,
+    before: &[Team],
+    after: &[Team],
+    source: DonationSource,
+) -> Result<(), StorageError> {
+    let total_of = |teams: &[Team], name: &str| {
+        teams.iter().find(|t| t.name == name).map(|t| t.total_raised).unwrap_or(0.0)
+    };
+
+    let names = after.iter().chain(before).map(|t| t.name.as_str());
+    let mut seen: Vec<&str> = Vec::new();
+    for name in names {
+        if seen.contains(&name) {
+            continue;
+        }
+        seen.push(name);
+
+        let delta = ((total_of(after, name) - total_of(before, name)) * 100.0).round() / 100.0;
+        if delta == 0.0 {
+            continue;
+        }
+
+        state
+            .storage
+            .append_donation(&Donation {
+                id: Uuid::new_v4().to_string(),
+                team: name.to_string(),
+                amount: delta,
+                donor_name: None,
+                donor_email: None,
+                source,
+                created_at: chrono::Utc::now().to_rfc3339(),
+                pledge_id: None,
+                method: None,
+                message: None,
+                moderation: ModerationStatus::Pending,
+                anonymous: None,
+                display_name: None,
+                caller: None,
+                tax_deductible: None,
+                gift_aid: None,
+                note: None,
+            })
+            .await?;
+    }
+    Ok(())
+}
+
+#[utoipa::path(
+    post,
+    path = "/admin/quick-donation",
+    tag = "Admin",
+    security(("edit_key" = [])),
+    params(IdempotencyKeyHeader),
+    request_body = QuickDonationRequest,
+    responses(
+        (status = 200, description = "Donation recorded", body = QuickDonationResponse),
+        (status = 400, description = "Bad request", body = ErrorResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse)
+    )
+)]
+pub async fn quick_donation(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Json(request): Json<QuickDonationRequest>,
+) -> Result<Json<QuickDonationResponse>, (StatusCode, Json<ErrorResponse>)> {
+    require_admin(&headers, &state)?;
+
+    let team = request.team.trim().to_string();
+    if team.is_empty() {
+        return Err(error_response(StatusCode::BAD_REQUEST, "team is required"));
+    }
+    if !request.amount.is_finite() || request.amount <= 0.0 {
+        return Err(error_response(StatusCode::BAD_REQUEST, "amount must be positive"));
+    }
+
+    let donation = Donation {
+        id: Uuid::new_v4().to_string(),
+        team: team.clone(),
+        amount: (request.amount * 100.0).round() / 100.0,
+        donor_name: request.donor.filter(|d| !d.trim().is_empty()),
+        donor_email: request.donor_email.filter(|d| !d.trim().is_empty()),
+        source: request.source.unwrap_or(match request.method {
+            PaymentMethod::Cash => DonationSource::Cash,
+            PaymentMethod::Check => DonationSource::Manual,
+        }),
+        created_at: chrono::Utc::now().to_rfc3339(),
+        pledge_id: None,
+        method: Some(request.method),
+        message: request.message.filter(|m| !m.trim().is_empty()),
+        moderation: ModerationStatus::Pending,
+        anonymous: request.anonymous,
+        display_name: request.display_name.filter(|n| !n.trim().is_empty()),
+        caller: None,
+        tax_deductible: request.tax_deductible,
+        gift_aid: request.gift_aid,
+        note: None,
+    };
+
+    let config = record_quick_donation(&state, &donation).await.map_err(|e| {
+        error_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to record donation: {}", e))
+    })?;
+
+    Ok(Json(QuickDonationResponse {
+        team_total: config
+            .teams
+            .iter()
+            .find(|t| t.name == team)
+            .map(|t| t.total_raised)
+            .unwrap_or(donation.amount),
+        total_raised: config.total_raised(),
+        donation,
+    }))
+}
+
+#[utoipa::path(
+    post,
+    path = "/admin/donation",
+    tag = "Admin",
+    security(("edit_key" = [])),
+    params(IdempotencyKeyHeader),
+    request_body = DonationRequest,
+    responses(
+        (status = 200, description = "Donation added to the team's total", body = SuccessResponse),
+        (status = 400, description = "Bad request", body = ErrorResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse)
+    )
+)]
+pub async fn add_donation(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Json(request): Json<DonationRequest>,
+) -> Result<Json<SuccessResponse>, (StatusCode, Json<ErrorResponse>)> {
+    require_admin(&headers, &state)?;
+
+    let team = request.team.trim().to_string();
+    if team.is_empty() {
+        return Err(error_response(StatusCode::BAD_REQUEST, "team is required"));
+    }
+    if !request.amount.is_finite() || request.amount <= 0.0 {
+        return Err(error_response(StatusCode::BAD_REQUEST, "amount must be positive"));
+    }
+
+    let donation = Donation {
+        id: Uuid::new_v4().to_string(),
+        team: team.clone(),
+        amount: (request.amount * 100.0).round() / 100.0,
+        donor_name: request.donor_name.filter(|d| !d.trim().is_empty()),
+        donor_email: None,
+        source: DonationSource::Manual,
+        created_at: chrono::Utc::now().to_rfc3339(),
+        pledge_id: None,
+        method: None,
+        message: None,
+        moderation: ModerationStatus::Pending,
+        anonymous: None,
+        display_name: None,
+        caller: None,
+        tax_deductible: None,
+        gift_aid: None,
+        note: request.note.filter(|n| !n.trim().is_empty()),
+    };
+
+    // The storage backends credit the team inside the same transaction that appends the ledger
+    // entry, so concurrent donations can't overwrite each other's increments
+    let config = record_quick_donation(&state, &donation).await.map_err(|e| {
+        error_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to record donation: {}", e))
+    })?;
+
+    Ok(Json(SuccessResponse {
+        message: format!("Added ${:.2} to {}", donation.amount, team),
+        config,
+    }))
+}
+
+/// Compute the recurring revenue summary from stored pledges and ledger payments
+pub async fn recurring_summary(state: &AppState) -> Result<RecurringSummary, StorageError> {
+    let pledges = state.storage.list_pledges().await?;
+    let donations = state.storage.list_donations().await?;
+
+    let active: Vec<&RecurringPledge> = pledges.iter().filter(|p| p.active).collect();
+    Ok(RecurringSummary {
+        active_pledges: active.len(),
+        projected_monthly: active.iter().map(|p| p.monthly_amount).sum::<f64>() + 0.0,
+        collected_to_date: donations
+            .iter()
+            .filter(|d| d.pledge_id.is_some())
+            .map(|d| d.amount)
+            .sum::<f64>()
+            + 0.0,
+    })
+}
+
+#[utoipa::path(
+    get,
+    path = "/stats/recurring",
+    tag = "Public",
+    responses(
+        (status = 200, description = "Projected recurring revenue", body = RecurringSummary)
+    )
+)]
+pub async fn get_recurring_summary(
+    State(state): State<AppState>,
+) -> Result<Json<RecurringSummary>, (StatusCode, Json<ErrorResponse>)> {
+    let summary = recurring_summary(&state).await.map_err(|e| {
+        error_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to load pledges: {}", e))
+    })?;
+    Ok(Json(summary))
+}
+
+#[utoipa::path(
+    get,
+    path = "/admin/pledges",
+    tag = "Admin",
+    security(("edit_key" = [])),
+    responses(
+        (status = 200, description = "All recurring pledges", body = [RecurringPledge]),
+        (status = 401, description = "Unauthorized", body = ErrorResponse)
+    )
+)]
+pub async fn list_pledges(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+) -> Result<Json<Vec<RecurringPledge>>, (StatusCode, Json<ErrorResponse>)> {
+    require_admin(&headers, &state)?;
+
+    let pledges = state.storage.list_pledges().await.map_err(|e| {
+        error_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to load pledges: {}", e))
+    })?;
+    Ok(Json(pledges))
+}
+
+#[utoipa::path(
+    post,
+    path = "/admin/pledges",
+    tag = "Admin",
+    security(("edit_key" = [])),
+    params(IdempotencyKeyHeader),
+    request_body = CreatePledgeRequest,
+    responses(
+        (status = 200, description = "Pledge created", body = RecurringPledge),
+        (status = 400, description = "Bad request", body = ErrorResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse)
+    )
+)]
+pub async fn create_pledge(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Json(request): Json<CreatePledgeRequest>,
+) -> Result<Json<RecurringPledge>, (StatusCode, Json<ErrorResponse>)> {
+    require_admin(&headers, &state)?;
+
+    let team = request.team.trim().to_string();
+    if team.is_empty() {
+        return Err(error_response(StatusCode::BAD_REQUEST, "team is required"));
+    }
+    if !request.monthly_amount.is_finite() || request.monthly_amount <= 0.0 {
+        return Err(error_response(StatusCode::BAD_REQUEST, "monthly_amount must be positive"));
+    }
+
+    let start_date = match request.start_date {
+        Some(date) => chrono::NaiveDate::parse_from_str(&date, "%Y-%m-%d")
+            .map_err(|_| error_response(StatusCode::BAD_REQUEST, "start_date must be YYYY-MM-DD"))?,
+        None => chrono::Utc::now().date_naive(),
+    };
+
+    let pledge = RecurringPledge {
+        id: Uuid::new_v4().to_string(),
+        team,
+        monthly_amount: request.monthly_amount,
+        donor_name: request.donor_name,
+        donor_email: request.donor_email,
+        start_date: start_date.to_string(),
+        subscription_id: request.subscription_id,
+        source: DonationSource::Manual,
+        active: true,
+        anonymous: request.anonymous,
+    };
+
+    state.storage.save_pledge(&pledge).await.map_err(|e| {
+        error_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to save pledge: {}", e))
+    })?;
+
+    tracing::info!("Created recurring pledge {} for {}", pledge.id, pledge.team);
+    Ok(Json(pledge))
+}
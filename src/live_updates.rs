@@ -0,0 +1,275 @@
+use axum::{
+    extract::{
+        ws::{Message, WebSocket, WebSocketUpgrade},
+        State,
+    },
+    response::{
+        sse::{Event, KeepAlive, Sse},
+        Response,
+    },
+};
+use serde::Serialize;
+use serde_json::json;
+use std::convert::Infallible;
+use std::time::{Duration, Instant};
+use tokio::sync::{broadcast, mpsc};
+use tokio_stream::wrappers::ReceiverStream;
+use utoipa::ToSchema;
+
+use crate::shutdown;
+use crate::versions::TeamTotal;
+use crate::views::PublicConfig;
+use crate::{AppState, ThermometerConfig};
+
+/// Updates kept for subscribers that fall behind before the oldest are dropped
+const CHANNEL_CAPACITY: usize = 16;
+/// How often a stream reloads the config to pick up saves made by other instances
+const RELOAD_INTERVAL: Duration = Duration::from_secs(30);
+/// How often `/ws` pings its clients. One that hasn't answered for two intervals is dropped.
+const HEARTBEAT_INTERVAL: Duration = Duration::from_secs(30);
+
+/// What a save changed
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ConfigChange {
+    /// The campaign total or a team's total
+    Total,
+    TeamAdded,
+    TeamRemoved,
+    Goal,
+    /// Anything else, e.g. the title or image settings
+    Other,
+}
+
+/// The campaign's figures after a save, sent to `/events` subscribers
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct ConfigUpdate {
+    /// Empty for the first event of a stream, which carries the figures at the time of connecting
+    pub changes: Vec<ConfigChange>,
+    pub title: String,
+    pub total_raised: f64,
+    pub goal: f64,
+    pub progress_percent: f64,
+    /// Active teams and their totals
+    pub teams: Vec<TeamTotal>,
+    pub last_updated: String,
+}
+
+impl ConfigUpdate {
+    fn of(config: &ThermometerConfig, changes: Vec<ConfigChange>) -> Self {
+        Self {
+            changes,
+            title: config.title.clone(),
+            total_raised: config.total_raised(),
+            goal: config.goal,
+            progress_percent: config.progress_percent(),
+            teams: config
+                .active_teams()
+                .map(|t| TeamTotal {
+                    name: t.name.clone(),
+                    total_raised: t.total_raised,
+                })
+                .collect(),
+            last_updated: config.last_updated.clone(),
+        }
+    }
+}
+
+/// Channel `/events` subscribers receive config updates on; one per campaign/tenant
+pub fn channel() -> broadcast::Sender<ConfigUpdate> {
+    broadcast::channel(CHANNEL_CAPACITY).0
+}
+
+/// What changed between two saves of the config
+pub fn changes(previous: &ThermometerConfig, config: &ThermometerConfig) -> Vec<ConfigChange> {
+    let names = |c: &ThermometerConfig| c.active_teams().map(|t| t.name.clone()).collect::<Vec<_>>();
+    let (before, after) = (names(previous), names(config));
+    let totals = |c: &ThermometerConfig| c.active_teams().map(|t| t.total_raised).collect::<Vec<_>>();
+
+    let mut changes = Vec::new();
+    if previous.total_raised() != config.total_raised() || totals(previous) != totals(config) {
+        changes.push(ConfigChange::Total);
+    }
+    if after.iter().any(|name| !before.contains(name)) {
+        changes.push(ConfigChange::TeamAdded);
+    }
+    if before.iter().any(|name| !after.contains(name)) {
+        changes.push(ConfigChange::TeamRemoved);
+    }
+    if previous.goal != config.goal {
+        changes.push(ConfigChange::Goal);
+    }
+    if changes.is_empty() {
+        changes.push(ConfigChange::Other);
+    }
+    changes
+}
+
+/// Send the saved config to live subscribers
+pub fn on_config_change(state: &AppState, previous: &ThermometerConfig, config: &ThermometerConfig) {
+    // Nobody listening is fine
+    let _ = state.config_updates.send(ConfigUpdate::of(config, changes(previous, config)));
+}
+
+fn event(update: &ConfigUpdate) -> Event {
+    Event::default()
+        .event("config")
+        .id(update.last_updated.clone())
+        .json_data(update)
+        .unwrap_or_default()
+}
+
+#[utoipa::path(
+    get,
+    path = "/events",
+    tag = "Public",
+    responses(
+        (status = 200, description = "Server-Sent Events stream of `config` events: the current figures on connecting, then the figures after every save with what it changed. The event ID is the config's `last_updated`", content_type = "text/event-stream", body = ConfigUpdate)
+    )
+)]
+pub async fn get_events(State(state): State<AppState>) -> Sse<ReceiverStream<Result<Event, Infallible>>> {
+    let (tx, rx) = mpsc::channel(CHANNEL_CAPACITY);
+    let mut updates = state.config_updates.subscribe();
+    tokio::spawn(async move {
+        // Saves on this instance arrive on the channel; the reload catches those made elsewhere
+        let mut current = match state.storage.load_config().await {
+            Ok(config) => config,
+            Err(e) => {
+                tracing::warn!("Failed to load config for live updates: {}", e);
+                return;
+            }
+        };
+        let mut last_sent = current.last_updated.clone();
+        if tx.send(Ok(event(&ConfigUpdate::of(&current, vec![])))).await.is_err() {
+            return;
+        }
+
+        let mut reload = tokio::time::interval(RELOAD_INTERVAL);
+        reload.tick().await;
+        loop {
+            let update = tokio::select! {
+                received = updates.recv() => match received {
+                    Ok(update) => update,
+                    Err(broadcast::error::RecvError::Lagged(_)) => continue,
+                    Err(broadcast::error::RecvError::Closed) => return,
+                },
+                _ = reload.tick() => match state.storage.load_config().await {
+                    Ok(config) => {
+                        let update = ConfigUpdate::of(&config, changes(&current, &config));
+                        current = config;
+                        update
+                    }
+                    Err(e) => {
+                        tracing::warn!("Failed to reload config for live updates: {}", e);
+                        continue;
+                    }
+                },
+                // Ending the stream lets the client reconnect to the next instance
+                _ = shutdown::draining() => return,
+            };
+            // Skips reloads that found nothing new and saves already sent
+            if update.last_updated == last_sent {
+                continue;
+            }
+            last_sent = update.last_updated.clone();
+            if tx.send(Ok(event(&update))).await.is_err() {
+                return;
+            }
+        }
+    });
+    Sse::new(ReceiverStream::new(rx)).keep_alive(KeepAlive::default())
+}
+
+#[utoipa::path(
+    get,
+    path = "/ws",
+    tag = "Public",
+    responses(
+        (status = 101, description = "WebSocket for live widgets. The server sends `{\"type\": \"config\", \"config\": {...}}` with the public configuration (as in `/config`) on connect and after every change, and pings every 30 seconds. Clients may send `{\"type\": \"ping\"}` and get `{\"type\": \"pong\"}` back")
+    )
+)]
+pub async fn get_ws(State(state): State<AppState>, ws: WebSocketUpgrade) -> Response {
+    ws.on_upgrade(move |socket| widget_socket(state, socket))
+}
+
+/// Send the config unless it's the one last sent, returning the `last_updated` now on the
+/// client. None once the client is gone.
+async fn send_config(state: &AppState, socket: &mut WebSocket, last_sent: &str) -> Option<String> {
+    let config = match state.storage.load_config().await {
+        Ok(config) => config,
+        Err(e) => {
+            tracing::warn!("Failed to load config for a widget socket: {}", e);
+            return Some(last_sent.to_string());
+        }
+    };
+    if config.last_updated == last_sent {
+        return Some(config.last_updated);
+    }
+    let message = json!({ "type": "config", "config": PublicConfig::from(&config) }).to_string();
+    socket.send(Message::Text(message)).await.ok()?;
+    Some(config.last_updated)
+}
+
+fn is_ping(text: &str) -> bool {
+    serde_json::from_str::<serde_json::Value>(text)
+        .is_ok_and(|message| message.get("type").and_then(|t| t.as_str()) == Some("ping"))
+}
+
+/// Push the config to a widget on connect and after every save until it disconnects, stops
+/// answering pings or the server starts shutting down
+async fn widget_socket(state: AppState, mut socket: WebSocket) {
+    let mut updates = state.config_updates.subscribe();
+    let Some(mut last_sent) = send_config(&state, &mut socket, "").await else {
+        return;
+    };
+
+    let mut reload = tokio::time::interval(RELOAD_INTERVAL);
+    reload.tick().await;
+    let mut heartbeat = tokio::time::interval(HEARTBEAT_INTERVAL);
+    heartbeat.tick().await;
+    let mut last_heard = Instant::now();
+    loop {
+        let sent = tokio::select! {
+            received = updates.recv() => match received {
+                Ok(update) if update.last_updated == last_sent => continue,
+                // A missed update still means the client is behind
+                Ok(_) | Err(broadcast::error::RecvError::Lagged(_)) => {
+                    send_config(&state, &mut socket, &last_sent).await
+                }
+                Err(broadcast::error::RecvError::Closed) => return,
+            },
+            // Saves on this instance arrive on the channel; the reload catches those made elsewhere
+            _ = reload.tick() => send_config(&state, &mut socket, &last_sent).await,
+            _ = heartbeat.tick() => {
+                if last_heard.elapsed() > HEARTBEAT_INTERVAL * 2 {
+                    let _ = socket.send(Message::Close(None)).await;
+                    return;
+                }
+                socket.send(Message::Ping(vec![])).await.ok().map(|_| last_sent.clone())
+            }
+            _ = shutdown::draining() => {
+                let _ = socket.send(Message::Close(None)).await;
+                return;
+            }
+            message = socket.recv() => match message {
+                Some(Ok(message)) => {
+                    last_heard = Instant::now();
+                    match message {
+                        Message::Text(text) if is_ping(&text) => socket
+                            .send(Message::Text(json!({ "type": "pong" }).to_string()))
+                            .await
+                            .ok()
+                            .map(|_| last_sent.clone()),
+                        Message::Close(_) => return,
+                        _ => continue,
+                    }
+                }
+                Some(Err(_)) | None => return,
+            },
+        };
+        match sent {
+            Some(last_updated) => last_sent = last_updated,
+            None => return,
+        }
+    }
+}
@@ -0,0 +1,166 @@
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use utoipa::ToSchema;
+
+use crate::{AppState, ThermometerConfig};
+
+/// Mailchimp milestone automation settings. The API key is `integrations.mailchimp_api_key`, or
+/// `MAILCHIMP_API_KEY` when that isn't set.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, ToSchema)]
+#[serde(default)]
+pub struct MailchimpSettings {
+    enabled: bool,
+    /// Audience (list) ID used for merge field updates
+    audience_id: String,
+    milestones: Vec<MailchimpMilestone>,
+}
+
+/// Action to run when progress crosses `percent`
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct MailchimpMilestone {
+    percent: f64,
+    /// Preconfigured campaign to replicate and send (the original stays as a reusable template)
+    campaign_id: Option<String>,
+    /// Audience merge field tag (e.g. `MILESTONE`) whose default value is set to the milestone percent
+    merge_field: Option<String>,
+}
+
+struct MailchimpClient {
+    http: reqwest::Client,
+    api_key: String,
+    base_url: String,
+}
+
+impl MailchimpClient {
+    fn new(http: reqwest::Client, api_key: String) -> Option<Self> {
+        // API keys end in the datacenter, e.g. "abc123-us21"
+        let datacenter = api_key.rsplit_once('-')?.1.to_string();
+        Some(Self {
+            http,
+            base_url: format!("https://{}.api.mailchimp.com/3.0", datacenter),
+            api_key,
+        })
+    }
+
+    async fn request(
+        &self,
+        method: reqwest::Method,
+        path: &str,
+        body: Option<serde_json::Value>,
+    ) -> Result<serde_json::Value, String> {
+        let mut request = self
+            .http
+            .request(method, format!("{}{}", self.base_url, path))
+            .basic_auth("thermometer", Some(&self.api_key));
+        if let Some(body) = body {
+            request = request.json(&body);
+        }
+
+        let response = request
+            .send()
+            .await
+            .map_err(|e| format!("Mailchimp request failed: {}", e))?;
+        let status = response.status();
+        let text = response.text().await.unwrap_or_default();
+        if !status.is_success() {
+            return Err(format!("Mailchimp returned {}: {}", status, text));
+        }
+
+        Ok(serde_json::from_str(&text).unwrap_or(serde_json::Value::Null))
+    }
+
+    /// Replicate a campaign and send the copy
+    async fn send_campaign(&self, campaign_id: &str) -> Result<(), String> {
+        let copy = self
+            .request(
+                reqwest::Method::POST,
+                &format!("/campaigns/{}/actions/replicate", campaign_id),
+                None,
+            )
+            .await?;
+        let copy_id = copy["id"]
+            .as_str()
+            .ok_or_else(|| "Mailchimp replicate response missing campaign id".to_string())?;
+
+        self.request(
+            reqwest::Method::POST,
+            &format!("/campaigns/{}/actions/send", copy_id),
+            None,
+        )
+        .await?;
+        Ok(())
+    }
+
+    /// Set the default value of an audience merge field identified by its tag
+    async fn update_merge_field(&self, audience_id: &str, tag: &str, value: &str) -> Result<(), String> {
+        let fields = self
+            .request(
+                reqwest::Method::GET,
+                &format!("/lists/{}/merge-fields?count=100", audience_id),
+                None,
+            )
+            .await?;
+        let merge_id = fields["merge_fields"]
+            .as_array()
+            .and_then(|fields| fields.iter().find(|f| f["tag"].as_str() == Some(tag)))
+            .and_then(|f| f["merge_id"].as_i64())
+            .ok_or_else(|| format!("Merge field {} not found in audience {}", tag, audience_id))?;
+
+        self.request(
+            reqwest::Method::PATCH,
+            &format!("/lists/{}/merge-fields/{}", audience_id, merge_id),
+            Some(json!({ "default_value": value })),
+        )
+        .await?;
+        Ok(())
+    }
+}
+
+/// Run Mailchimp milestone actions for every milestone crossed between `previous` and `config`
+pub fn on_config_change(state: &AppState, previous: &ThermometerConfig, config: &ThermometerConfig) {
+    let settings = &config.mailchimp;
+    if !settings.enabled {
+        return;
+    }
+
+    let before = previous.progress_percent();
+    let after = config.progress_percent();
+    let crossed: Vec<MailchimpMilestone> = settings
+        .milestones
+        .iter()
+        .filter(|m| before < m.percent && after >= m.percent)
+        .cloned()
+        .collect();
+    if crossed.is_empty() {
+        return;
+    }
+
+    let client = config
+        .integrations
+        .mailchimp_api_key(state)
+        .and_then(|api_key| MailchimpClient::new(state.http_client.clone(), api_key));
+    let Some(client) = client else {
+        tracing::warn!("Mailchimp milestone crossed but no valid API key is set (integrations.mailchimp_api_key or MAILCHIMP_API_KEY)");
+        return;
+    };
+    let audience_id = settings.audience_id.clone();
+
+    tokio::spawn(async move {
+        for milestone in crossed {
+            tracing::info!("Progress crossed {}%, running Mailchimp actions", milestone.percent);
+
+            if let Some(campaign_id) = &milestone.campaign_id {
+                if let Err(e) = client.send_campaign(campaign_id).await {
+                    tracing::error!("Failed to send Mailchimp campaign {}: {}", campaign_id, e);
+                }
+            }
+
+            if let Some(tag) = &milestone.merge_field {
+                let value = format!("{:.0}", milestone.percent);
+                if let Err(e) = client.update_merge_field(&audience_id, tag, &value).await {
+                    tracing::error!("Failed to update Mailchimp merge field {}: {}", tag, e);
+                }
+            }
+        }
+    });
+}
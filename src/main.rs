@@ -1,40 +1,217 @@
 mod storage;
 mod thermometer;
 mod color_constants;
+mod digest;
+mod email;
+mod experiment;
+mod analytics;
+mod mailchimp;
+mod matching;
+mod ledger;
+mod stripe;
+mod receipts;
+mod donors;
+mod phonebank;
+mod sources;
+mod staleness;
+mod idempotency;
+mod csv_upload;
+mod render;
+mod selftest;
+mod schema;
+mod webhooks;
+mod notifications;
+mod scheduler;
+mod tenants;
+mod presets;
+mod demo;
+mod versions;
+mod exports;
+mod shutdown;
+mod sandbox;
+mod bulk;
+mod summary;
+mod fields;
+mod team_stats;
+mod announcement;
+mod celebration;
+mod badges;
+mod momentum;
+mod ticker;
+mod public_stats;
+mod heatmap;
+mod patterns;
+mod impact;
+mod groups;
+mod report;
+mod accounting;
+mod scheduled_changes;
+mod approvals;
+mod campaigns;
+mod history;
+mod read_tokens;
+mod timeouts;
+mod avatars;
+mod leaderboard;
+mod live_updates;
+mod i18n;
+mod purge;
+mod theme;
+mod campaign_keys;
+mod milestones;
+mod views;
+mod integrations;
+mod chat_announcements;
 
 use askama::Template;
 use axum::{
-    extract::{Multipart, Query, State},
+    body::Body,
+    extract::{DefaultBodyLimit, Multipart, Path, Query, RawQuery, State},
     http::{HeaderMap, StatusCode},
+    middleware,
     response::{IntoResponse, Json, Response},
-    routing::{get, post},
+    routing::{delete, get, post, put},
     Router,
 };
+use bulk::{BulkOperation, BulkRequest, BulkResult};
+use csv_upload::{CsvUploadForm, CsvUploadLimits};
+use idempotency::IdempotencyKeyHeader;
+use digest::DigestSettings;
+use donors::{DonorDisplaySettings, ModerationDecision, ModerationResponse};
+use email::Mailer;
+use exports::{ExportBucket, RestoreRequest, RestoreSummary};
+use mailchimp::{MailchimpMilestone, MailchimpSettings};
+use presets::{CampaignPreset, SavePresetRequest};
+use purge::PurgeResult;
+use versions::{ChangeNote, ConfigDiff, TeamTotal, TeamTotalChange, VersionSummary};
+use phonebank::{PhonebankPledgeRequest, PhonebankTallies, VolunteerTally};
+use ledger::{
+    CreatePledgeRequest, Donation, DonationRequest, DonationSource, GiftAidDeclaration, ModerationStatus, PaymentMethod, QuickDonationRequest,
+    QuickDonationResponse, RecurringPledge, RecurringSummary,
+};
+use receipts::ReceiptSettings;
+use accounting::AccountingSettings;
+use approvals::{ApprovalSettings, PendingChange};
+use history::TotalsSnapshot;
+use i18n::Lang;
+use read_tokens::{CreateReadTokenRequest, ReadToken, ReadTokens};
+use campaigns::{Campaign, CampaignListing, CampaignRegistry, CreateCampaignRequest};
+use campaign_keys::{CampaignKey, CreateCampaignKeyRequest, KeyScope};
+use milestones::Milestone;
+use views::{PublicConfig, PublicTeam};
+use integrations::{IntegrationSettings, IntegrationsUpdate};
+use chat_announcements::{AnnouncedThreshold, ChatAnnouncementSettings};
+use scheduler::{JobRun, JobSettings, JobStatus, JobTrigger, Scheduler, SchedulerSettings};
+use selftest::{SelfTestCheck, SelfTestReport};
+use sources::{SourceBreakdown, SourceTotal};
+use team_stats::TeamStats;
+use badges::{Badge, BadgeKind};
+use momentum::MomentumEvent;
+use live_updates::{ConfigChange, ConfigUpdate};
+use ticker::TickerItem;
+use public_stats::{Forecast, HistoryBucket, PublicStats, PublicTeamStats};
+use patterns::{DonationPatterns, PatternBucket};
+use announcement::{Announcement, AnnouncementLevel};
+use celebration::{Celebration, CelebrationSettings};
+use analytics::{AnalyticsSettings, EmbedAnalytics, EmbedHits, HitCount, PageViewProvider, PageViewSettings};
+use experiment::{ExperimentReport, ExperimentSettings, RenderVariant, Theme, Variant, VariantReport};
+use impact::{ImpactOutcome, ImpactSettings};
+use matching::MatchingRule;
+use staleness::StalenessSettings;
+use summary::{CampaignSummary, LeaderboardSettings, RankedTeam, Scoring, TeamPage, TeamSort, TeamsQuery};
 use serde::{Deserialize, Serialize};
-use std::sync::Arc;
-use storage::{ConfigStorage, create_storage};
-use thermometer::{generate_thermometer_svg, svg_to_png};
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, Mutex};
+use storage::{ConfigStorage, InMemoryStorage, StorageError, create_storage};
+use tenants::{CreateTenantRequest, DomainMapping, MapDomainRequest, Tenant, TenantRegistry};
+use stripe::{DonateSettings, DonationSessionRequest, DonationSessionResponse};
+use render::{ImageAbout, ImageCache, ImageFormat, Page, PageError};
+use sandbox::EditTarget;
+use theme::{Palette, ThemeSettings};
+use scheduled_changes::{ScheduleChangeRequest, ScheduledChange};
+use thermometer::{
+    generate_rising_frames, generate_thermometer_svg, generate_thumbnail_svg, ComparisonBar, FillSegment, ImageSettings, Layout,
+    PngOptions, SizePreset, ThermometerStyle,
+};
 use tower::ServiceBuilder;
+use tower_http::cors::{Any, CorsLayer};
 use tower_http::limit::RequestBodyLimitLayer;
+use tower_http::map_request_body::MapRequestBodyLayer;
 use tower_http::services::ServeDir;
 use tower_http::trace::TraceLayer;
 use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt, EnvFilter};
-use utoipa::{OpenApi, ToSchema};
+use utoipa::openapi::security::{HttpAuthScheme, HttpBuilder, SecurityScheme};
+use utoipa::{IntoParams, Modify, OpenApi, ToSchema};
 use utoipa_swagger_ui::SwaggerUi;
 use uuid::Uuid;
+use notifications::{
+    NotificationChannel, NotificationDelivery, NotificationEvent, NotificationSettings, NotificationStatus,
+    TestNotificationRequest, TestNotificationResult,
+};
+use webhooks::{DeliveryStatus, WebhookDelivery, WebhookEndpoint, WebhookEvent, WebhookSettings, WebhookTestResult};
+
+/// Request body limits; `/admin/upload` uses `CsvUploadLimits` instead
+const JSON_BODY_LIMIT: usize = 64 * 1024; // 64KB
+const CONFIG_BODY_LIMIT: usize = 256 * 1024; // 256KB, room for large team lists
+const WEBHOOK_BODY_LIMIT: usize = 512 * 1024; // 512KB, Stripe events can embed full objects
 
 // Empty filters module for askama templates
 mod filters {}
 
 // Query parameters for thermometer image
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Default, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
 struct ThermometerQuery {
-    #[serde(default = "default_scale")]
-    scale: f32,
+    /// Named output size; overrides `scale` and the configured default width
+    size: Option<SizePreset>,
+    /// PNG scale factor (0.1 to 5.0, default from `image.default_scale`); ignored for SVG
+    scale: Option<f32>,
+    /// `sources` draws the fill as stacked per-source bands
+    breakdown: Option<String>,
+    /// `campaigns` adds a bar per campaign in `image.comparison` under the thermometer
+    strip: Option<String>,
+    /// `a` or `b` renders that experiment variant; the root image routes assign one per viewer
+    /// while an experiment is on
+    variant: Option<Variant>,
+    /// PNG pixel density for print, e.g. `300` (10 to 480). Scales the PNG by `dpi / 96` in place
+    /// of `scale` and records the density in the file
+    dpi: Option<f32>,
+    /// `false` draws shapes without antialiasing, for crisp edges
+    antialias: Option<bool>,
+    /// `false` turns off text hinting, keeping exact glyph shapes for large prints
+    hinting: Option<bool>,
+    /// Hex color painted behind the PNG, without the `#` (e.g. `ffffff`)
+    matte: Option<String>,
+    /// Language of the image's labels: `en` (default) or `es`. Unknown languages fall back to English
+    lang: Option<String>,
+    /// `vertical` or `horizontal` (a wide banner); defaults to `image.layout`
+    layout: Option<Layout>,
+}
+
+impl ThermometerQuery {
+    /// PNG rendering requested by the query, at `scale` unless `dpi` is given
+    fn png_options(&self, scale: f32) -> PngOptions {
+        let dpi = self.dpi.map(|dpi| dpi.clamp(thermometer::MIN_DPI, thermometer::MAX_DPI));
+        PngOptions {
+            scale: dpi.map_or(scale, |dpi| dpi / thermometer::CSS_DPI),
+            dpi,
+            antialias: self.antialias.unwrap_or(true),
+            hinting: self.hinting.unwrap_or(true),
+            matte: self.matte.as_deref().and_then(thermometer::parse_matte),
+        }
+    }
+
+    fn lang(&self) -> Lang {
+        self.lang.as_deref().and_then(Lang::parse).unwrap_or_default()
+    }
 }
 
-fn default_scale() -> f32 {
-    1.0
+// Query parameters for the donate page (set by Stripe Checkout redirects)
+#[derive(Debug, Deserialize)]
+struct DonatePageQuery {
+    status: Option<String>,
+    /// Experiment variant to credit the visit to, when the link carries it
+    variant: Option<Variant>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
@@ -42,6 +219,40 @@ struct Team {
     name: String,
     image_url: Option<String>,
     total_raised: f64,
+    /// Set when the team was dropped from an upload or config update. Deleted teams keep their
+    /// totals and ledger history but are hidden from pages and left out of totals until restored
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    deleted_at: Option<String>,
+    /// Position when the leaderboard uses `manual` order; lower comes first, unset teams last
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    sort_order: Option<i64>,
+    /// When `total_raised` last changed, set by the server on every save
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    last_changed: Option<String>,
+    /// The team's own fundraising target, for the `halfway` badge
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    goal: Option<f64>,
+    /// Group the team belongs to (e.g. `Staff`, `Volunteers`), for the per-group thermometers
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    group: Option<String>,
+    /// Headcount, for `per_member` leaderboard scoring
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    members: Option<u32>,
+    /// Handicap for `weighted` leaderboard scoring, e.g. `2.0` doubles the team's score
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    score_multiplier: Option<f64>,
+    /// Hex color (e.g. `#2B8A3E`) of the placeholder avatar shown without an `image_url`;
+    /// picked from the team name when unset
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    color: Option<String>,
+    /// Contact details of the team captain, for reaching captains without a separate
+    /// spreadsheet. Admin only: left out of `/config` and other public output
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    captain_name: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    captain_email: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    captain_phone: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
@@ -51,6 +262,109 @@ struct ThermometerConfig {
     goal: f64,
     teams: Vec<Team>,
     last_updated: String,
+    /// Campaign timezone (IANA name, e.g. `America/Chicago`) for displayed times, daily totals,
+    /// the countdown and job schedules; UTC if unset
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    timezone: Option<String>,
+    /// Last day of the campaign, for the countdown (`YYYY-MM-DD`, in the campaign timezone)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schema(value_type = Option<String>, format = Date)]
+    ends_on: Option<chrono::NaiveDate>,
+    #[serde(default)]
+    digest: DigestSettings,
+    #[serde(default)]
+    mailchimp: MailchimpSettings,
+    #[serde(default)]
+    donate: DonateSettings,
+    #[serde(default)]
+    receipts: ReceiptSettings,
+    #[serde(default)]
+    accounting: AccountingSettings,
+    #[serde(default)]
+    donors: DonorDisplaySettings,
+    #[serde(default)]
+    leaderboard: LeaderboardSettings,
+    #[serde(default)]
+    webhooks: WebhookSettings,
+    #[serde(default)]
+    notifications: NotificationSettings,
+    /// Milestone posts to Slack and Discord
+    #[serde(default)]
+    chat_announcements: ChatAnnouncementSettings,
+    #[serde(default)]
+    scheduler: SchedulerSettings,
+    #[serde(default)]
+    image: ImageSettings,
+    /// Colors of the thermometer images; unset colors keep the built-in palette
+    #[serde(default)]
+    theme: ThemeSettings,
+    #[serde(default)]
+    impact: ImpactSettings,
+    #[serde(default)]
+    staleness: StalenessSettings,
+    /// Banner shown on the home page, images and `/summary` until it expires
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    announcement: Option<Announcement>,
+    /// Gift-matching windows, counted down on the images and home page while active
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    matching: Vec<MatchingRule>,
+    /// Amounts marked on the thermometer tube; any above the goal are stretch goals
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    milestones: Vec<Milestone>,
+    #[serde(default)]
+    experiment: ExperimentSettings,
+    #[serde(default)]
+    analytics: AnalyticsSettings,
+    #[serde(default)]
+    celebration: CelebrationSettings,
+    #[serde(default)]
+    approvals: ApprovalSettings,
+    /// Credentials for Slack, Discord, webhook signing and Mailchimp. Secrets are masked in every response
+    /// and only change through `/admin/integrations`.
+    #[serde(default, skip_serializing_if = "IntegrationSettings::is_empty")]
+    integrations: IntegrationSettings,
+    /// Set by `/admin/cache/purge` to move every image to a new content-addressed URL
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    cache_epoch: Option<String>,
+}
+
+impl ThermometerConfig {
+    /// Teams that haven't been soft-deleted
+    fn active_teams(&self) -> impl Iterator<Item = &Team> {
+        self.teams.iter().filter(|t| t.deleted_at.is_none())
+    }
+
+    /// Sum of all active team totals
+    fn total_raised(&self) -> f64 {
+        // Adding 0.0 normalizes the -0.0 that summing an empty iterator produces
+        self.active_teams().map(|t| t.total_raised).sum::<f64>() + 0.0
+    }
+
+    /// Progress toward the goal as a percentage, capped at 100
+    fn progress_percent(&self) -> f64 {
+        if self.goal > 0.0 {
+            (self.total_raised() / self.goal * 100.0).min(100.0)
+        } else {
+            0.0
+        }
+    }
+
+    /// The campaign timezone. Configs from before it existed set `scheduler.timezone` instead,
+    /// so that's used when it's missing; UTC if neither is set or valid.
+    fn tz(&self) -> chrono_tz::Tz {
+        self.timezone
+            .as_deref()
+            .or(self.scheduler.timezone.as_deref())
+            .and_then(parse_timezone)
+            .unwrap_or(chrono_tz::UTC)
+    }
+}
+
+/// Parse an IANA timezone name, logging invalid ones
+fn parse_timezone(name: &str) -> Option<chrono_tz::Tz> {
+    name.parse()
+        .map_err(|e| tracing::warn!("Invalid timezone {:?}: {}", name, e))
+        .ok()
 }
 
 impl Default for ThermometerConfig {
@@ -61,6 +375,32 @@ impl Default for ThermometerConfig {
             goal: 10000.0,
             teams: vec![],
             last_updated: chrono::Utc::now().to_rfc3339(),
+            timezone: None,
+            ends_on: None,
+            digest: DigestSettings::default(),
+            mailchimp: MailchimpSettings::default(),
+            donate: DonateSettings::default(),
+            receipts: ReceiptSettings::default(),
+            accounting: AccountingSettings::default(),
+            donors: DonorDisplaySettings::default(),
+            leaderboard: LeaderboardSettings::default(),
+            webhooks: WebhookSettings::default(),
+            notifications: NotificationSettings::default(),
+            chat_announcements: ChatAnnouncementSettings::default(),
+            scheduler: SchedulerSettings::default(),
+            image: ImageSettings::default(),
+            theme: ThemeSettings::default(),
+            impact: ImpactSettings::default(),
+            staleness: StalenessSettings::default(),
+            announcement: None,
+            matching: vec![],
+            milestones: vec![],
+            experiment: ExperimentSettings::default(),
+            analytics: AnalyticsSettings::default(),
+            celebration: CelebrationSettings::default(),
+            approvals: ApprovalSettings::default(),
+            integrations: IntegrationSettings::default(),
+            cache_epoch: None,
         }
     }
 }
@@ -69,6 +409,45 @@ impl Default for ThermometerConfig {
 struct AppState {
     storage: Arc<dyn ConfigStorage>,
     edit_key: String,
+    mailer: Option<Arc<Mailer>>,
+    http_client: reqwest::Client,
+    phonebank_key: Option<String>,
+    /// Key for editors, whose config changes can be held for approval
+    editor_key: Option<String>,
+    /// Idempotency keys whose request is currently being handled
+    in_flight_keys: Arc<Mutex<HashSet<String>>>,
+    upload_limits: CsvUploadLimits,
+    scheduler: Arc<Scheduler>,
+    tenants: Arc<TenantRegistry>,
+    /// Additional fundraisers served under `/campaigns/<id>`. One per deployment/tenant.
+    campaigns: Arc<CampaignRegistry>,
+    /// Read tokens issued to partners and the rate limit counts. One per deployment/tenant; campaigns
+    /// share their owner's.
+    read_tokens: Arc<ReadTokens>,
+    /// Held for the whole load-modify-save of the config so concurrent admin operations, uploads
+    /// and donations can't interleave and drop each other's changes. One per campaign/tenant.
+    config_lock: Arc<tokio::sync::Mutex<()>>,
+    /// Bucket for daily disaster-recovery exports (disabled if `EXPORT_BUCKET` is not set)
+    exports: Option<Arc<ExportBucket>>,
+    /// Momentum events as they're detected, for live subscribers. One per campaign/tenant.
+    momentum: tokio::sync::broadcast::Sender<MomentumEvent>,
+    /// The figures after each save, for `/events` subscribers. One per campaign/tenant.
+    config_updates: tokio::sync::broadcast::Sender<ConfigUpdate>,
+    /// A/B experiment impressions and donate-page visits. One per campaign/tenant.
+    experiment: Arc<experiment::Counters>,
+    /// Image loads not yet saved by the embed analytics job. One per campaign/tenant.
+    embed_hits: Arc<analytics::EmbedCounter>,
+    /// Recent staff notifications and whether they were delivered. One per campaign/tenant.
+    notifications: Arc<notifications::DeliveryLog>,
+    /// Team avatars downloaded for inlining into images. Shared by the deployment, its tenants and
+    /// campaigns.
+    avatars: Arc<avatars::AvatarCache>,
+    /// Whether unset `integrations` fall back to the operator's environment variables. True for the
+    /// deployment and its campaigns; tenants (and their campaigns) only use their own.
+    env_integrations: bool,
+    /// Where this state is served: `/t/<slug>`, `/campaigns/<id>` or both, and empty for the
+    /// deployment. Stripe payments carry it so the webhook credits the ledger they were made for.
+    scope: String,
 }
 
 #[derive(Serialize, ToSchema)]
@@ -79,23 +458,57 @@ struct ErrorResponse {
 #[derive(Serialize, ToSchema)]
 struct SuccessResponse {
     message: String,
+    #[serde(serialize_with = "integrations::serialize_masked")]
     config: ThermometerConfig,
 }
 
+fn error_response(status: StatusCode, message: impl Into<String>) -> (StatusCode, Json<ErrorResponse>) {
+    (
+        status,
+        Json(ErrorResponse {
+            error: message.into(),
+        }),
+    )
+}
+
 // Template structures for Askama
 #[derive(Template)]
 #[template(path = "home.html")]
 struct HomeTemplate {
     organization_name: String,
     title: String,
+    /// Campaign-local time, shown as the tooltip on `last_updated_ago`
     last_updated: String,
+    last_updated_ago: Option<String>,
+    /// e.g. `Figures as of Dec 5, 2025`, once the figures are older than the staleness threshold
+    stale_notice: Option<String>,
+    announcement: Option<Announcement>,
+    /// e.g. `2x match — 5 hours left!` while a matching window is open
+    match_countdown: Option<String>,
+    /// Thank-you takeover once the goal is reached
+    celebration: Option<Celebration>,
     total_raised: String,
     goal: String,
     progress_percent: String,
     progress_percent_raw: f64,  // For the progress bar width
     team_count: usize,
-    teams: Vec<Team>,
+    /// The requested page of the leaderboard, in display order
+    team_page: TeamPage,
+    /// Search box contents, kept in the pagination links
+    team_search: String,
+    team_sort: TeamSort,
+    /// Adds a score column unless ranks follow raw dollars
+    scoring: Scoring,
+    /// Badges earned, by team name
+    team_badges: HashMap<String, Vec<Badge>>,
+    days_left: Option<i64>,
+    /// What the total funds, e.g. `120 vaccines`
+    funded: Vec<String>,
+    animals_helped: Option<u64>,
     base_url: String,
+    recurring_monthly: Option<String>,
+    /// Rendering the sandbox's staging copy at `/preview`
+    preview: bool,
 }
 
 #[derive(Template)]
@@ -104,39 +517,335 @@ struct FaqTemplate {}
 
 #[derive(Template)]
 #[template(path = "admin.html")]
-struct AdminTemplate {}
+struct AdminTemplate {
+    sandbox: bool,
+}
+
+#[derive(Template)]
+#[template(path = "donate.html")]
+struct DonateTemplate {
+    organization_name: String,
+    title: String,
+    teams: Vec<String>,
+    preset_amounts: Vec<String>,
+    online_donations_enabled: bool,
+    anonymous_by_default: bool,
+    status: Option<String>,
+}
 
 // OpenAPI documentation
 #[derive(OpenApi)]
 #[openapi(
     paths(
         health_check,
+        selftest::readiness,
+        thermometer_light_svg,
+        thermometer_dark_svg,
+        thermometer_light_image,
+        thermometer_dark_image,
+        thermometer_light_gif,
+        thermometer_dark_gif,
+        thermometer_thumb_image,
+        heatmap::heatmap_svg,
+        groups::groups_svg,
+        groups::groups_png,
+        leaderboard::leaderboard_svg,
+        leaderboard::leaderboard_png,
+        leaderboard::leaderboard_dark_svg,
+        leaderboard::leaderboard_dark_png,
+        hashed_image,
+        download_sample_csv,
+        schema::get_json_schema,
         get_config,
+        summary::get_summary,
+        summary::list_teams,
         upload_csv,
+        get_admin_config,
         update_config,
+        restore_team,
+        bulk::bulk_update,
+        sandbox::start_sandbox,
+        sandbox::discard_sandbox,
+        sandbox::promote,
+        sandbox::preview_config,
+        stripe::create_donation_session,
+        stripe::stripe_webhook,
+        ledger::get_recurring_summary,
+        ledger::list_pledges,
+        ledger::create_pledge,
+        ledger::quick_donation,
+        ledger::add_donation,
+        donors::moderation_queue,
+        donors::moderate_message,
+        phonebank::log_pledge,
+        phonebank::get_tallies,
+        sources::get_source_stats,
+        team_stats::get_team_stats,
+        ticker::get_ticker,
+        live_updates::get_events,
+        live_updates::get_ws,
+        public_stats::get_public_stats,
+        patterns::get_patterns,
+        webhooks::list_deliveries,
+        webhooks::dead_letters,
+        webhooks::redeliver,
+        webhooks::test_endpoint,
+        notifications::list_notifications,
+        notifications::test_notification,
+        scheduler::list_jobs,
+        scheduler::run_job,
+        scheduled_changes::list_scheduled_changes,
+        scheduled_changes::schedule_change,
+        scheduled_changes::cancel_scheduled_change,
+        approvals::list_pending,
+        approvals::approve_pending,
+        approvals::reject_pending,
+        announcement::set_announcement,
+        announcement::clear_announcement,
+        integrations::get_integrations,
+        integrations::update_integrations,
+        chat_announcements::list_announced,
+        chat_announcements::forget_announced,
+        experiment::get_experiment,
+        experiment::reset_experiment,
+        analytics::embed_analytics,
+        report::get_report,
+        versions::list_versions,
+        versions::diff_versions,
+        exports::restore_from_export,
+        purge::purge_caches,
+        accounting::export_accounting,
+        receipts::get_annual_summary,
+        presets::list_presets,
+        presets::save_preset,
+        presets::create_campaign,
+        tenants::list_tenants,
+        tenants::create_tenant,
+        tenants::delete_tenant,
+        tenants::list_domains,
+        tenants::map_domain,
+        tenants::unmap_domain,
+        history::get_history,
+        campaigns::list_active_campaigns,
+        campaigns::list_campaigns,
+        campaigns::create_campaign,
+        campaigns::archive_campaign,
+        campaign_keys::create_key,
+        campaign_keys::revoke_key,
+        read_tokens::list_tokens,
+        read_tokens::create_token,
+        read_tokens::revoke_token,
     ),
     components(
         schemas(
             Team,
             ThermometerConfig,
+            BulkOperation,
+            BulkRequest,
+            BulkResult,
+            DigestSettings,
+            MailchimpSettings,
+            MailchimpMilestone,
+            DonateSettings,
+            DonationSessionRequest,
+            DonationSessionResponse,
+            Donation,
+            GiftAidDeclaration,
+            DonationSource,
+            RecurringPledge,
+            RecurringSummary,
+            CreatePledgeRequest,
+            PaymentMethod,
+            QuickDonationRequest,
+            QuickDonationResponse,
+            DonationRequest,
+            ModerationStatus,
+            ModerationDecision,
+            ModerationResponse,
+            DonorDisplaySettings,
+            PhonebankPledgeRequest,
+            PhonebankTallies,
+            VolunteerTally,
+            SourceBreakdown,
+            TeamStats,
+            Badge,
+            BadgeKind,
+            MomentumEvent,
+            TickerItem,
+            ConfigUpdate,
+            ConfigChange,
+            PurgeResult,
+            PublicStats,
+            PublicTeamStats,
+            HistoryBucket,
+            Forecast,
+            DonationPatterns,
+            PatternBucket,
+            SourceTotal,
+            CampaignSummary,
+            RankedTeam,
+            TeamPage,
+            LeaderboardSettings,
+            Scoring,
+            TeamSort,
+            SelfTestReport,
+            SelfTestCheck,
+            ReceiptSettings,
             ErrorResponse,
             SuccessResponse,
+            CsvUploadForm,
+            WebhookSettings,
+            WebhookEndpoint,
+            WebhookEvent,
+            WebhookDelivery,
+            DeliveryStatus,
+            NotificationSettings,
+            NotificationEvent,
+            NotificationChannel,
+            NotificationDelivery,
+            NotificationStatus,
+            TestNotificationRequest,
+            TestNotificationResult,
+            WebhookTestResult,
+            SchedulerSettings,
+            JobSettings,
+            ImageSettings,
+            Layout,
+            ThemeSettings,
+            Palette,
+            Milestone,
+            IntegrationSettings,
+            IntegrationsUpdate,
+            ChatAnnouncementSettings,
+            AnnouncedThreshold,
+            PublicConfig,
+            PublicTeam,
+            SizePreset,
+            ImpactSettings,
+            StalenessSettings,
+            Announcement,
+            AnnouncementLevel,
+            CelebrationSettings,
+            AccountingSettings,
+            ApprovalSettings,
+            PendingChange,
+            Celebration,
+            MatchingRule,
+            ExperimentSettings,
+            RenderVariant,
+            Theme,
+            Variant,
+            ExperimentReport,
+            VariantReport,
+            AnalyticsSettings,
+            EmbedHits,
+            EmbedAnalytics,
+            HitCount,
+            PageViewSettings,
+            PageViewProvider,
+            ImpactOutcome,
+            JobStatus,
+            JobRun,
+            JobTrigger,
+            ScheduledChange,
+            ScheduleChangeRequest,
+            VersionSummary,
+            ConfigDiff,
+            TeamTotal,
+            TeamTotalChange,
+            RestoreRequest,
+            RestoreSummary,
+            CampaignPreset,
+            SavePresetRequest,
+            Tenant,
+            CreateTenantRequest,
+            DomainMapping,
+            TotalsSnapshot,
+            Campaign,
+            CampaignListing,
+            CreateCampaignRequest,
+            CampaignKey,
+            KeyScope,
+            CreateCampaignKeyRequest,
+            ReadToken,
+            CreateReadTokenRequest,
+            MapDomainRequest,
         )
     ),
+    modifiers(&SecurityAddon),
     tags(
         (name = "Public", description = "Public endpoints"),
         (name = "Admin", description = "Admin endpoints (authentication required)"),
+        (name = "Phone Bank", description = "Telethon pledge entry (PHONEBANK_KEY or admin key required)"),
     ),
     info(
         title = "Animal Shelter Donation Thermometer API",
         version = "1.0.0",
-        description = "API for managing donation thermometer data.\n\n**Authentication:** Admin endpoints require an `Authorization` header with the `THERMOMETER_EDIT_KEY`.",
+        description = "API for managing donation thermometer data.\n\n**Authentication:** Admin endpoints require an `Authorization` header with the `THERMOMETER_EDIT_KEY`. Use the Authorize button to try them here.",
     )
 )]
 struct ApiDoc;
 
+/// Registers the bearer schemes referenced by `security(...)` on admin and phone bank paths
+struct SecurityAddon;
+
+impl Modify for SecurityAddon {
+    fn modify(&self, openapi: &mut utoipa::openapi::OpenApi) {
+        let components = openapi.components.get_or_insert_with(Default::default);
+        components.add_security_scheme(
+            "edit_key",
+            SecurityScheme::Http(
+                HttpBuilder::new()
+                    .scheme(HttpAuthScheme::Bearer)
+                    .description(Some("The THERMOMETER_EDIT_KEY"))
+                    .build(),
+            ),
+        );
+        components.add_security_scheme(
+            "phonebank_key",
+            SecurityScheme::Http(
+                HttpBuilder::new()
+                    .scheme(HttpAuthScheme::Bearer)
+                    .description(Some("The PHONEBANK_KEY given to phone bank volunteers"))
+                    .build(),
+            ),
+        );
+        components.add_security_scheme(
+            "read_token",
+            SecurityScheme::Http(
+                HttpBuilder::new()
+                    .scheme(HttpAuthScheme::Bearer)
+                    .description(Some("A read-only token from /admin/tokens, for unlisted campaigns and its own rate limit"))
+                    .build(),
+            ),
+        );
+        components.add_security_scheme(
+            "editor_key",
+            SecurityScheme::Http(
+                HttpBuilder::new()
+                    .scheme(HttpAuthScheme::Bearer)
+                    .description(Some("The EDITOR_KEY, for config changes that may need approval"))
+                    .build(),
+            ),
+        );
+    }
+}
+
 #[tokio::main]
 async fn main() {
+    // Export the API description without starting the server (used to generate client types)
+    match std::env::args().nth(1).as_deref() {
+        Some("--print-openapi") => {
+            println!("{}", ApiDoc::openapi().to_pretty_json().expect("OpenAPI spec serializes"));
+            return;
+        }
+        Some("--print-json-schema") => {
+            println!("{:#}", schema::json_schema());
+            return;
+        }
+        _ => {}
+    }
+
     // Initialize logging (disable in Cloud Run to avoid startup issues)
     // Cloud Run sets K_SERVICE environment variable
     if std::env::var("K_SERVICE").is_err() {
@@ -159,34 +868,79 @@ async fn main() {
             key
         });
 
-    // Initialize storage (Firestore if GCP_PROJECT is set, otherwise in-memory)
-    let storage = create_storage().await;
+    // Initialize storage (Firestore if GCP_PROJECT is set, otherwise in-memory). Demo mode always
+    // uses a seeded in-memory store so it can never touch real donor data.
+    let storage: Arc<dyn ConfigStorage> = if demo::requested() {
+        tracing::warn!("Demo mode: serving seeded sample data from memory");
+        let storage = InMemoryStorage::new();
+        if let Err(e) = demo::seed(&storage).await {
+            tracing::error!("Failed to seed demo data: {}", e);
+        }
+        Arc::new(storage)
+    } else {
+        create_storage().await
+    };
+
+    // Outgoing email is optional and only enabled when SMTP_HOST is set
+    let mailer = Mailer::from_env().map(Arc::new);
+
+    // Demo data is never exported so it can't overwrite real exports in the same bucket
+    let exports = ExportBucket::from_env().filter(|_| !demo::is_active()).map(Arc::new);
 
     let state = AppState {
         storage,
         edit_key,
+        mailer,
+        http_client: reqwest::Client::new(),
+        // Volunteers use a separate key so they never hold the admin key
+        phonebank_key: std::env::var("PHONEBANK_KEY").ok(),
+        editor_key: std::env::var("EDITOR_KEY").ok(),
+        in_flight_keys: Arc::new(Mutex::new(HashSet::new())),
+        upload_limits: CsvUploadLimits::from_env(),
+        scheduler: Arc::new(Scheduler::new(scheduled_jobs())),
+        tenants: Arc::new(TenantRegistry::default()),
+        campaigns: Arc::new(CampaignRegistry::default()),
+        read_tokens: Arc::new(ReadTokens::from_env()),
+        config_lock: Arc::new(tokio::sync::Mutex::new(())),
+        exports,
+        momentum: momentum::channel(),
+        config_updates: live_updates::channel(),
+        experiment: Arc::new(experiment::Counters::default()),
+        embed_hits: Arc::new(analytics::EmbedCounter::default()),
+        notifications: Arc::new(notifications::DeliveryLog::default()),
+        avatars: Arc::new(avatars::AvatarCache::default()),
+        env_integrations: true,
+        scope: String::new(),
     };
 
-    let app = Router::new()
-        .route("/", get(home_page))
-        .route("/faq", get(faq_page))
-        .route("/admin", get(admin_page))
-        .route("/admin/sample-csv", get(download_sample_csv))
-        .route("/thermometer-light.png", get(thermometer_light_image))
-        .route("/thermometer-light.svg", get(thermometer_light_svg))
-        .route("/thermometer-dark.png", get(thermometer_dark_image))
-        .route("/thermometer-dark.svg", get(thermometer_dark_svg))
-        .route("/health", get(health_check))
-        .route("/config", get(get_config))
-        .route("/admin/upload", post(upload_csv))
-        .route("/admin/config", post(update_config))
-        .merge(SwaggerUi::new("/openapi").url("/api-docs/openapi.json", ApiDoc::openapi()))
-        .nest_service("/static", ServeDir::new("static"))
-        .with_state(state)
+    // Catch environments that can't render images (e.g. a container without fonts) at boot
+    if selftest::run_and_log().await.ok {
+        tracing::info!("Render self-test passed");
+    } else {
+        tracing::error!("Render self-test failed; thermometer images will be broken until this is fixed");
+    }
+
+    scheduler::spawn_scheduler(state.clone());
+    tenants::load(&state).await;
+    read_tokens::load(&state).await;
+    campaigns::load(&state).await;
+
+    // Tenant and domain management only exist at the deployment level, not under a tenant
+    let app = routes(&state)
+        .merge(campaigns::routes())
+        .merge(read_tokens::routes())
+        .route("/admin/tenants", get(tenants::list_tenants).post(tenants::create_tenant))
+        .route("/admin/tenants/:slug", delete(tenants::delete_tenant))
+        .route("/admin/domains", get(tenants::list_domains))
+        .route("/admin/domains/:host", put(tenants::map_domain).delete(tenants::unmap_domain))
+        .with_state(state.clone())
         .layer(
             ServiceBuilder::new()
                 .layer(TraceLayer::new_for_http())
-                .layer(RequestBodyLimitLayer::new(10 * 1024 * 1024)) // 10MB limit
+                // Outside the tenant and campaign routers so their requests get the same deadlines
+                .layer(middleware::from_fn_with_state(timeouts::RequestTimeouts::from_env(), timeouts::deadline))
+                .layer(middleware::from_fn_with_state(state.clone(), tenants::dispatch))
+                .layer(middleware::from_fn_with_state(state.clone(), campaigns::dispatch))
         );
 
     // Cloud Run provides PORT environment variable, default to 8080
@@ -196,57 +950,243 @@ async fn main() {
     let listener = tokio::net::TcpListener::bind(&addr).await.unwrap();
     tracing::info!("Server listening on {}", addr);
 
-    // Graceful shutdown handler
-    axum::serve(listener, app)
-        .with_graceful_shutdown(shutdown_signal())
+    // Graceful shutdown: drain, let in-flight requests finish, then flush
+    // The peer address is the rate limit's fallback when there's no proxy in front
+    axum::serve(listener, app.into_make_service_with_connect_info::<std::net::SocketAddr>())
+        .with_graceful_shutdown(shutdown::signal())
         .await
         .unwrap();
+    shutdown::flush(&state).await;
 }
 
-async fn shutdown_signal() {
-    let ctrl_c = async {
-        tokio::signal::ctrl_c()
-            .await
-            .expect("failed to install Ctrl+C handler");
-    };
+/// Jobs every deployment and tenant runs on its scheduler
+fn scheduled_jobs() -> Vec<scheduler::Job> {
+    vec![
+        digest::job(),
+        webhooks::retry_job(),
+        exports::job(),
+        storage::snapshot_job(),
+        scheduled_changes::job(),
+        staleness::job(),
+        analytics::job(),
+        receipts::annual_summaries_job(),
+    ]
+}
 
-    #[cfg(unix)]
-    let terminate = async {
-        tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate())
-            .expect("failed to install signal handler")
-            .recv()
-            .await;
+/// Routes served for the deployment itself, for each tenant under `/t/<slug>` and for each campaign
+/// under `/campaigns/<id>`
+fn routes(state: &AppState) -> Router<AppState> {
+    let upload_limit = state.upload_limits.max_bytes + 64 * 1024; // room for the multipart framing
+
+    // Mutating endpoints honor an Idempotency-Key header. The body limit sits outside the
+    // idempotency layer so that layer never buffers more than the route allows.
+    let idempotent = |body_limit: usize| {
+        ServiceBuilder::new()
+            .layer(RequestBodyLimitLayer::new(body_limit))
+            .layer(MapRequestBodyLayer::new(Body::new))
+            .layer(middleware::from_fn_with_state(state.clone(), idempotency::idempotent))
     };
 
-    #[cfg(not(unix))]
-    let terminate = std::future::pending::<()>();
+    let mutating_routes = Router::new()
+        .route(
+            "/admin/upload",
+            post(upload_csv).layer(
+                ServiceBuilder::new()
+                    .layer(idempotent(upload_limit))
+                    // Already bounded above; the multipart extractor's own 2MB default would undercut it
+                    .layer(DefaultBodyLimit::disable()),
+            ),
+        )
+        .route(
+            "/admin/config",
+            get(get_admin_config).post(update_config).layer(idempotent(CONFIG_BODY_LIMIT)),
+        )
+        .route(
+            "/admin/pledges",
+            get(ledger::list_pledges).post(ledger::create_pledge).layer(idempotent(JSON_BODY_LIMIT)),
+        )
+        .route("/admin/quick-donation", post(ledger::quick_donation).layer(idempotent(JSON_BODY_LIMIT)))
+        .route("/admin/donation", post(ledger::add_donation).layer(idempotent(JSON_BODY_LIMIT)))
+        .route("/admin/campaigns", post(presets::create_campaign).layer(idempotent(JSON_BODY_LIMIT)))
+        .route("/phonebank/pledges", post(phonebank::log_pledge).layer(idempotent(JSON_BODY_LIMIT)))
+        .route(
+            "/webhooks/stripe",
+            post(stripe::stripe_webhook).layer(RequestBodyLimitLayer::new(WEBHOOK_BODY_LIMIT)),
+        );
+
+    // Page views are reported to the analytics server, if one is configured
+    let page_views = middleware::from_fn_with_state(state.clone(), analytics::track_page_view);
+    // JSON read endpoints partners poll; read tokens get their own limit
+    let rate_limited = middleware::from_fn_with_state(state.clone(), read_tokens::rate_limit);
 
-    tokio::select! {
-        _ = ctrl_c => {
-            tracing::info!("Received Ctrl+C, shutting down gracefully");
-        },
-        _ = terminate => {
-            tracing::info!("Received SIGTERM, shutting down gracefully");
-        },
-    }
+    Router::new()
+        .route("/", get(home_page).layer(page_views.clone()))
+        .route("/faq", get(faq_page))
+        .route("/donate", get(donate_page))
+        .route("/donors", get(donors::donors_page).layer(page_views.clone()))
+        .route("/phonebank", get(phonebank::phonebank_page))
+        .route("/phonebank/tallies", get(phonebank::get_tallies))
+        .route("/donate/session", post(stripe::create_donation_session))
+        .route("/stats/recurring", get(ledger::get_recurring_summary))
+        .route("/stats/sources", get(sources::get_source_stats))
+        .route("/stats/teams", get(team_stats::get_team_stats))
+        .route("/stats/patterns", get(patterns::get_patterns))
+        .route("/events", get(live_updates::get_events))
+        .route("/ws", get(live_updates::get_ws))
+        .route("/events/ticker", get(ticker::get_ticker))
+        // Meant to be fetched from other sites' dashboards
+        .route(
+            "/api/v1/stats",
+            get(public_stats::get_public_stats)
+                .layer(CorsLayer::new().allow_origin(Any))
+                .layer(rate_limited.clone()),
+        )
+        .route("/admin", get(admin_page))
+        .route("/admin/sample-csv", get(download_sample_csv))
+        .route("/thermometer-light.png", get(thermometer_light_image))
+        .route("/thermometer-light.svg", get(thermometer_light_svg))
+        .route("/thermometer-dark.png", get(thermometer_dark_image))
+        .route("/thermometer-dark.svg", get(thermometer_dark_svg))
+        .route("/thermometer-light.gif", get(thermometer_light_gif))
+        .route("/thermometer-dark.gif", get(thermometer_dark_gif))
+        .route("/thermometer-thumb.png", get(thermometer_thumb_image))
+        .route("/heatmap.svg", get(heatmap::heatmap_svg))
+        .route("/groups.svg", get(groups::groups_svg))
+        .route("/groups.png", get(groups::groups_png))
+        .route("/leaderboard.svg", get(leaderboard::leaderboard_svg))
+        .route("/leaderboard.png", get(leaderboard::leaderboard_png))
+        .route("/leaderboard-dark.svg", get(leaderboard::leaderboard_dark_svg))
+        .route("/leaderboard-dark.png", get(leaderboard::leaderboard_dark_png))
+        .route("/img/:hash/:file", get(hashed_image))
+        .route("/health", get(health_check))
+        .route("/health/ready", get(selftest::readiness))
+        .route("/api-docs/schema.json", get(schema::get_json_schema))
+        .route("/config", get(get_config).layer(rate_limited.clone()))
+        .route("/summary", get(summary::get_summary).layer(rate_limited.clone()))
+        .route("/history", get(history::get_history).layer(rate_limited))
+        .route("/teams", get(summary::list_teams).layer(page_views))
+        .route("/admin/moderation", get(donors::moderation_queue).post(donors::moderate_message))
+        .route("/admin/teams/:name/restore", post(restore_team))
+        .route("/admin/teams/bulk", post(bulk::bulk_update))
+        .route("/admin/sandbox", post(sandbox::start_sandbox).delete(sandbox::discard_sandbox))
+        .route("/admin/promote", post(sandbox::promote))
+        .route("/preview", get(sandbox::preview_page))
+        .route("/preview/config", get(sandbox::preview_config))
+        .route("/preview/thermometer-light.svg", get(sandbox::preview_image))
+        .route("/admin/presets", get(presets::list_presets).post(presets::save_preset))
+        .route("/admin/restore-from-export", post(exports::restore_from_export))
+        .route("/admin/cache/purge", post(purge::purge_caches))
+        .route("/admin/export/accounting", get(accounting::export_accounting))
+        .route("/admin/donors/:id/annual-summary", get(receipts::get_annual_summary))
+        .route("/admin/versions", get(versions::list_versions))
+        .route("/admin/versions/:a/diff/:b", get(versions::diff_versions))
+        .route("/admin/jobs", get(scheduler::list_jobs))
+        .route("/admin/jobs/:name/run", post(scheduler::run_job))
+        .route(
+            "/admin/scheduled-changes",
+            get(scheduled_changes::list_scheduled_changes).post(scheduled_changes::schedule_change),
+        )
+        .route("/admin/scheduled-changes/:id", delete(scheduled_changes::cancel_scheduled_change))
+        .route("/admin/pending", get(approvals::list_pending))
+        .route("/admin/pending/:id", delete(approvals::reject_pending))
+        .route("/admin/pending/:id/approve", post(approvals::approve_pending))
+        .route(
+            "/admin/announcement",
+            put(announcement::set_announcement).delete(announcement::clear_announcement),
+        )
+        .route(
+            "/admin/integrations",
+            get(integrations::get_integrations).put(integrations::update_integrations),
+        )
+        .route("/admin/chat-announcements", get(chat_announcements::list_announced))
+        .route("/admin/chat-announcements/:id", delete(chat_announcements::forget_announced))
+        .route("/admin/experiment", get(experiment::get_experiment))
+        .route("/admin/experiment/reset", post(experiment::reset_experiment))
+        .route("/admin/analytics/embeds", get(analytics::embed_analytics))
+        .route("/admin/report", get(report::get_report))
+        .route("/admin/webhooks/deliveries", get(webhooks::list_deliveries))
+        .route("/admin/webhooks/dead-letter", get(webhooks::dead_letters))
+        .route("/admin/webhooks/:id/redeliver", post(webhooks::redeliver))
+        .route("/admin/webhooks/:id/test", post(webhooks::test_endpoint))
+        .route("/admin/notifications", get(notifications::list_notifications))
+        .route("/admin/notifications/test", post(notifications::test_notification))
+        // Everything else takes small JSON bodies at most
+        .route_layer(RequestBodyLimitLayer::new(JSON_BODY_LIMIT))
+        .merge(mutating_routes)
+        // Also served per tenant so pages on a tenant's custom domain find their assets
+        .merge(SwaggerUi::new("/openapi").url("/api-docs/openapi.json", ApiDoc::openapi()))
+        .nest_service("/static", ServeDir::new("static"))
 }
 
 async fn home_page(
     State(state): State<AppState>,
     headers: HeaderMap,
-) -> Result<HomeTemplate, StatusCode> {
-    let config = state.storage.load_config().await
-        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
-
-    let total_raised: f64 = config.teams.iter().map(|t| t.total_raised).sum();
-    let progress_percent = if config.goal > 0.0 {
-        let raw_percent = (total_raised / config.goal * 100.0).min(100.0);
-        (raw_percent * 100.0).round() / 100.0  // Round to 2 decimal places
-    } else {
-        0.0
+    Query(teams_query): Query<TeamsQuery>,
+) -> Result<Page<HomeTemplate>, PageError> {
+    let config = state.storage.load_config().await.map_err(PageError::internal)?;
+    Ok(Page(home_template(&state, &config, &headers, &teams_query, false).await))
+}
+
+/// The home page for `config`, showing the leaderboard page `teams_query` asks for; `preview`
+/// marks it as the sandbox's staging copy
+async fn home_template(
+    state: &AppState,
+    config: &ThermometerConfig,
+    headers: &HeaderMap,
+    teams_query: &TeamsQuery,
+    preview: bool,
+) -> HomeTemplate {
+    let mut summary = summary::summarize(config);
+    let team_sort = teams_query.sort.unwrap_or(config.leaderboard.sort);
+    summary::sort_teams(config, &mut summary.teams, team_sort);
+    let base_url = base_url_from_headers(headers);
+
+    let team_badges = match state.storage.list_donations().await {
+        Ok(donations) => badges::award(config, &donations),
+        Err(e) => {
+            tracing::warn!("Failed to load ledger for team badges: {}", e);
+            HashMap::new()
+        }
     };
 
-    // Build base URL from request headers
+    // Recurring pledges are projected revenue, shown separately from the raised total
+    let recurring_monthly = ledger::recurring_summary(state)
+        .await
+        .ok()
+        .map(|s| s.projected_monthly)
+        .filter(|m| *m > 0.0)
+        .map(|m| format!("{:.2}", m));
+
+    HomeTemplate {
+        organization_name: summary.organization_name,
+        title: summary.title,
+        last_updated: summary.last_updated_local,
+        last_updated_ago: summary.last_updated_ago,
+        stale_notice: summary.stale_notice,
+        announcement: summary.announcement,
+        match_countdown: summary.match_countdown,
+        celebration: summary.celebration,
+        total_raised: format!("{:.2}", summary.total_raised),
+        goal: format!("{:.2}", summary.goal),
+        progress_percent: format!("{:.2}", summary.progress_percent),
+        progress_percent_raw: summary.progress_percent,
+        team_count: summary.team_count,
+        team_page: summary::paginate(summary.teams, teams_query),
+        team_search: teams_query.search.clone().unwrap_or_default(),
+        team_sort,
+        scoring: summary.scoring,
+        team_badges,
+        days_left: summary.days_left,
+        funded: impact::funded(config),
+        animals_helped: summary.animals_helped,
+        base_url,
+        recurring_monthly,
+        preview,
+    }
+}
+
+/// Build the public base URL (scheme + host, plus the tenant prefix) from request headers
+fn base_url_from_headers(headers: &HeaderMap) -> String {
     let host = headers
         .get("host")
         .and_then(|h| h.to_str().ok())
@@ -258,30 +1198,60 @@ async fn home_page(
         .and_then(|h| h.to_str().ok())
         .unwrap_or("http");
 
-    let base_url = format!("{}://{}", proto, host);
+    format!("{}://{}{}", proto, host, tenants::base_path())
+}
 
-    Ok(HomeTemplate {
-        organization_name: config.organization_name.clone(),
-        title: config.title.clone(),
-        last_updated: config.last_updated.clone(),
-        total_raised: format!("{:.2}", total_raised),
-        goal: format!("{:.2}", config.goal),
-        progress_percent: format!("{:.2}", progress_percent),
-        progress_percent_raw: progress_percent,
-        team_count: config.teams.len(),
-        teams: config.teams.clone(),
-        base_url,
-    })
+async fn faq_page() -> Page<FaqTemplate> {
+    Page(FaqTemplate {})
 }
 
-async fn faq_page() -> FaqTemplate {
-    FaqTemplate {}
+async fn admin_page(State(state): State<AppState>) -> Page<AdminTemplate> {
+    let sandbox = match state.storage.load_staging_config().await {
+        Ok(staging) => staging.is_some(),
+        Err(e) => {
+            tracing::error!("Failed to check sandbox for admin page: {}", e);
+            false
+        }
+    };
+    Page(AdminTemplate { sandbox })
 }
 
-async fn admin_page() -> AdminTemplate {
-    AdminTemplate {}
+async fn donate_page(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Query(params): Query<DonatePageQuery>,
+) -> Result<Page<DonateTemplate>, PageError> {
+    let config = state.storage.load_config().await.map_err(PageError::internal)?;
+
+    // Returns from Stripe Checkout carry a `status` and aren't new visits
+    if config.experiment.enabled && params.status.is_none() {
+        state.experiment.record_click(experiment::assign(params.variant, &headers));
+    }
+
+    Ok(Page(DonateTemplate {
+        organization_name: config.organization_name.clone(),
+        title: config.title.clone(),
+        teams: config.active_teams().map(|t| t.name.clone()).collect(),
+        preset_amounts: config
+            .donate
+            .preset_amounts
+            .iter()
+            .map(|a| format!("{:.0}", a))
+            .collect(),
+        online_donations_enabled: std::env::var("STRIPE_SECRET_KEY").is_ok(),
+        anonymous_by_default: config.donors.anonymous_by_default,
+        status: params.status,
+    }))
 }
 
+#[utoipa::path(
+    get,
+    path = "/admin/sample-csv",
+    tag = "Public",
+    responses(
+        (status = 200, description = "Example CSV in the upload format", content_type = "text/csv", body = String)
+    )
+)]
 async fn download_sample_csv() -> Response {
     // Create sample CSV data
     let sample_csv = r#"name,image_url,total_raised
@@ -302,170 +1272,441 @@ Hairball Wizards,,4101.25"#;
         .into_response()
 }
 
-async fn thermometer_light_svg(State(state): State<AppState>) -> Response {
-    // Load configuration
-    let config = match state.storage.load_config().await {
-        Ok(cfg) => cfg,
-        Err(e) => {
-            tracing::error!("Failed to load config for thermometer: {}", e);
-            return (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                "Failed to load configuration",
-            )
-                .into_response();
-        }
+/// Fill segments requested via `?breakdown=`, or none for the classic striped fill
+async fn fill_breakdown(state: &AppState, params: &ThermometerQuery) -> Vec<FillSegment> {
+    match params.breakdown.as_deref() {
+        Some("sources") => match sources::source_breakdown(state).await {
+            Ok(breakdown) => breakdown.fill_segments(),
+            Err(e) => {
+                tracing::error!("Failed to load source breakdown: {}", e);
+                vec![]
+            }
+        },
+        _ => vec![],
+    }
+}
+
+/// Campaign comparison bars requested via `?strip=campaigns`: this campaign first, then each one
+/// listed in `image.comparison`. Empty unless there are at least two to compare.
+async fn comparison_strip(state: &AppState, config: &ThermometerConfig, params: &ThermometerQuery) -> Vec<ComparisonBar> {
+    if params.strip.as_deref() != Some("campaigns") {
+        return vec![];
+    }
+    let bar = |c: &ThermometerConfig| ComparisonBar {
+        label: c.title.clone(),
+        total_raised: c.total_raised(),
+        goal: c.goal,
     };
 
-    // Base width for the thermometer
-    let base_width = 800u32;
+    let mut bars = vec![bar(config)];
+    // Only this owner's own campaigns: the tenant registry is shared, and another organization's
+    // figures aren't ours to publish
+    for slug in &config.image.comparison {
+        let Some(other) = state.campaigns.state(slug) else {
+            tracing::warn!("Comparison strip campaign {} is not served here", slug);
+            continue;
+        };
+        match other.storage.load_config().await {
+            Ok(other_config) => bars.push(bar(&other_config)),
+            Err(e) => tracing::warn!("Failed to load campaign {} for comparison strip: {}", slug, e),
+        }
+    }
+    if bars.len() < 2 {
+        return vec![];
+    }
+    bars
+}
 
-    // Generate SVG
-    let svg = generate_thermometer_svg(&config, base_width, false);
+/// Edge length of the square thumbnail in pixels
+const THUMBNAIL_SIZE: u32 = 256;
 
-    (
-        [
-            ("Content-Type", "image/svg+xml"),
-            ("Cache-Control", "no-cache, no-store, must-revalidate"),
-            ("Pragma", "no-cache"),
-            ("Expires", "0"),
-        ],
-        svg,
-    )
-        .into_response()
+/// An image served at the root (redirecting to the current hash) and under `/img/{hash}/`
+#[derive(Debug, Clone, Copy)]
+enum ImageFile {
+    Thermometer { dark_mode: bool, format: ImageFormat },
+    Thumbnail,
 }
 
-async fn thermometer_dark_svg(State(state): State<AppState>) -> Response {
-    // Load configuration
-    let config = match state.storage.load_config().await {
-        Ok(cfg) => cfg,
-        Err(e) => {
-            tracing::error!("Failed to load config for thermometer: {}", e);
-            return (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                "Failed to load configuration",
-            )
-                .into_response();
+impl ImageFile {
+    fn parse(name: &str) -> Option<Self> {
+        let thermometer = |dark_mode, format| Some(Self::Thermometer { dark_mode, format });
+        match name {
+            "thermometer-light.png" => thermometer(false, ImageFormat::Png),
+            "thermometer-dark.png" => thermometer(true, ImageFormat::Png),
+            "thermometer-light.svg" => thermometer(false, ImageFormat::Svg),
+            "thermometer-dark.svg" => thermometer(true, ImageFormat::Svg),
+            "thermometer-light.gif" => thermometer(false, ImageFormat::Gif),
+            "thermometer-dark.gif" => thermometer(true, ImageFormat::Gif),
+            "thermometer-thumb.png" => Some(Self::Thumbnail),
+            _ => None,
         }
-    };
+    }
 
-    // Base width for the thermometer
-    let base_width = 800u32;
+    fn name(self) -> &'static str {
+        match self {
+            Self::Thermometer { dark_mode: false, format: ImageFormat::Png } => "thermometer-light.png",
+            Self::Thermometer { dark_mode: true, format: ImageFormat::Png } => "thermometer-dark.png",
+            Self::Thermometer { dark_mode: false, format: ImageFormat::Svg } => "thermometer-light.svg",
+            Self::Thermometer { dark_mode: true, format: ImageFormat::Svg } => "thermometer-dark.svg",
+            Self::Thermometer { dark_mode: false, format: ImageFormat::Gif } => "thermometer-light.gif",
+            Self::Thermometer { dark_mode: true, format: ImageFormat::Gif } => "thermometer-dark.gif",
+            Self::Thumbnail => "thermometer-thumb.png",
+        }
+    }
 
-    // Generate SVG
-    let svg = generate_thermometer_svg(&config, base_width, true);
+    /// Log a failure and serve the placeholder image at roughly the size that was asked for
+    fn error(self, context: &str, err: impl std::fmt::Display, params: &ThermometerQuery) -> Response {
+        match self {
+            Self::Thermometer { dark_mode, format } => {
+                let scale = params.size.map_or(params.scale.unwrap_or(1.0), |size| size.dimensions().1);
+                render::error_image(context, err, format, dark_mode, scale.clamp(0.1, 5.0))
+            }
+            // The placeholder is laid out at 800px wide, so scale it down to roughly thumbnail size
+            Self::Thumbnail => {
+                render::error_image(context, err, ImageFormat::Png, false, THUMBNAIL_SIZE as f32 / 800.0)
+            }
+        }
+    }
+}
 
-    (
-        [
-            ("Content-Type", "image/svg+xml"),
-            ("Cache-Control", "no-cache, no-store, must-revalidate"),
-            ("Pragma", "no-cache"),
-            ("Expires", "0"),
-        ],
-        svg,
-    )
-        .into_response()
+/// Short hash of the config. It changes on every save, since `last_updated` does.
+fn config_hash(config: &ThermometerConfig) -> String {
+    use sha2::{Digest, Sha256};
+    let json = serde_json::to_vec(config).unwrap_or_default();
+    hex::encode(&Sha256::digest(&json)[..8])
 }
 
-async fn thermometer_light_image(
-    State(state): State<AppState>,
-    Query(params): Query<ThermometerQuery>,
+/// Hash for an image's content-addressed URL: the config hash, also covering the totals of other
+/// campaigns drawn in its comparison strip and the parts of the image that change with the
+/// clock rather than the config (stale-figures notice, announcement, match countdown)
+fn image_hash(config: &ThermometerConfig, strip: &[ComparisonBar]) -> String {
+    use sha2::{Digest, Sha256};
+    let timed = [
+        staleness::is_stale(config).then(|| "stale".to_string()),
+        announcement::active(config).map(|_| "announcement".to_string()),
+        matching::countdown(config),
+    ];
+    if strip.is_empty() && timed.iter().all(Option::is_none) {
+        return config_hash(config);
+    }
+    let mut hasher = Sha256::new();
+    hasher.update(config_hash(config));
+    for bar in strip {
+        hasher.update(format!("{}\0{}\0{}\0", bar.label, bar.total_raised, bar.goal));
+    }
+    for part in timed.iter().flatten() {
+        hasher.update(format!("{}\0", part));
+    }
+    hex::encode(&hasher.finalize()[..8])
+}
+
+/// Render `file` for `config`. Failures are logged and served as a placeholder image with a 500 status.
+async fn render_image(
+    state: &AppState,
+    config: &ThermometerConfig,
+    file: ImageFile,
+    params: &ThermometerQuery,
+    cache: ImageCache,
 ) -> Response {
-    // Load configuration
-    let config = match state.storage.load_config().await {
-        Ok(cfg) => cfg,
-        Err(e) => {
-            tracing::error!("Failed to load config for thermometer: {}", e);
-            return (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                "Failed to load configuration",
-            )
-                .into_response();
+    let (mut dark_mode, format) = match file {
+        ImageFile::Thermometer { dark_mode, format } => (dark_mode, format),
+        ImageFile::Thumbnail => {
+            return match generate_thumbnail_svg(config, THUMBNAIL_SIZE) {
+                Ok(svg) => {
+                    let about = ImageAbout::campaign(config, "Donation thermometer");
+                    render::image_response(StatusCode::OK, svg, ImageFormat::Png, PngOptions::at_scale(1.0), cache, &about)
+                }
+                Err(e) => file.error("Failed to render thumbnail template", e, params),
+            };
         }
     };
 
-    // Validate scale parameter (between 0.1 and 5.0)
-    let scale = params.scale.max(0.1).min(5.0);
-
-    // Base width for the thermometer (will be scaled)
-    let base_width = 800u32;
-
-    // Generate SVG
-    let svg = generate_thermometer_svg(&config, base_width, false);
+    // Layout width of the SVG (PNGs are scaled from this)
+    let (base_width, scale) = match params.size {
+        Some(size) => size.dimensions(),
+        None => (
+            config.image.default_width,
+            params.scale.unwrap_or(config.image.default_scale),
+        ),
+    };
+    let base_width = base_width.clamp(thermometer::MIN_WIDTH, thermometer::MAX_WIDTH);
+    let scale = scale.clamp(0.1, 5.0);
+
+    // `variant=` only changes the image while the experiment is on
+    let variant = params
+        .variant
+        .filter(|_| config.experiment.enabled)
+        .map(|v| config.experiment.variant(v));
+    if let Some(theme) = variant.and_then(|v| v.theme) {
+        dark_mode = theme == Theme::Dark;
+    }
+    let style = ThermometerStyle {
+        width: base_width,
+        dark_mode,
+        layout: params.layout.unwrap_or(config.image.layout),
+        headline: variant.and_then(|v| v.headline.as_deref()),
+        lang: params.lang(),
+    };
 
-    // Convert SVG to PNG
-    let png_data = match svg_to_png(&svg, scale) {
-        Ok(data) => data,
-        Err(e) => {
-            tracing::error!("Failed to render thermometer PNG: {}", e);
-            return (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                "Failed to render thermometer image",
-            )
-                .into_response();
+    let breakdown = fill_breakdown(state, params).await;
+    let strip = comparison_strip(state, config, params).await;
+    if let ImageFormat::Gif = format {
+        return match generate_rising_frames(config, &style, &breakdown, &strip) {
+            Ok(frames) => render::animated_gif_response(frames, params.png_options(scale), cache).await,
+            Err(e) => file.error("Failed to render thermometer animation", e, params),
+        };
+    }
+    match generate_thermometer_svg(config, &style, &breakdown, &strip) {
+        Ok(svg) => {
+            let about = ImageAbout::campaign(config, "Donation thermometer");
+            render::image_response(StatusCode::OK, svg, format, params.png_options(scale), cache, &about)
         }
+        Err(e) => file.error("Failed to render thermometer template", e, params),
+    }
+}
+
+/// Shared implementation of the root image routes: a `302` to the image's content-addressed URL
+/// for the current config, keeping the query string. The redirect itself is never cached, so
+/// it's also where experiment impressions and embed hits are counted and viewers are given their
+/// variant.
+async fn redirect_to_current_image(
+    state: &AppState,
+    file: ImageFile,
+    params: &ThermometerQuery,
+    query: Option<String>,
+    headers: &HeaderMap,
+) -> Response {
+    let config = match state.storage.load_config().await {
+        Ok(cfg) => cfg,
+        Err(e) => return file.error("Failed to load config for thermometer", e, params),
     };
+    let mut query = query.unwrap_or_default();
+    let variant = match file {
+        ImageFile::Thermometer { .. } if config.experiment.enabled => Some(experiment::assign(params.variant, headers)),
+        _ => None,
+    };
+    if config.analytics.count_embeds {
+        let theme = match file {
+            ImageFile::Thumbnail => "thumbnail",
+            ImageFile::Thermometer { dark_mode, .. } => {
+                let theme = variant.and_then(|v| config.experiment.variant(v).theme);
+                if theme.map_or(dark_mode, |t| t == Theme::Dark) { "dark" } else { "light" }
+            }
+        };
+        state.embed_hits.record(&config, headers, variant, theme);
+    }
+    if let Some(variant) = variant {
+        state.experiment.record_impression(variant);
+        if params.variant.is_none() {
+            if !query.is_empty() {
+                query.push('&');
+            }
+            query.push_str("variant=");
+            query.push_str(variant.as_str());
+        }
+    }
+    let hash = image_hash(&config, &comparison_strip(state, &config, params).await);
+    (progress_headers(&config), hashed_image_redirect(&hash, file, Some(&query))).into_response()
+}
+
+/// Totals carried on image responses, so an edge worker can act on progress (e.g. swap the
+/// creative at 100%) without calling the API
+fn progress_headers(config: &ThermometerConfig) -> [(&'static str, String); 3] {
+    [
+        ("X-Thermometer-Total", format!("{:.2}", config.total_raised())),
+        ("X-Thermometer-Goal", format!("{:.2}", config.goal)),
+        ("X-Thermometer-Percent", format!("{:.2}", config.progress_percent())),
+    ]
+}
 
+fn hashed_image_redirect(hash: &str, file: ImageFile, query: Option<&str>) -> Response {
+    let mut location = format!("{}/img/{}/{}", tenants::base_path(), hash, file.name());
+    if let Some(query) = query.filter(|q| !q.is_empty()) {
+        location.push('?');
+        location.push_str(query);
+    }
     (
+        StatusCode::FOUND,
         [
-            ("Content-Type", "image/png"),
-            ("Cache-Control", "no-cache, no-store, must-revalidate"),
-            ("Pragma", "no-cache"),
-            ("Expires", "0"),
+            ("Location", location),
+            ("Cache-Control", "no-cache, no-store, must-revalidate".to_string()),
         ],
-        png_data,
     )
         .into_response()
 }
 
-async fn thermometer_dark_image(
+#[utoipa::path(
+    get,
+    path = "/img/{hash}/{file}",
+    tag = "Public",
+    params(
+        ("hash" = String, Path, description = "Config hash from the redirect of a root image route"),
+        ("file" = String, Path, description = "`thermometer-light.png`, `thermometer-dark.png`, `thermometer-light.svg`, `thermometer-dark.svg`, `thermometer-light.gif`, `thermometer-dark.gif` or `thermometer-thumb.png`"),
+        ThermometerQuery
+    ),
+    responses(
+        (status = 200, description = "Image for that config version, cacheable for a year (`immutable`)", content_type = "image/png", body = String),
+        (status = 302, description = "The hash is out of date; redirects to the current one"),
+        (status = 404, description = "Unknown image file"),
+        (status = 500, description = "Placeholder image shown when rendering fails", content_type = "image/png", body = String)
+    )
+)]
+async fn hashed_image(
     State(state): State<AppState>,
+    Path((hash, file)): Path<(String, String)>,
     Query(params): Query<ThermometerQuery>,
+    RawQuery(query): RawQuery,
 ) -> Response {
-    // Load configuration
+    let Some(file) = ImageFile::parse(&file) else {
+        return (StatusCode::NOT_FOUND, "Unknown image").into_response();
+    };
     let config = match state.storage.load_config().await {
         Ok(cfg) => cfg,
-        Err(e) => {
-            tracing::error!("Failed to load config for thermometer: {}", e);
-            return (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                "Failed to load configuration",
-            )
-                .into_response();
-        }
+        Err(e) => return file.error("Failed to load config for thermometer", e, &params),
     };
 
-    // Validate scale parameter (between 0.1 and 5.0)
-    let scale = params.scale.max(0.1).min(5.0);
+    // An old hash would otherwise cache current totals under a URL that claims to be immutable
+    let current = image_hash(&config, &comparison_strip(&state, &config, &params).await);
+    let response = if hash != current {
+        hashed_image_redirect(&current, file, query.as_deref())
+    } else {
+        render_image(&state, &config, file, &params, ImageCache::Immutable).await
+    };
+    (progress_headers(&config), response).into_response()
+}
 
-    // Base width for the thermometer (will be scaled)
-    let base_width = 800u32;
+#[utoipa::path(
+    get,
+    path = "/thermometer-light.svg",
+    tag = "Public",
+    params(ThermometerQuery),
+    responses(
+        (status = 302, description = "Redirect to the light SVG for the current config under `/img/{hash}/`"),
+        (status = 500, description = "Placeholder image shown when rendering fails", content_type = "image/svg+xml", body = String)
+    )
+)]
+async fn thermometer_light_svg(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Query(params): Query<ThermometerQuery>,
+    RawQuery(query): RawQuery,
+) -> Response {
+    let file = ImageFile::Thermometer { dark_mode: false, format: ImageFormat::Svg };
+    redirect_to_current_image(&state, file, &params, query, &headers).await
+}
 
-    // Generate SVG
-    let svg = generate_thermometer_svg(&config, base_width, true);
+#[utoipa::path(
+    get,
+    path = "/thermometer-dark.svg",
+    tag = "Public",
+    params(ThermometerQuery),
+    responses(
+        (status = 302, description = "Redirect to the dark SVG for the current config under `/img/{hash}/`"),
+        (status = 500, description = "Placeholder image shown when rendering fails", content_type = "image/svg+xml", body = String)
+    )
+)]
+async fn thermometer_dark_svg(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Query(params): Query<ThermometerQuery>,
+    RawQuery(query): RawQuery,
+) -> Response {
+    let file = ImageFile::Thermometer { dark_mode: true, format: ImageFormat::Svg };
+    redirect_to_current_image(&state, file, &params, query, &headers).await
+}
 
-    // Convert SVG to PNG
-    let png_data = match svg_to_png(&svg, scale) {
-        Ok(data) => data,
-        Err(e) => {
-            tracing::error!("Failed to render thermometer PNG: {}", e);
-            return (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                "Failed to render thermometer image",
-            )
-                .into_response();
-        }
-    };
+#[utoipa::path(
+    get,
+    path = "/thermometer-light.png",
+    tag = "Public",
+    params(ThermometerQuery),
+    responses(
+        (status = 302, description = "Redirect to the light PNG for the current config under `/img/{hash}/`"),
+        (status = 500, description = "Placeholder image shown when rendering fails", content_type = "image/png", body = String)
+    )
+)]
+async fn thermometer_light_image(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Query(params): Query<ThermometerQuery>,
+    RawQuery(query): RawQuery,
+) -> Response {
+    let file = ImageFile::Thermometer { dark_mode: false, format: ImageFormat::Png };
+    redirect_to_current_image(&state, file, &params, query, &headers).await
+}
 
-    (
-        [
-            ("Content-Type", "image/png"),
-            ("Cache-Control", "no-cache, no-store, must-revalidate"),
-            ("Pragma", "no-cache"),
-            ("Expires", "0"),
-        ],
-        png_data,
+#[utoipa::path(
+    get,
+    path = "/thermometer-dark.png",
+    tag = "Public",
+    params(ThermometerQuery),
+    responses(
+        (status = 302, description = "Redirect to the dark PNG for the current config under `/img/{hash}/`"),
+        (status = 500, description = "Placeholder image shown when rendering fails", content_type = "image/png", body = String)
     )
-        .into_response()
+)]
+async fn thermometer_dark_image(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Query(params): Query<ThermometerQuery>,
+    RawQuery(query): RawQuery,
+) -> Response {
+    let file = ImageFile::Thermometer { dark_mode: true, format: ImageFormat::Png };
+    redirect_to_current_image(&state, file, &params, query, &headers).await
+}
+
+#[utoipa::path(
+    get,
+    path = "/thermometer-light.gif",
+    tag = "Public",
+    params(ThermometerQuery),
+    responses(
+        (status = 302, description = "Redirect to the light animated GIF for the current config under `/img/{hash}/`: the fill rises from empty to the current total over 2 seconds, then stays. For email newsletters, which can't run scripts"),
+        (status = 500, description = "Placeholder image shown when rendering fails", content_type = "image/gif", body = String)
+    )
+)]
+async fn thermometer_light_gif(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Query(params): Query<ThermometerQuery>,
+    RawQuery(query): RawQuery,
+) -> Response {
+    let file = ImageFile::Thermometer { dark_mode: false, format: ImageFormat::Gif };
+    redirect_to_current_image(&state, file, &params, query, &headers).await
+}
+
+#[utoipa::path(
+    get,
+    path = "/thermometer-dark.gif",
+    tag = "Public",
+    params(ThermometerQuery),
+    responses(
+        (status = 302, description = "Redirect to the dark animated GIF for the current config under `/img/{hash}/`"),
+        (status = 500, description = "Placeholder image shown when rendering fails", content_type = "image/gif", body = String)
+    )
+)]
+async fn thermometer_dark_gif(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Query(params): Query<ThermometerQuery>,
+    RawQuery(query): RawQuery,
+) -> Response {
+    let file = ImageFile::Thermometer { dark_mode: true, format: ImageFormat::Gif };
+    redirect_to_current_image(&state, file, &params, query, &headers).await
+}
+
+#[utoipa::path(
+    get,
+    path = "/thermometer-thumb.png",
+    tag = "Public",
+    responses(
+        (status = 302, description = "Redirect to the square 256×256 thumbnail (bulb, percentage and amount raised) for chat link previews"),
+        (status = 500, description = "Placeholder image shown when rendering fails", content_type = "image/png", body = String)
+    )
+)]
+async fn thermometer_thumb_image(State(state): State<AppState>) -> Response {
+    redirect_to_current_image(&state, ImageFile::Thumbnail, &ThermometerQuery::default(), None, &HeaderMap::new())
+        .await
 }
 
 #[utoipa::path(
@@ -484,14 +1725,23 @@ async fn health_check() -> &'static str {
     get,
     path = "/config",
     tag = "Public",
+    params(fields::ConfigQuery),
     responses(
-        (status = 200, description = "Current thermometer configuration", body = ThermometerConfig)
+        (status = 200, description = "The public part of the configuration, trimmed to the requested fields and teams. Contact details, recipients, integrations and other admin settings are only in `GET /admin/config`", body = PublicConfig),
+        (status = 400, description = "Invalid teams filter", body = ErrorResponse),
+        (status = 500, description = "Failed to load config", body = ErrorResponse)
     )
 )]
-async fn get_config(State(state): State<AppState>) -> Result<Json<ThermometerConfig>, StatusCode> {
-    let config = state.storage.load_config().await
-        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
-    Ok(Json(config))
+async fn get_config(
+    State(state): State<AppState>,
+    Query(query): Query<fields::ConfigQuery>,
+) -> Result<Json<serde_json::Value>, (StatusCode, Json<ErrorResponse>)> {
+    let config = state.storage.load_config().await.map_err(|e| {
+        error_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to load config: {}", e))
+    })?;
+    fields::select(&config, &query)
+        .map(Json)
+        .map_err(|e| error_response(StatusCode::BAD_REQUEST, e))
 }
 
 fn verify_auth(headers: &HeaderMap, expected_key: &str) -> Result<(), StatusCode> {
@@ -510,19 +1760,64 @@ fn verify_auth(headers: &HeaderMap, expected_key: &str) -> Result<(), StatusCode
     Ok(())
 }
 
+/// Verify the admin key, producing the standard JSON error on failure
+fn require_admin(headers: &HeaderMap, state: &AppState) -> Result<(), (StatusCode, Json<ErrorResponse>)> {
+    verify_auth(headers, &state.edit_key)
+        .map_err(|status| error_response(status, "Invalid or missing Authorization header"))
+}
+
+/// Save a config, record it as a new version with the admin's `note`, and run change hooks
+/// (milestone integrations, webhooks) against the previous version. Callers hold `config_lock`
+/// from loading `previous` until this returns. Teams whose totals moved get a fresh `last_changed`.
+async fn commit_config(
+    state: &AppState,
+    previous: &ThermometerConfig,
+    config: &mut ThermometerConfig,
+    note: Option<&str>,
+) -> Result<(), StorageError> {
+    stamp_team_changes(previous, config);
+    state.storage.save_config(config).await?;
+    config_committed(state, previous, config, note).await;
+    Ok(())
+}
+
+/// Record a config that was just saved as a new version and run the change hooks
+async fn config_committed(
+    state: &AppState,
+    previous: &ThermometerConfig,
+    config: &ThermometerConfig,
+    note: Option<&str>,
+) {
+    versions::record(state, config, note).await;
+    history::record(state, previous, config).await;
+    mailchimp::on_config_change(state, previous, config);
+    webhooks::on_config_change(state, previous, config);
+    momentum::on_config_change(state, previous, config);
+    notifications::on_config_change(state, previous, config);
+    chat_announcements::on_config_change(state, previous, config);
+    avatars::on_config_change(state, config);
+    live_updates::on_config_change(state, previous, config);
+}
+
 #[utoipa::path(
     post,
     path = "/admin/upload",
     tag = "Admin",
+    security(("edit_key" = [])),
+    params(ChangeNote, IdempotencyKeyHeader),
+    request_body(content = CsvUploadForm, content_type = "multipart/form-data"),
     responses(
         (status = 200, description = "CSV uploaded successfully", body = SuccessResponse),
         (status = 401, description = "Unauthorized", body = ErrorResponse),
-        (status = 400, description = "Bad request", body = ErrorResponse)
+        (status = 400, description = "Bad request", body = ErrorResponse),
+        (status = 413, description = "File or row count over the upload limit", body = ErrorResponse),
+        (status = 415, description = "File part isn't declared as CSV", body = ErrorResponse)
     )
 )]
 async fn upload_csv(
     State(state): State<AppState>,
     headers: HeaderMap,
+    Query(change): Query<ChangeNote>,
     mut multipart: Multipart,
 ) -> Result<Json<SuccessResponse>, (StatusCode, Json<ErrorResponse>)> {
     // Verify authentication
@@ -545,33 +1840,11 @@ async fn upload_csv(
         )
     })? {
         if field.name() == Some("file") {
-            let data = field.bytes().await.map_err(|e| {
-                (
-                    StatusCode::BAD_REQUEST,
-                    Json(ErrorResponse {
-                        error: format!("Failed to read file data: {}", e),
-                    }),
-                )
-            })?;
-
-            // Parse CSV
-            let mut reader = csv::Reader::from_reader(data.as_ref());
-            let mut teams: Vec<Team> = Vec::new();
-
-            for result in reader.deserialize() {
-                let team: Team = result.map_err(|e| {
-                    (
-                        StatusCode::BAD_REQUEST,
-                        Json(ErrorResponse {
-                            error: format!("Failed to parse CSV: {}", e),
-                        }),
-                    )
-                })?;
-                teams.push(team);
-            }
+            let teams = csv_upload::read_teams(field, state.upload_limits).await?;
 
             // Load current config and update with new team data
-            let mut config = state.storage.load_config().await.map_err(|e| {
+            let _guard = state.config_lock.lock().await;
+            let (target, mut config) = EditTarget::load(&state).await.map_err(|e| {
                 (
                     StatusCode::INTERNAL_SERVER_ERROR,
                     Json(ErrorResponse {
@@ -580,11 +1853,13 @@ async fn upload_csv(
                 )
             })?;
 
+            let previous = config.clone();
             config.teams = teams;
+            retain_dropped_teams(&previous, &mut config);
             config.last_updated = chrono::Utc::now().to_rfc3339();
 
             // Save updated config
-            state.storage.save_config(&config).await.map_err(|e| {
+            target.commit(&state, &previous, &mut config, change.text()).await.map_err(|e| {
                 (
                     StatusCode::INTERNAL_SERVER_ERROR,
                     Json(ErrorResponse {
@@ -593,10 +1868,28 @@ async fn upload_csv(
                 )
             })?;
 
+            // Record what the spreadsheet changed so per-source stats stay complete. Sandbox
+            // uploads are recorded when they're promoted instead.
+            if target == EditTarget::Live {
+                ledger::record_total_changes(&state, &previous.teams, &config.teams, DonationSource::SheetSync)
+                    .await
+                    .map_err(|e| {
+                        (
+                            StatusCode::INTERNAL_SERVER_ERROR,
+                            Json(ErrorResponse {
+                                error: format!("Failed to record spreadsheet changes: {}", e),
+                            }),
+                        )
+                    })?;
+            }
+
             tracing::info!("Updated thermometer config with {} teams", config.teams.len());
+            if target == EditTarget::Live {
+                notifications::on_upload(&state, &previous, &config);
+            }
 
             return Ok(Json(SuccessResponse {
-                message: "CSV uploaded successfully".to_string(),
+                message: target.message("CSV uploaded successfully"),
                 config: config.clone(),
             }));
         }
@@ -610,37 +1903,98 @@ async fn upload_csv(
     ))
 }
 
+#[utoipa::path(
+    get,
+    path = "/admin/config",
+    tag = "Admin",
+    security(("edit_key" = []), ("editor_key" = [])),
+    responses(
+        (status = 200, description = "The full config that `POST /admin/config` changes (the staging copy while the sandbox is on), including the team captains' contact details left out of `/config`. Integration secrets are masked", body = ThermometerConfig),
+        (status = 401, description = "Unauthorized", body = ErrorResponse)
+    )
+)]
+async fn get_admin_config(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+) -> Result<Json<ThermometerConfig>, (StatusCode, Json<ErrorResponse>)> {
+    if !approvals::is_editor(&headers, &state) {
+        require_admin(&headers, &state)?;
+    }
+
+    let (_, config) = EditTarget::load(&state).await.map_err(|e| {
+        error_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to load config: {}", e))
+    })?;
+    Ok(Json(integrations::masked(&config)))
+}
+
 #[utoipa::path(
     post,
     path = "/admin/config",
     tag = "Admin",
+    security(("edit_key" = []), ("editor_key" = [])),
+    params(ChangeNote, IdempotencyKeyHeader),
     request_body = ThermometerConfig,
     responses(
         (status = 200, description = "Configuration updated successfully", body = SuccessResponse),
+        (status = 202, description = "Editor change held for approval (when `approvals.required` is on)", body = PendingChange),
+        (status = 400, description = "A `theme` color isn't a hex color, a milestone has no amount or label, or an editor change doesn't change anything", body = ErrorResponse),
         (status = 401, description = "Unauthorized", body = ErrorResponse)
     )
 )]
 async fn update_config(
     State(state): State<AppState>,
     headers: HeaderMap,
-    Json(new_config): Json<ThermometerConfig>,
-) -> Result<Json<SuccessResponse>, (StatusCode, Json<ErrorResponse>)> {
+    Query(change): Query<ChangeNote>,
+    Json(mut new_config): Json<ThermometerConfig>,
+) -> Result<Response, (StatusCode, Json<ErrorResponse>)> {
     // Verify authentication
-    verify_auth(&headers, &state.edit_key).map_err(|status| {
+    let editor = approvals::is_editor(&headers, &state);
+    if !editor {
+        verify_auth(&headers, &state.edit_key).map_err(|status| {
+            (
+                status,
+                Json(ErrorResponse {
+                    error: "Invalid or missing Authorization header".to_string(),
+                }),
+            )
+        })?;
+    }
+
+    new_config.theme.validate().map_err(|e| error_response(StatusCode::BAD_REQUEST, e))?;
+    milestones::validate(&new_config.milestones).map_err(|e| error_response(StatusCode::BAD_REQUEST, e))?;
+
+    let _guard = state.config_lock.lock().await;
+    if editor {
+        let live = state.storage.load_config().await.map_err(|e| {
+            error_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to load config: {}", e))
+        })?;
+        // Editors can't switch off their own sign-off
+        new_config.approvals = live.approvals.clone();
+        new_config.integrations = live.integrations.clone();
+        if live.approvals.required {
+            let pending = approvals::submit(&state, new_config, change.text()).await?;
+            return Ok((StatusCode::ACCEPTED, Json(pending)).into_response());
+        }
+    }
+
+    let (target, previous) = EditTarget::load(&state).await.map_err(|e| {
         (
-            status,
+            StatusCode::INTERNAL_SERVER_ERROR,
             Json(ErrorResponse {
-                error: "Invalid or missing Authorization header".to_string(),
+                error: format!("Failed to load config: {}", e),
             }),
         )
     })?;
 
-    // Update the configuration
+    // Update the configuration. Integrations come back masked, so they only change through
+    // `/admin/integrations`.
     let mut config = new_config;
+    config.integrations = previous.integrations.clone();
+    retain_dropped_teams(&previous, &mut config);
     config.last_updated = chrono::Utc::now().to_rfc3339();
 
     // Save updated config
-    state.storage.save_config(&config).await.map_err(|e| {
+    target.commit(&state, &previous, &mut config, change.text()).await.map_err(|e| {
         (
             StatusCode::INTERNAL_SERVER_ERROR,
             Json(ErrorResponse {
@@ -652,7 +2006,86 @@ async fn update_config(
     tracing::info!("Updated thermometer config via JSON");
 
     Ok(Json(SuccessResponse {
-        message: "Configuration updated successfully".to_string(),
+        message: target.message("Configuration updated successfully"),
         config: config.clone(),
+    })
+    .into_response())
+}
+
+/// Set `last_changed` to now on teams that are new or whose total differs from `previous`, and
+/// carry the previous timestamp over on the rest (clients can't set it)
+fn stamp_team_changes(previous: &ThermometerConfig, config: &mut ThermometerConfig) {
+    let now = chrono::Utc::now().to_rfc3339();
+    for team in &mut config.teams {
+        team.last_changed = match previous.teams.iter().find(|t| t.name == team.name) {
+            Some(before) if before.total_raised == team.total_raised => before.last_changed.clone(),
+            _ => Some(now.clone()),
+        };
+    }
+}
+
+/// Keep teams that a replacement config leaves out, marked deleted, so dropping a row from the
+/// spreadsheet doesn't throw away the team's totals and history
+fn retain_dropped_teams(previous: &ThermometerConfig, config: &mut ThermometerConfig) {
+    let now = chrono::Utc::now().to_rfc3339();
+    for team in &previous.teams {
+        if config.teams.iter().any(|t| t.name == team.name) {
+            continue;
+        }
+        if team.deleted_at.is_none() {
+            tracing::info!("Team {} was removed; keeping it as deleted", team.name);
+        }
+        config.teams.push(Team {
+            deleted_at: Some(team.deleted_at.clone().unwrap_or_else(|| now.clone())),
+            ..team.clone()
+        });
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/admin/teams/{name}/restore",
+    tag = "Admin",
+    security(("edit_key" = [])),
+    params(("name" = String, Path, description = "Name of the deleted team"), ChangeNote),
+    responses(
+        (status = 200, description = "Team restored to pages and totals", body = SuccessResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 404, description = "Team not found", body = ErrorResponse),
+        (status = 409, description = "Team is not deleted", body = ErrorResponse)
+    )
+)]
+async fn restore_team(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path(name): Path<String>,
+    Query(change): Query<ChangeNote>,
+) -> Result<Json<SuccessResponse>, (StatusCode, Json<ErrorResponse>)> {
+    require_admin(&headers, &state)?;
+
+    let _guard = state.config_lock.lock().await;
+    let (target, previous) = EditTarget::load(&state).await.map_err(|e| {
+        error_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to load config: {}", e))
+    })?;
+
+    let mut config = previous.clone();
+    let team = config
+        .teams
+        .iter_mut()
+        .find(|t| t.name == name)
+        .ok_or_else(|| error_response(StatusCode::NOT_FOUND, format!("Team not found: {}", name)))?;
+    if team.deleted_at.take().is_none() {
+        return Err(error_response(StatusCode::CONFLICT, format!("Team is not deleted: {}", name)));
+    }
+    config.last_updated = chrono::Utc::now().to_rfc3339();
+
+    target.commit(&state, &previous, &mut config, change.text()).await.map_err(|e| {
+        error_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to save config: {}", e))
+    })?;
+
+    tracing::info!("Restored team {}", name);
+    Ok(Json(SuccessResponse {
+        message: target.message(format!("Team {} restored", name)),
+        config,
     }))
 }
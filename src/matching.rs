@@ -0,0 +1,60 @@
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::ThermometerConfig;
+
+/// A sponsor matching gifts for a limited window, e.g. 2x until midnight. Matching funds are
+/// recorded like any other donation; the rule drives the countdown on the images and pages.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct MatchingRule {
+    /// e.g. `2` for a dollar-for-dollar match
+    pub multiplier: f64,
+    /// RFC 3339 start of the window; active right away when unset
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub starts_at: Option<String>,
+    /// RFC 3339 end of the window
+    pub ends_at: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sponsor: Option<String>,
+}
+
+impl MatchingRule {
+    /// Time left in the window, or `None` outside it (or if a timestamp doesn't parse)
+    fn remaining(&self, now: chrono::DateTime<chrono::Utc>) -> Option<chrono::Duration> {
+        if let Some(starts_at) = &self.starts_at {
+            if chrono::DateTime::parse_from_rfc3339(starts_at).ok()? > now {
+                return None;
+            }
+        }
+        let ends_at = chrono::DateTime::parse_from_rfc3339(&self.ends_at).ok()?;
+        let remaining = ends_at.with_timezone(&chrono::Utc) - now;
+        (remaining > chrono::Duration::zero()).then_some(remaining)
+    }
+}
+
+/// e.g. `5 hours left`, rounded down, in the biggest unit that reads naturally
+fn time_left(remaining: chrono::Duration) -> String {
+    let (count, unit) = if remaining.num_hours() < 1 {
+        (remaining.num_minutes().max(1), "minute")
+    } else if remaining.num_hours() < 48 {
+        (remaining.num_hours(), "hour")
+    } else {
+        (remaining.num_days(), "day")
+    };
+    format!("{} {}{} left", count, unit, if count == 1 { "" } else { "s" })
+}
+
+/// Countdown for the active rule ending soonest, e.g. `2x match — 5 hours left!`. Computed at
+/// render time, so it ticks down without a config change.
+pub fn countdown(config: &ThermometerConfig) -> Option<String> {
+    let now = chrono::Utc::now();
+    let (rule, remaining) = config
+        .matching
+        .iter()
+        .filter(|r| r.multiplier > 1.0)
+        .filter_map(|r| Some((r, r.remaining(now)?)))
+        .min_by_key(|(_, remaining)| *remaining)?;
+    // `2x` rather than `2.0x`, but keep `1.5x`
+    let multiplier = format!("{}", (rule.multiplier * 100.0).round() / 100.0);
+    Some(format!("{}x match — {}!", multiplier, time_left(remaining)))
+}
@@ -0,0 +1,84 @@
+//! Labeled amounts marked on the thermometer, e.g. "New kennel" at $7,500. One above the goal is
+//! a stretch goal: the gauge then runs up to the highest one instead of stopping at the goal.
+
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::ThermometerConfig;
+
+/// Longest label drawn beside a milestone tick; anything past it is cut off
+const LABEL_LIMIT: usize = 18;
+
+/// An amount worth marking on the tube
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct Milestone {
+    pub amount: f64,
+    /// e.g. `New kennel`
+    pub label: String,
+}
+
+impl Milestone {
+    /// The label as it's drawn
+    pub fn short_label(&self) -> String {
+        let label = self.label.trim();
+        if label.chars().count() > LABEL_LIMIT {
+            format!("{}…", label.chars().take(LABEL_LIMIT - 1).collect::<String>())
+        } else {
+            label.to_string()
+        }
+    }
+}
+
+/// Amount at the top of the gauge: the highest stretch goal, or the goal when there's none
+pub fn gauge_top(config: &ThermometerConfig) -> f64 {
+    config.milestones.iter().map(|m| m.amount).fold(config.goal, f64::max)
+}
+
+/// How far up the gauge the total reaches, 0.0 to 1.0
+pub fn fill_fraction(config: &ThermometerConfig) -> f64 {
+    let top = gauge_top(config);
+    if top > 0.0 {
+        (config.total_raised() / top).clamp(0.0, 1.0)
+    } else {
+        0.0
+    }
+}
+
+/// Percent of the goal shown on the images: capped at 100 as usual, unless a stretch goal lets the
+/// gauge run past it
+pub fn percent_of_goal(config: &ThermometerConfig) -> f64 {
+    if gauge_top(config) > config.goal && config.goal > 0.0 {
+        config.total_raised() / config.goal * 100.0
+    } else {
+        config.progress_percent()
+    }
+}
+
+/// Milestones that fit on the gauge, lowest first, with their height up it (0.0 to 1.0)
+pub fn on_gauge(config: &ThermometerConfig) -> Vec<(f64, &Milestone)> {
+    let top = gauge_top(config);
+    if top <= 0.0 {
+        return vec![];
+    }
+    let mut milestones: Vec<_> = config
+        .milestones
+        .iter()
+        .filter(|m| m.amount > 0.0)
+        .map(|m| (m.amount / top, m))
+        .collect();
+    milestones.sort_by(|a, b| a.0.total_cmp(&b.0));
+    milestones
+}
+
+/// Every milestone needs a positive amount and a label
+pub fn validate(milestones: &[Milestone]) -> Result<(), String> {
+    for milestone in milestones {
+        if !(milestone.amount.is_finite() && milestone.amount > 0.0) {
+            return Err(format!("Milestone {:?} needs an amount above zero", milestone.label));
+        }
+        if milestone.label.trim().is_empty() {
+            return Err(format!("Milestone at ${:.2} needs a label", milestone.amount));
+        }
+    }
+    Ok(())
+}
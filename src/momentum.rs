@@ -0,0 +1,145 @@
+use serde::Serialize;
+use std::collections::BTreeMap;
+use tokio::sync::broadcast;
+use utoipa::ToSchema;
+
+use crate::ledger::Donation;
+use crate::webhooks;
+use crate::{AppState, ThermometerConfig};
+
+/// Consecutive days of growth a team needs for a `growth_streak` event
+const STREAK_DAYS: usize = 3;
+/// Events kept for subscribers that fall behind before the oldest are dropped
+const CHANNEL_CAPACITY: usize = 64;
+
+/// A notable change in the standings, sent to `team.momentum` webhooks and live subscribers
+#[derive(Debug, Clone, PartialEq, Serialize, ToSchema)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum MomentumEvent {
+    /// `team` moved ahead of `passed` in the standings
+    Overtake { team: String, passed: String, total_raised: f64 },
+    /// The largest gift so far today, beating at least one earlier gift
+    BiggestDonationToday { team: String, amount: f64, donor: String },
+    /// `team` grew on each of the last `days` days, counting today
+    GrowthStreak { team: String, days: usize },
+}
+
+impl MomentumEvent {
+    /// One-line description, e.g. `UnderDogs passed Team Beta with $3,250.00`
+    pub fn message(&self) -> String {
+        match self {
+            Self::Overtake { team, passed, total_raised } => {
+                format!("{} passed {} with ${:.2}", team, passed, total_raised)
+            }
+            Self::BiggestDonationToday { team, amount, donor } => {
+                format!("{} gave ${:.2} to {}, the biggest gift today", donor, amount, team)
+            }
+            Self::GrowthStreak { team, days } => format!("{} has grown {} days in a row", team, days),
+        }
+    }
+}
+
+/// Channel live subscribers receive momentum events on; one per campaign/tenant
+pub fn channel() -> broadcast::Sender<MomentumEvent> {
+    broadcast::channel(CHANNEL_CAPACITY).0
+}
+
+/// Send an event to live subscribers and `team.momentum` webhooks
+fn publish(state: &AppState, config: &ThermometerConfig, event: MomentumEvent) {
+    tracing::info!("Momentum: {}", event.message());
+    // Nobody listening is fine; the event still goes to webhooks
+    let _ = state.momentum.send(event.clone());
+    webhooks::on_momentum(state, config, &event);
+}
+
+/// Detect teams that moved ahead of others between two versions of the config
+pub fn on_config_change(state: &AppState, previous: &ThermometerConfig, config: &ThermometerConfig) {
+    let before: BTreeMap<&str, f64> = previous.active_teams().map(|t| (t.name.as_str(), t.total_raised)).collect();
+    for team in config.active_teams() {
+        let Some(&was) = before.get(team.name.as_str()) else {
+            continue;
+        };
+        if team.total_raised <= was {
+            continue;
+        }
+        // Only teams that were ahead before and are behind now; new or deleted teams don't count
+        for other in config.active_teams() {
+            let passed = before
+                .get(other.name.as_str())
+                .is_some_and(|&other_was| other_was > was && other.total_raised < team.total_raised);
+            if passed {
+                publish(
+                    state,
+                    config,
+                    MomentumEvent::Overtake {
+                        team: team.name.clone(),
+                        passed: other.name.clone(),
+                        total_raised: team.total_raised,
+                    },
+                );
+            }
+        }
+    }
+}
+
+/// Detect the day's biggest gift and growth streaks once `donation` is in the ledger
+pub async fn on_donation(state: &AppState, config: &ThermometerConfig, donation: &Donation) {
+    if !donation.source.is_gift() || donation.amount <= 0.0 {
+        return;
+    }
+    let donations = match state.storage.list_donations().await {
+        Ok(donations) => donations,
+        Err(e) => {
+            tracing::warn!("Failed to load ledger for momentum events: {}", e);
+            return;
+        }
+    };
+
+    let tz = config.tz();
+    let local_date = |timestamp: &str| {
+        chrono::DateTime::parse_from_rfc3339(timestamp)
+            .ok()
+            .map(|t| t.with_timezone(&tz).date_naive())
+    };
+    let Some(today) = local_date(&donation.created_at) else {
+        return;
+    };
+    let earlier: Vec<&Donation> = donations.iter().filter(|d| d.id != donation.id).collect();
+
+    let earlier_gifts_today: Vec<f64> = earlier
+        .iter()
+        .filter(|d| d.source.is_gift() && local_date(&d.created_at) == Some(today))
+        .map(|d| d.amount)
+        .collect();
+    if !earlier_gifts_today.is_empty() && earlier_gifts_today.iter().all(|&a| donation.amount > a) {
+        publish(
+            state,
+            config,
+            MomentumEvent::BiggestDonationToday {
+                team: donation.team.clone(),
+                amount: donation.amount,
+                donor: donation.public_name(&config.donors),
+            },
+        );
+    }
+
+    // Net ledger change per day for the team, before this donation
+    let mut daily: BTreeMap<chrono::NaiveDate, f64> = BTreeMap::new();
+    for d in earlier.iter().filter(|d| d.team == donation.team) {
+        if let Some(day) = local_date(&d.created_at) {
+            *daily.entry(day).or_default() += d.amount;
+        }
+    }
+    // Only the donation that turns today positive extends the streak, so it's announced once a day
+    let today_before = daily.get(&today).copied().unwrap_or(0.0);
+    if today_before > 0.0 || today_before + donation.amount <= 0.0 {
+        return;
+    }
+    let days = 1 + (1..)
+        .map_while(|n| today.checked_sub_days(chrono::Days::new(n)))
+        .take_while(|day| daily.get(day).copied().unwrap_or(0.0) > 0.0)
+        .count();
+    if days >= STREAK_DAYS {
+        publish(state, config, MomentumEvent::GrowthStreak { team: donation.team.clone(), days });
+    }
+}
@@ -0,0 +1,522 @@
+use async_trait::async_trait;
+use axum::{
+    extract::{Query, State},
+    http::{HeaderMap, StatusCode},
+    response::Json,
+};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use utoipa::{IntoParams, ToSchema};
+use uuid::Uuid;
+
+use crate::email::{Mailer, OutgoingEmail};
+use crate::{error_response, require_admin, AppState, ErrorResponse, ThermometerConfig};
+
+const SLACK_TIMEOUT: Duration = Duration::from_secs(10);
+/// Deliveries kept for `/admin/notifications`, newest first
+const DELIVERY_LOG_LIMIT: usize = 100;
+
+/// Staff notifications. Slack messages go to the incoming webhook in
+/// `integrations.slack_webhook_url` (or `SLACK_WEBHOOK_URL`); email needs SMTP like the digest.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[serde(default)]
+pub struct NotificationSettings {
+    pub enabled: bool,
+    /// Recipients for the email channel
+    pub email_to: Vec<String>,
+    /// Progress percentages that send a `milestone` notification when crossed. While
+    /// `chat_announcements` is on, these go by email only.
+    pub milestones: Vec<f64>,
+    /// Channels per event; events left out use their default channels
+    pub routes: HashMap<NotificationEvent, Vec<NotificationChannel>>,
+    /// Message per event with `{{placeholders}}`; events left out use their default message
+    pub templates: HashMap<NotificationEvent, String>,
+}
+
+impl Default for NotificationSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            email_to: vec![],
+            milestones: vec![25.0, 50.0, 75.0, 100.0],
+            routes: HashMap::new(),
+            templates: HashMap::new(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum NotificationEvent {
+    /// Progress crossed one of `milestones`
+    Milestone,
+    /// A CSV upload changed the live totals
+    Upload,
+    /// A scheduled job failed
+    Error,
+}
+
+impl NotificationEvent {
+    fn label(&self) -> &'static str {
+        match self {
+            NotificationEvent::Milestone => "Milestone reached",
+            NotificationEvent::Upload => "Totals uploaded",
+            NotificationEvent::Error => "Job failed",
+        }
+    }
+
+    fn default_channels(&self) -> Vec<NotificationChannel> {
+        match self {
+            NotificationEvent::Milestone => vec![NotificationChannel::Slack, NotificationChannel::Email],
+            NotificationEvent::Upload => vec![NotificationChannel::Slack],
+            NotificationEvent::Error => vec![NotificationChannel::Email],
+        }
+    }
+
+    fn default_template(&self) -> &'static str {
+        match self {
+            NotificationEvent::Milestone => {
+                "{{title}} just passed {{milestone}}% of its ${{goal}} goal (${{total}} raised)!"
+            }
+            NotificationEvent::Upload => {
+                "New totals uploaded for {{teams}} teams: ${{total}} of ${{goal}} raised ({{percent}}%)."
+            }
+            NotificationEvent::Error => "The {{job}} job failed: {{error}}",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum NotificationChannel {
+    Slack,
+    Email,
+}
+
+/// A rendered message, ready for any channel
+pub struct Notification {
+    pub subject: String,
+    pub message: String,
+}
+
+/// A channel notifications can be delivered on
+#[async_trait]
+pub trait Notifier: Send + Sync {
+    async fn send(&self, notification: &Notification) -> Result<(), String>;
+
+    /// Make a placeholder value safe to insert into this channel's message text
+    fn escape(&self, value: &str) -> String {
+        value.to_string()
+    }
+}
+
+struct SlackNotifier {
+    client: reqwest::Client,
+    webhook_url: String,
+}
+
+#[async_trait]
+impl Notifier for SlackNotifier {
+    async fn send(&self, notification: &Notification) -> Result<(), String> {
+        let response = self
+            .client
+            .post(&self.webhook_url)
+            .timeout(SLACK_TIMEOUT)
+            .json(&json!({ "text": format!("*{}*\n{}", notification.subject, notification.message) }))
+            .send()
+            .await
+            .map_err(|e| format!("Slack request failed: {}", e))?;
+        let status = response.status();
+        if !status.is_success() {
+            // Slack explains the problem in the body, e.g. `no_service` for a revoked webhook
+            let body = response.text().await.unwrap_or_default();
+            return Err(format!("Slack returned {}: {}", status, body.trim()));
+        }
+        Ok(())
+    }
+
+    /// Slack treats `<...>` as links and mentions, so a team named `<!channel>` would ping everyone
+    fn escape(&self, value: &str) -> String {
+        value.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;")
+    }
+}
+
+struct EmailNotifier {
+    mailer: Arc<Mailer>,
+    to: Vec<String>,
+}
+
+#[async_trait]
+impl Notifier for EmailNotifier {
+    async fn send(&self, notification: &Notification) -> Result<(), String> {
+        let html_body = format!("<p>{}</p>", html_escape(&notification.message));
+        self.mailer
+            .send(OutgoingEmail {
+                to: self.to.clone(),
+                subject: notification.subject.clone(),
+                html_body,
+                text_body: notification.message.clone(),
+                inline_images: vec![],
+            })
+            .await
+    }
+}
+
+fn html_escape(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;")
+}
+
+/// The notifier for `channel`, or why it can't be used
+fn notifier(
+    state: &AppState,
+    config: &ThermometerConfig,
+    channel: NotificationChannel,
+) -> Result<Box<dyn Notifier>, String> {
+    let settings = &config.notifications;
+    match channel {
+        NotificationChannel::Slack => {
+            let webhook_url = config.integrations.slack_webhook_url(state).ok_or_else(|| {
+                "Slack is not configured (no integrations.slack_webhook_url or SLACK_WEBHOOK_URL)".to_string()
+            })?;
+            Ok(Box::new(SlackNotifier {
+                client: state.http_client.clone(),
+                webhook_url,
+            }))
+        }
+        NotificationChannel::Email => {
+            let mailer = state
+                .mailer
+                .clone()
+                .ok_or_else(|| "Email is not configured (SMTP_HOST not set)".to_string())?;
+            if settings.email_to.is_empty() {
+                return Err("No recipients in notifications.email_to".to_string());
+            }
+            Ok(Box::new(EmailNotifier {
+                mailer,
+                to: settings.email_to.clone(),
+            }))
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum NotificationStatus {
+    Sent,
+    Failed,
+}
+
+/// One notification sent on one channel
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct NotificationDelivery {
+    pub id: String,
+    pub event: NotificationEvent,
+    pub channel: NotificationChannel,
+    pub subject: String,
+    pub message: String,
+    pub status: NotificationStatus,
+    pub error: Option<String>,
+    pub created_at: String,
+}
+
+/// Recent deliveries, kept in memory. Notifications aren't retried, so the log only has to
+/// explain what was sent since the last restart.
+#[derive(Default)]
+pub struct DeliveryLog {
+    entries: Mutex<VecDeque<NotificationDelivery>>,
+}
+
+impl DeliveryLog {
+    fn push(&self, delivery: NotificationDelivery) {
+        let mut entries = self.entries.lock().unwrap();
+        entries.push_front(delivery);
+        entries.truncate(DELIVERY_LOG_LIMIT);
+    }
+}
+
+/// Replace each `{{name}}` in `template` with its value, passed through `escape`. Values are
+/// inserted as-is, never expanded themselves, and unknown names are left in place so a typo shows
+/// up in the message instead of silently disappearing.
+fn render(template: &str, vars: &[(&str, String)], escape: impl Fn(&str) -> String) -> String {
+    let mut out = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(start) = rest.find("{{") {
+        let Some(len) = rest[start + 2..].find("}}") else {
+            break;
+        };
+        out.push_str(&rest[..start]);
+        let tag = &rest[start..start + len + 4];
+        let name = tag[2..tag.len() - 2].trim();
+        match vars.iter().find(|(var, _)| *var == name) {
+            Some((_, value)) => out.push_str(&escape(value)),
+            None => out.push_str(tag),
+        }
+        rest = &rest[start + tag.len()..];
+    }
+    out.push_str(rest);
+    out
+}
+
+/// `team` (the team whose total moved the most) and `delta` (how much the campaign total moved)
+/// for a change from `previous` to `config`
+fn change_vars(previous: &ThermometerConfig, config: &ThermometerConfig) -> Vec<(&'static str, String)> {
+    let before: HashMap<&str, f64> = previous.active_teams().map(|t| (t.name.as_str(), t.total_raised)).collect();
+    let team = config
+        .active_teams()
+        .map(|t| (t, t.total_raised - before.get(t.name.as_str()).copied().unwrap_or(0.0)))
+        .filter(|(_, change)| *change != 0.0)
+        .max_by(|a, b| a.1.abs().total_cmp(&b.1.abs()))
+        .map(|(t, _)| t.name.clone())
+        .unwrap_or_default();
+    vec![
+        ("team", team),
+        ("delta", format!("{:.2}", config.total_raised() - previous.total_raised())),
+    ]
+}
+
+type Prepared = (NotificationChannel, Result<Box<dyn Notifier>, String>, Notification);
+
+/// `event` rendered for each of `channels`, with the notifier that sends it (or why there isn't
+/// one). `vars` are added to the placeholders every event has: `title`, `organization`, `total`,
+/// `goal` and `percent`.
+fn prepare(
+    state: &AppState,
+    config: &ThermometerConfig,
+    event: NotificationEvent,
+    channels: Vec<NotificationChannel>,
+    vars: Vec<(&str, String)>,
+) -> Vec<Prepared> {
+    let settings = &config.notifications;
+    let mut placeholders = vec![
+        ("title", config.title.clone()),
+        ("organization", config.organization_name.clone()),
+        ("total", format!("{:.2}", config.total_raised())),
+        ("goal", format!("{:.2}", config.goal)),
+        ("percent", format!("{:.0}", config.progress_percent())),
+    ];
+    placeholders.extend(vars);
+    let template = settings.templates.get(&event).map_or(event.default_template(), String::as_str);
+    let subject = format!("{}: {}", config.title, event.label());
+
+    // Each channel escapes the values for its own format
+    channels
+        .into_iter()
+        .map(|channel| {
+            let notifier = notifier(state, config, channel);
+            let message = match &notifier {
+                Ok(notifier) => render(template, &placeholders, |v| notifier.escape(v)),
+                Err(_) => render(template, &placeholders, str::to_string),
+            };
+            let notification = Notification {
+                subject: subject.clone(),
+                message,
+            };
+            (channel, notifier, notification)
+        })
+        .collect()
+}
+
+/// Send `event` on its routed channels in the background
+fn dispatch(state: &AppState, config: &ThermometerConfig, event: NotificationEvent, vars: Vec<(&str, String)>) {
+    let settings = &config.notifications;
+    if !settings.enabled {
+        return;
+    }
+    let mut channels = settings.routes.get(&event).cloned().unwrap_or_else(|| event.default_channels());
+    // Chat announcements already celebrate milestones in Slack; one post per milestone is enough
+    if event == NotificationEvent::Milestone && config.chat_announcements.enabled {
+        channels.retain(|c| *c != NotificationChannel::Slack);
+    }
+    if channels.is_empty() {
+        return;
+    }
+
+    let notifiers = prepare(state, config, event, channels, vars);
+    let state = state.clone();
+    tokio::spawn(async move {
+        for (channel, notifier, notification) in notifiers {
+            let result = match notifier {
+                Ok(notifier) => notifier.send(&notification).await,
+                Err(e) => Err(e),
+            };
+            if let Err(e) = &result {
+                tracing::error!("Failed to send {:?} notification via {:?}: {}", event, channel, e);
+            }
+            state.notifications.push(NotificationDelivery {
+                id: Uuid::new_v4().to_string(),
+                event,
+                channel,
+                subject: notification.subject,
+                message: notification.message,
+                status: if result.is_ok() { NotificationStatus::Sent } else { NotificationStatus::Failed },
+                error: result.err(),
+                created_at: chrono::Utc::now().to_rfc3339(),
+            });
+        }
+    });
+}
+
+/// Send a `milestone` notification for every milestone crossed between `previous` and `config`
+pub fn on_config_change(state: &AppState, previous: &ThermometerConfig, config: &ThermometerConfig) {
+    let before = previous.progress_percent();
+    let after = config.progress_percent();
+    for milestone in &config.notifications.milestones {
+        if before < *milestone && after >= *milestone {
+            let mut vars = change_vars(previous, config);
+            vars.push(("milestone", format!("{:.0}", milestone)));
+            dispatch(state, config, NotificationEvent::Milestone, vars);
+        }
+    }
+}
+
+/// Send an `upload` notification after a CSV upload changed the live totals
+pub fn on_upload(state: &AppState, previous: &ThermometerConfig, config: &ThermometerConfig) {
+    let mut vars = change_vars(previous, config);
+    vars.push(("teams", config.active_teams().count().to_string()));
+    dispatch(state, config, NotificationEvent::Upload, vars);
+}
+
+/// Send an `error` notification for a failed scheduled job
+pub async fn on_job_failed(state: &AppState, job: &str, error: &str) {
+    let config = match state.storage.load_config().await {
+        Ok(config) => config,
+        Err(e) => {
+            tracing::error!("Failed to load config for job failure notification: {}", e);
+            return;
+        }
+    };
+    dispatch(
+        state,
+        &config,
+        NotificationEvent::Error,
+        vec![("job", job.to_string()), ("error", error.to_string())],
+    );
+}
+
+/// Sample values for a test message, with real campaign totals
+fn sample_vars(config: &ThermometerConfig, event: NotificationEvent) -> Vec<(&'static str, String)> {
+    let team = config
+        .active_teams()
+        .next()
+        .map_or_else(|| "Sample Team".to_string(), |t| t.name.clone());
+    match event {
+        NotificationEvent::Milestone => vec![
+            ("team", team),
+            ("delta", "250.00".to_string()),
+            ("milestone", "50".to_string()),
+        ],
+        NotificationEvent::Upload => vec![
+            ("team", team),
+            ("delta", "250.00".to_string()),
+            ("teams", config.active_teams().count().to_string()),
+        ],
+        NotificationEvent::Error => vec![
+            ("job", "daily-digest".to_string()),
+            ("error", "This is a test. Nothing actually failed.".to_string()),
+        ],
+    }
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct TestNotificationRequest {
+    /// Channel to test; both when omitted
+    pub channel: Option<NotificationChannel>,
+    /// Event whose template is used for the message; `milestone` when omitted
+    pub event: Option<NotificationEvent>,
+}
+
+/// Outcome of sending a test message on one channel
+#[derive(Debug, Serialize, ToSchema)]
+pub struct TestNotificationResult {
+    pub channel: NotificationChannel,
+    pub ok: bool,
+    /// The message as sent
+    pub message: String,
+    /// Why it wasn't delivered, e.g. the status and body Slack returned
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+#[utoipa::path(
+    post,
+    path = "/admin/notifications/test",
+    tag = "Admin",
+    security(("edit_key" = [])),
+    request_body = TestNotificationRequest,
+    responses(
+        (status = 200, description = "Result per channel; sent even while notifications are disabled and not added to the delivery log", body = [TestNotificationResult]),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 500, description = "Failed to load config", body = ErrorResponse)
+    )
+)]
+pub async fn test_notification(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Json(request): Json<TestNotificationRequest>,
+) -> Result<Json<Vec<TestNotificationResult>>, (StatusCode, Json<ErrorResponse>)> {
+    require_admin(&headers, &state)?;
+
+    let config = state.storage.load_config().await.map_err(|e| {
+        error_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to load config: {}", e))
+    })?;
+    let event = request.event.unwrap_or(NotificationEvent::Milestone);
+    let channels = match request.channel {
+        Some(channel) => vec![channel],
+        None => vec![NotificationChannel::Slack, NotificationChannel::Email],
+    };
+
+    let mut results = vec![];
+    let prepared = prepare(&state, &config, event, channels, sample_vars(&config, event));
+    for (channel, notifier, mut notification) in prepared {
+        notification.subject = format!("[Test] {}", notification.subject);
+        let result = match notifier {
+            Ok(notifier) => notifier.send(&notification).await,
+            Err(e) => Err(e),
+        };
+        tracing::info!("Test notification via {:?}: {:?}", channel, result);
+        results.push(TestNotificationResult {
+            channel,
+            ok: result.is_ok(),
+            message: notification.message,
+            error: result.err(),
+        });
+    }
+    Ok(Json(results))
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct NotificationQuery {
+    /// Only deliveries in this state
+    status: Option<NotificationStatus>,
+}
+
+#[utoipa::path(
+    get,
+    path = "/admin/notifications",
+    tag = "Admin",
+    security(("edit_key" = [])),
+    params(NotificationQuery),
+    responses(
+        (status = 200, description = "Recent notification deliveries since the last restart, newest first", body = [NotificationDelivery]),
+        (status = 401, description = "Unauthorized", body = ErrorResponse)
+    )
+)]
+pub async fn list_notifications(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Query(query): Query<NotificationQuery>,
+) -> Result<Json<Vec<NotificationDelivery>>, (StatusCode, Json<ErrorResponse>)> {
+    require_admin(&headers, &state)?;
+    let entries = state.notifications.entries.lock().unwrap();
+    Ok(Json(
+        entries
+            .iter()
+            .filter(|d| query.status.is_none_or(|status| d.status == status))
+            .cloned()
+            .collect(),
+    ))
+}
@@ -0,0 +1,243 @@
+use askama::Template;
+use axum::{
+    extract::{Query, State},
+    http::StatusCode,
+    response::{IntoResponse, Json, Response},
+};
+use chrono::{Datelike, Timelike};
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+
+use crate::color_constants;
+use crate::ledger::Donation;
+use crate::render::{self, ImageAbout, ImageCache, ImageFormat};
+use crate::storage::StorageError;
+use crate::thermometer::PngOptions;
+use crate::{error_response, AppState, ErrorResponse, ThermometerConfig};
+
+const WEEKDAYS: [&str; 7] = ["Monday", "Tuesday", "Wednesday", "Thursday", "Friday", "Saturday", "Sunday"];
+
+/// Gifts counted into one hour or weekday
+#[derive(Debug, Clone, Default, Serialize, ToSchema)]
+pub struct PatternBucket {
+    /// Hour of the day (0 to 23) or weekday name
+    pub label: String,
+    pub donations: usize,
+    pub amount: f64,
+}
+
+/// When donors give, by local time in the campaign timezone
+#[derive(Debug, Serialize, ToSchema)]
+pub struct DonationPatterns {
+    pub timezone: String,
+    /// 24 buckets, midnight first
+    pub by_hour: Vec<PatternBucket>,
+    /// 7 buckets, Monday first
+    pub by_weekday: Vec<PatternBucket>,
+    /// Hour with the most gifts, if there are any
+    pub busiest_hour: Option<u32>,
+    pub busiest_weekday: Option<String>,
+}
+
+#[derive(Debug, Default, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct PatternsQuery {
+    /// `svg` or `png` renders the histograms as a chart instead of JSON
+    format: Option<String>,
+}
+
+/// Only gifts count; spreadsheet syncs and adjustments happen when admins work, not donors
+pub fn patterns(config: &ThermometerConfig, donations: &[Donation]) -> DonationPatterns {
+    let tz = config.tz();
+    let mut by_hour: Vec<PatternBucket> = (0..24)
+        .map(|h| PatternBucket { label: h.to_string(), ..Default::default() })
+        .collect();
+    let mut by_weekday: Vec<PatternBucket> = WEEKDAYS
+        .iter()
+        .map(|d| PatternBucket { label: d.to_string(), ..Default::default() })
+        .collect();
+
+    for donation in donations.iter().filter(|d| d.source.is_gift() && d.amount > 0.0) {
+        let Ok(t) = chrono::DateTime::parse_from_rfc3339(&donation.created_at) else {
+            continue;
+        };
+        let local = t.with_timezone(&tz);
+        for bucket in [
+            &mut by_hour[local.hour() as usize],
+            &mut by_weekday[local.weekday().num_days_from_monday() as usize],
+        ] {
+            bucket.donations += 1;
+            bucket.amount += donation.amount;
+        }
+    }
+    for bucket in by_hour.iter_mut().chain(by_weekday.iter_mut()) {
+        bucket.amount = (bucket.amount * 100.0).round() / 100.0;
+    }
+
+    let busiest = |buckets: &[PatternBucket]| {
+        buckets
+            .iter()
+            .enumerate()
+            .filter(|(_, b)| b.donations > 0)
+            .max_by_key(|(i, b)| (b.donations, std::cmp::Reverse(*i)))
+            .map(|(i, _)| i)
+    };
+    DonationPatterns {
+        timezone: tz.name().to_string(),
+        busiest_hour: busiest(&by_hour).map(|h| h as u32),
+        busiest_weekday: busiest(&by_weekday).map(|d| WEEKDAYS[d].to_string()),
+        by_hour,
+        by_weekday,
+    }
+}
+
+#[derive(Template)]
+#[template(path = "patterns.svg")]
+struct PatternsTemplate {
+    width: u32,
+    height: u32,
+    timezone: String,
+    charts: Vec<Chart>,
+    // Color constants
+    background_color: &'static str,
+    title_text_color: &'static str,
+    text_secondary_color: &'static str,
+    bar_color: &'static str,
+    axis_color: &'static str,
+}
+
+struct Chart {
+    title: &'static str,
+    title_x: String,
+    title_y: String,
+    axis_x1: String,
+    axis_x2: String,
+    axis_y: String,
+    bars: Vec<Bar>,
+}
+
+struct Bar {
+    x: String,
+    y: String,
+    width: String,
+    height: String,
+    label_x: String,
+    label_y: String,
+    /// Empty for bars left unlabeled to avoid crowding
+    label: String,
+    tooltip: String,
+}
+
+const CHART_WIDTH: f64 = 360.0;
+const CHART_HEIGHT: f64 = 120.0;
+const MARGIN: f64 = 20.0;
+
+fn chart(title: &'static str, buckets: &[PatternBucket], left: f64, label_every: usize) -> Chart {
+    let top = MARGIN + 40.0;
+    let baseline = top + CHART_HEIGHT;
+    let slot = CHART_WIDTH / buckets.len() as f64;
+    let max = buckets.iter().map(|b| b.donations).max().unwrap_or(0).max(1) as f64;
+
+    Chart {
+        title,
+        title_x: format!("{:.2}", left),
+        title_y: format!("{:.2}", top - 10.0),
+        axis_x1: format!("{:.2}", left),
+        axis_x2: format!("{:.2}", left + CHART_WIDTH),
+        axis_y: format!("{:.2}", baseline),
+        bars: buckets
+            .iter()
+            .enumerate()
+            .map(|(i, bucket)| {
+                let height = CHART_HEIGHT * bucket.donations as f64 / max;
+                let x = left + i as f64 * slot;
+                Bar {
+                    x: format!("{:.2}", x + slot * 0.15),
+                    y: format!("{:.2}", baseline - height),
+                    width: format!("{:.2}", slot * 0.7),
+                    height: format!("{:.2}", height),
+                    label_x: format!("{:.2}", x + slot / 2.0),
+                    label_y: format!("{:.2}", baseline + 14.0),
+                    label: if i % label_every == 0 {
+                        bucket.label.chars().take(3).collect()
+                    } else {
+                        String::new()
+                    },
+                    tooltip: format!("{}: {} gifts, ${:.2}", bucket.label, bucket.donations, bucket.amount),
+                }
+            })
+            .collect(),
+    }
+}
+
+/// Two small bar charts side by side: gifts per hour of the day and per weekday
+pub fn generate_patterns_svg(patterns: &DonationPatterns) -> Result<String, askama::Error> {
+    PatternsTemplate {
+        width: (MARGIN * 3.0 + CHART_WIDTH * 2.0) as u32,
+        height: (MARGIN * 2.0 + 40.0 + CHART_HEIGHT + 30.0) as u32,
+        timezone: patterns.timezone.clone(),
+        charts: vec![
+            chart("Gifts by hour", &patterns.by_hour, MARGIN, 3),
+            chart("Gifts by weekday", &patterns.by_weekday, MARGIN * 2.0 + CHART_WIDTH, 1),
+        ],
+        background_color: color_constants::light::BACKGROUND,
+        title_text_color: color_constants::light::TITLE_TEXT,
+        text_secondary_color: color_constants::light::TEXT_SECONDARY,
+        bar_color: color_constants::light::FILL_COLOR_1,
+        axis_color: color_constants::light::MARKER_STROKE,
+    }
+    .render()
+}
+
+async fn load_patterns(state: &AppState) -> Result<DonationPatterns, StorageError> {
+    let config = state.storage.load_config().await?;
+    let donations = state.storage.list_donations().await?;
+    Ok(patterns(&config, &donations))
+}
+
+#[utoipa::path(
+    get,
+    path = "/stats/patterns",
+    tag = "Public",
+    params(PatternsQuery),
+    responses(
+        (status = 200, description = "Gift counts and amounts by hour of day and weekday, in the campaign timezone. With `format=svg` or `format=png`, a chart of the same", body = DonationPatterns),
+        (status = 400, description = "Unknown format", body = ErrorResponse),
+        (status = 500, description = "Failed to load the ledger", body = ErrorResponse)
+    )
+)]
+pub async fn get_patterns(
+    State(state): State<AppState>,
+    Query(query): Query<PatternsQuery>,
+) -> Result<Response, (StatusCode, Json<ErrorResponse>)> {
+    let format = match query.format.as_deref() {
+        None | Some("json") => None,
+        Some("svg") => Some(ImageFormat::Svg),
+        Some("png") => Some(ImageFormat::Png),
+        Some(other) => {
+            return Err(error_response(StatusCode::BAD_REQUEST, format!("Unknown format: {}", other)));
+        }
+    };
+    let patterns = load_patterns(&state).await.map_err(|e| {
+        error_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to load donation patterns: {}", e))
+    })?;
+
+    let Some(format) = format else {
+        return Ok(Json(patterns).into_response());
+    };
+    Ok(match generate_patterns_svg(&patterns) {
+        Ok(svg) => {
+            let busiest = match (patterns.busiest_hour, &patterns.busiest_weekday) {
+                (Some(hour), Some(weekday)) => format!(" Busiest hour {:02}:00, busiest day {}.", hour, weekday),
+                _ => String::new(),
+            };
+            let about = ImageAbout::new(
+                "When donations come in",
+                format!("Gift counts by hour of day and by weekday.{}", busiest),
+                chrono::Utc::now().to_rfc3339(),
+            );
+            render::image_response(StatusCode::OK, svg, format, PngOptions::at_scale(1.0), ImageCache::NoCache, &about)
+        }
+        Err(e) => render::error_image("Failed to render patterns chart", e, format, false, 1.0),
+    })
+}
@@ -0,0 +1,183 @@
+use askama::Template;
+use axum::{
+    extract::State,
+    http::{HeaderMap, StatusCode},
+    response::Json,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::ledger::{self, Donation, DonationSource, ModerationStatus};
+use crate::render::{Page, PageError};
+use crate::idempotency::IdempotencyKeyHeader;
+use crate::{error_response, verify_auth, AppState, ErrorResponse};
+
+#[derive(Template)]
+#[template(path = "phonebank.html")]
+pub struct PhonebankTemplate {
+    organization_name: String,
+    title: String,
+    teams: Vec<String>,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct PhonebankPledgeRequest {
+    /// Volunteer's initials
+    caller: String,
+    donor: Option<String>,
+    amount: f64,
+    team: String,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct VolunteerTally {
+    caller: String,
+    pledges: usize,
+    amount: f64,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct PhonebankTallies {
+    total_raised: f64,
+    goal: f64,
+    progress_percent: f64,
+    /// Total pledged through the phone bank
+    phonebank_total: f64,
+    volunteers: Vec<VolunteerTally>,
+}
+
+/// Accept either the admin key or the volunteer `PHONEBANK_KEY`
+fn require_volunteer(headers: &HeaderMap, state: &AppState) -> Result<(), (StatusCode, Json<ErrorResponse>)> {
+    let admin = verify_auth(headers, &state.edit_key);
+    let volunteer = state
+        .phonebank_key
+        .as_deref()
+        .map(|key| verify_auth(headers, key));
+
+    match (admin, volunteer) {
+        (Ok(()), _) | (_, Some(Ok(()))) => Ok(()),
+        _ => Err(error_response(
+            StatusCode::UNAUTHORIZED,
+            "Invalid or missing Authorization header",
+        )),
+    }
+}
+
+pub async fn phonebank_page(State(state): State<AppState>) -> Result<Page<PhonebankTemplate>, PageError> {
+    let config = state.storage.load_config().await.map_err(PageError::internal)?;
+
+    Ok(Page(PhonebankTemplate {
+        teams: config.active_teams().map(|t| t.name.clone()).collect(),
+        organization_name: config.organization_name,
+        title: config.title,
+    }))
+}
+
+#[utoipa::path(
+    post,
+    path = "/phonebank/pledges",
+    tag = "Phone Bank",
+    security(("phonebank_key" = []), ("edit_key" = [])),
+    params(IdempotencyKeyHeader),
+    request_body = PhonebankPledgeRequest,
+    responses(
+        (status = 200, description = "Pledge logged", body = PhonebankTallies),
+        (status = 400, description = "Bad request", body = ErrorResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse)
+    )
+)]
+pub async fn log_pledge(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Json(request): Json<PhonebankPledgeRequest>,
+) -> Result<Json<PhonebankTallies>, (StatusCode, Json<ErrorResponse>)> {
+    require_volunteer(&headers, &state)?;
+
+    let caller = request.caller.trim().to_uppercase();
+    if caller.is_empty() || request.team.trim().is_empty() {
+        return Err(error_response(StatusCode::BAD_REQUEST, "caller and team are required"));
+    }
+    if !request.amount.is_finite() || request.amount <= 0.0 {
+        return Err(error_response(StatusCode::BAD_REQUEST, "amount must be positive"));
+    }
+
+    let donation = Donation {
+        id: Uuid::new_v4().to_string(),
+        team: request.team.trim().to_string(),
+        amount: (request.amount * 100.0).round() / 100.0,
+        donor_name: request.donor.filter(|d| !d.trim().is_empty()),
+        donor_email: None,
+        source: DonationSource::Phonebank,
+        created_at: chrono::Utc::now().to_rfc3339(),
+        pledge_id: None,
+        method: None,
+        message: None,
+        moderation: ModerationStatus::Pending,
+        anonymous: None,
+        display_name: None,
+        caller: Some(caller),
+        tax_deductible: None,
+        gift_aid: None,
+        note: None,
+    };
+
+    ledger::record_donation(&state, donation).await.map_err(|e| {
+        error_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to record pledge: {}", e))
+    })?;
+
+    tallies(&state).await.map(Json)
+}
+
+#[utoipa::path(
+    get,
+    path = "/phonebank/tallies",
+    tag = "Phone Bank",
+    security(("phonebank_key" = []), ("edit_key" = [])),
+    responses(
+        (status = 200, description = "Campaign total and per-volunteer tallies", body = PhonebankTallies),
+        (status = 401, description = "Unauthorized", body = ErrorResponse)
+    )
+)]
+pub async fn get_tallies(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+) -> Result<Json<PhonebankTallies>, (StatusCode, Json<ErrorResponse>)> {
+    require_volunteer(&headers, &state)?;
+    tallies(&state).await.map(Json)
+}
+
+async fn tallies(state: &AppState) -> Result<PhonebankTallies, (StatusCode, Json<ErrorResponse>)> {
+    let storage_error = |e| {
+        error_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Storage error: {}", e))
+    };
+    let config = state.storage.load_config().await.map_err(storage_error)?;
+    let donations = state.storage.list_donations().await.map_err(storage_error)?;
+
+    let mut by_caller: BTreeMap<String, (usize, f64)> = BTreeMap::new();
+    for donation in donations.iter().filter(|d| d.source == DonationSource::Phonebank) {
+        let caller = donation.caller.clone().unwrap_or_else(|| "?".to_string());
+        let tally = by_caller.entry(caller).or_default();
+        tally.0 += 1;
+        tally.1 += donation.amount;
+    }
+
+    let mut volunteers: Vec<VolunteerTally> = by_caller
+        .into_iter()
+        .map(|(caller, (pledges, amount))| VolunteerTally {
+            caller,
+            pledges,
+            amount,
+        })
+        .collect();
+    volunteers.sort_by(|a, b| b.amount.total_cmp(&a.amount));
+
+    Ok(PhonebankTallies {
+        total_raised: config.total_raised(),
+        goal: config.goal,
+        progress_percent: config.progress_percent(),
+        phonebank_total: volunteers.iter().map(|v| v.amount).sum::<f64>() + 0.0,
+        volunteers,
+    })
+}
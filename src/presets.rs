@@ -0,0 +1,171 @@
+use axum::{
+    extract::{Query, State},
+    http::{HeaderMap, StatusCode},
+    response::Json,
+};
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+
+use crate::idempotency::IdempotencyKeyHeader;
+use crate::sandbox::EditTarget;
+use crate::tenants::is_valid_slug;
+use crate::versions::ChangeNote;
+use crate::{error_response, require_admin, AppState, ErrorResponse, SuccessResponse, ThermometerConfig};
+
+/// A reusable campaign blueprint: labels, goal, team names and campaign settings
+/// (digest, milestones, donate page, receipts, donor display)
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct CampaignPreset {
+    /// Slug used to instantiate the preset, e.g. `holiday-drive`
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    /// Team totals are ignored; new campaigns always start from zero
+    pub config: ThermometerConfig,
+    pub updated_at: String,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct SavePresetRequest {
+    name: String,
+    #[serde(default)]
+    description: String,
+    /// Blueprint to store; omit to snapshot the current campaign
+    config: Option<ThermometerConfig>,
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct CampaignQuery {
+    /// Name of the preset to start the campaign from
+    from_preset: String,
+}
+
+fn storage_error(e: impl std::fmt::Display) -> (StatusCode, Json<ErrorResponse>) {
+    error_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to access presets: {}", e))
+}
+
+#[utoipa::path(
+    get,
+    path = "/admin/presets",
+    tag = "Admin",
+    security(("edit_key" = [])),
+    responses(
+        (status = 200, description = "Saved campaign presets", body = [CampaignPreset]),
+        (status = 401, description = "Unauthorized", body = ErrorResponse)
+    )
+)]
+pub async fn list_presets(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+) -> Result<Json<Vec<CampaignPreset>>, (StatusCode, Json<ErrorResponse>)> {
+    require_admin(&headers, &state)?;
+
+    let mut presets = state.storage.list_presets().await.map_err(storage_error)?;
+    presets.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(Json(presets))
+}
+
+#[utoipa::path(
+    post,
+    path = "/admin/presets",
+    tag = "Admin",
+    security(("edit_key" = [])),
+    request_body = SavePresetRequest,
+    responses(
+        (status = 200, description = "Preset saved (replacing any preset with the same name)", body = CampaignPreset),
+        (status = 400, description = "Invalid preset name", body = ErrorResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse)
+    )
+)]
+pub async fn save_preset(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Json(request): Json<SavePresetRequest>,
+) -> Result<Json<CampaignPreset>, (StatusCode, Json<ErrorResponse>)> {
+    require_admin(&headers, &state)?;
+
+    let name = request.name.trim().to_string();
+    if !is_valid_slug(&name) {
+        return Err(error_response(
+            StatusCode::BAD_REQUEST,
+            "Preset name must be lowercase letters, digits or dashes",
+        ));
+    }
+
+    let mut config = match request.config {
+        Some(config) => config,
+        None => state.storage.load_config().await.map_err(storage_error)?,
+    };
+    // Credentials stay with the deployment and are never copied into a preset
+    config.integrations = Default::default();
+    let preset = CampaignPreset {
+        name,
+        description: request.description,
+        config,
+        updated_at: chrono::Utc::now().to_rfc3339(),
+    };
+    state.storage.save_preset(&preset).await.map_err(storage_error)?;
+
+    tracing::info!("Saved campaign preset {}", preset.name);
+    Ok(Json(preset))
+}
+
+#[utoipa::path(
+    post,
+    path = "/admin/campaigns",
+    tag = "Admin",
+    security(("edit_key" = [])),
+    params(CampaignQuery, ChangeNote, IdempotencyKeyHeader),
+    responses(
+        (status = 201, description = "Current campaign replaced by a fresh one built from the preset", body = SuccessResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 404, description = "Preset not found", body = ErrorResponse)
+    )
+)]
+pub async fn create_campaign(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Query(query): Query<CampaignQuery>,
+    Query(change): Query<ChangeNote>,
+) -> Result<(StatusCode, Json<SuccessResponse>), (StatusCode, Json<ErrorResponse>)> {
+    require_admin(&headers, &state)?;
+
+    let preset = state
+        .storage
+        .list_presets()
+        .await
+        .map_err(storage_error)?
+        .into_iter()
+        .find(|p| p.name == query.from_preset)
+        .ok_or_else(|| error_response(StatusCode::NOT_FOUND, format!("Preset not found: {}", query.from_preset)))?;
+
+    let _guard = state.config_lock.lock().await;
+    let (target, previous) = EditTarget::load(&state).await.map_err(|e| {
+        error_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to load config: {}", e))
+    })?;
+
+    let mut config = preset.config;
+    config.teams.retain(|t| t.deleted_at.is_none());
+    for team in &mut config.teams {
+        team.total_raised = 0.0;
+    }
+    // Webhooks, notifications, job schedules and integrations belong to the deployment, not the campaign
+    config.webhooks = previous.webhooks.clone();
+    config.notifications = previous.notifications.clone();
+    config.scheduler = previous.scheduler.clone();
+    config.integrations = previous.integrations.clone();
+    config.last_updated = chrono::Utc::now().to_rfc3339();
+
+    target.commit(&state, &previous, &mut config, change.text()).await.map_err(|e| {
+        error_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to save config: {}", e))
+    })?;
+
+    tracing::info!("Started new campaign from preset {}", preset.name);
+    Ok((
+        StatusCode::CREATED,
+        Json(SuccessResponse {
+            message: target.message(format!("Campaign started from preset {}", preset.name)),
+            config,
+        }),
+    ))
+}
@@ -0,0 +1,187 @@
+use axum::{extract::State, http::StatusCode, response::Json};
+use serde::Serialize;
+use std::collections::BTreeMap;
+use utoipa::ToSchema;
+
+use crate::ledger::Donation;
+use crate::storage::StorageError;
+use crate::summary::{self, HOT_HOURS};
+use crate::{error_response, AppState, ErrorResponse, ThermometerConfig};
+
+/// Bumped only for breaking changes to `PublicStats`; fields may be added without a bump
+const SCHEMA_VERSION: u32 = 1;
+/// Recent days the forecast averages over
+const FORECAST_DAYS: i64 = 7;
+
+/// Everything a third-party dashboard needs in one response. Field names and meanings are kept
+/// stable; new fields may be added.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct PublicStats {
+    pub schema_version: u32,
+    pub organization_name: String,
+    pub title: String,
+    pub goal: f64,
+    pub total_raised: f64,
+    /// Progress toward the goal, 0 to 100
+    pub progress_percent: f64,
+    pub remaining: f64,
+    #[schema(value_type = Option<String>, format = Date)]
+    pub ends_on: Option<chrono::NaiveDate>,
+    pub days_left: Option<i64>,
+    /// Active teams, highest total first
+    pub teams: Vec<PublicTeamStats>,
+    /// Amount raised per day (campaign timezone), oldest first, from the first ledger entry to today
+    pub history: Vec<HistoryBucket>,
+    /// Missing until the ledger has entries in the last 7 days
+    pub forecast: Option<Forecast>,
+    pub last_updated: String,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct PublicTeamStats {
+    pub rank: usize,
+    pub name: String,
+    pub total_raised: f64,
+    /// Share of the campaign total, 0 to 100
+    pub percent_of_total: f64,
+    pub raised_last_24h: f64,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct HistoryBucket {
+    #[schema(value_type = String, format = Date)]
+    pub date: chrono::NaiveDate,
+    /// Net ledger entries that day
+    pub raised: f64,
+    /// Campaign total at the end of the day
+    pub cumulative: f64,
+}
+
+/// Straight-line projection from the average of the last 7 days
+#[derive(Debug, Serialize, ToSchema)]
+pub struct Forecast {
+    pub daily_average: f64,
+    /// Expected total on `ends_on`, if set
+    pub projected_total: Option<f64>,
+    /// Day the goal is reached at the current pace; missing if it's met or the pace isn't positive
+    #[schema(value_type = Option<String>, format = Date)]
+    pub projected_goal_date: Option<chrono::NaiveDate>,
+}
+
+fn round_cents(amount: f64) -> f64 {
+    (amount * 100.0).round() / 100.0 + 0.0
+}
+
+/// Daily buckets from the first ledger entry to `today`, for active teams
+pub fn history(config: &ThermometerConfig, donations: &[Donation], today: chrono::NaiveDate) -> Vec<HistoryBucket> {
+    let tz = config.tz();
+    let active: Vec<&str> = config.active_teams().map(|t| t.name.as_str()).collect();
+
+    let mut daily: BTreeMap<chrono::NaiveDate, f64> = BTreeMap::new();
+    for donation in donations.iter().filter(|d| active.contains(&d.team.as_str())) {
+        if let Ok(t) = chrono::DateTime::parse_from_rfc3339(&donation.created_at) {
+            *daily.entry(t.with_timezone(&tz).date_naive()).or_default() += donation.amount;
+        }
+    }
+    let Some(&first) = daily.keys().next() else {
+        return vec![];
+    };
+
+    // Totals can include amounts that predate the ledger; count them before the first day
+    let mut cumulative = config.total_raised() - daily.values().sum::<f64>();
+    first
+        .iter_days()
+        .take_while(|day| *day <= today)
+        .map(|date| {
+            let raised = daily.get(&date).copied().unwrap_or(0.0);
+            cumulative += raised;
+            HistoryBucket {
+                date,
+                raised: round_cents(raised),
+                cumulative: round_cents(cumulative),
+            }
+        })
+        .collect()
+}
+
+fn forecast(config: &ThermometerConfig, history: &[HistoryBucket], today: chrono::NaiveDate) -> Option<Forecast> {
+    let since = today - chrono::Duration::days(FORECAST_DAYS - 1);
+    let recent: Vec<&HistoryBucket> = history.iter().filter(|b| b.date >= since).collect();
+    if recent.is_empty() {
+        return None;
+    }
+    let daily_average = recent.iter().map(|b| b.raised).sum::<f64>() / FORECAST_DAYS as f64;
+    let total = config.total_raised();
+    let remaining = config.goal - total;
+
+    Some(Forecast {
+        daily_average: round_cents(daily_average),
+        projected_total: config
+            .ends_on
+            .map(|end| round_cents(total + daily_average * (end - today).num_days().max(0) as f64)),
+        projected_goal_date: (remaining > 0.0 && daily_average > 0.0)
+            .then(|| today + chrono::Duration::days((remaining / daily_average).ceil() as i64)),
+    })
+}
+
+pub async fn public_stats(state: &AppState) -> Result<PublicStats, StorageError> {
+    let config = state.storage.load_config().await?;
+    let donations = state.storage.list_donations().await?;
+    let summary = summary::summarize(&config);
+    let today = chrono::Utc::now().with_timezone(&config.tz()).date_naive();
+    let since = chrono::Utc::now() - chrono::Duration::hours(HOT_HOURS);
+
+    let teams = summary
+        .teams
+        .into_iter()
+        .map(|team| PublicTeamStats {
+            raised_last_24h: round_cents(
+                donations
+                    .iter()
+                    .filter(|d| d.team == team.name)
+                    .filter(|d| chrono::DateTime::parse_from_rfc3339(&d.created_at).is_ok_and(|t| t >= since))
+                    .map(|d| d.amount)
+                    .sum(),
+            ),
+            rank: team.rank,
+            name: team.name,
+            total_raised: team.total_raised,
+            percent_of_total: team.percent_of_total,
+        })
+        .collect();
+    let history = history(&config, &donations, today);
+
+    Ok(PublicStats {
+        schema_version: SCHEMA_VERSION,
+        forecast: forecast(&config, &history, today),
+        organization_name: summary.organization_name,
+        title: summary.title,
+        goal: summary.goal,
+        total_raised: summary.total_raised,
+        progress_percent: summary.progress_percent,
+        remaining: summary.remaining,
+        ends_on: summary.ends_on,
+        days_left: summary.days_left,
+        teams,
+        history,
+        last_updated: summary.last_updated,
+    })
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/stats",
+    tag = "Public",
+    responses(
+        (status = 200, description = "Totals, per-team summaries, daily history and forecast in a stable format. Any origin may fetch it (CORS)", body = PublicStats),
+        (status = 500, description = "Failed to load stats", body = ErrorResponse)
+    )
+)]
+pub async fn get_public_stats(
+    State(state): State<AppState>,
+) -> Result<Json<PublicStats>, (StatusCode, Json<ErrorResponse>)> {
+    let stats = public_stats(&state).await.map_err(|e| {
+        error_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to load stats: {}", e))
+    })?;
+    Ok(Json(stats))
+}
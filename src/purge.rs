@@ -0,0 +1,58 @@
+//! Emergency cache purge, for when something embarrassing (a misspelled team name) has to
+//! disappear from every cached copy of the images right away.
+
+use axum::{
+    extract::State,
+    http::{HeaderMap, StatusCode},
+    response::Json,
+};
+use serde::Serialize;
+use utoipa::ToSchema;
+
+use crate::{error_response, require_admin, AppState, ErrorResponse};
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct PurgeResult {
+    /// New `cache_epoch` of the config, which every content-addressed image URL now includes
+    pub cache_epoch: String,
+    /// Team avatars dropped from the server's cache; they're downloaded again on the next render
+    pub avatars_cleared: usize,
+}
+
+#[utoipa::path(
+    post,
+    path = "/admin/cache/purge",
+    tag = "Admin",
+    security(("edit_key" = [])),
+    responses(
+        (status = 200, description = "Caches cleared and image URLs rotated: every root image route now redirects to a new `/img/{hash}/` URL, so copies of the old ones held by browsers, mail proxies and CDNs stop being linked", body = PurgeResult),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 500, description = "Failed to load or save the config", body = ErrorResponse)
+    )
+)]
+pub async fn purge_caches(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+) -> Result<Json<PurgeResult>, (StatusCode, Json<ErrorResponse>)> {
+    require_admin(&headers, &state)?;
+
+    let avatars_cleared = state.avatars.clear();
+
+    // The hash covers the whole config, so a new epoch moves every image without touching
+    // `last_updated`, which would reset the stale-figures warning
+    let _guard = state.config_lock.lock().await;
+    let mut config = state.storage.load_config().await.map_err(|e| {
+        error_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to load config: {}", e))
+    })?;
+    let cache_epoch = uuid::Uuid::new_v4().to_string();
+    config.cache_epoch = Some(cache_epoch.clone());
+    state.storage.save_config(&config).await.map_err(|e| {
+        error_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to save config: {}", e))
+    })?;
+
+    tracing::info!("Purged caches: new cache epoch {}, {} avatars cleared", cache_epoch, avatars_cleared);
+    Ok(Json(PurgeResult {
+        cache_epoch,
+        avatars_cleared,
+    }))
+}
@@ -0,0 +1,269 @@
+use axum::{
+    extract::{ConnectInfo, Path, Request, State},
+    http::{HeaderMap, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Json, Response},
+    routing::{delete, get},
+    Router,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::net::SocketAddr;
+use std::sync::{Mutex, RwLock};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::{error_response, require_admin, verify_auth, AppState, ErrorResponse};
+
+/// A read-only key for a trusted partner (e.g. the county giving portal). It can't change
+/// anything, but it can read unlisted campaigns and has its own rate limit.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct ReadToken {
+    pub id: String,
+    /// Who the token was issued to
+    pub name: String,
+    /// Sent as `Authorization: Bearer <token>`
+    pub token: String,
+    /// Requests per minute to the rate-limited read endpoints; unlimited when unset
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rate_limit_per_minute: Option<u32>,
+    pub created_at: String,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct CreateReadTokenRequest {
+    name: String,
+    /// Requests per minute; unlimited when unset
+    rate_limit_per_minute: Option<u32>,
+}
+
+/// Requests counted in the current minute, per client
+#[derive(Default)]
+struct Window {
+    minute: i64,
+    counts: HashMap<String, u32>,
+}
+
+/// Issued read tokens of one deployment or tenant, and request counts for the rate limit.
+/// Campaigns use their owner's.
+pub struct ReadTokens {
+    tokens: RwLock<Vec<ReadToken>>,
+    /// `PUBLIC_RATE_LIMIT`: requests per minute per address without a token; unlimited when unset
+    anonymous_limit: Option<u32>,
+    /// `TRUSTED_PROXY_HOPS`: proxies in front of the server that append to `X-Forwarded-For`
+    proxy_hops: usize,
+    window: Mutex<Window>,
+}
+
+impl ReadTokens {
+    pub fn from_env() -> Self {
+        Self {
+            tokens: RwLock::new(vec![]),
+            anonymous_limit: std::env::var("PUBLIC_RATE_LIMIT")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .filter(|limit| *limit > 0),
+            // Cloud Run's front end appends the caller's address
+            proxy_hops: std::env::var("TRUSTED_PROXY_HOPS")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(1),
+            window: Mutex::new(Window::default()),
+        }
+    }
+
+    /// The caller's address: the `X-Forwarded-For` entry our own proxies appended, counting
+    /// `proxy_hops` from the right, since anything left of it is whatever the client sent. Without
+    /// one, the address of the connection.
+    fn client_address(&self, request: &Request) -> String {
+        if self.proxy_hops > 0 {
+            let hops: Vec<&str> = request
+                .headers()
+                .get_all("x-forwarded-for")
+                .iter()
+                .filter_map(|v| v.to_str().ok())
+                .flat_map(|v| v.split(','))
+                .map(str::trim)
+                .filter(|hop| !hop.is_empty())
+                .collect();
+            if let Some(i) = hops.len().checked_sub(self.proxy_hops) {
+                return hops[i].to_string();
+            }
+        }
+        request
+            .extensions()
+            .get::<ConnectInfo<SocketAddr>>()
+            .map(|ConnectInfo(address)| address.ip().to_string())
+            .unwrap_or_default()
+    }
+
+    /// The token the request carries, if it's one of ours
+    pub fn find(&self, headers: &HeaderMap) -> Option<ReadToken> {
+        self.tokens
+            .read()
+            .unwrap()
+            .iter()
+            .find(|t| verify_auth(headers, &t.token).is_ok())
+            .cloned()
+    }
+
+    fn insert(&self, token: ReadToken) {
+        self.tokens.write().unwrap().push(token);
+    }
+
+    fn remove(&self, id: &str) {
+        self.tokens.write().unwrap().retain(|t| t.id != id);
+    }
+
+    /// Count a request from `client`, returning false once it's over `limit` this minute
+    fn allow(&self, client: &str, limit: u32) -> bool {
+        let minute = chrono::Utc::now().timestamp() / 60;
+        let mut window = self.window.lock().unwrap();
+        if window.minute != minute {
+            *window = Window {
+                minute,
+                counts: HashMap::new(),
+            };
+        }
+        let count = window.counts.entry(client.to_string()).or_insert(0);
+        *count += 1;
+        *count <= limit
+    }
+}
+
+/// Load the stored tokens of a deployment or tenant; called once at startup
+pub async fn load(state: &AppState) {
+    match state.storage.list_read_tokens().await {
+        Ok(tokens) => *state.read_tokens.tokens.write().unwrap() = tokens,
+        Err(e) => tracing::error!("Failed to load read tokens: {}", e),
+    }
+}
+
+/// Token management, served by the deployment and each tenant but not by campaigns
+pub fn routes() -> Router<AppState> {
+    Router::new()
+        .route("/admin/tokens", get(list_tokens).post(create_token))
+        .route("/admin/tokens/:id", delete(revoke_token))
+}
+
+/// Whether the request may read an unlisted campaign: a read token or the admin key
+pub fn can_read_unlisted(headers: &HeaderMap, state: &AppState) -> bool {
+    state.read_tokens.find(headers).is_some() || verify_auth(headers, &state.edit_key).is_ok()
+}
+
+/// Limit requests per minute: a token to its own limit, anyone else by address to
+/// `PUBLIC_RATE_LIMIT`
+pub async fn rate_limit(State(state): State<AppState>, request: Request, next: Next) -> Response {
+    let (client, limit) = match state.read_tokens.find(request.headers()) {
+        Some(token) => (format!("token:{}", token.id), token.rate_limit_per_minute),
+        None => {
+            let address = state.read_tokens.client_address(&request);
+            (format!("address:{}", address), state.read_tokens.anonymous_limit)
+        }
+    };
+    if let Some(limit) = limit {
+        if !state.read_tokens.allow(&client, limit) {
+            let retry_after = 60 - chrono::Utc::now().timestamp() % 60;
+            let mut response = error_response(
+                StatusCode::TOO_MANY_REQUESTS,
+                format!("Rate limit of {} requests per minute exceeded", limit),
+            )
+            .into_response();
+            response.headers_mut().insert("Retry-After", retry_after.into());
+            return response;
+        }
+    }
+    next.run(request).await
+}
+
+fn storage_error(e: impl std::fmt::Display) -> (StatusCode, Json<ErrorResponse>) {
+    error_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to access read tokens: {}", e))
+}
+
+#[utoipa::path(
+    get,
+    path = "/admin/tokens",
+    tag = "Admin",
+    security(("edit_key" = [])),
+    responses(
+        (status = 200, description = "Issued read tokens, oldest first", body = [ReadToken]),
+        (status = 401, description = "Unauthorized", body = ErrorResponse)
+    )
+)]
+pub async fn list_tokens(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+) -> Result<Json<Vec<ReadToken>>, (StatusCode, Json<ErrorResponse>)> {
+    require_admin(&headers, &state)?;
+
+    let mut tokens = state.storage.list_read_tokens().await.map_err(storage_error)?;
+    tokens.sort_by(|a, b| a.created_at.cmp(&b.created_at));
+    Ok(Json(tokens))
+}
+
+#[utoipa::path(
+    post,
+    path = "/admin/tokens",
+    tag = "Admin",
+    security(("edit_key" = [])),
+    request_body = CreateReadTokenRequest,
+    responses(
+        (status = 201, description = "Token issued; share `token` with the partner", body = ReadToken),
+        (status = 400, description = "Missing name", body = ErrorResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse)
+    )
+)]
+pub async fn create_token(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Json(request): Json<CreateReadTokenRequest>,
+) -> Result<(StatusCode, Json<ReadToken>), (StatusCode, Json<ErrorResponse>)> {
+    require_admin(&headers, &state)?;
+
+    let name = request.name.trim();
+    if name.is_empty() {
+        return Err(error_response(StatusCode::BAD_REQUEST, "Name is required"));
+    }
+    let token = ReadToken {
+        id: Uuid::new_v4().to_string(),
+        name: name.to_string(),
+        token: Uuid::new_v4().to_string(),
+        rate_limit_per_minute: request.rate_limit_per_minute,
+        created_at: chrono::Utc::now().to_rfc3339(),
+    };
+    state.storage.save_read_token(&token).await.map_err(storage_error)?;
+    state.read_tokens.insert(token.clone());
+
+    tracing::info!("Issued read token {} to {}", token.id, token.name);
+    Ok((StatusCode::CREATED, Json(token)))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/admin/tokens/{id}",
+    tag = "Admin",
+    security(("edit_key" = [])),
+    params(("id" = String, Path, description = "Token ID (not the token itself)")),
+    responses(
+        (status = 204, description = "Token revoked"),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 404, description = "Token not found", body = ErrorResponse)
+    )
+)]
+pub async fn revoke_token(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path(id): Path<String>,
+) -> Result<StatusCode, (StatusCode, Json<ErrorResponse>)> {
+    require_admin(&headers, &state)?;
+
+    let tokens = state.storage.list_read_tokens().await.map_err(storage_error)?;
+    if !tokens.iter().any(|t| t.id == id) {
+        return Err(error_response(StatusCode::NOT_FOUND, "Token not found"));
+    }
+    state.storage.delete_read_token(&id).await.map_err(storage_error)?;
+    state.read_tokens.remove(&id);
+
+    tracing::info!("Revoked read token {}", id);
+    Ok(StatusCode::NO_CONTENT)
+}
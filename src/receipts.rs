@@ -0,0 +1,376 @@
+use askama::Template;
+use axum::{
+    extract::{Path, Query, State},
+    http::{HeaderMap, StatusCode},
+    response::Json,
+};
+use chrono::Datelike;
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+
+use crate::email::OutgoingEmail;
+use crate::ledger::{Donation, DonationSource};
+use crate::render::Page;
+use crate::scheduler::Job;
+use crate::{error_response, require_admin, AppState, ErrorResponse, ThermometerConfig};
+
+/// Thank-you/receipt email settings
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[serde(default)]
+pub struct ReceiptSettings {
+    enabled: bool,
+    /// Employer Identification Number printed on receipts
+    ein: String,
+    /// Tax-deductibility statement included in every receipt
+    tax_statement: String,
+    /// Ledger sources that get a receipt; leave out sources whose processor already sends one
+    sources: Vec<DonationSource>,
+    /// Email every donor a summary of the previous year's gifts in early January
+    annual_summaries: bool,
+    /// Whether gifts are tax-deductible when the donation doesn't say
+    deductible_by_default: bool,
+    /// UK charities: show Gift Aid declarations on annual summaries
+    gift_aid: bool,
+}
+
+impl Default for ReceiptSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            ein: String::new(),
+            tax_statement: "No goods or services were provided in exchange for this contribution. \
+                Your gift is tax-deductible to the extent allowed by law."
+                .to_string(),
+            sources: vec![DonationSource::Stripe, DonationSource::Cash, DonationSource::Manual],
+            annual_summaries: false,
+            deductible_by_default: true,
+            gift_aid: false,
+        }
+    }
+}
+
+/// Used in place of `tax_statement` on receipts for gifts that aren't tax-deductible
+const NOT_DEDUCTIBLE_STATEMENT: &str = "This gift is not tax-deductible.";
+
+impl ReceiptSettings {
+    /// Whether `donation` is tax-deductible, falling back to the campaign default
+    pub fn is_deductible(&self, donation: &Donation) -> bool {
+        donation.tax_deductible.unwrap_or(self.deductible_by_default)
+    }
+}
+
+#[derive(Template)]
+#[template(path = "receipt-email.html")]
+struct ReceiptEmailTemplate {
+    organization_name: String,
+    campaign_title: String,
+    donor_name: String,
+    amount: String,
+    team: String,
+    date: String,
+    receipt_id: String,
+    ein: String,
+    tax_statement: String,
+}
+
+/// Send a receipt for a newly recorded donation in the background, if enabled for its source
+pub fn send_receipt(state: &AppState, config: &ThermometerConfig, donation: &Donation) {
+    let settings = &config.receipts;
+    if !settings.enabled || !settings.sources.contains(&donation.source) {
+        return;
+    }
+
+    let Some(to) = donation.donor_email.clone() else {
+        return;
+    };
+
+    let Some(mailer) = state.mailer.clone() else {
+        tracing::warn!("Receipts are enabled but email is not configured (SMTP_HOST not set)");
+        return;
+    };
+
+    let date = chrono::DateTime::parse_from_rfc3339(&donation.created_at)
+        .map(|d| d.format("%B %-d, %Y").to_string())
+        .unwrap_or_else(|_| donation.created_at.clone());
+
+    let template = ReceiptEmailTemplate {
+        organization_name: config.organization_name.clone(),
+        campaign_title: config.title.clone(),
+        donor_name: donation
+            .donor_name
+            .clone()
+            .unwrap_or_else(|| "Friend".to_string()),
+        amount: format!("{:.2}", donation.amount),
+        team: donation.team.clone(),
+        date,
+        receipt_id: donation.id.clone(),
+        ein: settings.ein.clone(),
+        tax_statement: if settings.is_deductible(donation) {
+            settings.tax_statement.clone()
+        } else {
+            NOT_DEDUCTIBLE_STATEMENT.to_string()
+        },
+    };
+
+    let html_body = match template.render() {
+        Ok(html) => html,
+        Err(e) => {
+            tracing::error!("Failed to render receipt template: {}", e);
+            return;
+        }
+    };
+
+    let mut text_body = format!(
+        "Dear {},\n\nThank you for your gift of ${} to {} ({}) on {}.\n\nReceipt ID: {}\n",
+        template.donor_name,
+        template.amount,
+        template.organization_name,
+        template.campaign_title,
+        template.date,
+        template.receipt_id
+    );
+    if !template.ein.is_empty() {
+        text_body.push_str(&format!("EIN: {}\n", template.ein));
+    }
+    text_body.push_str(&format!("\n{}\n", template.tax_statement));
+
+    let email = OutgoingEmail {
+        to: vec![to],
+        subject: format!("Thank you for supporting {}", config.organization_name),
+        html_body,
+        text_body,
+        inline_images: vec![],
+    };
+    let donation_id = donation.id.clone();
+
+    tokio::spawn(async move {
+        match mailer.send(email).await {
+            Ok(()) => tracing::info!("Sent receipt for donation {}", donation_id),
+            Err(e) => tracing::error!("Failed to send receipt for donation {}: {}", donation_id, e),
+        }
+    });
+}
+
+struct SummaryGift {
+    date: String,
+    amount: String,
+    receipt_id: String,
+    deductible: bool,
+    gift_aid: bool,
+}
+
+#[derive(Template)]
+#[template(path = "annual-summary.html")]
+pub struct AnnualSummaryTemplate {
+    organization_name: String,
+    year: i32,
+    donor_name: String,
+    donor_email: String,
+    gifts: Vec<SummaryGift>,
+    total: String,
+    /// Only shown when some gifts weren't deductible
+    deductible_total: Option<String>,
+    /// Show the Gift Aid column
+    gift_aid: bool,
+    ein: String,
+    tax_statement: String,
+}
+
+/// A donor's gifts in `year` (campaign timezone), found by email, or `None` if there were none.
+/// Every gift counts, whichever source it came through, so the total matches the donor's records.
+fn annual_summary(
+    config: &ThermometerConfig,
+    donations: &[Donation],
+    donor_email: &str,
+    year: i32,
+) -> Option<AnnualSummaryTemplate> {
+    let tz = config.tz();
+    let gifts: Vec<(chrono::NaiveDate, &Donation)> = donations
+        .iter()
+        .filter(|d| d.source.is_gift())
+        .filter(|d| d.donor_email.as_deref().is_some_and(|e| e.trim().eq_ignore_ascii_case(donor_email)))
+        .filter_map(|d| {
+            let date = chrono::DateTime::parse_from_rfc3339(&d.created_at).ok()?.with_timezone(&tz).date_naive();
+            (date.year() == year).then_some((date, d))
+        })
+        .collect();
+    if gifts.is_empty() {
+        return None;
+    }
+
+    // The most recent name they gave, in case it changed during the year
+    let donor_name = gifts
+        .iter()
+        .rev()
+        .find_map(|(_, d)| d.donor_name.as_deref().map(str::trim).filter(|n| !n.is_empty()))
+        .unwrap_or("Friend")
+        .to_string();
+    let settings = &config.receipts;
+    let total: f64 = gifts.iter().map(|(_, d)| d.amount).sum();
+    let deductible: f64 = gifts
+        .iter()
+        .filter(|(_, d)| settings.is_deductible(d))
+        .map(|(_, d)| d.amount)
+        .sum();
+    Some(AnnualSummaryTemplate {
+        organization_name: config.organization_name.clone(),
+        year,
+        donor_name,
+        donor_email: donor_email.to_string(),
+        gifts: gifts
+            .iter()
+            .map(|(date, d)| SummaryGift {
+                date: date.format("%B %-d, %Y").to_string(),
+                amount: format!("{:.2}", d.amount),
+                receipt_id: d.id.clone(),
+                deductible: settings.is_deductible(d),
+                gift_aid: d.gift_aid.is_some(),
+            })
+            .collect(),
+        total: format!("{:.2}", total),
+        deductible_total: (deductible != total).then(|| format!("{:.2}", deductible)),
+        gift_aid: settings.gift_aid,
+        ein: config.receipts.ein.clone(),
+        tax_statement: config.receipts.tax_statement.clone(),
+    })
+}
+
+impl AnnualSummaryTemplate {
+    fn email(&self) -> Result<OutgoingEmail, askama::Error> {
+        let mut text_body = format!(
+            "Dear {},\n\nThank you for your support in {}. Your gifts to {} totaled ${}:\n\n",
+            self.donor_name, self.year, self.organization_name, self.total
+        );
+        for gift in &self.gifts {
+            text_body.push_str(&format!("{}  ${}  (receipt {})", gift.date, gift.amount, gift.receipt_id));
+            if !gift.deductible {
+                text_body.push_str("  not tax-deductible");
+            }
+            if self.gift_aid && gift.gift_aid {
+                text_body.push_str("  Gift Aid");
+            }
+            text_body.push('\n');
+        }
+        if let Some(deductible_total) = &self.deductible_total {
+            text_body.push_str(&format!("\nTax-deductible total: ${}\n", deductible_total));
+        }
+        if !self.ein.is_empty() {
+            text_body.push_str(&format!("\nEIN: {}\n", self.ein));
+        }
+        text_body.push_str(&format!("\n{}\n", self.tax_statement));
+
+        Ok(OutgoingEmail {
+            to: vec![self.donor_email.clone()],
+            subject: format!("Your {} giving summary from {}", self.year, self.organization_name),
+            html_body: self.render()?,
+            text_body,
+            inline_images: vec![],
+        })
+    }
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct AnnualSummaryQuery {
+    /// Calendar year in the campaign timezone; the current year when omitted
+    year: Option<i32>,
+}
+
+#[utoipa::path(
+    get,
+    path = "/admin/donors/{id}/annual-summary",
+    tag = "Admin",
+    security(("edit_key" = [])),
+    params(
+        ("id" = String, Path, description = "Donor email address"),
+        AnnualSummaryQuery
+    ),
+    responses(
+        (status = 200, description = "Printable summary of the donor's gifts that year, with the organization's tax details", content_type = "text/html", body = String),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 404, description = "No gifts from that donor that year", body = ErrorResponse),
+        (status = 500, description = "Failed to load the ledger", body = ErrorResponse)
+    )
+)]
+pub async fn get_annual_summary(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path(id): Path<String>,
+    Query(query): Query<AnnualSummaryQuery>,
+) -> Result<Page<AnnualSummaryTemplate>, (StatusCode, Json<ErrorResponse>)> {
+    require_admin(&headers, &state)?;
+
+    let config = state.storage.load_config().await.map_err(|e| {
+        error_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to load config: {}", e))
+    })?;
+    let donations = state.storage.list_donations().await.map_err(|e| {
+        error_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to load ledger: {}", e))
+    })?;
+    let year = query
+        .year
+        .unwrap_or_else(|| chrono::Utc::now().with_timezone(&config.tz()).year());
+    annual_summary(&config, &donations, id.trim(), year)
+        .map(Page)
+        .ok_or_else(|| error_response(StatusCode::NOT_FOUND, format!("No gifts from {} in {}", id, year)))
+}
+
+/// Scheduled job that emails every donor with an address their summary for the year just ended.
+/// Runs on January 2nd so late December gifts have been recorded.
+pub fn annual_summaries_job() -> Job {
+    Job::new("annual-summaries", "0 9 2 1 *", |state| async move { send_annual_summaries(&state).await })
+}
+
+async fn send_annual_summaries(state: &AppState) -> Result<String, String> {
+    let config = state
+        .storage
+        .load_config()
+        .await
+        .map_err(|e| format!("Failed to load config for annual summaries: {}", e))?;
+    if !config.receipts.enabled || !config.receipts.annual_summaries {
+        return Ok("Annual summaries are off".to_string());
+    }
+    let mailer = state
+        .mailer
+        .as_ref()
+        .ok_or_else(|| "Annual summaries are enabled but email is not configured (SMTP_HOST not set)".to_string())?;
+    let donations = state
+        .storage
+        .list_donations()
+        .await
+        .map_err(|e| format!("Failed to load ledger for annual summaries: {}", e))?;
+
+    let year = chrono::Utc::now().with_timezone(&config.tz()).year() - 1;
+    let mut emails: Vec<String> = donations
+        .iter()
+        .filter(|d| d.source.is_gift())
+        .filter_map(|d| d.donor_email.as_deref().map(|e| e.trim().to_lowercase()))
+        .filter(|e| !e.is_empty())
+        .collect();
+    emails.sort();
+    emails.dedup();
+
+    let (mut sent, mut failed) = (0, 0);
+    for email in emails {
+        let Some(summary) = annual_summary(&config, &donations, &email, year) else {
+            continue;
+        };
+        let result = match summary.email() {
+            Ok(message) => mailer.send(message).await,
+            Err(e) => Err(format!("Failed to render annual summary: {}", e)),
+        };
+        match result {
+            Ok(()) => sent += 1,
+            Err(e) => {
+                tracing::error!("Failed to send {} annual summary to {}: {}", year, email, e);
+                failed += 1;
+            }
+        }
+    }
+
+    let message = format!("Sent {} annual summaries for {}", sent, year);
+    tracing::info!("{}", message);
+    if failed > 0 {
+        return Err(format!("{}; {} failed", message, failed));
+    }
+    Ok(message)
+}
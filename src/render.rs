@@ -0,0 +1,426 @@
+use askama::Template;
+use axum::{
+    http::StatusCode,
+    response::{AppendHeaders, Html, IntoResponse, Response},
+};
+
+use std::sync::OnceLock;
+
+use crate::color_constants;
+use crate::thermometer::{render_gif, render_png, PngOptions};
+use crate::ThermometerConfig;
+
+#[derive(Template)]
+#[template(path = "error.html")]
+struct ErrorTemplate {
+    heading: String,
+    message: String,
+}
+
+/// An HTML page template, rendered with a styled error page as the fallback
+pub struct Page<T>(pub T);
+
+impl<T: Template> IntoResponse for Page<T> {
+    fn into_response(self) -> Response {
+        match self.0.render() {
+            Ok(body) => Html(body).into_response(),
+            Err(e) => {
+                tracing::error!("Failed to render page template: {}", e);
+                PageError::new(StatusCode::INTERNAL_SERVER_ERROR, "This page couldn't be displayed.")
+                    .into_response()
+            }
+        }
+    }
+}
+
+/// An error shown to visitors as a styled error page
+pub struct PageError {
+    status: StatusCode,
+    message: String,
+}
+
+impl PageError {
+    pub fn new(status: StatusCode, message: impl Into<String>) -> Self {
+        Self {
+            status,
+            message: message.into(),
+        }
+    }
+
+    /// Log an internal failure and show a generic message, since details may be sensitive
+    pub fn internal(err: impl std::fmt::Display) -> Self {
+        tracing::error!("Failed to load page data: {}", err);
+        Self::new(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "Something went wrong loading this page. Please try again in a moment.",
+        )
+    }
+}
+
+impl IntoResponse for PageError {
+    fn into_response(self) -> Response {
+        let heading = self
+            .status
+            .canonical_reason()
+            .unwrap_or("Error")
+            .to_string();
+        let body = ErrorTemplate {
+            heading,
+            message: self.message,
+        }
+        .render()
+        .unwrap_or_else(|e| {
+            // The error page shares base.html with everything else, so keep a plain fallback
+            tracing::error!("Failed to render error page: {}", e);
+            "<!doctype html><html><body><h1>Something went wrong</h1><p><a href=\"/\">Back to Home</a></p></body></html>"
+                .to_string()
+        });
+
+        (self.status, Html(body)).into_response()
+    }
+}
+
+/// Image format for thermometer routes
+#[derive(Debug, Clone, Copy)]
+pub enum ImageFormat {
+    Svg,
+    Png,
+    /// Animated where the route supports it, otherwise a single frame
+    Gif,
+}
+
+/// Placeholder SVG shown in place of the thermometer, drawn without templates so it can't fail itself
+fn error_svg(width: u32, dark_mode: bool) -> String {
+    let height = (width as f64 * 1.2) as u32;
+    let (background, text, secondary) = if dark_mode {
+        (
+            color_constants::dark::BACKGROUND,
+            color_constants::dark::TEXT_PRIMARY,
+            color_constants::dark::TEXT_SECONDARY,
+        )
+    } else {
+        (
+            color_constants::light::BACKGROUND,
+            color_constants::light::TEXT_PRIMARY,
+            color_constants::light::TEXT_SECONDARY,
+        )
+    };
+
+    format!(
+        r#"<?xml version="1.0" encoding="UTF-8"?>
+<svg width="{width}" height="{height}" xmlns="http://www.w3.org/2000/svg">
+  <rect width="{width}" height="{height}" fill="{background}"/>
+  <text x="{cx:.2}" y="{title_y:.2}" font-family="DejaVu Sans" font-size="{title_size:.2}" font-weight="bold" fill="{text}" text-anchor="middle">Thermometer unavailable</text>
+  <text x="{cx:.2}" y="{note_y:.2}" font-family="DejaVu Sans" font-size="{note_size:.2}" fill="{secondary}" text-anchor="middle">Please check back in a few minutes</text>
+</svg>"#,
+        cx = width as f64 / 2.0,
+        title_y = height as f64 * 0.45,
+        title_size = width as f64 * 0.05,
+        note_y = height as f64 * 0.52,
+        note_size = width as f64 * 0.03,
+    )
+}
+
+/// What an image shows, written into served SVGs for screen readers and for anyone who comes
+/// across one re-shared without the page it came from
+pub struct ImageAbout {
+    /// Short summary, used as the `<title>` and `aria-label`
+    pub title: String,
+    /// Longer description for `<desc>`
+    pub description: String,
+    /// RFC 3339 time the figures are from
+    pub as_of: String,
+}
+
+impl ImageAbout {
+    pub fn new(title: impl Into<String>, description: impl Into<String>, as_of: impl Into<String>) -> Self {
+        Self {
+            title: title.into(),
+            description: description.into(),
+            as_of: as_of.into(),
+        }
+    }
+
+    /// An image of the campaign's progress, e.g. `Donation thermometer: Winter Drive` with the
+    /// total and goal as the description
+    pub fn campaign(config: &ThermometerConfig, image: &str) -> Self {
+        Self::new(
+            format!("{}: {}", image, config.title),
+            format!(
+                "{} has raised ${:.2} of its ${:.2} goal ({:.0}%).",
+                config.organization_name,
+                config.total_raised(),
+                config.goal,
+                config.progress_percent()
+            ),
+            config.last_updated.clone(),
+        )
+    }
+}
+
+/// `IMAGE_LICENSE`, written into served SVGs: a license URL (e.g. a Creative Commons deed) or
+/// a rights statement
+fn image_license() -> Option<&'static str> {
+    static LICENSE: OnceLock<Option<String>> = OnceLock::new();
+    LICENSE
+        .get_or_init(|| std::env::var("IMAGE_LICENSE").ok().filter(|l| !l.trim().is_empty()))
+        .as_deref()
+}
+
+fn xml_escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// Value of `name="..."` in a tag
+fn attribute<'a>(tag: &'a str, name: &str) -> Option<&'a str> {
+    let start = tag.find(&format!(" {}=\"", name))? + name.len() + 3;
+    let end = tag[start..].find('"')?;
+    Some(&tag[start..start + end])
+}
+
+/// Make an SVG accessible and traceable: `role="img"`, an `aria-label`, a `viewBox` so it scales
+/// when resized, `<title>` and `<desc>`, and RDF metadata with the generator, the time of the
+/// figures and the license
+pub fn annotate_svg(svg: &str, about: &ImageAbout) -> String {
+    let Some(start) = svg.find("<svg") else {
+        return svg.to_string();
+    };
+    let Some(end) = svg[start..].find('>').map(|i| start + i) else {
+        return svg.to_string();
+    };
+    let tag = &svg[start..end];
+    if tag.ends_with('/') {
+        return svg.to_string();
+    }
+
+    let title = xml_escape(&about.title);
+    let mut open = tag.to_string();
+    if attribute(tag, "role").is_none() {
+        open.push_str(" role=\"img\"");
+    }
+    if attribute(tag, "aria-label").is_none() {
+        open.push_str(&format!(" aria-label=\"{}\"", title));
+    }
+    if attribute(tag, "viewBox").is_none() {
+        if let (Some(width), Some(height)) = (attribute(tag, "width"), attribute(tag, "height")) {
+            open.push_str(&format!(" viewBox=\"0 0 {} {}\"", width, height));
+        }
+    }
+    if attribute(tag, "xmlns:rdf").is_none() {
+        open.push_str(concat!(
+            " xmlns:rdf=\"http://www.w3.org/1999/02/22-rdf-syntax-ns#\"",
+            " xmlns:dc=\"http://purl.org/dc/elements/1.1/\"",
+            " xmlns:cc=\"http://creativecommons.org/ns#\""
+        ));
+    }
+
+    let license = match image_license() {
+        Some(url) if url.starts_with("http://") || url.starts_with("https://") => {
+            format!("<cc:license rdf:resource=\"{}\"/>", xml_escape(url))
+        }
+        Some(rights) => format!("<dc:rights>{}</dc:rights>", xml_escape(rights)),
+        None => String::new(),
+    };
+    let description = xml_escape(&about.description);
+    let metadata = format!(
+        r#"<title>{title}</title><desc>{description}</desc><metadata><rdf:RDF><cc:Work rdf:about=""><dc:format>image/svg+xml</dc:format><dc:title>{title}</dc:title><dc:description>{description}</dc:description><dc:date>{date}</dc:date><dc:creator>{generator}</dc:creator>{license}</cc:Work></rdf:RDF></metadata>"#,
+        date = xml_escape(&about.as_of),
+        generator = concat!(env!("CARGO_PKG_NAME"), " ", env!("CARGO_PKG_VERSION")),
+    );
+
+    format!("{}{}>{}{}", &svg[..start], open, metadata, &svg[end + 1..])
+}
+
+/// Whether served SVGs are minified: `SVG_MINIFY=1`/`0`, defaulting to on under Cloud Run
+fn minify_enabled() -> bool {
+    static ENABLED: OnceLock<bool> = OnceLock::new();
+    *ENABLED.get_or_init(|| match std::env::var("SVG_MINIFY") {
+        Ok(value) => value == "1" || value.eq_ignore_ascii_case("true"),
+        Err(_) => std::env::var("K_SERVICE").is_ok(),
+    })
+}
+
+/// Strip comments and layout whitespace from an SVG and round decimal attribute values to one
+/// place. Text content is only trimmed, so amounts like `$1250.50` are left as they are.
+pub fn minify_svg(svg: &str) -> String {
+    let mut out = String::with_capacity(svg.len() / 2);
+    let mut rest = svg;
+
+    while !rest.is_empty() {
+        if let Some(comment) = rest.strip_prefix("<!--") {
+            rest = comment.find("-->").map_or("", |end| &comment[end + 3..]);
+        } else if rest.starts_with('<') {
+            let end = rest.find('>').map_or(rest.len(), |i| i + 1);
+            let tag = &rest[..end];
+            // The XML declaration's `version="1.0"` must stay as written
+            if tag.starts_with("<?") {
+                out.push_str(tag);
+            } else {
+                out.push_str(&minify_tag(tag));
+            }
+            rest = &rest[end..];
+        } else {
+            let end = rest.find('<').unwrap_or(rest.len());
+            out.push_str(&rest[..end].split_whitespace().collect::<Vec<_>>().join(" "));
+            rest = &rest[end..];
+        }
+    }
+    out
+}
+
+/// Collapse whitespace inside a tag and round the numbers in its attribute values, except the
+/// `aria-label`, which is text
+fn minify_tag(tag: &str) -> String {
+    let mut out = String::with_capacity(tag.len());
+    let mut previous = "";
+    for (i, part) in tag.split('"').enumerate() {
+        if i > 0 {
+            out.push('"');
+        }
+        if i % 2 == 1 {
+            if previous.ends_with("aria-label=") {
+                out.push_str(part);
+            } else {
+                out.push_str(&round_numbers(part));
+            }
+        } else {
+            // Keep one space where there was any, since it separates attributes
+            let mut last_was_space = false;
+            for c in part.chars() {
+                let is_space = c.is_whitespace();
+                if !(is_space && last_was_space) {
+                    out.push(if is_space { ' ' } else { c });
+                }
+                last_was_space = is_space;
+            }
+        }
+        previous = part;
+    }
+    out.replace(" />", "/>").replace(" >", ">")
+}
+
+/// Round every decimal number in an attribute value to one place (`123.46` -> `123.5`, `10.00` -> `10`).
+/// Digits that are part of a word or hex color (`#1e90ff`) are left alone.
+fn round_numbers(value: &str) -> String {
+    let mut out = String::with_capacity(value.len());
+    let mut rest = value;
+    let mut previous: Option<char> = None;
+
+    while let Some(c) = rest.chars().next() {
+        let starts_number = c.is_ascii_digit() && !previous.is_some_and(|p| p.is_ascii_alphanumeric() || p == '#' || p == '.');
+        if starts_number {
+            let end = rest.find(|ch: char| !(ch.is_ascii_digit() || ch == '.')).unwrap_or(rest.len());
+            let token = &rest[..end];
+            match token.parse::<f64>() {
+                Ok(number) if token.contains('.') => {
+                    // Adding 0.0 turns -0.0 into 0.0
+                    out.push_str(&(((number * 10.0).round() / 10.0) + 0.0).to_string());
+                }
+                _ => out.push_str(token),
+            }
+            previous = token.chars().last();
+            rest = &rest[end..];
+        } else {
+            out.push(c);
+            previous = Some(c);
+            rest = &rest[c.len_utf8()..];
+        }
+    }
+    out
+}
+
+/// How long browsers and CDNs may keep an image
+#[derive(Debug, Clone, Copy)]
+pub enum ImageCache {
+    /// Always refetch, for URLs whose image follows the live totals
+    NoCache,
+    /// Keep for a year, for content-addressed URLs whose image never changes
+    Immutable,
+}
+
+/// An encoded image with the caching headers for `cache`
+fn cached_image(status: StatusCode, content_type: &'static str, cache: ImageCache, body: impl IntoResponse) -> Response {
+    let (cache_control, extra): (_, &[(&str, &str)]) = match cache {
+        ImageCache::NoCache => ("no-cache, no-store, must-revalidate", &[("Pragma", "no-cache"), ("Expires", "0")]),
+        ImageCache::Immutable => ("public, max-age=31536000, immutable", &[]),
+    };
+    (
+        status,
+        [("Content-Type", content_type), ("Cache-Control", cache_control)],
+        AppendHeaders(extra.iter().copied()),
+        body,
+    )
+        .into_response()
+}
+
+/// Serve a thermometer image in the requested format with the given caching headers. SVGs are
+/// annotated with `about` first.
+pub fn image_response(
+    status: StatusCode,
+    svg: String,
+    format: ImageFormat,
+    png: PngOptions,
+    cache: ImageCache,
+    about: &ImageAbout,
+) -> Response {
+    match format {
+        ImageFormat::Svg if minify_enabled() => {
+            cached_image(status, "image/svg+xml", cache, minify_svg(&annotate_svg(&svg, about)))
+        }
+        ImageFormat::Svg => cached_image(status, "image/svg+xml", cache, annotate_svg(&svg, about)),
+        ImageFormat::Png => match render_png(&svg, &png) {
+            Ok(png_data) => cached_image(status, "image/png", cache, png_data),
+            Err(e) => {
+                tracing::error!("Failed to render thermometer PNG: {}", e);
+                (StatusCode::INTERNAL_SERVER_ERROR, "Failed to render thermometer image").into_response()
+            }
+        },
+        ImageFormat::Gif => match render_gif(&[&svg], &png) {
+            Ok(gif_data) => cached_image(status, "image/gif", cache, gif_data),
+            Err(e) => {
+                tracing::error!("Failed to render thermometer GIF: {}", e);
+                (StatusCode::INTERNAL_SERVER_ERROR, "Failed to render thermometer image").into_response()
+            }
+        },
+    }
+}
+
+/// Serve SVG frames as an animated GIF. Every frame is rasterized, so encoding runs on the
+/// blocking pool rather than holding up other requests.
+pub async fn animated_gif_response(frames: Vec<String>, png: PngOptions, cache: ImageCache) -> Response {
+    let encoded = tokio::task::spawn_blocking(move || {
+        let frames: Vec<&str> = frames.iter().map(String::as_str).collect();
+        render_gif(&frames, &png)
+    })
+    .await
+    .map_err(|e| e.to_string())
+    .and_then(|result| result);
+    match encoded {
+        Ok(gif_data) => cached_image(StatusCode::OK, "image/gif", cache, gif_data),
+        Err(e) => {
+            tracing::error!("Failed to render animated thermometer GIF: {}", e);
+            (StatusCode::INTERNAL_SERVER_ERROR, "Failed to render thermometer image").into_response()
+        }
+    }
+}
+
+/// Log a failure on an image route and serve the placeholder image with a 500 status, so embeds
+/// show a readable notice instead of a broken image icon
+pub fn error_image(context: &str, err: impl std::fmt::Display, format: ImageFormat, dark_mode: bool, scale: f32) -> Response {
+    tracing::error!("{}: {}", context, err);
+    image_response(
+        StatusCode::INTERNAL_SERVER_ERROR,
+        error_svg(800, dark_mode),
+        format,
+        PngOptions::at_scale(scale),
+        ImageCache::NoCache,
+        &ImageAbout::new(
+            "Thermometer unavailable",
+            "The image couldn't be drawn. Please check back in a few minutes.",
+            chrono::Utc::now().to_rfc3339(),
+        ),
+    )
+}
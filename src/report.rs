@@ -0,0 +1,278 @@
+use askama::Template;
+use axum::{
+    extract::State,
+    http::{HeaderMap, StatusCode},
+    response::Json,
+};
+use std::collections::HashMap;
+
+use crate::ledger::Donation;
+use crate::public_stats::{self, HistoryBucket};
+use crate::render::Page;
+use crate::sources::{self, SourceBreakdown};
+use crate::storage::StorageError;
+use crate::thermometer::{generate_thermometer_svg, ThermometerStyle};
+use crate::{error_response, groups, heatmap, patterns, require_admin, summary, AppState, ErrorResponse};
+
+/// Donors listed in the report, by amount given
+const TOP_DONOR_LIMIT: usize = 10;
+const CURVE_WIDTH: f64 = 640.0;
+const CURVE_HEIGHT: f64 = 200.0;
+
+struct TeamRow {
+    rank: usize,
+    name: String,
+    total_raised: String,
+    percent_of_total: String,
+}
+
+struct DonorRow {
+    name: String,
+    gifts: usize,
+    total: String,
+    /// Asked not to be named publicly
+    anonymous: bool,
+}
+
+struct SourceRow {
+    label: &'static str,
+    amount: String,
+    donations: usize,
+}
+
+/// Cumulative total per day as an SVG polyline
+struct DailyCurve {
+    points: String,
+    goal_y: Option<String>,
+    first_day: String,
+    last_day: String,
+    max_label: String,
+}
+
+#[derive(Template)]
+#[template(path = "report.html")]
+pub struct ReportTemplate {
+    organization_name: String,
+    title: String,
+    generated: String,
+    total_raised: String,
+    goal: String,
+    progress_percent: String,
+    gifts: usize,
+    donors: usize,
+    average_gift: String,
+    /// e.g. `Dec 2, 2025 – Dec 31, 2025`, from the first to the last ledger entry
+    period: Option<String>,
+    /// e.g. `Dec 31, 2025 ($4,210.00)`
+    best_day: Option<String>,
+    teams: Vec<TeamRow>,
+    top_donors: Vec<DonorRow>,
+    sources: Vec<SourceRow>,
+    unattributed: String,
+    curve: Option<DailyCurve>,
+    // Rendered charts, as inline SVG
+    thermometer_svg: String,
+    heatmap_svg: Option<String>,
+    groups_svg: Option<String>,
+    patterns_svg: Option<String>,
+}
+
+/// An SVG document without its XML declaration, so it can sit inline in HTML
+fn inline_svg(svg: String) -> String {
+    match svg.find("<svg") {
+        Some(start) => svg[start..].to_string(),
+        None => svg,
+    }
+}
+
+fn daily_curve(history: &[HistoryBucket], goal: f64) -> Option<DailyCurve> {
+    let (first, last) = (history.first()?, history.last()?);
+    let max = history.iter().map(|b| b.cumulative).fold(goal, f64::max);
+    if max <= 0.0 {
+        return None;
+    }
+    let step = if history.len() > 1 { CURVE_WIDTH / (history.len() - 1) as f64 } else { 0.0 };
+    let y = |amount: f64| CURVE_HEIGHT - (amount.max(0.0) / max) * CURVE_HEIGHT;
+    let points = history
+        .iter()
+        .enumerate()
+        .map(|(i, b)| format!("{:.1},{:.1}", i as f64 * step, y(b.cumulative)))
+        .collect::<Vec<_>>()
+        .join(" ");
+    Some(DailyCurve {
+        points,
+        goal_y: (goal > 0.0).then(|| format!("{:.1}", y(goal))),
+        first_day: first.date.format("%b %-d").to_string(),
+        last_day: last.date.format("%b %-d").to_string(),
+        max_label: format!("${:.0}", max),
+    })
+}
+
+/// Gifts summed per donor (by email, else name), largest first
+fn top_donors(donations: &[Donation], settings: &crate::donors::DonorDisplaySettings) -> Vec<DonorRow> {
+    let mut totals: HashMap<String, (String, usize, f64, bool)> = HashMap::new();
+    for donation in donations.iter().filter(|d| d.source.is_gift()) {
+        let name = donation
+            .donor_name
+            .as_deref()
+            .map(str::trim)
+            .filter(|n| !n.is_empty());
+        let key = match (donation.donor_email.as_deref(), name) {
+            (Some(email), _) if !email.trim().is_empty() => email.trim().to_lowercase(),
+            (_, Some(name)) => name.to_lowercase(),
+            _ => continue,
+        };
+        let entry = totals
+            .entry(key)
+            .or_insert_with(|| (name.unwrap_or("Unnamed donor").to_string(), 0, 0.0, false));
+        entry.1 += 1;
+        entry.2 += donation.amount;
+        entry.3 |= donation.anonymous.unwrap_or(settings.anonymous_by_default);
+    }
+
+    let mut donors: Vec<_> = totals.into_values().collect();
+    donors.sort_by(|a, b| b.2.total_cmp(&a.2).then_with(|| a.0.cmp(&b.0)));
+    donors
+        .into_iter()
+        .take(TOP_DONOR_LIMIT)
+        .map(|(name, gifts, total, anonymous)| DonorRow {
+            name,
+            gifts,
+            total: format!("{:.2}", total),
+            anonymous,
+        })
+        .collect()
+}
+
+fn source_rows(breakdown: &SourceBreakdown) -> Vec<SourceRow> {
+    breakdown
+        .sources
+        .iter()
+        .filter(|s| s.donations > 0)
+        .map(|s| SourceRow {
+            label: sources::source_label(s.source),
+            amount: format!("{:.2}", s.amount),
+            donations: s.donations,
+        })
+        .collect()
+}
+
+/// Log a chart that failed to render and leave it out of the report
+fn chart(name: &str, svg: Result<String, askama::Error>) -> Option<String> {
+    svg.map(inline_svg)
+        .map_err(|e| tracing::warn!("Failed to render {} for the campaign report: {}", name, e))
+        .ok()
+}
+
+async fn build(state: &AppState) -> Result<ReportTemplate, StorageError> {
+    let config = state.storage.load_config().await?;
+    let donations = state.storage.list_donations().await?;
+    let breakdown = sources::source_breakdown(state).await?;
+    let tz = config.tz();
+    let today = chrono::Utc::now().with_timezone(&tz).date_naive();
+
+    let summary = summary::summarize(&config);
+    let history = public_stats::history(&config, &donations, today);
+    let gifts: Vec<&Donation> = donations.iter().filter(|d| d.source.is_gift()).collect();
+    let gift_total: f64 = gifts.iter().map(|d| d.amount).sum();
+    let top_donors = top_donors(&donations, &config.donors);
+    let donor_count = {
+        let mut keys: Vec<String> = gifts
+            .iter()
+            .filter_map(|d| d.donor_email.clone().or_else(|| d.donor_name.clone()))
+            .map(|k| k.trim().to_lowercase())
+            .filter(|k| !k.is_empty())
+            .collect();
+        keys.sort();
+        keys.dedup();
+        keys.len()
+    };
+
+    let local_date = |ts: &str| {
+        chrono::DateTime::parse_from_rfc3339(ts)
+            .ok()
+            .map(|t| t.with_timezone(&tz).format("%b %-d, %Y").to_string())
+    };
+    let period = match (donations.first(), donations.last()) {
+        (Some(first), Some(last)) => match (local_date(&first.created_at), local_date(&last.created_at)) {
+            (Some(from), Some(to)) if from == to => Some(from),
+            (Some(from), Some(to)) => Some(format!("{} – {}", from, to)),
+            _ => None,
+        },
+        _ => None,
+    };
+    let best_day = history
+        .iter()
+        .filter(|b| b.raised > 0.0)
+        .max_by(|a, b| a.raised.total_cmp(&b.raised))
+        .map(|b| format!("{} (${:.2})", b.date.format("%b %-d, %Y"), b.raised));
+
+    let style = ThermometerStyle::new(config.image.default_width, false);
+    let thermometer_svg = generate_thermometer_svg(&config, &style, &breakdown.fill_segments(), &[])
+        .map(inline_svg)
+        .unwrap_or_default();
+    let heatmap_svg = (!history.is_empty())
+        .then(|| chart("heatmap", heatmap::generate_heatmap_svg(&config.title, &history, today)))
+        .flatten();
+    let group_totals = groups::group_totals(&config);
+    let groups_svg = (!group_totals.is_empty())
+        .then(|| chart("group thermometers", groups::generate_groups_svg(&config.title, &group_totals)))
+        .flatten();
+    let patterns_svg = (!gifts.is_empty())
+        .then(|| chart("giving patterns", patterns::generate_patterns_svg(&patterns::patterns(&config, &donations))))
+        .flatten();
+
+    Ok(ReportTemplate {
+        organization_name: config.organization_name.clone(),
+        title: config.title.clone(),
+        generated: summary::local_time(tz, &chrono::Utc::now().to_rfc3339()),
+        total_raised: format!("{:.2}", summary.total_raised),
+        goal: format!("{:.2}", summary.goal),
+        progress_percent: format!("{:.2}", summary.progress_percent),
+        gifts: gifts.len(),
+        donors: donor_count,
+        average_gift: format!("{:.2}", if gifts.is_empty() { 0.0 } else { gift_total / gifts.len() as f64 }),
+        period,
+        best_day,
+        teams: summary
+            .teams
+            .iter()
+            .map(|t| TeamRow {
+                rank: t.rank,
+                name: t.name.clone(),
+                total_raised: format!("{:.2}", t.total_raised),
+                percent_of_total: format!("{:.1}", t.percent_of_total),
+            })
+            .collect(),
+        top_donors,
+        sources: source_rows(&breakdown),
+        unattributed: format!("{:.2}", breakdown.unattributed),
+        curve: daily_curve(&history, config.goal),
+        thermometer_svg,
+        heatmap_svg,
+        groups_svg,
+        patterns_svg,
+    })
+}
+
+#[utoipa::path(
+    get,
+    path = "/admin/report",
+    tag = "Admin",
+    security(("edit_key" = [])),
+    responses(
+        (status = 200, description = "Printable end-of-campaign report: totals, team results, daily curve, top donors, sources and charts", content_type = "text/html", body = String),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 500, description = "Failed to load campaign data", body = ErrorResponse)
+    )
+)]
+pub async fn get_report(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+) -> Result<Page<ReportTemplate>, (StatusCode, Json<ErrorResponse>)> {
+    require_admin(&headers, &state)?;
+    let report = build(&state).await.map_err(|e| {
+        error_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to load campaign data: {}", e))
+    })?;
+    Ok(Page(report))
+}
@@ -0,0 +1,277 @@
+use axum::{
+    extract::{Query, State},
+    http::{HeaderMap, StatusCode},
+    response::{IntoResponse, Json, Response},
+};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+use crate::ledger::{self, DonationSource};
+use crate::render::{ImageCache, Page, PageError};
+use crate::storage::StorageError;
+use crate::summary::TeamsQuery;
+use crate::versions::ChangeNote;
+use crate::views::PublicConfig;
+use crate::{
+    commit_config, error_response, require_admin, AppState, ErrorResponse, HomeTemplate, ImageFile, SuccessResponse,
+    ThermometerConfig, ThermometerQuery,
+};
+
+/// The sandbox's staging copy of the config, which admin changes go to while the sandbox is on
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Staging {
+    pub config: ThermometerConfig,
+    pub started_at: String,
+    /// Live team totals when the sandbox started, so donations recorded since are carried over
+    /// when it's promoted
+    pub base_totals: BTreeMap<String, f64>,
+}
+
+/// Where an admin config change is saved
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EditTarget {
+    Live,
+    Staging,
+}
+
+impl EditTarget {
+    /// Load the config admin changes apply to: the staging copy while the sandbox is on,
+    /// otherwise the live config. Callers hold `config_lock` until `commit` returns.
+    pub async fn load(state: &AppState) -> Result<(Self, ThermometerConfig), StorageError> {
+        match state.storage.load_staging_config().await? {
+            Some(staging) => Ok((Self::Staging, staging.config)),
+            None => Ok((Self::Live, state.storage.load_config().await?)),
+        }
+    }
+
+    /// Save a change made to a config from `load`. Staging saves skip version history and change
+    /// hooks, since nothing public changed, so their `note` is dropped; the promote's note covers them.
+    pub async fn commit(
+        self,
+        state: &AppState,
+        previous: &ThermometerConfig,
+        config: &mut ThermometerConfig,
+        note: Option<&str>,
+    ) -> Result<(), StorageError> {
+        match self {
+            Self::Live => commit_config(state, previous, config, note).await,
+            Self::Staging => {
+                crate::stamp_team_changes(previous, config);
+                let mut staging = state.storage.load_staging_config().await?.ok_or(StorageError::NotFound)?;
+                staging.config = config.clone();
+                state.storage.save_staging_config(&staging).await
+            }
+        }
+    }
+
+    /// Mark a success message as applying to the sandbox
+    pub fn message(self, message: impl Into<String>) -> String {
+        match self {
+            Self::Live => message.into(),
+            Self::Staging => format!("{} (sandbox: preview at /preview, promote to go live)", message.into()),
+        }
+    }
+}
+
+fn storage_error(e: StorageError) -> (StatusCode, Json<ErrorResponse>) {
+    error_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to access sandbox: {}", e))
+}
+
+fn totals(config: &ThermometerConfig) -> BTreeMap<String, f64> {
+    config.teams.iter().map(|t| (t.name.clone(), t.total_raised)).collect()
+}
+
+/// Add what each team raised on the live config since the sandbox started to the staging copy
+fn carry_over_donations(staging: &Staging, live: &ThermometerConfig, config: &mut ThermometerConfig) {
+    for team in &live.teams {
+        let base = staging.base_totals.get(&team.name).copied().unwrap_or(0.0);
+        let delta = ((team.total_raised - base) * 100.0).round() / 100.0;
+        if delta == 0.0 {
+            continue;
+        }
+        match config.teams.iter_mut().find(|t| t.name == team.name) {
+            Some(staged) => staged.total_raised += delta,
+            None => config.teams.push(crate::Team {
+                total_raised: delta,
+                ..team.clone()
+            }),
+        }
+        tracing::info!("Carrying ${:.2} raised during the sandbox session over to {}", delta, team.name);
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/admin/sandbox",
+    tag = "Admin",
+    security(("edit_key" = [])),
+    responses(
+        (status = 201, description = "Sandbox started from a copy of the live config", body = SuccessResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 409, description = "Sandbox is already on", body = ErrorResponse)
+    )
+)]
+pub async fn start_sandbox(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+) -> Result<(StatusCode, Json<SuccessResponse>), (StatusCode, Json<ErrorResponse>)> {
+    require_admin(&headers, &state)?;
+
+    let _guard = state.config_lock.lock().await;
+    if state.storage.load_staging_config().await.map_err(storage_error)?.is_some() {
+        return Err(error_response(StatusCode::CONFLICT, "Sandbox is already on"));
+    }
+
+    let config = state.storage.load_config().await.map_err(storage_error)?;
+    let staging = Staging {
+        base_totals: totals(&config),
+        config,
+        started_at: chrono::Utc::now().to_rfc3339(),
+    };
+    state.storage.save_staging_config(&staging).await.map_err(storage_error)?;
+
+    tracing::info!("Sandbox started");
+    Ok((
+        StatusCode::CREATED,
+        Json(SuccessResponse {
+            message: "Sandbox started; admin changes now apply to the preview at /preview".to_string(),
+            config: staging.config,
+        }),
+    ))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/admin/sandbox",
+    tag = "Admin",
+    security(("edit_key" = [])),
+    responses(
+        (status = 200, description = "Staging copy discarded; the live config is unchanged", body = SuccessResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 409, description = "Sandbox is not on", body = ErrorResponse)
+    )
+)]
+pub async fn discard_sandbox(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+) -> Result<Json<SuccessResponse>, (StatusCode, Json<ErrorResponse>)> {
+    require_admin(&headers, &state)?;
+
+    let _guard = state.config_lock.lock().await;
+    if state.storage.load_staging_config().await.map_err(storage_error)?.is_none() {
+        return Err(error_response(StatusCode::CONFLICT, "Sandbox is not on"));
+    }
+    state.storage.delete_staging_config().await.map_err(storage_error)?;
+    let config = state.storage.load_config().await.map_err(storage_error)?;
+
+    tracing::info!("Sandbox discarded");
+    Ok(Json(SuccessResponse {
+        message: "Sandbox discarded".to_string(),
+        config,
+    }))
+}
+
+#[utoipa::path(
+    post,
+    path = "/admin/promote",
+    tag = "Admin",
+    security(("edit_key" = [])),
+    params(ChangeNote),
+    responses(
+        (status = 200, description = "Staging copy is now the live config and the sandbox is off", body = SuccessResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 409, description = "Sandbox is not on", body = ErrorResponse)
+    )
+)]
+pub async fn promote(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Query(change): Query<ChangeNote>,
+) -> Result<Json<SuccessResponse>, (StatusCode, Json<ErrorResponse>)> {
+    require_admin(&headers, &state)?;
+
+    let _guard = state.config_lock.lock().await;
+    let staging = state
+        .storage
+        .load_staging_config()
+        .await
+        .map_err(storage_error)?
+        .ok_or_else(|| error_response(StatusCode::CONFLICT, "Sandbox is not on"))?;
+    let previous = state.storage.load_config().await.map_err(storage_error)?;
+
+    let mut config = staging.config.clone();
+    carry_over_donations(&staging, &previous, &mut config);
+    config.last_updated = chrono::Utc::now().to_rfc3339();
+
+    commit_config(&state, &previous, &mut config, change.text()).await.map_err(|e| {
+        error_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to save config: {}", e))
+    })?;
+    state.storage.delete_staging_config().await.map_err(storage_error)?;
+    // Totals changed in the sandbox reach the ledger the same way a spreadsheet upload's do
+    ledger::record_total_changes(&state, &previous.teams, &config.teams, DonationSource::SheetSync)
+        .await
+        .map_err(|e| {
+            error_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to record sandbox changes: {}", e))
+        })?;
+
+    tracing::info!("Promoted sandbox started at {} to live", staging.started_at);
+    Ok(Json(SuccessResponse {
+        message: "Sandbox promoted to live".to_string(),
+        config,
+    }))
+}
+
+/// The staging config, or a 404 page when the sandbox is off
+async fn staging_config(state: &AppState) -> Result<ThermometerConfig, PageError> {
+    state
+        .storage
+        .load_staging_config()
+        .await
+        .map_err(PageError::internal)?
+        .map(|staging| staging.config)
+        .ok_or_else(|| PageError::new(StatusCode::NOT_FOUND, "The sandbox is not on, so there is nothing to preview."))
+}
+
+/// Home page rendered from the staging copy
+pub async fn preview_page(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Query(teams_query): Query<TeamsQuery>,
+) -> Result<Page<HomeTemplate>, PageError> {
+    let config = staging_config(&state).await?;
+    Ok(Page(crate::home_template(&state, &config, &headers, &teams_query, true).await))
+}
+
+#[utoipa::path(
+    get,
+    path = "/preview/config",
+    tag = "Public",
+    responses(
+        (status = 200, description = "The public part of the sandbox's staging config, as in `/config`", body = PublicConfig),
+        (status = 404, description = "Sandbox is not on", body = ErrorResponse)
+    )
+)]
+pub async fn preview_config(
+    State(state): State<AppState>,
+) -> Result<Json<PublicConfig>, (StatusCode, Json<ErrorResponse>)> {
+    state
+        .storage
+        .load_staging_config()
+        .await
+        .map_err(storage_error)?
+        .map(|staging| Json(PublicConfig::from(&staging.config)))
+        .ok_or_else(|| error_response(StatusCode::NOT_FOUND, "Sandbox is not on"))
+}
+
+/// Light SVG thermometer for the staging copy, shown on the preview page
+pub async fn preview_image(State(state): State<AppState>, Query(params): Query<ThermometerQuery>) -> Response {
+    let file = ImageFile::Thermometer {
+        dark_mode: false,
+        format: crate::render::ImageFormat::Svg,
+    };
+    match state.storage.load_staging_config().await {
+        Ok(Some(staging)) => crate::render_image(&state, &staging.config, file, &params, ImageCache::NoCache).await,
+        Ok(None) => (StatusCode::NOT_FOUND, "Sandbox is not on").into_response(),
+        Err(e) => file.error("Failed to load staging config", e, &params),
+    }
+}
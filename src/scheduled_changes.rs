@@ -0,0 +1,246 @@
+use axum::{
+    extract::{Path, Query, State},
+    http::{HeaderMap, StatusCode},
+    response::Json,
+};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::scheduler::Job;
+use crate::versions::ChangeNote;
+use crate::{commit_config, error_response, require_admin, AppState, ErrorResponse, ThermometerConfig};
+
+/// A config change waiting for its time, e.g. revealing a stretch goal on Friday at 9am
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct ScheduledChange {
+    pub id: String,
+    /// When the change goes live (UTC); applied within a minute of this
+    pub apply_at: String,
+    /// JSON merge patch applied to the live config: fields given replace the current ones, `null`
+    /// clears a field, and anything left out stays as it is at apply time
+    #[schema(value_type = Object)]
+    pub change: serde_json::Value,
+    /// Saved with the config version the change creates
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub note: Option<String>,
+    pub created_at: String,
+    /// Why applying the change failed; failed changes stay listed and aren't retried
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct ScheduleChangeRequest {
+    /// RFC 3339 time with an offset, e.g. `2025-12-05T09:00:00-06:00`
+    apply_at: String,
+    /// Partial config to merge in, e.g. `{"goal": 15000}`
+    #[schema(value_type = Object)]
+    change: serde_json::Value,
+}
+
+/// Apply an RFC 7386 JSON merge patch to `target`
+fn merge_patch(target: &mut serde_json::Value, patch: &serde_json::Value) {
+    let serde_json::Value::Object(fields) = patch else {
+        *target = patch.clone();
+        return;
+    };
+    if !target.is_object() {
+        *target = serde_json::Value::Object(serde_json::Map::new());
+    }
+    let Some(map) = target.as_object_mut() else {
+        return;
+    };
+    for (key, value) in fields {
+        if value.is_null() {
+            map.remove(key);
+        } else {
+            merge_patch(map.entry(key.clone()).or_insert(serde_json::Value::Null), value);
+        }
+    }
+}
+
+/// `config` with `change` merged in, or why the result isn't a valid config
+pub fn apply(config: &ThermometerConfig, change: &serde_json::Value) -> Result<ThermometerConfig, String> {
+    let mut value = serde_json::to_value(config).map_err(|e| format!("Failed to serialize config: {}", e))?;
+    merge_patch(&mut value, change);
+    serde_json::from_value(value).map_err(|e| format!("Change doesn't produce a valid config: {}", e))
+}
+
+/// Scheduled job that applies every pending change whose time has come, oldest first
+pub fn job() -> Job {
+    Job::new("scheduled-changes", "* * * * *", |state| async move { apply_due(&state).await })
+}
+
+async fn apply_due(state: &AppState) -> Result<String, String> {
+    let now = chrono::Utc::now();
+    let mut due: Vec<ScheduledChange> = state
+        .storage
+        .list_scheduled_changes()
+        .await
+        .map_err(|e| format!("Failed to load scheduled changes: {}", e))?
+        .into_iter()
+        .filter(|c| c.error.is_none())
+        .filter(|c| chrono::DateTime::parse_from_rfc3339(&c.apply_at).is_ok_and(|t| t <= now))
+        .collect();
+    due.sort_by(|a, b| a.apply_at.cmp(&b.apply_at));
+
+    let (mut applied, mut failed) = (0, 0);
+    for scheduled in due {
+        match apply_change(state, scheduled).await? {
+            Some(true) => applied += 1,
+            Some(false) => failed += 1,
+            None => {}
+        }
+    }
+
+    match failed {
+        0 => Ok(format!("Applied {} scheduled change(s)", applied)),
+        _ => Err(format!("Applied {} scheduled change(s), {} failed", applied, failed)),
+    }
+}
+
+/// Merge a change into the live config (never the sandbox's staging copy) and save it, then
+/// remove the change, or keep it with the error if it can't be applied. Returns whether it was
+/// applied, or `None` if it was cancelled in the meantime.
+async fn apply_change(state: &AppState, mut scheduled: ScheduledChange) -> Result<Option<bool>, String> {
+    let _guard = state.config_lock.lock().await;
+    let id = scheduled.id.clone();
+    let storage_error = |e: crate::storage::StorageError| format!("Scheduled change {}: {}", id, e);
+    let pending = state.storage.list_scheduled_changes().await.map_err(storage_error)?;
+    if !pending.iter().any(|c| c.id == scheduled.id) {
+        return Ok(None);
+    }
+
+    let previous = state.storage.load_config().await.map_err(storage_error)?;
+    let result = match apply(&previous, &scheduled.change) {
+        Ok(mut config) => {
+            // Integrations only change through `/admin/integrations`
+            config.integrations = previous.integrations.clone();
+            crate::retain_dropped_teams(&previous, &mut config);
+            config.last_updated = chrono::Utc::now().to_rfc3339();
+            let note = scheduled.note.clone().unwrap_or_else(|| format!("Scheduled change {}", scheduled.id));
+            commit_config(state, &previous, &mut config, Some(&note))
+                .await
+                .map_err(|e| format!("Failed to save config: {}", e))
+        }
+        Err(e) => Err(e),
+    };
+
+    match result {
+        Ok(()) => {
+            state.storage.delete_scheduled_change(&scheduled.id).await.map_err(storage_error)?;
+            tracing::info!("Applied scheduled change {}", scheduled.id);
+            Ok(Some(true))
+        }
+        Err(e) => {
+            tracing::error!("Failed to apply scheduled change {}: {}", scheduled.id, e);
+            scheduled.error = Some(e);
+            state.storage.save_scheduled_change(&scheduled).await.map_err(storage_error)?;
+            Ok(Some(false))
+        }
+    }
+}
+
+fn storage_error(e: impl std::fmt::Display) -> (StatusCode, Json<ErrorResponse>) {
+    error_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to access scheduled changes: {}", e))
+}
+
+#[utoipa::path(
+    get,
+    path = "/admin/scheduled-changes",
+    tag = "Admin",
+    security(("edit_key" = [])),
+    responses(
+        (status = 200, description = "Pending and failed scheduled changes, soonest first", body = [ScheduledChange]),
+        (status = 401, description = "Unauthorized", body = ErrorResponse)
+    )
+)]
+pub async fn list_scheduled_changes(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+) -> Result<Json<Vec<ScheduledChange>>, (StatusCode, Json<ErrorResponse>)> {
+    require_admin(&headers, &state)?;
+
+    let mut changes = state.storage.list_scheduled_changes().await.map_err(storage_error)?;
+    changes.sort_by(|a, b| a.apply_at.cmp(&b.apply_at));
+    Ok(Json(changes))
+}
+
+#[utoipa::path(
+    post,
+    path = "/admin/scheduled-changes",
+    tag = "Admin",
+    security(("edit_key" = [])),
+    params(ChangeNote),
+    request_body = ScheduleChangeRequest,
+    responses(
+        (status = 201, description = "Change scheduled", body = ScheduledChange),
+        (status = 400, description = "Invalid time, a time in the past, or a change that doesn't produce a valid config", body = ErrorResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse)
+    )
+)]
+pub async fn schedule_change(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Query(note): Query<ChangeNote>,
+    Json(request): Json<ScheduleChangeRequest>,
+) -> Result<(StatusCode, Json<ScheduledChange>), (StatusCode, Json<ErrorResponse>)> {
+    require_admin(&headers, &state)?;
+
+    let apply_at = chrono::DateTime::parse_from_rfc3339(&request.apply_at)
+        .map_err(|e| error_response(StatusCode::BAD_REQUEST, format!("Invalid apply_at: {}", e)))?
+        .with_timezone(&chrono::Utc);
+    if apply_at <= chrono::Utc::now() {
+        return Err(error_response(StatusCode::BAD_REQUEST, "apply_at must be in the future"));
+    }
+    if !request.change.is_object() {
+        return Err(error_response(StatusCode::BAD_REQUEST, "change must be a JSON object"));
+    }
+    // Catch typos now rather than at apply time; the change is merged into whatever is live then
+    let config = state.storage.load_config().await.map_err(storage_error)?;
+    apply(&config, &request.change).map_err(|e| error_response(StatusCode::BAD_REQUEST, e))?;
+
+    let scheduled = ScheduledChange {
+        id: uuid::Uuid::new_v4().to_string(),
+        apply_at: apply_at.to_rfc3339(),
+        change: request.change,
+        note: note.text().map(str::to_string),
+        created_at: chrono::Utc::now().to_rfc3339(),
+        error: None,
+    };
+    state.storage.save_scheduled_change(&scheduled).await.map_err(storage_error)?;
+
+    tracing::info!("Scheduled config change {} for {}", scheduled.id, scheduled.apply_at);
+    Ok((StatusCode::CREATED, Json(scheduled)))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/admin/scheduled-changes/{id}",
+    tag = "Admin",
+    security(("edit_key" = [])),
+    params(("id" = String, Path, description = "Scheduled change ID")),
+    responses(
+        (status = 204, description = "Change cancelled"),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 404, description = "Scheduled change not found", body = ErrorResponse)
+    )
+)]
+pub async fn cancel_scheduled_change(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path(id): Path<String>,
+) -> Result<StatusCode, (StatusCode, Json<ErrorResponse>)> {
+    require_admin(&headers, &state)?;
+
+    // Held so a cancel can't race the scheduler applying the same change
+    let _guard = state.config_lock.lock().await;
+    let changes = state.storage.list_scheduled_changes().await.map_err(storage_error)?;
+    if !changes.iter().any(|c| c.id == id) {
+        return Err(error_response(StatusCode::NOT_FOUND, "Scheduled change not found"));
+    }
+    state.storage.delete_scheduled_change(&id).await.map_err(storage_error)?;
+
+    tracing::info!("Cancelled scheduled change {}", id);
+    Ok(StatusCode::NO_CONTENT)
+}
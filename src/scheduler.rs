@@ -0,0 +1,448 @@
+use axum::{
+    extract::{Path, State},
+    http::{HeaderMap, StatusCode},
+    response::Json,
+};
+use chrono::{DateTime, Datelike, DurationRound, Timelike, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::{error_response, parse_timezone, require_admin, AppState, ErrorResponse, ThermometerConfig};
+
+pub type JobFuture = Pin<Box<dyn Future<Output = Result<String, String>> + Send>>;
+pub type JobFn = Arc<dyn Fn(AppState) -> JobFuture + Send + Sync>;
+
+/// Scheduler settings; jobs not listed in `jobs` run on their built-in schedule
+#[derive(Debug, Clone, Default, Serialize, Deserialize, ToSchema)]
+#[serde(default)]
+pub struct SchedulerSettings {
+    /// IANA timezone used to evaluate job schedules (e.g. `America/Chicago`); defaults to the
+    /// campaign timezone
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub timezone: Option<String>,
+    /// Per-job overrides keyed by job name
+    pub jobs: BTreeMap<String, JobSettings>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, ToSchema)]
+#[serde(default)]
+pub struct JobSettings {
+    pub enabled: Option<bool>,
+    /// Five-field cron expression (`minute hour day-of-month month day-of-week`) or `@hourly`/`@daily`
+    pub schedule: Option<String>,
+    /// Random delay of up to this many seconds before each run
+    pub jitter_secs: Option<u64>,
+}
+
+/// A recurring task registered with the scheduler
+pub struct Job {
+    pub name: &'static str,
+    schedule: &'static str,
+    run: JobFn,
+}
+
+impl Job {
+    pub fn new<F, Fut>(name: &'static str, schedule: &'static str, run: F) -> Self
+    where
+        F: Fn(AppState) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Result<String, String>> + Send + 'static,
+    {
+        Self {
+            name,
+            schedule,
+            run: Arc::new(move |state| Box::pin(run(state))),
+        }
+    }
+
+    fn effective(&self, settings: &SchedulerSettings) -> Effective {
+        let overrides = settings.jobs.get(self.name).cloned().unwrap_or_default();
+        Effective {
+            enabled: overrides.enabled.unwrap_or(true),
+            schedule: overrides.schedule.unwrap_or_else(|| self.schedule.to_string()),
+            jitter_secs: overrides.jitter_secs.unwrap_or(0),
+        }
+    }
+}
+
+/// A job's schedule after applying config overrides
+struct Effective {
+    enabled: bool,
+    schedule: String,
+    jitter_secs: u64,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum JobTrigger {
+    Scheduled,
+    /// Started from `POST /admin/jobs/{name}/run`
+    Manual,
+}
+
+/// Outcome of a job's most recent run
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct JobRun {
+    pub trigger: JobTrigger,
+    pub started_at: String,
+    pub finished_at: Option<String>,
+    /// Unset while the run is in progress
+    pub ok: Option<bool>,
+    /// Summary returned by the job, or the error it failed with
+    pub message: Option<String>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct JobStatus {
+    pub name: String,
+    pub schedule: String,
+    pub enabled: bool,
+    pub jitter_secs: u64,
+    pub running: bool,
+    /// Next scheduled run; unset when the job is disabled or its schedule is invalid
+    pub next_run_at: Option<String>,
+    pub last_run: Option<JobRun>,
+    /// Set when the configured schedule can't be parsed; the job won't run until it's fixed
+    pub schedule_error: Option<String>,
+}
+
+pub struct Scheduler {
+    jobs: Vec<Job>,
+    runs: Mutex<HashMap<&'static str, JobRun>>,
+    stopped: AtomicBool,
+}
+
+impl Scheduler {
+    pub fn new(jobs: Vec<Job>) -> Self {
+        Self {
+            jobs,
+            runs: Mutex::new(HashMap::new()),
+            stopped: AtomicBool::new(false),
+        }
+    }
+
+    /// Stop scheduling new runs (e.g. when a tenant is removed); runs in progress finish normally
+    pub fn stop(&self) {
+        self.stopped.store(true, Ordering::Relaxed);
+    }
+
+    fn job(&self, name: &str) -> Option<&Job> {
+        self.jobs.iter().find(|job| job.name == name)
+    }
+
+    /// Run `job` in the background after its jitter delay. Returns false if it's already running.
+    fn start(&self, state: &AppState, job: &Job, jitter_secs: u64, trigger: JobTrigger) -> bool {
+        {
+            let mut runs = self.runs.lock().unwrap();
+            if runs.get(job.name).is_some_and(|run| run.finished_at.is_none()) {
+                return false;
+            }
+            runs.insert(
+                job.name,
+                JobRun {
+                    trigger,
+                    started_at: Utc::now().to_rfc3339(),
+                    finished_at: None,
+                    ok: None,
+                    message: None,
+                },
+            );
+        }
+
+        let name = job.name;
+        let run = job.run.clone();
+        let state = state.clone();
+        tokio::spawn(async move {
+            if jitter_secs > 0 {
+                // Version 4 UUIDs are random, which is all the jitter needs
+                let delay = (Uuid::new_v4().as_u128() % (jitter_secs as u128 + 1)) as u64;
+                tokio::time::sleep(Duration::from_secs(delay)).await;
+            }
+
+            let result = run(state.clone()).await;
+            match &result {
+                Ok(message) => tracing::debug!("Job {} finished: {}", name, message),
+                Err(e) => {
+                    tracing::error!("Job {} failed: {}", name, e);
+                    crate::notifications::on_job_failed(&state, name, e).await;
+                }
+            }
+
+            let mut runs = state.scheduler.runs.lock().unwrap();
+            if let Some(entry) = runs.get_mut(name) {
+                entry.finished_at = Some(Utc::now().to_rfc3339());
+                entry.ok = Some(result.is_ok());
+                entry.message = Some(result.unwrap_or_else(|e| e));
+            }
+        });
+        true
+    }
+
+    fn status(&self, job: &Job, settings: &SchedulerSettings, tz: chrono_tz::Tz) -> JobStatus {
+        let effective = job.effective(settings);
+        let last_run = self.runs.lock().unwrap().get(job.name).cloned();
+        let schedule = Schedule::parse(&effective.schedule);
+        let next_run_at = match &schedule {
+            Ok(schedule) if effective.enabled => schedule
+                .next_after(Utc::now().with_timezone(&tz))
+                .map(|t| t.to_rfc3339()),
+            _ => None,
+        };
+
+        JobStatus {
+            name: job.name.to_string(),
+            schedule_error: schedule.err(),
+            schedule: effective.schedule,
+            enabled: effective.enabled,
+            jitter_secs: effective.jitter_secs,
+            running: last_run.as_ref().is_some_and(|run| run.finished_at.is_none()),
+            next_run_at,
+            last_run,
+        }
+    }
+}
+
+/// Parsed cron expression. Each field is a bitmask of the values it matches.
+struct Schedule {
+    minutes: u64,
+    hours: u64,
+    days: u64,
+    months: u64,
+    weekdays: u64,
+    /// Whether day-of-month / day-of-week were restricted (cron ORs them when both are)
+    days_restricted: bool,
+    weekdays_restricted: bool,
+}
+
+impl Schedule {
+    fn parse(expr: &str) -> Result<Self, String> {
+        let expr = match expr.trim() {
+            "@hourly" => "0 * * * *",
+            "@daily" | "@midnight" => "0 0 * * *",
+            "@weekly" => "0 0 * * 0",
+            "@monthly" => "0 0 1 * *",
+            other => other,
+        };
+        let fields: Vec<&str> = expr.split_whitespace().collect();
+        let [minute, hour, day, month, weekday] = fields[..] else {
+            return Err(format!("Expected 5 cron fields, got {}: {:?}", fields.len(), expr));
+        };
+
+        let mut weekdays = parse_field(weekday, 0, 7)?;
+        // Both 0 and 7 mean Sunday
+        if weekdays & (1 << 7) != 0 {
+            weekdays |= 1;
+        }
+
+        Ok(Self {
+            minutes: parse_field(minute, 0, 59)?,
+            hours: parse_field(hour, 0, 23)?,
+            days: parse_field(day, 1, 31)?,
+            months: parse_field(month, 1, 12)?,
+            weekdays,
+            days_restricted: !day.starts_with('*'),
+            weekdays_restricted: !weekday.starts_with('*'),
+        })
+    }
+
+    fn matches<Tz: chrono::TimeZone>(&self, time: &DateTime<Tz>) -> bool {
+        let bit = |mask: u64, value: u32| mask & (1 << value) != 0;
+        let day = bit(self.days, time.day());
+        let weekday = bit(self.weekdays, time.weekday().num_days_from_sunday());
+        let day_matches = match (self.days_restricted, self.weekdays_restricted) {
+            (true, true) => day || weekday,
+            _ => day && weekday,
+        };
+
+        bit(self.minutes, time.minute())
+            && bit(self.hours, time.hour())
+            && bit(self.months, time.month())
+            && day_matches
+    }
+
+    /// First matching minute after `time`, searching up to a year ahead
+    fn next_after<Tz: chrono::TimeZone>(&self, time: DateTime<Tz>) -> Option<DateTime<Tz>> {
+        let start = time.duration_trunc(chrono::Duration::minutes(1)).ok()?;
+        (1..=366 * 24 * 60)
+            .map(|minutes| start.clone() + chrono::Duration::minutes(minutes))
+            .find(|candidate| self.matches(candidate))
+    }
+}
+
+/// The timezone job schedules are evaluated in: the scheduler's own if set, otherwise the
+/// campaign timezone
+pub fn timezone(config: &ThermometerConfig) -> chrono_tz::Tz {
+    config
+        .scheduler
+        .timezone
+        .as_deref()
+        .and_then(parse_timezone)
+        .unwrap_or_else(|| config.tz())
+}
+
+/// Current scheduler settings and timezone, falling back to built-in schedules in UTC when
+/// storage is down
+async fn load_settings(state: &AppState) -> (SchedulerSettings, chrono_tz::Tz) {
+    match state.storage.load_config().await {
+        Ok(config) => (config.scheduler.clone(), timezone(&config)),
+        Err(e) => {
+            tracing::error!("Failed to load config for job status: {}", e);
+            (SchedulerSettings::default(), chrono_tz::UTC)
+        }
+    }
+}
+
+/// Parse one cron field (`*`, `5`, `1-5`, `*/15`, `0-30/10`, or a comma-separated list of these)
+fn parse_field(field: &str, min: u32, max: u32) -> Result<u64, String> {
+    let mut mask = 0u64;
+    for part in field.split(',') {
+        let (range, step) = match part.split_once('/') {
+            Some((range, step)) => (
+                range,
+                step.parse::<u32>()
+                    .ok()
+                    .filter(|s| *s > 0)
+                    .ok_or_else(|| format!("Invalid step in cron field {:?}", field))?,
+            ),
+            None => (part, 1),
+        };
+
+        let value = |s: &str| {
+            s.parse::<u32>()
+                .ok()
+                .filter(|v| (min..=max).contains(v))
+                .ok_or_else(|| format!("Cron value {:?} out of range {}-{}", s, min, max))
+        };
+        let (start, end) = match range {
+            "*" => (min, max),
+            _ => match range.split_once('-') {
+                Some((start, end)) => (value(start)?, value(end)?),
+                // `5/10` means every 10 starting at 5
+                None if part.contains('/') => (value(range)?, max),
+                None => {
+                    let v = value(range)?;
+                    (v, v)
+                }
+            },
+        };
+        if start > end {
+            return Err(format!("Invalid cron range {:?}", range));
+        }
+
+        for v in (start..=end).step_by(step as usize) {
+            mask |= 1 << v;
+        }
+    }
+    Ok(mask)
+}
+
+/// Spawn the scheduler loop, which checks every job's schedule at the top of each minute
+pub fn spawn_scheduler(state: AppState) {
+    tokio::spawn(async move {
+        let mut last_tick: Option<DateTime<Utc>> = None;
+        while !state.scheduler.stopped.load(Ordering::Relaxed) {
+            let now = Utc::now();
+            let minute = now.duration_trunc(chrono::Duration::minutes(1)).unwrap_or(now);
+            let next = minute + chrono::Duration::minutes(1);
+            if last_tick == Some(minute) {
+                tokio::time::sleep((next - now).to_std().unwrap_or(Duration::from_secs(1))).await;
+                continue;
+            }
+            last_tick = Some(minute);
+
+            let config = match state.storage.load_config().await {
+                Ok(config) => config,
+                Err(e) => {
+                    tracing::error!("Failed to load config for scheduler: {}", e);
+                    continue;
+                }
+            };
+            let settings = &config.scheduler;
+            let local = minute.with_timezone(&timezone(&config));
+
+            for job in &state.scheduler.jobs {
+                let effective = job.effective(settings);
+                if !effective.enabled {
+                    continue;
+                }
+                match Schedule::parse(&effective.schedule) {
+                    Ok(schedule) if schedule.matches(&local) => {
+                        if !state.scheduler.start(&state, job, effective.jitter_secs, JobTrigger::Scheduled) {
+                            tracing::warn!("Skipping scheduled run of {}: previous run still in progress", job.name);
+                        }
+                    }
+                    Ok(_) => {}
+                    Err(e) => tracing::warn!("Invalid schedule for job {}: {}", job.name, e),
+                }
+            }
+        }
+    });
+}
+
+#[utoipa::path(
+    get,
+    path = "/admin/jobs",
+    tag = "Admin",
+    security(("edit_key" = [])),
+    responses(
+        (status = 200, description = "Scheduled jobs with their next and last run", body = [JobStatus]),
+        (status = 401, description = "Unauthorized", body = ErrorResponse)
+    )
+)]
+pub async fn list_jobs(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+) -> Result<Json<Vec<JobStatus>>, (StatusCode, Json<ErrorResponse>)> {
+    require_admin(&headers, &state)?;
+
+    let (settings, tz) = load_settings(&state).await;
+    Ok(Json(
+        state
+            .scheduler
+            .jobs
+            .iter()
+            .map(|job| state.scheduler.status(job, &settings, tz))
+            .collect(),
+    ))
+}
+
+#[utoipa::path(
+    post,
+    path = "/admin/jobs/{name}/run",
+    tag = "Admin",
+    security(("edit_key" = [])),
+    params(("name" = String, Path, description = "Job name, e.g. `daily-digest`")),
+    responses(
+        (status = 202, description = "Job started; poll `GET /admin/jobs` for the result", body = JobStatus),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 404, description = "Unknown job", body = ErrorResponse),
+        (status = 409, description = "Job is already running", body = ErrorResponse)
+    )
+)]
+pub async fn run_job(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path(name): Path<String>,
+) -> Result<(StatusCode, Json<JobStatus>), (StatusCode, Json<ErrorResponse>)> {
+    require_admin(&headers, &state)?;
+
+    let job = state
+        .scheduler
+        .job(&name)
+        .ok_or_else(|| error_response(StatusCode::NOT_FOUND, format!("Unknown job: {}", name)))?;
+
+    // Manual runs skip jitter and ignore the enabled flag, so a paused job can still be kicked by hand
+    if !state.scheduler.start(&state, job, 0, JobTrigger::Manual) {
+        return Err(error_response(StatusCode::CONFLICT, format!("Job {} is already running", name)));
+    }
+    tracing::info!("Job {} started manually", name);
+
+    let (settings, tz) = load_settings(&state).await;
+    Ok((StatusCode::ACCEPTED, Json(state.scheduler.status(job, &settings, tz))))
+}
@@ -0,0 +1,66 @@
+use axum::response::Json;
+use serde_json::{json, Map, Value};
+use utoipa::OpenApi;
+
+use crate::ApiDoc;
+
+/// JSON Schema (draft-07) for `ThermometerConfig`, with every API type under `definitions`.
+/// Derived from the OpenAPI components so the two can't drift apart.
+pub fn json_schema() -> Value {
+    let openapi = ApiDoc::openapi();
+    let schemas = openapi
+        .components
+        .map(|c| serde_json::to_value(c.schemas).unwrap_or_default())
+        .unwrap_or_default();
+
+    json!({
+        "$schema": "http://json-schema.org/draft-07/schema#",
+        "title": "ThermometerConfig",
+        "$ref": "#/definitions/ThermometerConfig",
+        "definitions": to_json_schema(schemas),
+    })
+}
+
+/// Rewrite OpenAPI 3.0 schema objects into plain JSON Schema: component refs point at
+/// `definitions` and `nullable` becomes a `null` type alternative
+fn to_json_schema(value: Value) -> Value {
+    match value {
+        Value::Object(map) => {
+            let mut map: Map<String, Value> = map
+                .into_iter()
+                .map(|(key, value)| match (key.as_str(), value) {
+                    ("$ref", Value::String(reference)) => (
+                        key,
+                        Value::String(reference.replace("#/components/schemas/", "#/definitions/")),
+                    ),
+                    (_, value) => (key, to_json_schema(value)),
+                })
+                .collect();
+
+            if map.remove("nullable") != Some(Value::Bool(true)) {
+                return Value::Object(map);
+            }
+            match map.get("type").cloned() {
+                Some(Value::String(kind)) => {
+                    map.insert("type".to_string(), json!([kind, "null"]));
+                    Value::Object(map)
+                }
+                _ => json!({ "anyOf": [Value::Object(map), { "type": "null" }] }),
+            }
+        }
+        Value::Array(items) => Value::Array(items.into_iter().map(to_json_schema).collect()),
+        other => other,
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/api-docs/schema.json",
+    tag = "Public",
+    responses(
+        (status = 200, description = "JSON Schema for ThermometerConfig and related API types", body = Object)
+    )
+)]
+pub async fn get_json_schema() -> Json<Value> {
+    Json(json_schema())
+}
@@ -0,0 +1,133 @@
+use axum::{http::StatusCode, response::Json};
+use resvg::usvg;
+use serde::Serialize;
+use utoipa::ToSchema;
+
+use crate::thermometer::{generate_thermometer_svg, svg_to_png, ThermometerStyle};
+use crate::ThermometerConfig;
+
+/// Font family the thermometer templates ask for
+const TEMPLATE_FONT: &str = "DejaVu Sans";
+
+const TEXT_PROBE_SVG: &str = r#"<svg width="120" height="40" xmlns="http://www.w3.org/2000/svg">
+  <text x="10" y="30" font-family="DejaVu Sans" font-size="24" fill="black">$100</text>
+</svg>"#;
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct SelfTestCheck {
+    name: &'static str,
+    ok: bool,
+    detail: String,
+}
+
+/// Result of rendering test images through the same pipeline as the thermometer routes
+#[derive(Debug, Serialize, ToSchema)]
+pub struct SelfTestReport {
+    pub ok: bool,
+    checks: Vec<SelfTestCheck>,
+}
+
+fn check(name: &'static str, result: Result<String, String>) -> SelfTestCheck {
+    match result {
+        Ok(detail) => SelfTestCheck { name, ok: true, detail },
+        Err(detail) => SelfTestCheck { name, ok: false, detail },
+    }
+}
+
+fn check_fonts() -> Result<String, String> {
+    let mut fontdb = usvg::fontdb::Database::new();
+    fontdb.load_system_fonts();
+
+    if fontdb.is_empty() {
+        return Err("No system fonts found; all text in rendered images would be blank".to_string());
+    }
+
+    let has_template_font = fontdb
+        .faces()
+        .any(|face| face.families.iter().any(|(family, _)| family == TEMPLATE_FONT));
+    if has_template_font {
+        Ok(format!("{} font faces loaded, including {}", fontdb.len(), TEMPLATE_FONT))
+    } else {
+        Ok(format!("{} font faces loaded; {} missing, a fallback font will be used", fontdb.len(), TEMPLATE_FONT))
+    }
+}
+
+fn check_thermometer() -> Result<String, String> {
+    let svg = generate_thermometer_svg(&ThermometerConfig::default(), &ThermometerStyle::new(200, false), &[], &[])
+        .map_err(|e| format!("Template failed to render: {}", e))?;
+    let png = svg_to_png(&svg, 1.0)?;
+    Ok(format!("Rendered {} byte test thermometer", png.len()))
+}
+
+/// Text is silently dropped when no usable font is found, so look for drawn pixels
+fn check_text() -> Result<String, String> {
+    let png = svg_to_png(TEXT_PROBE_SVG, 1.0)?;
+    let pixmap = tiny_skia::Pixmap::decode_png(&png).map_err(|e| format!("Failed to decode test PNG: {}", e))?;
+
+    let drawn = pixmap.pixels().iter().filter(|p| p.alpha() > 0).count();
+    if drawn > 0 {
+        Ok(format!("Test text drew {} pixels", drawn))
+    } else {
+        Err("Test text rendered as blank; fonts are missing or unusable".to_string())
+    }
+}
+
+/// Render test images to verify fonts, templates and resvg work in this environment
+pub fn run() -> SelfTestReport {
+    let checks = vec![
+        check("fonts", check_fonts()),
+        check("thermometer", check_thermometer()),
+        check("text", check_text()),
+    ];
+
+    SelfTestReport {
+        ok: checks.iter().all(|c| c.ok),
+        checks,
+    }
+}
+
+/// Run the self-test off the async runtime and log any failures
+pub async fn run_and_log() -> SelfTestReport {
+    let report = tokio::task::spawn_blocking(run).await.unwrap_or_else(|e| SelfTestReport {
+        ok: false,
+        checks: vec![SelfTestCheck {
+            name: "render",
+            ok: false,
+            detail: format!("Self-test panicked: {}", e),
+        }],
+    });
+
+    for check in &report.checks {
+        if check.ok {
+            tracing::debug!("Render self-test {}: {}", check.name, check.detail);
+        } else {
+            tracing::error!("Render self-test {} FAILED: {}", check.name, check.detail);
+        }
+    }
+    report
+}
+
+#[utoipa::path(
+    get,
+    path = "/health/ready",
+    tag = "Public",
+    responses(
+        (status = 200, description = "Render pipeline works", body = SelfTestReport),
+        (status = 503, description = "Render pipeline is broken (e.g. no fonts installed), or the server is shutting down", body = SelfTestReport)
+    )
+)]
+pub async fn readiness() -> (StatusCode, Json<SelfTestReport>) {
+    // Fail fast while draining so the load balancer stops sending traffic here
+    if crate::shutdown::is_draining() {
+        let check = check("shutdown", Err("Server is shutting down".to_string()));
+        return (StatusCode::SERVICE_UNAVAILABLE, Json(SelfTestReport { ok: false, checks: vec![check] }));
+    }
+
+    let report = run_and_log().await;
+    let status = if report.ok {
+        StatusCode::OK
+    } else {
+        StatusCode::SERVICE_UNAVAILABLE
+    };
+    (status, Json(report))
+}
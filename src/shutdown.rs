@@ -0,0 +1,108 @@
+//! Graceful shutdown. On SIGTERM the readiness check starts failing so the load balancer stops
+//! routing here, requests keep being served for a drain period, and once in-flight requests
+//! finish pending webhooks and storage are flushed.
+
+use std::sync::LazyLock;
+use std::time::Duration;
+use tokio::sync::watch;
+
+use crate::{analytics, webhooks, AppState};
+
+/// Default time to keep serving after a shutdown signal; Cloud Run allows 10 seconds in total
+const DEFAULT_DRAIN_SECS: u64 = 5;
+/// Upper bound on flushing webhooks so the process exits before it is killed
+const FLUSH_TIMEOUT: Duration = Duration::from_secs(3);
+
+/// Flips to true on the shutdown signal
+static DRAINING: LazyLock<watch::Sender<bool>> = LazyLock::new(|| watch::channel(false).0);
+
+/// Whether a shutdown signal has been received
+pub fn is_draining() -> bool {
+    *DRAINING.borrow()
+}
+
+/// Resolves once a shutdown signal has been received. Long-lived streams end on it, since the
+/// server waits for every open connection before flushing.
+pub async fn draining() {
+    // Only fails if the sender is dropped, which a static never is
+    let _ = DRAINING.subscribe().wait_for(|draining| *draining).await;
+}
+
+/// `SHUTDOWN_DRAIN_SECS`, defaulting to 5
+fn drain_period() -> Duration {
+    let secs = std::env::var("SHUTDOWN_DRAIN_SECS")
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(DEFAULT_DRAIN_SECS);
+    Duration::from_secs(secs)
+}
+
+async fn wait_for_signal() {
+    let ctrl_c = async {
+        tokio::signal::ctrl_c()
+            .await
+            .expect("failed to install Ctrl+C handler");
+    };
+
+    #[cfg(unix)]
+    let terminate = async {
+        tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate())
+            .expect("failed to install signal handler")
+            .recv()
+            .await;
+    };
+
+    #[cfg(not(unix))]
+    let terminate = std::future::pending::<()>();
+
+    tokio::select! {
+        _ = ctrl_c => {
+            tracing::info!("Received Ctrl+C, shutting down gracefully");
+        },
+        _ = terminate => {
+            tracing::info!("Received SIGTERM, shutting down gracefully");
+        },
+    }
+}
+
+/// Resolves once the server should stop accepting connections: after a shutdown signal and the
+/// drain period, during which `/health/ready` fails but requests are still served
+pub async fn signal() {
+    wait_for_signal().await;
+    DRAINING.send_replace(true);
+
+    let period = drain_period();
+    tracing::info!("Draining for {}s before closing connections", period.as_secs());
+    tokio::time::sleep(period).await;
+}
+
+/// Stop scheduled jobs and flush pending webhooks, embed counts and storage for the deployment,
+/// every tenant and their campaigns. Runs after the server has finished its in-flight requests.
+pub async fn flush(state: &AppState) {
+    let mut states = vec![state.clone()];
+    states.extend(state.tenants.states());
+    let campaigns: Vec<AppState> = states.iter().flat_map(|s| s.campaigns.states()).collect();
+    states.extend(campaigns);
+    for state in &states {
+        state.scheduler.stop();
+    }
+
+    let webhooks = async {
+        for state in &states {
+            webhooks::flush(state).await;
+        }
+    };
+    if tokio::time::timeout(FLUSH_TIMEOUT, webhooks).await.is_err() {
+        tracing::warn!("Gave up flushing webhooks after {}s; the retry job will pick them up", FLUSH_TIMEOUT.as_secs());
+    }
+
+    for state in &states {
+        if let Err(e) = analytics::flush(state).await {
+            tracing::error!("Failed to save embed counts: {}", e);
+        }
+        if let Err(e) = state.storage.flush().await {
+            tracing::error!("Failed to flush storage: {}", e);
+        }
+    }
+    tracing::info!("Shutdown complete");
+}
@@ -0,0 +1,134 @@
+use axum::{extract::State, http::StatusCode, response::Json};
+use serde::Serialize;
+use utoipa::ToSchema;
+
+use crate::color_constants;
+use crate::ledger::DonationSource;
+use crate::storage::StorageError;
+use crate::thermometer::FillSegment;
+use crate::{error_response, AppState, ErrorResponse};
+
+/// Every source, in the order they are stacked in the breakdown render (bottom first)
+const ALL_SOURCES: [DonationSource; 8] = [
+    DonationSource::Stripe,
+    DonationSource::Paypal,
+    DonationSource::Facebook,
+    DonationSource::Phonebank,
+    DonationSource::Cash,
+    DonationSource::Manual,
+    DonationSource::SheetSync,
+    DonationSource::Adjustment,
+];
+
+#[derive(Serialize, ToSchema)]
+pub struct SourceTotal {
+    pub source: DonationSource,
+    pub amount: f64,
+    pub donations: usize,
+}
+
+/// Totals raised per channel
+#[derive(Serialize, ToSchema)]
+pub struct SourceBreakdown {
+    pub sources: Vec<SourceTotal>,
+    /// Part of the thermometer total not backed by any ledger entry
+    /// (e.g. totals entered before the ledger existed or edited directly in `/admin/config`)
+    pub unattributed: f64,
+    pub total_raised: f64,
+}
+
+impl SourceBreakdown {
+    /// Thermometer fill segments for the stacked render, skipping empty channels
+    pub fn fill_segments(&self) -> Vec<FillSegment> {
+        let mut segments: Vec<FillSegment> = self
+            .sources
+            .iter()
+            .filter(|s| s.amount > 0.0)
+            .map(|s| FillSegment {
+                label: source_label(s.source).to_string(),
+                amount: s.amount,
+                color: source_color(s.source),
+            })
+            .collect();
+
+        if self.unattributed > 0.0 {
+            segments.push(FillSegment {
+                label: "Other".to_string(),
+                amount: self.unattributed,
+                color: color_constants::sources::OTHER,
+            });
+        }
+        segments
+    }
+}
+
+pub fn source_label(source: DonationSource) -> &'static str {
+    match source {
+        DonationSource::Stripe => "Online (Stripe)",
+        DonationSource::Paypal => "PayPal",
+        DonationSource::Facebook => "Facebook",
+        DonationSource::Phonebank => "Phone bank",
+        DonationSource::Cash => "Cash",
+        DonationSource::Manual => "Check / other",
+        DonationSource::SheetSync => "Spreadsheet",
+        DonationSource::Adjustment => "Adjustments",
+    }
+}
+
+fn source_color(source: DonationSource) -> &'static str {
+    use color_constants::sources;
+    match source {
+        DonationSource::Stripe => sources::STRIPE,
+        DonationSource::Paypal => sources::PAYPAL,
+        DonationSource::Facebook => sources::FACEBOOK,
+        DonationSource::Phonebank => sources::PHONEBANK,
+        DonationSource::Cash => sources::CASH,
+        DonationSource::Manual => sources::MANUAL,
+        DonationSource::SheetSync => sources::SHEET_SYNC,
+        DonationSource::Adjustment => sources::ADJUSTMENT,
+    }
+}
+
+/// Sum the ledger per source and compare against the thermometer total
+pub async fn source_breakdown(state: &AppState) -> Result<SourceBreakdown, StorageError> {
+    let config = state.storage.load_config().await?;
+    let donations = state.storage.list_donations().await?;
+
+    let sources: Vec<SourceTotal> = ALL_SOURCES
+        .iter()
+        .map(|&source| {
+            let entries = donations.iter().filter(|d| d.source == source);
+            SourceTotal {
+                source,
+                amount: entries.clone().map(|d| d.amount).sum::<f64>() + 0.0,
+                donations: entries.count(),
+            }
+        })
+        .collect();
+
+    let total_raised = config.total_raised();
+    let attributed: f64 = sources.iter().map(|s| s.amount).sum();
+
+    Ok(SourceBreakdown {
+        unattributed: ((total_raised - attributed) * 100.0).round() / 100.0 + 0.0,
+        sources,
+        total_raised,
+    })
+}
+
+#[utoipa::path(
+    get,
+    path = "/stats/sources",
+    tag = "Public",
+    responses(
+        (status = 200, description = "Amount raised per donation channel", body = SourceBreakdown)
+    )
+)]
+pub async fn get_source_stats(
+    State(state): State<AppState>,
+) -> Result<Json<SourceBreakdown>, (StatusCode, Json<ErrorResponse>)> {
+    let breakdown = source_breakdown(&state).await.map_err(|e| {
+        error_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to load ledger: {}", e))
+    })?;
+    Ok(Json(breakdown))
+}
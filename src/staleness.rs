@@ -0,0 +1,121 @@
+use askama::Template;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use tokio::sync::Mutex;
+use utoipa::ToSchema;
+
+use crate::email::OutgoingEmail;
+use crate::i18n::Lang;
+use crate::scheduler::Job;
+use crate::{AppState, ThermometerConfig};
+
+/// Flags figures that haven't been updated for a while, so a forgotten sync doesn't quietly
+/// show week-old numbers
+#[derive(Debug, Clone, Default, Serialize, Deserialize, ToSchema)]
+#[serde(default)]
+pub struct StalenessSettings {
+    /// Hours without an update before the figures count as stale; the watchdog is off when unset
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub stale_after_hours: Option<u32>,
+    /// Emailed when the figures go stale; the digest recipients when empty
+    pub notify: Vec<String>,
+}
+
+#[derive(Template)]
+#[template(path = "stale-email.html")]
+struct StaleEmailTemplate {
+    organization_name: String,
+    title: String,
+    updated: String,
+    total_raised: String,
+    goal: String,
+}
+
+/// Whether `last_updated` is older than `stale_after_hours`
+pub fn is_stale(config: &ThermometerConfig) -> bool {
+    let Some(hours) = config.staleness.stale_after_hours.filter(|h| *h > 0) else {
+        return false;
+    };
+    chrono::DateTime::parse_from_rfc3339(&config.last_updated)
+        .is_ok_and(|t| chrono::Utc::now().signed_duration_since(t) > chrono::Duration::hours(hours.into()))
+}
+
+/// e.g. `Figures as of Dec 5, 2025`, once the figures are stale
+pub fn notice(config: &ThermometerConfig, lang: Lang) -> Option<String> {
+    if !is_stale(config) {
+        return None;
+    }
+    let updated = chrono::DateTime::parse_from_rfc3339(&config.last_updated).ok()?;
+    Some(lang.catalog().figures_as_of(&updated.with_timezone(&config.tz())))
+}
+
+/// Scheduled job that emails admins once each time the figures go stale. Which `last_updated`
+/// was reported is kept in memory, so a restart can repeat the email.
+pub fn job() -> Job {
+    let reported: Arc<Mutex<Option<String>>> = Arc::new(Mutex::new(None));
+    Job::new("staleness-watchdog", "*/15 * * * *", move |state| {
+        let reported = reported.clone();
+        async move { check(&state, &mut *reported.lock().await).await }
+    })
+}
+
+async fn check(state: &AppState, reported: &mut Option<String>) -> Result<String, String> {
+    let config = state
+        .storage
+        .load_config()
+        .await
+        .map_err(|e| format!("Failed to load config for staleness check: {}", e))?;
+
+    if !is_stale(&config) {
+        return Ok("Figures are up to date".to_string());
+    }
+    if reported.as_deref() == Some(config.last_updated.as_str()) {
+        return Ok("Already reported".to_string());
+    }
+
+    let recipients = if config.staleness.notify.is_empty() {
+        config.digest.recipients.clone()
+    } else {
+        config.staleness.notify.clone()
+    };
+    tracing::warn!("Figures haven't been updated since {}", config.last_updated);
+    if recipients.is_empty() {
+        return Err("Figures are stale, but there is nobody to notify".to_string());
+    }
+    let mailer = state
+        .mailer
+        .as_ref()
+        .ok_or_else(|| "Figures are stale, but email is not configured (SMTP_HOST not set)".to_string())?;
+
+    // Only attempt once per stale period; failures are reported rather than retried
+    *reported = Some(config.last_updated.clone());
+    let template = StaleEmailTemplate {
+        organization_name: config.organization_name.clone(),
+        title: config.title.clone(),
+        updated: crate::summary::local_time(config.tz(), &config.last_updated),
+        total_raised: format!("{:.2}", config.total_raised()),
+        goal: format!("{:.2}", config.goal),
+    };
+    let html_body = template
+        .render()
+        .map_err(|e| format!("Failed to render staleness template: {}", e))?;
+    let text_body = format!(
+        "{} - {}\n\nThe figures haven't been updated since {}. Donors are still seeing ${} of ${}.\n\
+         Please upload the latest totals from the admin portal.\n",
+        template.organization_name, template.title, template.updated, template.total_raised, template.goal
+    );
+
+    mailer
+        .send(OutgoingEmail {
+            to: recipients.clone(),
+            subject: format!("{}: figures not updated since {}", config.title, template.updated),
+            html_body,
+            text_body,
+            inline_images: vec![],
+        })
+        .await?;
+
+    let message = format!("Notified {} recipient(s) of stale figures", recipients.len());
+    tracing::info!("{}", message);
+    Ok(message)
+}
@@ -0,0 +1,495 @@
+use axum::{
+    extract::State,
+    http::{HeaderMap, StatusCode},
+    response::Json,
+};
+use hmac::{Hmac, Mac};
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+use utoipa::ToSchema;
+
+use crate::campaigns::CAMPAIGN_PATH_PREFIX;
+use crate::ledger::{self, Donation, DonationSource, ModerationStatus, RecurringPledge, GENERAL_TEAM};
+use crate::storage::StorageError;
+use crate::tenants::TENANT_PATH_PREFIX;
+use crate::{base_url_from_headers, error_response, AppState, ErrorResponse};
+
+const STRIPE_API_URL: &str = "https://api.stripe.com/v1";
+/// Reject webhook signatures older than this to prevent replays
+const SIGNATURE_TOLERANCE_SECS: i64 = 300;
+const MIN_DONATION: f64 = 1.0;
+const MAX_DONATION: f64 = 100_000.0;
+/// Stripe metadata values are limited to 500 characters
+const MAX_MESSAGE_CHARS: usize = 500;
+
+/// Donate page settings
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[serde(default)]
+pub struct DonateSettings {
+    /// Amounts offered as one-click buttons on the donate page
+    pub preset_amounts: Vec<f64>,
+    /// ISO currency code passed to Stripe
+    pub currency: String,
+}
+
+impl Default for DonateSettings {
+    fn default() -> Self {
+        Self {
+            preset_amounts: vec![25.0, 50.0, 100.0, 250.0],
+            currency: "usd".to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct DonationSessionRequest {
+    /// Team to credit (defaults to the general fund)
+    team: Option<String>,
+    amount: f64,
+    /// Give this amount every month instead of once
+    #[serde(default)]
+    recurring: bool,
+    /// Optional public message for the donor wall (shown after moderation)
+    message: Option<String>,
+    /// Hide the donor's name publicly; omitted uses the campaign default
+    anonymous: Option<bool>,
+    /// Name to show publicly instead of the name on the card
+    display_name: Option<String>,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct DonationSessionResponse {
+    /// Stripe Checkout URL to redirect the donor to
+    url: String,
+}
+
+fn secret_key() -> Option<String> {
+    std::env::var("STRIPE_SECRET_KEY").ok()
+}
+
+#[utoipa::path(
+    post,
+    path = "/donate/session",
+    tag = "Public",
+    request_body = DonationSessionRequest,
+    responses(
+        (status = 200, description = "Stripe Checkout session created", body = DonationSessionResponse),
+        (status = 400, description = "Invalid amount or team", body = ErrorResponse),
+        (status = 503, description = "Online donations are not configured", body = ErrorResponse)
+    )
+)]
+pub async fn create_donation_session(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Json(request): Json<DonationSessionRequest>,
+) -> Result<Json<DonationSessionResponse>, (StatusCode, Json<ErrorResponse>)> {
+    let secret_key = secret_key().ok_or_else(|| {
+        error_response(StatusCode::SERVICE_UNAVAILABLE, "Online donations are not configured")
+    })?;
+
+    if !(MIN_DONATION..=MAX_DONATION).contains(&request.amount) {
+        return Err(error_response(
+            StatusCode::BAD_REQUEST,
+            format!("Amount must be between ${:.2} and ${:.2}", MIN_DONATION, MAX_DONATION),
+        ));
+    }
+
+    let config = state.storage.load_config().await.map_err(|e| {
+        error_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to load config: {}", e))
+    })?;
+
+    let team = match request.team.filter(|t| !t.trim().is_empty()) {
+        Some(team) if config.active_teams().any(|t| t.name == team) => team,
+        Some(team) => {
+            return Err(error_response(
+                StatusCode::BAD_REQUEST,
+                format!("Unknown team: {}", team),
+            ))
+        }
+        None => GENERAL_TEAM.to_string(),
+    };
+
+    let base_url = base_url_from_headers(&headers);
+    let unit_amount = ((request.amount * 100.0).round() as i64).to_string();
+    let product_name = format!("Donation to {} ({})", config.title, team);
+    let success_url = format!("{}/donate?status=success", base_url);
+    let cancel_url = format!("{}/donate?status=cancelled", base_url);
+
+    let message: String = request
+        .message
+        .unwrap_or_default()
+        .trim()
+        .chars()
+        .take(MAX_MESSAGE_CHARS)
+        .collect();
+
+    let mut form = vec![
+        ("success_url", success_url.as_str()),
+        ("cancel_url", cancel_url.as_str()),
+        ("line_items[0][quantity]", "1"),
+        ("line_items[0][price_data][currency]", config.donate.currency.as_str()),
+        ("line_items[0][price_data][unit_amount]", unit_amount.as_str()),
+        ("line_items[0][price_data][product_data][name]", product_name.as_str()),
+        ("metadata[team]", team.as_str()),
+    ];
+    // The deployment's Stripe account takes payments for every tenant and campaign; the scope
+    // tells the webhook whose ledger to credit
+    if !state.scope.is_empty() {
+        form.push(("metadata[scope]", state.scope.as_str()));
+    }
+    if !message.is_empty() {
+        form.push(("metadata[message]", message.as_str()));
+    }
+    let display_name: String = request
+        .display_name
+        .unwrap_or_default()
+        .trim()
+        .chars()
+        .take(MAX_MESSAGE_CHARS)
+        .collect();
+    if !display_name.is_empty() {
+        form.push(("metadata[display_name]", display_name.as_str()));
+    }
+    if let Some(anonymous) = request.anonymous {
+        form.push(("metadata[anonymous]", if anonymous { "true" } else { "false" }));
+    }
+    if request.recurring {
+        // Monthly subscription; each paid invoice is credited to the ledger as it arrives
+        form.extend([
+            ("mode", "subscription"),
+            ("line_items[0][price_data][recurring][interval]", "month"),
+            ("subscription_data[metadata][team]", team.as_str()),
+        ]);
+        if !state.scope.is_empty() {
+            form.push(("subscription_data[metadata][scope]", state.scope.as_str()));
+        }
+        if let Some(anonymous) = request.anonymous {
+            form.push((
+                "subscription_data[metadata][anonymous]",
+                if anonymous { "true" } else { "false" },
+            ));
+        }
+    } else {
+        form.extend([("mode", "payment"), ("submit_type", "donate")]);
+    }
+
+    let response = state
+        .http_client
+        .post(format!("{}/checkout/sessions", STRIPE_API_URL))
+        .bearer_auth(secret_key)
+        .form(&form)
+        .send()
+        .await
+        .map_err(|e| {
+            tracing::error!("Stripe request failed: {}", e);
+            error_response(StatusCode::BAD_GATEWAY, "Failed to reach payment provider")
+        })?;
+
+    let status = response.status();
+    let body: serde_json::Value = response.json().await.unwrap_or_default();
+    if !status.is_success() {
+        tracing::error!("Stripe returned {}: {}", status, body);
+        return Err(error_response(
+            StatusCode::BAD_GATEWAY,
+            "Payment provider rejected the donation session",
+        ));
+    }
+
+    let url = body["url"].as_str().ok_or_else(|| {
+        error_response(StatusCode::BAD_GATEWAY, "Payment provider returned no checkout URL")
+    })?;
+
+    Ok(Json(DonationSessionResponse {
+        url: url.to_string(),
+    }))
+}
+
+/// Verify a `Stripe-Signature` header (`t=<timestamp>,v1=<hex hmac>,...`) against the raw payload
+fn verify_signature(payload: &str, header: &str, secret: &str) -> Result<(), String> {
+    let mut timestamp = None;
+    let mut signatures = Vec::new();
+    for part in header.split(',') {
+        match part.split_once('=') {
+            Some(("t", value)) => timestamp = value.parse::<i64>().ok(),
+            Some(("v1", value)) => signatures.push(value),
+            _ => {}
+        }
+    }
+
+    let timestamp = timestamp.ok_or_else(|| "Missing signature timestamp".to_string())?;
+    if (chrono::Utc::now().timestamp() - timestamp).abs() > SIGNATURE_TOLERANCE_SECS {
+        return Err("Signature timestamp outside tolerance".to_string());
+    }
+
+    for signature in signatures {
+        let Ok(expected) = hex::decode(signature) else {
+            continue;
+        };
+        let mut mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes())
+            .map_err(|e| format!("Invalid webhook secret: {}", e))?;
+        mac.update(format!("{}.{}", timestamp, payload).as_bytes());
+        if mac.verify_slice(&expected).is_ok() {
+            return Ok(());
+        }
+    }
+
+    Err("No matching signature".to_string())
+}
+
+#[utoipa::path(
+    post,
+    path = "/webhooks/stripe",
+    tag = "Public",
+    responses(
+        (status = 200, description = "Event processed"),
+        (status = 400, description = "Invalid signature or payload", body = ErrorResponse)
+    )
+)]
+pub async fn stripe_webhook(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    payload: String,
+) -> Result<StatusCode, (StatusCode, Json<ErrorResponse>)> {
+    let secret = std::env::var("STRIPE_WEBHOOK_SECRET").map_err(|_| {
+        error_response(StatusCode::SERVICE_UNAVAILABLE, "Stripe webhooks are not configured")
+    })?;
+
+    let signature = headers
+        .get("Stripe-Signature")
+        .and_then(|h| h.to_str().ok())
+        .ok_or_else(|| error_response(StatusCode::BAD_REQUEST, "Missing Stripe-Signature header"))?;
+
+    verify_signature(&payload, signature, &secret).map_err(|e| {
+        tracing::warn!("Rejected Stripe webhook: {}", e);
+        error_response(StatusCode::BAD_REQUEST, "Invalid signature")
+    })?;
+
+    let event: serde_json::Value = serde_json::from_str(&payload)
+        .map_err(|e| error_response(StatusCode::BAD_REQUEST, format!("Invalid payload: {}", e)))?;
+
+    let object = &event["data"]["object"];
+    let scope = match event["type"].as_str() {
+        Some("invoice.paid") => &object["subscription_details"]["metadata"]["scope"],
+        _ => &object["metadata"]["scope"],
+    };
+    let scope = scope.as_str().unwrap_or_default();
+    let Some(state) = scoped_state(&state, scope) else {
+        tracing::info!(
+            "Ignoring Stripe event {} for {}, which isn't served here",
+            event["id"].as_str().unwrap_or_default(),
+            scope
+        );
+        return Ok(StatusCode::OK);
+    };
+
+    let result = match event["type"].as_str() {
+        Some("checkout.session.completed") => handle_checkout_completed(&state, object).await,
+        Some("invoice.paid") => handle_invoice_paid(&state, object).await,
+        Some("customer.subscription.deleted") => handle_subscription_deleted(&state, object).await,
+        _ => Ok(()),
+    };
+
+    result.map_err(|e| {
+        error_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Storage error: {}", e))
+    })?;
+
+    Ok(StatusCode::OK)
+}
+
+/// The state a payment made under `scope` belongs to. The deployment's endpoint hands payments on
+/// to the tenant or campaign they were made in; any other endpoint only takes its own, so
+/// registering several endpoints doesn't credit a payment more than once.
+fn scoped_state(state: &AppState, scope: &str) -> Option<AppState> {
+    if scope == state.scope {
+        return Some(state.clone());
+    }
+    if !state.scope.is_empty() {
+        return None;
+    }
+    let (owner, campaign) = match scope.split_once(CAMPAIGN_PATH_PREFIX) {
+        Some((owner, campaign)) => (owner, Some(campaign)),
+        None => (scope, None),
+    };
+    let owner = match owner.strip_prefix(TENANT_PATH_PREFIX) {
+        Some(slug) => state.tenants.state(slug)?,
+        None if owner.is_empty() => state.clone(),
+        None => return None,
+    };
+    match campaign {
+        Some(id) => owner.campaigns.state(id),
+        None => Some(owner),
+    }
+}
+
+/// Record a donation unless one with the same ID is already in the ledger
+/// (Stripe retries deliveries, so each payment must only be credited once). The check happens
+/// in the same transaction that appends the entry, so concurrent retries can't both count.
+async fn record_once(state: &AppState, donation: Donation) -> Result<(), StorageError> {
+    ledger::record_donation(state, donation).await?;
+    Ok(())
+}
+
+async fn handle_checkout_completed(
+    state: &AppState,
+    session: &serde_json::Value,
+) -> Result<(), StorageError> {
+    let team = session["metadata"]["team"]
+        .as_str()
+        .unwrap_or(GENERAL_TEAM)
+        .to_string();
+    let donor_name = session["customer_details"]["name"].as_str().map(String::from);
+    let donor_email = session["customer_details"]["email"].as_str().map(String::from);
+    let amount = session["amount_total"].as_i64().unwrap_or(0) as f64 / 100.0;
+
+    // Subscription payments are credited per invoice; the session only establishes the pledge
+    if session["mode"] == "subscription" {
+        let Some(subscription_id) = session["subscription"].as_str() else {
+            return Ok(());
+        };
+        let anonymous = session["metadata"]["anonymous"].as_str().map(|a| a == "true");
+        return ensure_pledge(state, subscription_id, team, amount, donor_name, donor_email, anonymous)
+            .await
+            .map(|_| ());
+    }
+
+    if session["payment_status"] != "paid" {
+        return Ok(());
+    }
+    let Some(session_id) = session["id"].as_str() else {
+        return Ok(());
+    };
+
+    record_once(
+        state,
+        Donation {
+            id: format!("stripe_{}", session_id),
+            team,
+            amount,
+            donor_name,
+            donor_email,
+            source: DonationSource::Stripe,
+            created_at: chrono::Utc::now().to_rfc3339(),
+            pledge_id: None,
+            method: None,
+            message: session["metadata"]["message"].as_str().map(String::from),
+            moderation: ModerationStatus::Pending,
+            anonymous: session["metadata"]["anonymous"].as_str().map(|a| a == "true"),
+            display_name: session["metadata"]["display_name"].as_str().map(String::from),
+            caller: None,
+            tax_deductible: None,
+            gift_aid: None,
+            note: None,
+        },
+    )
+    .await
+}
+
+async fn handle_invoice_paid(state: &AppState, invoice: &serde_json::Value) -> Result<(), StorageError> {
+    let (Some(invoice_id), Some(subscription_id)) =
+        (invoice["id"].as_str(), invoice["subscription"].as_str())
+    else {
+        return Ok(());
+    };
+
+    let amount = invoice["amount_paid"].as_i64().unwrap_or(0) as f64 / 100.0;
+    if amount <= 0.0 {
+        return Ok(());
+    }
+
+    // The invoice can arrive before checkout.session.completed, so create the pledge if needed
+    let team = invoice["subscription_details"]["metadata"]["team"]
+        .as_str()
+        .unwrap_or(GENERAL_TEAM)
+        .to_string();
+    let pledge = ensure_pledge(
+        state,
+        subscription_id,
+        team,
+        amount,
+        invoice["customer_name"].as_str().map(String::from),
+        invoice["customer_email"].as_str().map(String::from),
+        invoice["subscription_details"]["metadata"]["anonymous"]
+            .as_str()
+            .map(|a| a == "true"),
+    )
+    .await?;
+
+    record_once(
+        state,
+        Donation {
+            id: format!("stripe_{}", invoice_id),
+            team: pledge.team.clone(),
+            amount,
+            donor_name: pledge.donor_name.clone(),
+            donor_email: pledge.donor_email.clone(),
+            source: DonationSource::Stripe,
+            created_at: chrono::Utc::now().to_rfc3339(),
+            pledge_id: Some(pledge.id),
+            method: None,
+            message: None,
+            moderation: ModerationStatus::Pending,
+            anonymous: pledge.anonymous,
+            display_name: None,
+            caller: None,
+            tax_deductible: None,
+            gift_aid: None,
+            note: None,
+        },
+    )
+    .await
+}
+
+async fn handle_subscription_deleted(
+    state: &AppState,
+    subscription: &serde_json::Value,
+) -> Result<(), StorageError> {
+    let Some(subscription_id) = subscription["id"].as_str() else {
+        return Ok(());
+    };
+
+    let pledges = state.storage.list_pledges().await?;
+    if let Some(mut pledge) = pledges
+        .into_iter()
+        .find(|p| p.subscription_id.as_deref() == Some(subscription_id))
+    {
+        pledge.active = false;
+        state.storage.save_pledge(&pledge).await?;
+        tracing::info!("Recurring pledge {} cancelled", pledge.id);
+    }
+    Ok(())
+}
+
+/// Find the pledge for a Stripe subscription, creating it if this is the first event we've seen
+async fn ensure_pledge(
+    state: &AppState,
+    subscription_id: &str,
+    team: String,
+    monthly_amount: f64,
+    donor_name: Option<String>,
+    donor_email: Option<String>,
+    anonymous: Option<bool>,
+) -> Result<RecurringPledge, StorageError> {
+    let pledges = state.storage.list_pledges().await?;
+    if let Some(pledge) = pledges
+        .into_iter()
+        .find(|p| p.subscription_id.as_deref() == Some(subscription_id))
+    {
+        return Ok(pledge);
+    }
+
+    let pledge = RecurringPledge {
+        id: format!("stripe_{}", subscription_id),
+        team,
+        monthly_amount,
+        donor_name,
+        donor_email,
+        start_date: chrono::Utc::now().date_naive().to_string(),
+        subscription_id: Some(subscription_id.to_string()),
+        source: DonationSource::Stripe,
+        active: true,
+        anonymous,
+    };
+    state.storage.save_pledge(&pledge).await?;
+    tracing::info!("Created recurring pledge {} for {}", pledge.id, pledge.team);
+    Ok(pledge)
+}
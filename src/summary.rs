@@ -0,0 +1,384 @@
+use axum::{
+    extract::{Query, State},
+    http::StatusCode,
+    response::Json,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use utoipa::{IntoParams, ToSchema};
+
+use crate::announcement::{self, Announcement};
+use crate::avatars::Placeholder;
+use crate::celebration::{self, Celebration};
+use crate::i18n::Lang;
+use crate::impact;
+use crate::matching;
+use crate::staleness;
+use crate::{error_response, AppState, ErrorResponse, Team, ThermometerConfig};
+
+/// Teams per page when `per_page` isn't given
+const DEFAULT_PER_PAGE: usize = 25;
+const MAX_PER_PAGE: usize = 100;
+/// Teams whose total changed this recently are marked hot
+pub const HOT_HOURS: i64 = 24;
+
+/// Order teams are listed in on the home page and `/teams`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum TeamSort {
+    /// Highest score first; that's the total unless `leaderboard.scoring` weights it
+    #[default]
+    Amount,
+    /// By team name, A to Z
+    Alphabetical,
+    /// Most recently changed total first; teams never changed since tracking began go last
+    RecentActivity,
+    /// By each team's `sort_order`, lowest first; teams without one go last
+    Manual,
+}
+
+impl TeamSort {
+    pub const ALL: [TeamSort; 4] = [Self::Amount, Self::Alphabetical, Self::RecentActivity, Self::Manual];
+
+    /// Query string value, matching the serde name
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Amount => "amount",
+            Self::Alphabetical => "alphabetical",
+            Self::RecentActivity => "recent_activity",
+            Self::Manual => "manual",
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Amount => "Amount raised",
+            Self::Alphabetical => "Name",
+            Self::RecentActivity => "Recent activity",
+            Self::Manual => "Featured",
+        }
+    }
+}
+
+/// What leaderboard ranks are based on. The thermometer and totals always use raw dollars.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum Scoring {
+    /// Dollars raised
+    #[default]
+    Raw,
+    /// Dollars raised times each team's `score_multiplier` (1 when unset)
+    Weighted,
+    /// Dollars raised per member, from each team's `members` (1 when unset)
+    PerMember,
+}
+
+impl Scoring {
+    /// Heading for the score column on the home page; `None` when ranks follow raw dollars
+    pub fn column_label(self) -> Option<&'static str> {
+        match self {
+            Self::Raw => None,
+            Self::Weighted => Some("Score"),
+            Self::PerMember => Some("Per Member"),
+        }
+    }
+}
+
+/// How the public leaderboard lists teams
+#[derive(Debug, Clone, Default, Serialize, Deserialize, ToSchema)]
+#[serde(default)]
+pub struct LeaderboardSettings {
+    /// Default order; `?sort=` overrides it per request
+    pub sort: TeamSort,
+    /// What ranks are based on, so small teams can compete with large ones
+    pub scoring: Scoring,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct RankedTeam {
+    /// 1 for the top team; teams with equal scores share a rank
+    pub rank: usize,
+    pub name: String,
+    pub image_url: Option<String>,
+    /// Placeholder avatar color, when the team has one
+    pub color: Option<String>,
+    pub total_raised: f64,
+    /// What the team is ranked by under `leaderboard.scoring`; equals `total_raised` for `raw`
+    pub score: f64,
+    /// Share of the total raised, 0 to 100
+    pub percent_of_total: f64,
+    /// When the team's total last changed
+    pub last_changed: Option<String>,
+    /// The total changed in the last 24 hours
+    pub hot: bool,
+}
+
+impl RankedTeam {
+    /// How long ago the total changed, e.g. `3 hours ago`
+    pub fn updated_ago(&self) -> Option<String> {
+        time_ago(self.last_changed.as_deref()?)
+    }
+
+    /// Stand-in avatar, for when the team has no `image_url`
+    pub fn placeholder(&self) -> Placeholder {
+        Placeholder::for_team(&self.name, self.color.as_deref())
+    }
+}
+
+/// The value a team is ranked by, rounded to cents
+pub fn score(scoring: Scoring, team: &Team) -> f64 {
+    let score = match scoring {
+        Scoring::Raw => team.total_raised,
+        Scoring::Weighted => team.total_raised * team.score_multiplier.unwrap_or(1.0),
+        Scoring::PerMember => team.total_raised / team.members.filter(|m| *m > 0).unwrap_or(1) as f64,
+    };
+    round_cents(score)
+}
+
+/// Whether a `last_changed` timestamp falls within the last `HOT_HOURS`
+pub fn is_hot(last_changed: Option<&str>) -> bool {
+    last_changed
+        .and_then(|t| chrono::DateTime::parse_from_rfc3339(t).ok())
+        .is_some_and(|t| chrono::Utc::now().signed_duration_since(t) < chrono::Duration::hours(HOT_HOURS))
+}
+
+/// How long ago an RFC 3339 timestamp was, e.g. `2 hours ago`; `None` if it doesn't parse.
+/// Timestamps in the future (clock skew) count as `just now`.
+pub fn time_ago(timestamp: &str) -> Option<String> {
+    let t = chrono::DateTime::parse_from_rfc3339(timestamp).ok()?;
+    let elapsed = chrono::Utc::now().signed_duration_since(t);
+    let (count, unit) = if elapsed.num_minutes() < 1 {
+        return Some("just now".to_string());
+    } else if elapsed.num_hours() < 1 {
+        (elapsed.num_minutes(), "minute")
+    } else if elapsed.num_days() < 1 {
+        (elapsed.num_hours(), "hour")
+    } else {
+        (elapsed.num_days(), "day")
+    };
+    Some(format!("{} {}{} ago", count, unit, if count == 1 { "" } else { "s" }))
+}
+
+/// An RFC 3339 timestamp as campaign-local time, e.g. `Dec 5, 2025, 5:02 PM CST`. Unparseable
+/// timestamps are returned as they are.
+pub fn local_time(tz: chrono_tz::Tz, timestamp: &str) -> String {
+    match chrono::DateTime::parse_from_rfc3339(timestamp) {
+        Ok(t) => t.with_timezone(&tz).format("%b %-d, %Y, %-I:%M %p %Z").to_string(),
+        Err(_) => timestamp.to_string(),
+    }
+}
+
+/// Campaign totals and standings, computed once here for the home page and API clients
+#[derive(Debug, Serialize, ToSchema)]
+pub struct CampaignSummary {
+    pub organization_name: String,
+    pub title: String,
+    pub goal: f64,
+    pub total_raised: f64,
+    /// Progress toward the goal, 0 to 100 (rounded to two decimals)
+    pub progress_percent: f64,
+    /// Amount still needed to reach the goal; zero once it's met
+    pub remaining: f64,
+    #[schema(value_type = Option<String>, format = Date)]
+    pub ends_on: Option<chrono::NaiveDate>,
+    /// Days until `ends_on` counting today, in the campaign timezone; zero once it has passed
+    pub days_left: Option<i64>,
+    /// Total divided by `impact.cost_per_animal`, rounded down; missing if no cost is set
+    pub animals_helped: Option<u64>,
+    /// What team ranks are based on
+    pub scoring: Scoring,
+    pub team_count: usize,
+    /// Active teams, highest score first
+    pub teams: Vec<RankedTeam>,
+    pub last_updated: String,
+    /// `last_updated` relative to now, e.g. `2 hours ago`
+    pub last_updated_ago: Option<String>,
+    /// `last_updated` in the campaign timezone, e.g. `Dec 5, 2025, 5:02 PM CST`
+    pub last_updated_local: String,
+    /// e.g. `Figures as of Dec 5, 2025`, once `last_updated` is older than
+    /// `staleness.stale_after_hours`
+    pub stale_notice: Option<String>,
+    /// The current announcement; missing once it expires
+    pub announcement: Option<Announcement>,
+    /// e.g. `2x match — 5 hours left!` while a `matching` window is open
+    pub match_countdown: Option<String>,
+    /// Headline and thank-you once the goal is reached, if `celebration` is enabled
+    pub celebration: Option<Celebration>,
+    /// Campaign timezone (IANA name) for `days_left` and displayed times
+    pub timezone: String,
+}
+
+fn round_cents(amount: f64) -> f64 {
+    (amount * 100.0).round() / 100.0
+}
+
+pub fn summarize(config: &ThermometerConfig) -> CampaignSummary {
+    let total_raised = round_cents(config.total_raised());
+
+    let scoring = config.leaderboard.scoring;
+
+    let mut teams: Vec<(f64, &Team)> = config.active_teams().map(|t| (score(scoring, t), t)).collect();
+    teams.sort_by(|(a_score, a), (b_score, b)| b_score.total_cmp(a_score).then_with(|| a.name.cmp(&b.name)));
+    let mut ranked: Vec<RankedTeam> = Vec::with_capacity(teams.len());
+    for (i, (score, team)) in teams.into_iter().enumerate() {
+        let rank = match ranked.last() {
+            Some(prev) if prev.score == score => prev.rank,
+            _ => i + 1,
+        };
+        ranked.push(RankedTeam {
+            rank,
+            name: team.name.clone(),
+            image_url: team.image_url.clone(),
+            color: team.color.clone(),
+            total_raised: team.total_raised,
+            score,
+            percent_of_total: if total_raised > 0.0 {
+                round_cents(team.total_raised / total_raised * 100.0)
+            } else {
+                0.0
+            },
+            last_changed: team.last_changed.clone(),
+            hot: is_hot(team.last_changed.as_deref()),
+        });
+    }
+
+    let tz = config.tz();
+    let today = chrono::Utc::now().with_timezone(&tz).date_naive();
+    CampaignSummary {
+        organization_name: config.organization_name.clone(),
+        title: config.title.clone(),
+        goal: config.goal,
+        total_raised,
+        progress_percent: round_cents(config.progress_percent()),
+        remaining: round_cents((config.goal - total_raised).max(0.0)),
+        ends_on: config.ends_on,
+        days_left: config.ends_on.map(|end| ((end - today).num_days() + 1).max(0)),
+        animals_helped: impact::animals_helped(config),
+        scoring,
+        team_count: ranked.len(),
+        teams: ranked,
+        last_updated: config.last_updated.clone(),
+        last_updated_ago: time_ago(&config.last_updated),
+        last_updated_local: local_time(tz, &config.last_updated),
+        stale_notice: staleness::notice(config, Lang::En),
+        announcement: announcement::active(config).cloned(),
+        match_countdown: matching::countdown(config),
+        celebration: celebration::active(config),
+        timezone: tz.name().to_string(),
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/summary",
+    tag = "Public",
+    responses(
+        (status = 200, description = "Totals, progress, countdown and ranked teams", body = CampaignSummary),
+        (status = 500, description = "Failed to load config", body = ErrorResponse)
+    )
+)]
+pub async fn get_summary(
+    State(state): State<AppState>,
+) -> Result<Json<CampaignSummary>, (StatusCode, Json<ErrorResponse>)> {
+    let config = state.storage.load_config().await.map_err(|e| {
+        error_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to load config: {}", e))
+    })?;
+    Ok(Json(summarize(&config)))
+}
+
+// Query parameters for paging through the leaderboard
+#[derive(Debug, Default, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct TeamsQuery {
+    /// Case-insensitive substring of the team name
+    pub search: Option<String>,
+    /// 1-based page number (default 1)
+    pub page: Option<usize>,
+    /// Teams per page (default 25, at most 100)
+    pub per_page: Option<usize>,
+    /// Overrides the configured `leaderboard.sort`
+    pub sort: Option<TeamSort>,
+}
+
+/// One page of the leaderboard. Teams keep their overall rank when a search narrows the list.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct TeamPage {
+    pub teams: Vec<RankedTeam>,
+    pub page: usize,
+    pub per_page: usize,
+    /// Teams matching the search, across all pages
+    pub total_teams: usize,
+    pub total_pages: usize,
+}
+
+impl TeamPage {
+    pub fn has_previous(&self) -> bool {
+        self.page > 1
+    }
+
+    pub fn has_next(&self) -> bool {
+        self.page < self.total_pages
+    }
+}
+
+/// Put ranked teams (highest score first) in display order. Ranks still follow scores, and
+/// ties keep their ranked order.
+pub fn sort_teams(config: &ThermometerConfig, teams: &mut [RankedTeam], sort: TeamSort) {
+    match sort {
+        TeamSort::Amount => {}
+        TeamSort::Alphabetical => teams.sort_by_key(|t| t.name.to_lowercase()),
+        // Timestamps are all UTC RFC 3339, so they order as strings
+        TeamSort::RecentActivity => teams.sort_by(|a, b| b.last_changed.cmp(&a.last_changed)),
+        TeamSort::Manual => {
+            let order: HashMap<&str, i64> = config
+                .teams
+                .iter()
+                .filter_map(|t| t.sort_order.map(|o| (t.name.as_str(), o)))
+                .collect();
+            teams.sort_by_key(|t| order.get(t.name.as_str()).copied().unwrap_or(i64::MAX));
+        }
+    }
+}
+
+/// Narrow ranked teams to those matching the search and cut out the requested page. Pages past
+/// the end are empty.
+pub fn paginate(teams: Vec<RankedTeam>, query: &TeamsQuery) -> TeamPage {
+    let search = query.search.as_deref().map(str::trim).unwrap_or("").to_lowercase();
+    let matching: Vec<RankedTeam> = teams
+        .into_iter()
+        .filter(|t| search.is_empty() || t.name.to_lowercase().contains(&search))
+        .collect();
+
+    let per_page = query.per_page.unwrap_or(DEFAULT_PER_PAGE).clamp(1, MAX_PER_PAGE);
+    let page = query.page.unwrap_or(1).max(1);
+    let total_teams = matching.len();
+    TeamPage {
+        teams: matching.into_iter().skip((page - 1) * per_page).take(per_page).collect(),
+        page,
+        per_page,
+        total_teams,
+        total_pages: total_teams.div_ceil(per_page).max(1),
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/teams",
+    tag = "Public",
+    params(TeamsQuery),
+    responses(
+        (status = 200, description = "One page of active teams, in leaderboard order", body = TeamPage),
+        (status = 500, description = "Failed to load config", body = ErrorResponse)
+    )
+)]
+pub async fn list_teams(
+    State(state): State<AppState>,
+    Query(query): Query<TeamsQuery>,
+) -> Result<Json<TeamPage>, (StatusCode, Json<ErrorResponse>)> {
+    let config = state.storage.load_config().await.map_err(|e| {
+        error_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to load config: {}", e))
+    })?;
+    let mut teams = summarize(&config).teams;
+    sort_teams(&config, &mut teams, query.sort.unwrap_or(config.leaderboard.sort));
+    Ok(Json(paginate(teams, &query)))
+}
@@ -0,0 +1,71 @@
+use axum::{extract::State, http::StatusCode, response::Json};
+use serde::Serialize;
+use utoipa::ToSchema;
+
+use crate::badges::{self, Badge};
+use crate::storage::StorageError;
+use crate::summary::{self, HOT_HOURS};
+use crate::{error_response, AppState, ErrorResponse};
+
+/// A team's standing, recent activity and badges
+#[derive(Serialize, ToSchema)]
+pub struct TeamStats {
+    rank: usize,
+    name: String,
+    total_raised: f64,
+    /// When the team's total last changed
+    last_changed: Option<String>,
+    /// The total changed in the last 24 hours
+    hot: bool,
+    /// Ledger entries for the team in the last 24 hours (negative if corrections outweighed gifts)
+    raised_last_24h: f64,
+    badges: Vec<Badge>,
+}
+
+/// Active teams in rank order with their recent activity and badges
+pub async fn team_stats(state: &AppState) -> Result<Vec<TeamStats>, StorageError> {
+    let config = state.storage.load_config().await?;
+    let donations = state.storage.list_donations().await?;
+    let since = chrono::Utc::now() - chrono::Duration::hours(HOT_HOURS);
+    let mut awarded = badges::award(&config, &donations);
+
+    Ok(summary::summarize(&config)
+        .teams
+        .into_iter()
+        .map(|team| {
+            let recent: f64 = donations
+                .iter()
+                .filter(|d| d.team == team.name)
+                .filter(|d| chrono::DateTime::parse_from_rfc3339(&d.created_at).is_ok_and(|t| t >= since))
+                .map(|d| d.amount)
+                .sum();
+            let badges = awarded.remove(&team.name).unwrap_or_default();
+            TeamStats {
+                rank: team.rank,
+                name: team.name,
+                total_raised: team.total_raised,
+                last_changed: team.last_changed,
+                hot: team.hot,
+                raised_last_24h: (recent * 100.0).round() / 100.0 + 0.0,
+                badges,
+            }
+        })
+        .collect())
+}
+
+#[utoipa::path(
+    get,
+    path = "/stats/teams",
+    tag = "Public",
+    responses(
+        (status = 200, description = "Active teams in rank order with their recent activity and badges", body = [TeamStats])
+    )
+)]
+pub async fn get_team_stats(
+    State(state): State<AppState>,
+) -> Result<Json<Vec<TeamStats>>, (StatusCode, Json<ErrorResponse>)> {
+    let stats = team_stats(&state).await.map_err(|e| {
+        error_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to load team stats: {}", e))
+    })?;
+    Ok(Json(stats))
+}
@@ -0,0 +1,485 @@
+use axum::{
+    extract::{Path, Request, State},
+    http::{HeaderMap, StatusCode, Uri},
+    middleware::{self, Next},
+    response::{IntoResponse, Json, Response},
+    Router,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, Mutex, RwLock};
+use tower::ServiceExt;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::campaigns::{self, CampaignRegistry};
+use crate::read_tokens::{self, ReadTokens};
+use crate::scheduler::{self, Scheduler};
+use crate::{error_response, require_admin, AppState, ErrorResponse, ThermometerConfig};
+
+/// Path prefix that selects a tenant, e.g. `/t/paws/thermometer-light.png`
+pub const TENANT_PATH_PREFIX: &str = "/t/";
+const MAX_SLUG_LEN: usize = 40;
+
+tokio::task_local! {
+    static BASE_PATH: String;
+}
+
+/// Path prefix of the tenant handling the current request (`/t/<slug>`), or empty outside a tenant.
+/// Templates and absolute URLs put this in front of every link so tenant pages stay on their tenant.
+pub fn base_path() -> String {
+    BASE_PATH.try_with(|path| path.clone()).unwrap_or_default()
+}
+
+/// Run `f` with `base_path()` returning `path`, for routers nested under another prefix
+pub async fn with_base_path<F: std::future::Future>(path: String, f: F) -> F::Output {
+    BASE_PATH.scope(path, f).await
+}
+
+/// A partner organization served from this deployment with its own config, data and keys
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct Tenant {
+    /// URL slug, lowercase letters, digits and dashes
+    pub slug: String,
+    pub name: String,
+    /// Admin key for this tenant only; the deployment's key doesn't work on tenant routes
+    pub edit_key: String,
+    /// Phone bank volunteer key for this tenant
+    pub phonebank_key: String,
+    /// Editor key for this tenant; unset for tenants created before editors existed
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub editor_key: Option<String>,
+    pub created_at: String,
+}
+
+/// A custom domain that serves one tenant at its root, e.g. `donate.happytails.org`
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct DomainMapping {
+    /// Host name without port, lowercase
+    pub host: String,
+    /// Slug of the tenant served on this host
+    pub tenant: String,
+    pub created_at: String,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct MapDomainRequest {
+    tenant: String,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct CreateTenantRequest {
+    slug: String,
+    /// Organization name shown on the tenant's pages
+    name: String,
+}
+
+/// Routers for every registered tenant, keyed by slug
+#[derive(Default)]
+pub struct TenantRegistry {
+    tenants: RwLock<HashMap<String, (Router, AppState)>>,
+    /// Custom host name -> tenant slug
+    domains: RwLock<HashMap<String, String>>,
+}
+
+impl TenantRegistry {
+    fn tenant_for_host(&self, host: &str) -> Option<String> {
+        self.domains.read().unwrap().get(host).cloned()
+    }
+
+    fn map_domain(&self, host: &str, slug: &str) {
+        self.domains.write().unwrap().insert(host.to_string(), slug.to_string());
+    }
+
+    fn unmap_domain(&self, host: &str) {
+        self.domains.write().unwrap().remove(host);
+    }
+
+    fn router(&self, slug: &str) -> Option<Router> {
+        self.tenants.read().unwrap().get(slug).map(|(router, _)| router.clone())
+    }
+
+    /// State of every registered tenant
+    pub fn states(&self) -> Vec<AppState> {
+        self.tenants.read().unwrap().values().map(|(_, state)| state.clone()).collect()
+    }
+
+    /// State of one registered tenant
+    pub fn state(&self, slug: &str) -> Option<AppState> {
+        self.tenants.read().unwrap().get(slug).map(|(_, state)| state.clone())
+    }
+
+    fn contains(&self, slug: &str) -> bool {
+        self.tenants.read().unwrap().contains_key(slug)
+    }
+
+    /// Build the tenant's state and routes and start its scheduled jobs
+    fn register(&self, root: &AppState, tenant: &Tenant) -> AppState {
+        let state = AppState {
+            storage: root.storage.for_tenant(&tenant.slug),
+            edit_key: tenant.edit_key.clone(),
+            phonebank_key: Some(tenant.phonebank_key.clone()),
+            editor_key: tenant.editor_key.clone(),
+            in_flight_keys: Arc::new(Mutex::new(HashSet::new())),
+            scheduler: Arc::new(Scheduler::new(crate::scheduled_jobs())),
+            config_lock: Arc::new(tokio::sync::Mutex::new(())),
+            exports: root.exports.as_ref().map(|e| Arc::new(e.for_tenant(&tenant.slug))),
+            momentum: crate::momentum::channel(),
+            config_updates: crate::live_updates::channel(),
+            experiment: Arc::new(crate::experiment::Counters::default()),
+            embed_hits: Arc::new(crate::analytics::EmbedCounter::default()),
+            notifications: Arc::new(crate::notifications::DeliveryLog::default()),
+            campaigns: Arc::new(CampaignRegistry::default()),
+            read_tokens: Arc::new(ReadTokens::from_env()),
+            // The operator's Slack, Discord, Mailchimp and signing credentials stay the operator's
+            env_integrations: false,
+            scope: format!("{}{}", TENANT_PATH_PREFIX, tenant.slug),
+            ..root.clone()
+        };
+        scheduler::spawn_scheduler(state.clone());
+
+        let router = crate::routes(&state)
+            .merge(campaigns::routes())
+            .merge(read_tokens::routes())
+            .with_state(state.clone())
+            .layer(middleware::from_fn_with_state(state.clone(), campaigns::dispatch));
+        if let Some((_, previous)) = self
+            .tenants
+            .write()
+            .unwrap()
+            .insert(tenant.slug.clone(), (router, state.clone()))
+        {
+            previous.scheduler.stop();
+        }
+        state
+    }
+
+    fn remove(&self, slug: &str) {
+        if let Some((_, state)) = self.tenants.write().unwrap().remove(slug) {
+            state.scheduler.stop();
+            for campaign in state.campaigns.states() {
+                campaign.scheduler.stop();
+            }
+        }
+    }
+}
+
+/// Register every stored tenant; called once at startup
+pub async fn load(state: &AppState) {
+    match state.storage.list_tenants().await {
+        Ok(tenants) => {
+            for tenant in &tenants {
+                let tenant_state = state.tenants.register(state, tenant);
+                read_tokens::load(&tenant_state).await;
+                campaigns::load(&tenant_state).await;
+            }
+            if !tenants.is_empty() {
+                tracing::info!("Serving {} tenant(s)", tenants.len());
+            }
+        }
+        Err(e) => tracing::error!("Failed to load tenants: {}", e),
+    }
+
+    match state.storage.list_domains().await {
+        Ok(domains) => {
+            for domain in &domains {
+                state.tenants.map_domain(&domain.host, &domain.tenant);
+            }
+        }
+        Err(e) => tracing::error!("Failed to load domain mappings: {}", e),
+    }
+}
+
+/// Lowercase host name from the Host header, without the port
+fn request_host(headers: &HeaderMap) -> Option<String> {
+    let host = headers.get("host")?.to_str().ok()?;
+    let host = host.rsplit_once(':').map_or(host, |(name, _)| name);
+    Some(host.trim_end_matches('.').to_ascii_lowercase())
+}
+
+fn is_valid_host(host: &str) -> bool {
+    host.contains('.')
+        && host.len() <= 253
+        && !host.starts_with(['.', '-'])
+        && host.chars().all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '.' || c == '-')
+}
+
+/// Route requests for a mapped custom domain, or for `/t/<slug>/...` with the prefix stripped,
+/// to that tenant's router
+pub async fn dispatch(State(state): State<AppState>, mut request: Request, next: Next) -> Response {
+    // A custom domain serves its tenant at the root, so links need no prefix
+    if let Some(slug) = request_host(request.headers()).and_then(|host| state.tenants.tenant_for_host(&host)) {
+        let Some(router) = state.tenants.router(&slug) else {
+            return error_response(StatusCode::NOT_FOUND, format!("Unknown organization: {}", slug)).into_response();
+        };
+        return match router.oneshot(request).await {
+            Ok(response) => response,
+            Err(never) => match never {},
+        };
+    }
+
+    let Some(rest) = request.uri().path().strip_prefix(TENANT_PATH_PREFIX) else {
+        return next.run(request).await;
+    };
+    let (slug, path) = match rest.find('/') {
+        Some(i) => (&rest[..i], &rest[i..]),
+        None => (rest, "/"),
+    };
+    let slug = slug.to_string();
+
+    let Some(router) = state.tenants.router(&slug) else {
+        return error_response(StatusCode::NOT_FOUND, format!("Unknown organization: {}", slug)).into_response();
+    };
+
+    let path_and_query = match request.uri().query() {
+        Some(query) => format!("{}?{}", path, query),
+        None => path.to_string(),
+    };
+    let Ok(uri) = path_and_query.parse::<Uri>() else {
+        return error_response(StatusCode::BAD_REQUEST, "Invalid request path").into_response();
+    };
+    *request.uri_mut() = uri;
+
+    let base_path = format!("{}{}", TENANT_PATH_PREFIX, slug);
+    match with_base_path(base_path, router.oneshot(request)).await {
+        Ok(response) => response,
+        Err(never) => match never {},
+    }
+}
+
+pub fn is_valid_slug(slug: &str) -> bool {
+    !slug.is_empty()
+        && slug.len() <= MAX_SLUG_LEN
+        && !slug.starts_with('-')
+        && slug.chars().all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-')
+}
+
+#[utoipa::path(
+    get,
+    path = "/admin/tenants",
+    tag = "Admin",
+    security(("edit_key" = [])),
+    responses(
+        (status = 200, description = "Registered tenants, including their keys", body = [Tenant]),
+        (status = 401, description = "Unauthorized", body = ErrorResponse)
+    )
+)]
+pub async fn list_tenants(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+) -> Result<Json<Vec<Tenant>>, (StatusCode, Json<ErrorResponse>)> {
+    require_admin(&headers, &state)?;
+
+    let mut tenants = state.storage.list_tenants().await.map_err(|e| {
+        error_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to load tenants: {}", e))
+    })?;
+    tenants.sort_by(|a, b| a.slug.cmp(&b.slug));
+    Ok(Json(tenants))
+}
+
+#[utoipa::path(
+    post,
+    path = "/admin/tenants",
+    tag = "Admin",
+    security(("edit_key" = [])),
+    request_body = CreateTenantRequest,
+    responses(
+        (status = 201, description = "Tenant created; share its edit key with the organization", body = Tenant),
+        (status = 400, description = "Invalid slug", body = ErrorResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 409, description = "Slug already in use", body = ErrorResponse)
+    )
+)]
+pub async fn create_tenant(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Json(request): Json<CreateTenantRequest>,
+) -> Result<(StatusCode, Json<Tenant>), (StatusCode, Json<ErrorResponse>)> {
+    require_admin(&headers, &state)?;
+
+    let slug = request.slug.trim().to_string();
+    if !is_valid_slug(&slug) {
+        return Err(error_response(
+            StatusCode::BAD_REQUEST,
+            format!(
+                "Slug must be 1-{} lowercase letters, digits or dashes, not starting with a dash",
+                MAX_SLUG_LEN
+            ),
+        ));
+    }
+    if state.tenants.contains(&slug) {
+        return Err(error_response(StatusCode::CONFLICT, format!("Tenant {} already exists", slug)));
+    }
+
+    let tenant = Tenant {
+        slug,
+        name: request.name.trim().to_string(),
+        edit_key: Uuid::new_v4().to_string(),
+        phonebank_key: Uuid::new_v4().to_string(),
+        editor_key: Some(Uuid::new_v4().to_string()),
+        created_at: chrono::Utc::now().to_rfc3339(),
+    };
+
+    let config = ThermometerConfig {
+        organization_name: tenant.name.clone(),
+        ..ThermometerConfig::default()
+    };
+    let tenant_state = state.tenants.register(&state, &tenant);
+    let saved = async {
+        tenant_state.storage.save_config(&config).await?;
+        state.storage.save_tenant(&tenant).await
+    }
+    .await;
+    if let Err(e) = saved {
+        state.tenants.remove(&tenant.slug);
+        return Err(error_response(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("Failed to create tenant: {}", e),
+        ));
+    }
+
+    tracing::info!("Created tenant {}", tenant.slug);
+    Ok((StatusCode::CREATED, Json(tenant)))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/admin/tenants/{slug}",
+    tag = "Admin",
+    security(("edit_key" = [])),
+    params(("slug" = String, Path, description = "Tenant slug")),
+    responses(
+        (status = 204, description = "Tenant and its domain mappings no longer served; its stored data is kept"),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 404, description = "Tenant not found", body = ErrorResponse)
+    )
+)]
+pub async fn delete_tenant(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path(slug): Path<String>,
+) -> Result<StatusCode, (StatusCode, Json<ErrorResponse>)> {
+    require_admin(&headers, &state)?;
+
+    if !state.tenants.contains(&slug) {
+        return Err(error_response(StatusCode::NOT_FOUND, "Tenant not found"));
+    }
+    let storage_error =
+        |e| error_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to delete tenant: {}", e));
+    let domains = state.storage.list_domains().await.map_err(storage_error)?;
+    for domain in domains.iter().filter(|d| d.tenant == slug) {
+        state.storage.delete_domain(&domain.host).await.map_err(storage_error)?;
+        state.tenants.unmap_domain(&domain.host);
+    }
+    state.storage.delete_tenant(&slug).await.map_err(storage_error)?;
+    state.tenants.remove(&slug);
+
+    tracing::info!("Removed tenant {}", slug);
+    Ok(StatusCode::NO_CONTENT)
+}
+
+#[utoipa::path(
+    get,
+    path = "/admin/domains",
+    tag = "Admin",
+    security(("edit_key" = [])),
+    responses(
+        (status = 200, description = "Custom domains and the tenant each one serves", body = [DomainMapping]),
+        (status = 401, description = "Unauthorized", body = ErrorResponse)
+    )
+)]
+pub async fn list_domains(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+) -> Result<Json<Vec<DomainMapping>>, (StatusCode, Json<ErrorResponse>)> {
+    require_admin(&headers, &state)?;
+
+    let mut domains = state.storage.list_domains().await.map_err(|e| {
+        error_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to load domain mappings: {}", e))
+    })?;
+    domains.sort_by(|a, b| a.host.cmp(&b.host));
+    Ok(Json(domains))
+}
+
+#[utoipa::path(
+    put,
+    path = "/admin/domains/{host}",
+    tag = "Admin",
+    security(("edit_key" = [])),
+    params(("host" = String, Path, description = "Host name, e.g. `donate.happytails.org`")),
+    request_body = MapDomainRequest,
+    responses(
+        (status = 200, description = "Domain now serves the tenant", body = DomainMapping),
+        (status = 400, description = "Invalid host name", body = ErrorResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 404, description = "Tenant not found", body = ErrorResponse)
+    )
+)]
+pub async fn map_domain(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path(host): Path<String>,
+    Json(request): Json<MapDomainRequest>,
+) -> Result<Json<DomainMapping>, (StatusCode, Json<ErrorResponse>)> {
+    require_admin(&headers, &state)?;
+
+    let host = host.trim().trim_end_matches('.').to_ascii_lowercase();
+    if !is_valid_host(&host) {
+        return Err(error_response(StatusCode::BAD_REQUEST, format!("Invalid host name: {}", host)));
+    }
+    if request_host(&headers).as_deref() == Some(host.as_str()) {
+        // Otherwise the deployment's own admin endpoints would disappear behind the tenant
+        return Err(error_response(
+            StatusCode::BAD_REQUEST,
+            "Can't map the host this request was sent to",
+        ));
+    }
+    if !state.tenants.contains(&request.tenant) {
+        return Err(error_response(StatusCode::NOT_FOUND, "Tenant not found"));
+    }
+
+    let domain = DomainMapping {
+        host,
+        tenant: request.tenant,
+        created_at: chrono::Utc::now().to_rfc3339(),
+    };
+    state.storage.save_domain(&domain).await.map_err(|e| {
+        error_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to save domain mapping: {}", e))
+    })?;
+    state.tenants.map_domain(&domain.host, &domain.tenant);
+
+    tracing::info!("Mapped {} to tenant {}", domain.host, domain.tenant);
+    Ok(Json(domain))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/admin/domains/{host}",
+    tag = "Admin",
+    security(("edit_key" = [])),
+    params(("host" = String, Path, description = "Host name")),
+    responses(
+        (status = 204, description = "Domain mapping removed"),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 404, description = "Domain not mapped", body = ErrorResponse)
+    )
+)]
+pub async fn unmap_domain(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path(host): Path<String>,
+) -> Result<StatusCode, (StatusCode, Json<ErrorResponse>)> {
+    require_admin(&headers, &state)?;
+
+    let host = host.trim().trim_end_matches('.').to_ascii_lowercase();
+    if state.tenants.tenant_for_host(&host).is_none() {
+        return Err(error_response(StatusCode::NOT_FOUND, "Domain not mapped"));
+    }
+    state.storage.delete_domain(&host).await.map_err(|e| {
+        error_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to delete domain mapping: {}", e))
+    })?;
+    state.tenants.unmap_domain(&host);
+
+    tracing::info!("Removed domain mapping for {}", host);
+    Ok(StatusCode::NO_CONTENT)
+}
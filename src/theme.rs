@@ -0,0 +1,158 @@
+//! Campaign colors for the thermometer images, so a shelter can match its branding instead of
+//! the built-in Christmas red. Anything left unset keeps the color from `color_constants`.
+
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::color_constants::{dark, light};
+
+/// The `theme` section of the config
+#[derive(Debug, Clone, Default, Serialize, Deserialize, ToSchema)]
+#[serde(default)]
+pub struct ThemeSettings {
+    /// Overrides for the light images
+    pub light: Palette,
+    /// Overrides for the dark images
+    pub dark: Palette,
+}
+
+/// Hex colors (`#rgb` or `#rrggbb`), each replacing one built-in color
+#[derive(Debug, Clone, Default, Serialize, Deserialize, ToSchema)]
+#[serde(default)]
+pub struct Palette {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub background: Option<String>,
+    /// The headline
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub title_text: Option<String>,
+    /// The goal amount
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub text_primary: Option<String>,
+    /// Labels and the stale-figures notice
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub text_secondary: Option<String>,
+    /// Inside of the empty tube and bulb
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tube_fill: Option<String>,
+    /// Outline of the tube and bulb
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tube_stroke: Option<String>,
+    /// The fill is striped in these two colors
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fill_color_1: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fill_color_2: Option<String>,
+    /// The amount raised and percentage
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub achieved_text: Option<String>,
+}
+
+/// Colors an image is drawn with
+#[derive(Debug, Clone, Copy)]
+pub struct Colors<'a> {
+    pub background: &'a str,
+    pub title_text: &'a str,
+    pub text_primary: &'a str,
+    pub text_secondary: &'a str,
+    pub tube_fill: &'a str,
+    pub tube_stroke: &'a str,
+    pub fill_color_1: &'a str,
+    pub fill_color_2: &'a str,
+    pub achieved_text: &'a str,
+    pub marker_stroke: &'a str,
+    pub marker_text: &'a str,
+}
+
+const LIGHT: Colors<'static> = Colors {
+    background: light::BACKGROUND,
+    title_text: light::TITLE_TEXT,
+    text_primary: light::TEXT_PRIMARY,
+    text_secondary: light::TEXT_SECONDARY,
+    tube_fill: light::TUBE_FILL,
+    tube_stroke: light::TUBE_STROKE,
+    fill_color_1: light::FILL_COLOR_1,
+    fill_color_2: light::FILL_COLOR_2,
+    achieved_text: light::ACHIEVED_TEXT,
+    marker_stroke: light::MARKER_STROKE,
+    marker_text: light::MARKER_TEXT,
+};
+
+const DARK: Colors<'static> = Colors {
+    background: dark::BACKGROUND,
+    title_text: dark::TITLE_TEXT,
+    text_primary: dark::TEXT_PRIMARY,
+    text_secondary: dark::TEXT_SECONDARY,
+    tube_fill: dark::TUBE_FILL,
+    tube_stroke: dark::TUBE_STROKE,
+    fill_color_1: dark::FILL_COLOR_1,
+    fill_color_2: dark::FILL_COLOR_2,
+    achieved_text: dark::ACHIEVED_TEXT,
+    marker_stroke: dark::MARKER_STROKE,
+    marker_text: dark::MARKER_TEXT,
+};
+
+impl Palette {
+    fn entries(&self) -> [(&'static str, &Option<String>); 9] {
+        [
+            ("background", &self.background),
+            ("title_text", &self.title_text),
+            ("text_primary", &self.text_primary),
+            ("text_secondary", &self.text_secondary),
+            ("tube_fill", &self.tube_fill),
+            ("tube_stroke", &self.tube_stroke),
+            ("fill_color_1", &self.fill_color_1),
+            ("fill_color_2", &self.fill_color_2),
+            ("achieved_text", &self.achieved_text),
+        ]
+    }
+
+    /// `defaults` with this palette's colors in place of theirs
+    fn over<'a>(&'a self, defaults: Colors<'a>) -> Colors<'a> {
+        let pick = |color: &'a Option<String>, default: &'a str| color.as_deref().unwrap_or(default);
+        Colors {
+            background: pick(&self.background, defaults.background),
+            title_text: pick(&self.title_text, defaults.title_text),
+            text_primary: pick(&self.text_primary, defaults.text_primary),
+            text_secondary: pick(&self.text_secondary, defaults.text_secondary),
+            tube_fill: pick(&self.tube_fill, defaults.tube_fill),
+            tube_stroke: pick(&self.tube_stroke, defaults.tube_stroke),
+            fill_color_1: pick(&self.fill_color_1, defaults.fill_color_1),
+            fill_color_2: pick(&self.fill_color_2, defaults.fill_color_2),
+            achieved_text: pick(&self.achieved_text, defaults.achieved_text),
+            ..defaults
+        }
+    }
+}
+
+impl ThemeSettings {
+    /// The colors of a light or dark image
+    pub fn colors(&self, dark_mode: bool) -> Colors<'_> {
+        if dark_mode {
+            self.dark.over(DARK)
+        } else {
+            self.light.over(LIGHT)
+        }
+    }
+
+    /// Every color must be hex, since they're written into the SVG as they are
+    pub fn validate(&self) -> Result<(), String> {
+        for (mode, palette) in [("light", &self.light), ("dark", &self.dark)] {
+            for (name, color) in palette.entries() {
+                if let Some(color) = color.as_deref().filter(|c| !is_hex_color(c)) {
+                    return Err(format!(
+                        "theme.{}.{} must be a hex color like #2B8A3E, not {:?}",
+                        mode, name, color
+                    ));
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// `#rgb` or `#rrggbb`
+pub fn is_hex_color(color: &str) -> bool {
+    color
+        .strip_prefix('#')
+        .is_some_and(|hex| matches!(hex.len(), 3 | 6) && hex.chars().all(|c| c.is_ascii_hexdigit()))
+}
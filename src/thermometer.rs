@@ -1,12 +1,86 @@
 use askama::Template;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
 use crate::ThermometerConfig;
 use crate::color_constants;
+use crate::i18n::{Catalog, Lang};
+use crate::milestones;
+
+/// Smallest and largest layout width accepted for the thermometer SVG
+pub const MIN_WIDTH: u32 = 200;
+pub const MAX_WIDTH: u32 = 4000;
+/// Longest watermark drawn; anything past it is cut off so it stays in its corner
+const WATERMARK_LIMIT: usize = 60;
+/// Frames in the animated GIF and how long each is shown, in hundredths of a second (2 seconds)
+const ANIMATION_FRAMES: usize = 20;
+const FRAME_DELAY: u16 = 10;
+/// NeuQuant sampling factor for the GIF palette, 1 (best) to 30 (fastest)
+const GIF_QUANTIZER_SPEED: i32 = 10;
+
+/// Named output sizes so embeds don't have to guess `scale=` values
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum SizePreset {
+    /// 600×720, fits the usual 600px email column
+    Email,
+    /// 2400×2880 for printing
+    Poster,
+    /// 1200×1440 for social media posts
+    Social,
+    /// 200×240 for link previews and sidebars
+    Thumbnail,
+}
+
+impl SizePreset {
+    /// Layout width of the SVG and the PNG scale applied to it. Small outputs are laid out at a
+    /// smaller width rather than scaled down further, so strokes stay visible.
+    pub fn dimensions(self) -> (u32, f32) {
+        match self {
+            SizePreset::Email => (600, 1.0),
+            SizePreset::Poster => (800, 3.0),
+            SizePreset::Social => (600, 2.0),
+            SizePreset::Thumbnail => (400, 0.5),
+        }
+    }
+}
+
+/// Defaults for thermometer images requested without `size=` or `scale=`
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[serde(default)]
+pub struct ImageSettings {
+    /// Layout width of the SVG in pixels (200 to 4000)
+    pub default_width: u32,
+    /// PNG scale factor (0.1 to 5.0)
+    pub default_scale: f32,
+    /// Ids of this deployment's (or tenant's) other campaigns, shown as bars under the thermometer
+    /// with `?strip=campaigns`, after this one
+    pub comparison: Vec<String>,
+    /// Small text (e.g. `give.pawsrescue.org`) in the bottom-right corner of thermometer images,
+    /// so copies re-shared without context still lead back to the campaign. Off when unset
+    pub watermark: Option<String>,
+    /// Layout of thermometer images requested without `layout=`
+    pub layout: Layout,
+}
+
+impl Default for ImageSettings {
+    fn default() -> Self {
+        Self {
+            default_width: 800,
+            default_scale: 1.0,
+            comparison: vec![],
+            watermark: None,
+            layout: Layout::Vertical,
+        }
+    }
+}
 
 #[derive(Template)]
 #[template(path = "thermometer-light.svg")]
-struct ThermometerLightTemplate {
+struct ThermometerLightTemplate<'a> {
     width: u32,
     height: u32,
+    headline: String,
     title_x: String,
     title_y: String,
     title_font_size: String,
@@ -23,10 +97,28 @@ struct ThermometerLightTemplate {
     bulb_radius: String,
     bulb_fill_radius: String,
     percentage_markers: Vec<PercentageMarker>,
+    milestone_ticks: Vec<MilestoneTick>,
+    segments: Vec<SegmentRect>,
+    legend: Vec<LegendEntry>,
+    strip: Vec<StripRow>,
     text_x: String,
     achieved_y: String,
     achieved_amount: String,
     achieved_label_y: String,
+    /// e.g. `≈ 34 animals helped`, under the achieved amount
+    animals_helped: Option<String>,
+    animals_y: String,
+    announcement: Option<AnnouncementBanner>,
+    match_badge: Option<MatchBadge>,
+    /// e.g. `Figures as of Dec 5, 2025`, along the bottom once the figures are stale
+    stale_notice: Option<String>,
+    stale_y: String,
+    stale_font_size: String,
+    /// `image.watermark`, in the bottom-right corner
+    watermark: Option<String>,
+    watermark_x: String,
+    watermark_y: String,
+    watermark_font_size: String,
     goal_y: String,
     goal_amount: String,
     goal_label_y: String,
@@ -37,25 +129,27 @@ struct ThermometerLightTemplate {
     label_font_size: String,
     percent_font_size: String,
     percent_label_font_size: String,
-    // Color constants
-    background_color: &'static str,
-    title_text_color: &'static str,
-    text_primary_color: &'static str,
-    text_secondary_color: &'static str,
-    tube_fill_color: &'static str,
-    tube_stroke_color: &'static str,
-    fill_color_1: &'static str,
-    fill_color_2: &'static str,
-    achieved_text_color: &'static str,
-    marker_stroke_color: &'static str,
-    marker_text_color: &'static str,
+    text: &'static Catalog,
+    // Theme colors
+    background_color: &'a str,
+    title_text_color: &'a str,
+    text_primary_color: &'a str,
+    text_secondary_color: &'a str,
+    tube_fill_color: &'a str,
+    tube_stroke_color: &'a str,
+    fill_color_1: &'a str,
+    fill_color_2: &'a str,
+    achieved_text_color: &'a str,
+    marker_stroke_color: &'a str,
+    marker_text_color: &'a str,
 }
 
 #[derive(Template)]
 #[template(path = "thermometer-dark.svg")]
-struct ThermometerDarkTemplate {
+struct ThermometerDarkTemplate<'a> {
     width: u32,
     height: u32,
+    headline: String,
     title_x: String,
     title_y: String,
     title_font_size: String,
@@ -72,10 +166,28 @@ struct ThermometerDarkTemplate {
     bulb_radius: String,
     bulb_fill_radius: String,
     percentage_markers: Vec<PercentageMarker>,
+    milestone_ticks: Vec<MilestoneTick>,
+    segments: Vec<SegmentRect>,
+    legend: Vec<LegendEntry>,
+    strip: Vec<StripRow>,
     text_x: String,
     achieved_y: String,
     achieved_amount: String,
     achieved_label_y: String,
+    /// e.g. `≈ 34 animals helped`, under the achieved amount
+    animals_helped: Option<String>,
+    animals_y: String,
+    announcement: Option<AnnouncementBanner>,
+    match_badge: Option<MatchBadge>,
+    /// e.g. `Figures as of Dec 5, 2025`, along the bottom once the figures are stale
+    stale_notice: Option<String>,
+    stale_y: String,
+    stale_font_size: String,
+    /// `image.watermark`, in the bottom-right corner
+    watermark: Option<String>,
+    watermark_x: String,
+    watermark_y: String,
+    watermark_font_size: String,
     goal_y: String,
     goal_amount: String,
     goal_label_y: String,
@@ -86,18 +198,150 @@ struct ThermometerDarkTemplate {
     label_font_size: String,
     percent_font_size: String,
     percent_label_font_size: String,
-    // Color constants
-    background_color: &'static str,
-    title_text_color: &'static str,
-    text_primary_color: &'static str,
-    text_secondary_color: &'static str,
-    tube_fill_color: &'static str,
-    tube_stroke_color: &'static str,
-    fill_color_1: &'static str,
-    fill_color_2: &'static str,
-    achieved_text_color: &'static str,
-    marker_stroke_color: &'static str,
-    marker_text_color: &'static str,
+    text: &'static Catalog,
+    // Theme colors
+    background_color: &'a str,
+    title_text_color: &'a str,
+    text_primary_color: &'a str,
+    text_secondary_color: &'a str,
+    tube_fill_color: &'a str,
+    tube_stroke_color: &'a str,
+    fill_color_1: &'a str,
+    fill_color_2: &'a str,
+    achieved_text_color: &'a str,
+    marker_stroke_color: &'a str,
+    marker_text_color: &'a str,
+}
+
+/// `layout=horizontal`: one template for both themes, since only the colors differ
+#[derive(Template)]
+#[template(path = "thermometer-horizontal.svg")]
+struct ThermometerHorizontalTemplate<'a> {
+    width: u32,
+    height: u32,
+    headline: String,
+    title_x: String,
+    title_y: String,
+    title_font_size: String,
+    tube_x: String,
+    tube_y: String,
+    tube_width: String,
+    tube_height: String,
+    fill_y: String,
+    fill_width: String,
+    fill_height: String,
+    bulb_center_x: String,
+    bulb_center_y: String,
+    bulb_radius: String,
+    bulb_fill_radius: String,
+    percentage_markers: Vec<HorizontalMarker>,
+    milestone_ticks: Vec<MilestoneTick>,
+    segments: Vec<HorizontalSegment>,
+    strip: Vec<StripRow>,
+    percent_x: String,
+    percent_y: String,
+    progress_percent: String,
+    percent_font_size: String,
+    text_x: String,
+    achieved_y: String,
+    achieved_amount: String,
+    achieved_label_y: String,
+    animals_helped: Option<String>,
+    animals_y: String,
+    goal_y: String,
+    goal_amount: String,
+    goal_label_y: String,
+    amount_font_size: String,
+    label_font_size: String,
+    announcement: Option<AnnouncementBanner>,
+    stale_notice: Option<String>,
+    stale_y: String,
+    stale_font_size: String,
+    watermark: Option<String>,
+    watermark_x: String,
+    watermark_y: String,
+    watermark_font_size: String,
+    text: &'static Catalog,
+    // Theme colors
+    background_color: &'a str,
+    title_text_color: &'a str,
+    text_primary_color: &'a str,
+    text_secondary_color: &'a str,
+    tube_fill_color: &'a str,
+    tube_stroke_color: &'a str,
+    fill_color_1: &'a str,
+    fill_color_2: &'a str,
+    achieved_text_color: &'a str,
+    marker_stroke_color: &'a str,
+    marker_text_color: &'a str,
+}
+
+/// Full-width strip across the top of the image for the current announcement
+#[derive(Clone)]
+struct AnnouncementBanner {
+    text: String,
+    color: &'static str,
+    height: String,
+    text_y: String,
+    font_size: String,
+}
+
+/// Countdown badge between the title and the achieved amount while a gift match is running
+#[derive(Clone)]
+struct MatchBadge {
+    text: String,
+    x: String,
+    y: String,
+    width: String,
+    height: String,
+    text_y: String,
+    font_size: String,
+    color: &'static str,
+    text_color: &'static str,
+}
+
+#[derive(Template)]
+#[template(path = "thermometer-thumb.svg")]
+struct ThermometerThumbTemplate<'a> {
+    size: u32,
+    tube_x: String,
+    tube_y: String,
+    tube_width: String,
+    tube_height: String,
+    tube_radius: String,
+    stroke_width: String,
+    fill_x: String,
+    fill_y: String,
+    fill_width: String,
+    fill_height: String,
+    bulb_center_x: String,
+    bulb_center_y: String,
+    bulb_radius: String,
+    bulb_fill_radius: String,
+    text_x: String,
+    percent_y: String,
+    progress_percent: String,
+    percent_font_size: String,
+    /// "of goal", or the celebration headline once the goal is reached
+    label: String,
+    label_y: String,
+    label_font_size: String,
+    amount_y: String,
+    achieved_amount: String,
+    amount_font_size: String,
+    /// What the total funds, e.g. `Funds 120 vaccines`
+    impact: Option<String>,
+    impact_x: String,
+    impact_y: String,
+    impact_font_size: String,
+    // Theme colors
+    background_color: &'a str,
+    text_primary_color: &'a str,
+    text_secondary_color: &'a str,
+    tube_fill_color: &'a str,
+    tube_stroke_color: &'a str,
+    fill_color: &'a str,
+    achieved_text_color: &'a str,
 }
 
 #[derive(Debug, Clone)]
@@ -111,21 +355,249 @@ struct PercentageMarker {
     percentage: i32,
 }
 
-/// Generate an SVG thermometer image based on the configuration
-pub fn generate_thermometer_svg(config: &ThermometerConfig, width: u32, dark_mode: bool) -> String {
-    let total_raised: f64 = config.teams.iter().map(|t| t.total_raised).sum();
-    let progress_percent = if config.goal > 0.0 {
-        ((total_raised / config.goal) * 100.0).min(100.0)
-    } else {
+/// A labeled line across the tube at a milestone
+#[derive(Debug, Clone)]
+struct MilestoneTick {
+    x1: String,
+    y1: String,
+    x2: String,
+    y2: String,
+    text_x: String,
+    text_y: String,
+    font_size: String,
+    /// `start` beside the upright tube, `middle` above the horizontal one
+    anchor: &'static str,
+    label: String,
+}
+
+/// A slice of the total drawn as its own band in the stacked breakdown
+#[derive(Debug, Clone)]
+pub struct FillSegment {
+    pub label: String,
+    pub amount: f64,
+    pub color: &'static str,
+}
+
+#[derive(Debug, Clone)]
+struct SegmentRect {
+    y: String,
+    height: String,
+    color: &'static str,
+}
+
+/// A breakdown band along the horizontal tube
+#[derive(Debug, Clone)]
+struct HorizontalSegment {
+    x: String,
+    width: String,
+    color: &'static str,
+}
+
+/// A tick below the horizontal tube
+#[derive(Debug, Clone)]
+struct HorizontalMarker {
+    x: String,
+    line_y1: String,
+    line_y2: String,
+    text_y: String,
+    font_size: String,
+    percentage: i32,
+}
+
+/// One campaign's bar in the comparison strip under the thermometer
+#[derive(Debug, Clone)]
+pub struct ComparisonBar {
+    pub label: String,
+    pub total_raised: f64,
+    pub goal: f64,
+}
+
+#[derive(Debug, Clone)]
+struct StripRow {
+    label_x: String,
+    text_y: String,
+    amount_x: String,
+    font_size: String,
+    track_y: String,
+    track_width: String,
+    track_height: String,
+    fill_width: String,
+    label: String,
+    amount: String,
+}
+
+#[derive(Debug, Clone)]
+struct LegendEntry {
+    swatch_x: String,
+    swatch_y: String,
+    swatch_size: String,
+    text_x: String,
+    text_y: String,
+    font_size: String,
+    color: &'static str,
+    label: String,
+}
+
+/// Shape of the thermometer image
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum Layout {
+    /// Upright thermometer with the figures beside it (height 1.2× the width)
+    #[default]
+    Vertical,
+    /// Wide banner: the tube lies on its side with the markers below and the figures to the right
+    /// (height 0.3× the width)
+    Horizontal,
+}
+
+/// How a thermometer image is drawn, apart from the config
+#[derive(Debug, Clone, Copy)]
+pub struct ThermometerStyle<'a> {
+    /// Layout width of the SVG in pixels
+    pub width: u32,
+    pub dark_mode: bool,
+    pub layout: Layout,
+    /// Replaces "Our Goal" at the top, e.g. for an experiment variant
+    pub headline: Option<&'a str>,
+    /// Language of the labels
+    pub lang: Lang,
+}
+
+impl ThermometerStyle<'_> {
+    /// The usual upright English thermometer
+    pub fn new(width: u32, dark_mode: bool) -> Self {
+        Self {
+            width,
+            dark_mode,
+            layout: Layout::Vertical,
+            headline: None,
+            lang: Lang::En,
+        }
+    }
+}
+
+/// Generate an SVG thermometer image based on the configuration.
+/// When `breakdown` is non-empty the fill is drawn as stacked bands instead of stripes.
+/// When `strip` is non-empty the image grows downward to fit one small progress bar per campaign.
+pub fn generate_thermometer_svg(
+    config: &ThermometerConfig,
+    style: &ThermometerStyle,
+    breakdown: &[FillSegment],
+    strip: &[ComparisonBar],
+) -> Result<String, askama::Error> {
+    thermometer_svg(config, style, breakdown, strip, 1.0)
+}
+
+/// Frames of the mercury rising from empty to the current total, slowing as it nears it. The
+/// figures beside the thermometer show the current totals throughout.
+pub fn generate_rising_frames(
+    config: &ThermometerConfig,
+    style: &ThermometerStyle,
+    breakdown: &[FillSegment],
+    strip: &[ComparisonBar],
+) -> Result<Vec<String>, askama::Error> {
+    (0..ANIMATION_FRAMES)
+        .map(|i| {
+            let t = i as f64 / (ANIMATION_FRAMES - 1) as f64;
+            let eased = 1.0 - (1.0 - t).powi(3);
+            thermometer_svg(config, style, breakdown, strip, eased)
+        })
+        .collect()
+}
+
+/// Rows of the comparison strip starting at `top`, and the height they take up
+fn strip_rows(strip: &[ComparisonBar], width: u32, top: f64, text: &Catalog) -> (Vec<StripRow>, f64) {
+    let strip_font_size = width as f64 * 0.025;
+    let strip_row_height = strip_font_size * 2.8;
+    let strip_top = top + strip_font_size * 2.0;
+    let strip_height = if strip.is_empty() {
         0.0
+    } else {
+        strip_font_size * 3.0 + strip.len() as f64 * strip_row_height
     };
+    let strip_x = width as f64 * 0.05;
+    let strip_width = width as f64 * 0.9;
+    let rows = strip
+        .iter()
+        .enumerate()
+        .map(|(i, bar)| {
+            let row_top = strip_top + i as f64 * strip_row_height;
+            let share = if bar.goal > 0.0 { (bar.total_raised / bar.goal).clamp(0.0, 1.0) } else { 0.0 };
+            StripRow {
+                label_x: format!("{:.2}", strip_x),
+                text_y: format!("{:.2}", row_top + strip_font_size),
+                amount_x: format!("{:.2}", strip_x + strip_width),
+                font_size: format!("{:.2}", strip_font_size),
+                track_y: format!("{:.2}", row_top + strip_font_size * 1.4),
+                track_width: format!("{:.2}", strip_width),
+                track_height: format!("{:.2}", strip_font_size * 0.8),
+                fill_width: format!("{:.2}", strip_width * share),
+                label: bar.label.clone(),
+                amount: text.amount_of_goal(bar.total_raised, bar.goal, share * 100.0),
+            }
+        })
+        .collect();
+    (rows, strip_height)
+}
+
+/// Where the goal sits on the gauge: 1.0, or less when a stretch goal extends it
+fn goal_share(config: &ThermometerConfig) -> f64 {
+    let top = milestones::gauge_top(config);
+    if top > 0.0 {
+        config.goal / top
+    } else {
+        1.0
+    }
+}
+
+/// `image.watermark` as it's drawn, or None when it's off
+fn watermark(config: &ThermometerConfig) -> Option<String> {
+    config
+        .image
+        .watermark
+        .as_deref()
+        .map(str::trim)
+        .filter(|text| !text.is_empty())
+        .map(|text| text.chars().take(WATERMARK_LIMIT).collect())
+}
+
+fn announcement_banner(config: &ThermometerConfig, width: u32) -> Option<AnnouncementBanner> {
+    crate::announcement::active(config).map(|a| AnnouncementBanner {
+        text: a.image_text(),
+        color: a.level.color(),
+        height: format!("{:.2}", width as f64 * 0.06),
+        text_y: format!("{:.2}", width as f64 * 0.04),
+        font_size: format!("{:.2}", width as f64 * 0.025),
+    })
+}
+
+/// The thermometer with its fill drawn at `fill` (0.0 to 1.0) of the current progress
+fn thermometer_svg(
+    config: &ThermometerConfig,
+    style: &ThermometerStyle,
+    breakdown: &[FillSegment],
+    strip: &[ComparisonBar],
+    fill: f64,
+) -> Result<String, askama::Error> {
+    if style.layout == Layout::Horizontal {
+        return horizontal_svg(config, style, breakdown, strip, fill);
+    }
+    let (width, dark_mode) = (style.width, style.dark_mode);
+    let total_raised = config.total_raised();
+    let text = style.lang.catalog();
+    let colors = config.theme.colors(dark_mode);
+    let headline = style.headline.unwrap_or(text.our_goal).to_string();
+    let progress_percent = milestones::percent_of_goal(config);
 
-    // Calculate dimensions based on width
-    let height = (width as f64 * 1.2) as u32; // Maintain aspect ratio
+    // Calculate dimensions based on width; the comparison strip is added below the usual layout
+    let base_height = width as f64 * 1.2; // Maintain aspect ratio
+    let (strip, strip_height) = strip_rows(strip, width, base_height, text);
+    let height = (base_height + strip_height) as u32;
+    let base_height = base_height as u32;
     let thermometer_width = width as f64 * 0.35;
-    let thermometer_height = height as f64 * 0.6;
+    let thermometer_height = base_height as f64 * 0.6;
     let thermometer_x = width as f64 * 0.1;
-    let thermometer_y = height as f64 * 0.15;
+    let thermometer_y = base_height as f64 * 0.15;
 
     // Thermometer dimensions
     let bulb_radius = thermometer_width * 0.4;
@@ -136,16 +608,74 @@ pub fn generate_thermometer_svg(config: &ThermometerConfig, width: u32, dark_mod
     let bulb_center_x = thermometer_x + thermometer_width / 2.0;
     let bulb_center_y = tube_y + tube_height + bulb_radius;
 
-    // Fill height based on progress
-    let fill_height = (tube_height * progress_percent / 100.0).max(0.0);
+    // Fill height based on progress; a stretch goal makes the tube run past the goal
+    let fill_height = (tube_height * milestones::fill_fraction(config) * fill).max(0.0);
+    let goal_share = goal_share(config);
     let fill_y = tube_y + tube_height - fill_height;
 
+    // Stacked breakdown bands, bottom up, scaled so they exactly cover the fill
+    let breakdown_total: f64 = breakdown.iter().map(|s| s.amount).sum();
+    let mut band_bottom = tube_y + tube_height;
+    let segments: Vec<SegmentRect> = breakdown
+        .iter()
+        .map(|segment| {
+            let band_height = if breakdown_total > 0.0 {
+                fill_height * segment.amount / breakdown_total
+            } else {
+                0.0
+            };
+            band_bottom -= band_height;
+            SegmentRect {
+                y: format!("{:.2}", band_bottom),
+                height: format!("{:.2}", band_height),
+                color: segment.color,
+            }
+        })
+        .collect();
+
+    // Legend below the percentage text
+    let legend_font_size = width as f64 * 0.02;
+    let legend_x = width as f64 * 0.42;
+    let legend: Vec<LegendEntry> = breakdown
+        .iter()
+        .enumerate()
+        .map(|(i, segment)| {
+            let row_y = base_height as f64 * 0.82 + i as f64 * legend_font_size * 1.3;
+            LegendEntry {
+                swatch_x: format!("{:.2}", legend_x),
+                swatch_y: format!("{:.2}", row_y - legend_font_size * 0.8),
+                swatch_size: format!("{:.2}", legend_font_size),
+                text_x: format!("{:.2}", legend_x + legend_font_size * 1.5),
+                text_y: format!("{:.2}", row_y),
+                font_size: format!("{:.2}", legend_font_size),
+                color: segment.color,
+                label: format!("{} ${:.2}", segment.label, segment.amount),
+            }
+        })
+        .collect();
+
+    let animals_helped = crate::impact::animals_helped(config).map(|n| text.animals_helped(n));
+    let stale_notice = crate::staleness::notice(config, style.lang);
+    let watermark = watermark(config);
+    let match_badge = crate::matching::countdown(config).map(|text| MatchBadge {
+        text,
+        x: format!("{:.2}", width as f64 * 0.34),
+        y: format!("{:.2}", width as f64 * 0.18),
+        width: format!("{:.2}", width as f64 * 0.42),
+        height: format!("{:.2}", width as f64 * 0.06),
+        text_y: format!("{:.2}", width as f64 * 0.219),
+        font_size: format!("{:.2}", width as f64 * 0.024),
+        color: color_constants::matching::BADGE,
+        text_color: color_constants::matching::BADGE_TEXT,
+    });
+    let announcement = announcement_banner(config, width);
+
     // Text positioning
     let text_x = width as f64 * 0.55;
-    let title_y = height as f64 * 0.1;
-    let achieved_y = height as f64 * 0.35;
-    let goal_y = height as f64 * 0.55;
-    let percent_y = height as f64 * 0.75;
+    let title_y = base_height as f64 * 0.1;
+    let achieved_y = base_height as f64 * 0.35;
+    let goal_y = base_height as f64 * 0.55;
+    let percent_y = base_height as f64 * 0.75;
 
     // Generate percentage markers
     let percentages = [100, 80, 60, 40, 20, 0];
@@ -155,7 +685,7 @@ pub fn generate_thermometer_svg(config: &ThermometerConfig, width: u32, dark_mod
     let percentage_markers: Vec<PercentageMarker> = percentages
         .iter()
         .map(|&p| {
-            let y = tube_y + tube_height * (1.0 - p as f64 / 100.0);
+            let y = tube_y + tube_height * (1.0 - p as f64 / 100.0 * goal_share);
             let marker_x = tube_x - marker_length - 5.0;
             let text_x = marker_x - 5.0;
 
@@ -171,10 +701,31 @@ pub fn generate_thermometer_svg(config: &ThermometerConfig, width: u32, dark_mod
         })
         .collect();
 
+    let milestone_font_size = width as f64 * 0.018;
+    let milestone_ticks: Vec<MilestoneTick> = milestones::on_gauge(config)
+        .into_iter()
+        .map(|(height, milestone)| {
+            let y = tube_y + tube_height * (1.0 - height);
+            let x2 = tube_x + tube_width + marker_length;
+            MilestoneTick {
+                x1: format!("{:.2}", tube_x),
+                y1: format!("{:.2}", y),
+                x2: format!("{:.2}", x2),
+                y2: format!("{:.2}", y),
+                text_x: format!("{:.2}", x2 + 4.0),
+                text_y: format!("{:.2}", y + milestone_font_size * 0.35),
+                font_size: format!("{:.2}", milestone_font_size),
+                anchor: "start",
+                label: milestone.short_label(),
+            }
+        })
+        .collect();
+
     if dark_mode {
         let template = ThermometerDarkTemplate {
             width,
             height,
+            headline: headline.clone(),
             title_x: format!("{:.2}", width as f64 / 2.0),
             title_y: format!("{:.2}", title_y),
             title_font_size: format!("{:.2}", width as f64 * 0.035),
@@ -191,10 +742,25 @@ pub fn generate_thermometer_svg(config: &ThermometerConfig, width: u32, dark_mod
             bulb_radius: format!("{:.2}", bulb_radius),
             bulb_fill_radius: format!("{:.2}", bulb_radius - 3.0),
             percentage_markers: percentage_markers.clone(),
+            milestone_ticks: milestone_ticks.clone(),
+            segments: segments.clone(),
+            legend: legend.clone(),
+            strip: strip.clone(),
             text_x: format!("{:.2}", text_x),
             achieved_y: format!("{:.2}", achieved_y),
             achieved_amount: format!("{:.2}", total_raised),
             achieved_label_y: format!("{:.2}", achieved_y + width as f64 * 0.03),
+            animals_helped: animals_helped.clone(),
+            animals_y: format!("{:.2}", achieved_y + width as f64 * 0.075),
+            announcement: announcement.clone(),
+            match_badge: match_badge.clone(),
+            stale_notice: stale_notice.clone(),
+            stale_y: format!("{:.2}", base_height as f64 - width as f64 * 0.02),
+            stale_font_size: format!("{:.2}", width as f64 * 0.02),
+            watermark: watermark.clone(),
+            watermark_x: format!("{:.2}", width as f64 * 0.98),
+            watermark_y: format!("{:.2}", height as f64 - width as f64 * 0.006),
+            watermark_font_size: format!("{:.2}", width as f64 * 0.014),
             goal_y: format!("{:.2}", goal_y),
             goal_amount: format!("{:.2}", config.goal),
             goal_label_y: format!("{:.2}", goal_y + width as f64 * 0.03),
@@ -205,28 +771,27 @@ pub fn generate_thermometer_svg(config: &ThermometerConfig, width: u32, dark_mod
             label_font_size: format!("{:.2}", width as f64 * 0.025),
             percent_font_size: format!("{:.2}", width as f64 * 0.09),
             percent_label_font_size: format!("{:.2}", width as f64 * 0.022),
-            // Color constants
-            background_color: color_constants::dark::BACKGROUND,
-            title_text_color: color_constants::dark::TITLE_TEXT,
-            text_primary_color: color_constants::dark::TEXT_PRIMARY,
-            text_secondary_color: color_constants::dark::TEXT_SECONDARY,
-            tube_fill_color: color_constants::dark::TUBE_FILL,
-            tube_stroke_color: color_constants::dark::TUBE_STROKE,
-            fill_color_1: color_constants::dark::FILL_COLOR_1,
-            fill_color_2: color_constants::dark::FILL_COLOR_2,
-            achieved_text_color: color_constants::dark::ACHIEVED_TEXT,
-            marker_stroke_color: color_constants::dark::MARKER_STROKE,
-            marker_text_color: color_constants::dark::MARKER_TEXT,
+            text,
+            // Theme colors
+            background_color: colors.background,
+            title_text_color: colors.title_text,
+            text_primary_color: colors.text_primary,
+            text_secondary_color: colors.text_secondary,
+            tube_fill_color: colors.tube_fill,
+            tube_stroke_color: colors.tube_stroke,
+            fill_color_1: colors.fill_color_1,
+            fill_color_2: colors.fill_color_2,
+            achieved_text_color: colors.achieved_text,
+            marker_stroke_color: colors.marker_stroke,
+            marker_text_color: colors.marker_text,
         };
 
-        template.render().unwrap_or_else(|e| {
-            eprintln!("Failed to render thermometer template: {}", e);
-            String::from("<svg><text>Error rendering thermometer</text></svg>")
-        })
+        template.render()
     } else {
         let template = ThermometerLightTemplate {
             width,
             height,
+            headline,
             title_x: format!("{:.2}", width as f64 / 2.0),
             title_y: format!("{:.2}", title_y),
             title_font_size: format!("{:.2}", width as f64 * 0.035),
@@ -243,10 +808,25 @@ pub fn generate_thermometer_svg(config: &ThermometerConfig, width: u32, dark_mod
             bulb_radius: format!("{:.2}", bulb_radius),
             bulb_fill_radius: format!("{:.2}", bulb_radius - 3.0),
             percentage_markers,
+            milestone_ticks,
+            segments,
+            legend,
+            strip,
             text_x: format!("{:.2}", text_x),
             achieved_y: format!("{:.2}", achieved_y),
             achieved_amount: format!("{:.2}", total_raised),
             achieved_label_y: format!("{:.2}", achieved_y + width as f64 * 0.03),
+            animals_helped,
+            animals_y: format!("{:.2}", achieved_y + width as f64 * 0.075),
+            announcement,
+            match_badge,
+            stale_notice,
+            stale_y: format!("{:.2}", base_height as f64 - width as f64 * 0.02),
+            stale_font_size: format!("{:.2}", width as f64 * 0.02),
+            watermark,
+            watermark_x: format!("{:.2}", width as f64 * 0.98),
+            watermark_y: format!("{:.2}", height as f64 - width as f64 * 0.006),
+            watermark_font_size: format!("{:.2}", width as f64 * 0.014),
             goal_y: format!("{:.2}", goal_y),
             goal_amount: format!("{:.2}", config.goal),
             goal_label_y: format!("{:.2}", goal_y + width as f64 * 0.03),
@@ -257,32 +837,362 @@ pub fn generate_thermometer_svg(config: &ThermometerConfig, width: u32, dark_mod
             label_font_size: format!("{:.2}", width as f64 * 0.025),
             percent_font_size: format!("{:.2}", width as f64 * 0.09),
             percent_label_font_size: format!("{:.2}", width as f64 * 0.022),
-            // Color constants
-            background_color: color_constants::light::BACKGROUND,
-            title_text_color: color_constants::light::TITLE_TEXT,
-            text_primary_color: color_constants::light::TEXT_PRIMARY,
-            text_secondary_color: color_constants::light::TEXT_SECONDARY,
-            tube_fill_color: color_constants::light::TUBE_FILL,
-            tube_stroke_color: color_constants::light::TUBE_STROKE,
-            fill_color_1: color_constants::light::FILL_COLOR_1,
-            fill_color_2: color_constants::light::FILL_COLOR_2,
-            achieved_text_color: color_constants::light::ACHIEVED_TEXT,
-            marker_stroke_color: color_constants::light::MARKER_STROKE,
-            marker_text_color: color_constants::light::MARKER_TEXT,
+            text,
+            // Theme colors
+            background_color: colors.background,
+            title_text_color: colors.title_text,
+            text_primary_color: colors.text_primary,
+            text_secondary_color: colors.text_secondary,
+            tube_fill_color: colors.tube_fill,
+            tube_stroke_color: colors.tube_stroke,
+            fill_color_1: colors.fill_color_1,
+            fill_color_2: colors.fill_color_2,
+            achieved_text_color: colors.achieved_text,
+            marker_stroke_color: colors.marker_stroke,
+            marker_text_color: colors.marker_text,
         };
 
-        template.render().unwrap_or_else(|e| {
-            eprintln!("Failed to render thermometer template: {}", e);
-            String::from("<svg><text>Error rendering thermometer</text></svg>")
+        template.render()
+    }
+}
+
+/// `layout=horizontal`: a banner with the bulb on the left, the tube running right with the
+/// markers below it, and the amounts in a column at the right. The breakdown legend and gift
+/// match badge are left out for lack of room; the bands are still drawn.
+fn horizontal_svg(
+    config: &ThermometerConfig,
+    style: &ThermometerStyle,
+    breakdown: &[FillSegment],
+    strip: &[ComparisonBar],
+    fill: f64,
+) -> Result<String, askama::Error> {
+    let width = style.width;
+    let w = width as f64;
+    let text = style.lang.catalog();
+    let colors = config.theme.colors(style.dark_mode);
+    let headline = style.headline.unwrap_or(text.our_goal).to_string();
+    let progress_percent = milestones::percent_of_goal(config);
+
+    // Everything sits below the announcement banner when there is one
+    let announcement = announcement_banner(config, width);
+    let top = if announcement.is_some() { w * 0.06 } else { 0.0 };
+    let base_height = top + w * 0.3;
+    let (strip, strip_height) = strip_rows(strip, width, base_height, text);
+    let height = (base_height + strip_height) as u32;
+
+    // The tube starts under the bulb and runs to the amounts column
+    let bulb_radius = w * 0.055;
+    let bulb_center_x = w * 0.1;
+    let bulb_center_y = top + w * 0.14;
+    let tube_height = w * 0.05;
+    let tube_x = bulb_center_x;
+    let tube_y = bulb_center_y - tube_height / 2.0;
+    let tube_width = w * 0.66 - tube_x;
+    let track_x = bulb_center_x + bulb_radius;
+    let track_width = w * 0.64 - track_x;
+
+    let fill_width = bulb_radius + (track_width * milestones::fill_fraction(config) * fill).max(0.0);
+    let goal_share = goal_share(config);
+
+    // Stacked breakdown bands, left to right, scaled so they exactly cover the fill
+    let breakdown_total: f64 = breakdown.iter().map(|s| s.amount).sum();
+    let mut band_left = tube_x;
+    let segments = breakdown
+        .iter()
+        .map(|segment| {
+            let band_width = if breakdown_total > 0.0 {
+                fill_width * segment.amount / breakdown_total
+            } else {
+                0.0
+            };
+            band_left += band_width;
+            HorizontalSegment {
+                x: format!("{:.2}", band_left - band_width),
+                width: format!("{:.2}", band_width),
+                color: segment.color,
+            }
+        })
+        .collect();
+
+    let marker_top = tube_y + tube_height + 5.0;
+    let marker_length = w * 0.015;
+    let marker_font_size = w * 0.018;
+    let percentage_markers = [0, 20, 40, 60, 80, 100]
+        .iter()
+        .map(|&p| HorizontalMarker {
+            x: format!("{:.2}", track_x + track_width * p as f64 / 100.0 * goal_share),
+            line_y1: format!("{:.2}", marker_top),
+            line_y2: format!("{:.2}", marker_top + marker_length),
+            text_y: format!("{:.2}", marker_top + marker_length + marker_font_size * 1.2),
+            font_size: format!("{:.2}", marker_font_size),
+            percentage: p,
         })
+        .collect();
+
+    let milestone_font_size = w * 0.016;
+    let milestone_ticks = milestones::on_gauge(config)
+        .into_iter()
+        .map(|(share, milestone)| {
+            let x = format!("{:.2}", track_x + track_width * share);
+            MilestoneTick {
+                x1: x.clone(),
+                y1: format!("{:.2}", tube_y - marker_length),
+                x2: x.clone(),
+                y2: format!("{:.2}", tube_y + tube_height),
+                text_x: x,
+                text_y: format!("{:.2}", tube_y - marker_length - 3.0),
+                font_size: format!("{:.2}", milestone_font_size),
+                anchor: "middle",
+                label: milestone.short_label(),
+            }
+        })
+        .collect();
+
+    let text_x = w * 0.83;
+    let achieved_y = top + w * 0.1;
+    let goal_y = top + w * 0.21;
+
+    ThermometerHorizontalTemplate {
+        width,
+        height,
+        headline,
+        title_x: format!("{:.2}", w / 2.0),
+        title_y: format!("{:.2}", top + w * 0.045),
+        title_font_size: format!("{:.2}", w * 0.03),
+        tube_x: format!("{:.2}", tube_x),
+        tube_y: format!("{:.2}", tube_y),
+        tube_width: format!("{:.2}", tube_width),
+        tube_height: format!("{:.2}", tube_height),
+        fill_y: format!("{:.2}", tube_y + 2.5),
+        fill_width: format!("{:.2}", fill_width),
+        fill_height: format!("{:.2}", tube_height - 5.0),
+        bulb_center_x: format!("{:.2}", bulb_center_x),
+        bulb_center_y: format!("{:.2}", bulb_center_y),
+        bulb_radius: format!("{:.2}", bulb_radius),
+        bulb_fill_radius: format!("{:.2}", bulb_radius - 3.0),
+        percentage_markers,
+        milestone_ticks,
+        segments,
+        strip,
+        percent_x: format!("{:.2}", track_x + track_width / 2.0),
+        percent_y: format!("{:.2}", top + w * 0.255),
+        progress_percent: format!("{:.0}", progress_percent),
+        percent_font_size: format!("{:.2}", w * 0.025),
+        text_x: format!("{:.2}", text_x),
+        achieved_y: format!("{:.2}", achieved_y),
+        achieved_amount: format!("{:.2}", config.total_raised()),
+        achieved_label_y: format!("{:.2}", achieved_y + w * 0.025),
+        animals_helped: crate::impact::animals_helped(config).map(|n| text.animals_helped(n)),
+        animals_y: format!("{:.2}", achieved_y + w * 0.048),
+        goal_y: format!("{:.2}", goal_y),
+        goal_amount: format!("{:.2}", config.goal),
+        goal_label_y: format!("{:.2}", goal_y + w * 0.025),
+        amount_font_size: format!("{:.2}", w * 0.04),
+        label_font_size: format!("{:.2}", w * 0.018),
+        announcement,
+        stale_notice: crate::staleness::notice(config, style.lang),
+        stale_y: format!("{:.2}", base_height - w * 0.01),
+        stale_font_size: format!("{:.2}", w * 0.016),
+        watermark: watermark(config),
+        watermark_x: format!("{:.2}", w * 0.98),
+        watermark_y: format!("{:.2}", height as f64 - w * 0.006),
+        watermark_font_size: format!("{:.2}", w * 0.014),
+        text,
+        // Theme colors
+        background_color: colors.background,
+        title_text_color: colors.title_text,
+        text_primary_color: colors.text_primary,
+        text_secondary_color: colors.text_secondary,
+        tube_fill_color: colors.tube_fill,
+        tube_stroke_color: colors.tube_stroke,
+        fill_color_1: colors.fill_color_1,
+        fill_color_2: colors.fill_color_2,
+        achieved_text_color: colors.achieved_text,
+        marker_stroke_color: colors.marker_stroke,
+        marker_text_color: colors.marker_text,
+    }
+    .render()
+}
+
+/// Generate a square thumbnail: a small thermometer beside a large percentage and the amount raised.
+/// Text is sized relative to the square rather than the full layout so it stays readable in chat
+/// link previews.
+pub fn generate_thumbnail_svg(config: &ThermometerConfig, size: u32) -> Result<String, askama::Error> {
+    let s = size as f64;
+    let progress_percent = config.progress_percent();
+    let colors = config.theme.colors(false);
+
+    let tube_width = s * 0.12;
+    let tube_height = s * 0.58;
+    let tube_x = s * 0.14;
+    let tube_y = s * 0.08;
+    let bulb_radius = s * 0.13;
+    let stroke_width = s * 0.012;
+    let inset = stroke_width * 1.5;
+    let fill_height = (tube_height - inset) * progress_percent / 100.0;
+
+    let text_x = s * 0.66;
+
+    ThermometerThumbTemplate {
+        size,
+        tube_x: format!("{:.2}", tube_x),
+        tube_y: format!("{:.2}", tube_y),
+        tube_width: format!("{:.2}", tube_width),
+        tube_height: format!("{:.2}", tube_height),
+        tube_radius: format!("{:.2}", tube_width / 2.0),
+        stroke_width: format!("{:.2}", stroke_width),
+        fill_x: format!("{:.2}", tube_x + inset),
+        fill_y: format!("{:.2}", tube_y + tube_height - fill_height),
+        fill_width: format!("{:.2}", tube_width - inset * 2.0),
+        // Runs down into the bulb so the fill joins it without a gap
+        fill_height: format!("{:.2}", fill_height + bulb_radius),
+        bulb_center_x: format!("{:.2}", tube_x + tube_width / 2.0),
+        bulb_center_y: format!("{:.2}", tube_y + tube_height + bulb_radius * 0.7),
+        bulb_radius: format!("{:.2}", bulb_radius),
+        bulb_fill_radius: format!("{:.2}", bulb_radius - inset),
+        text_x: format!("{:.2}", text_x),
+        percent_y: format!("{:.2}", s * 0.47),
+        progress_percent: format!("{:.0}", progress_percent),
+        percent_font_size: format!("{:.2}", s * 0.2),
+        label: crate::celebration::active(config).map_or_else(|| "of goal".to_string(), |c| c.thumbnail_text()),
+        label_y: format!("{:.2}", s * 0.59),
+        label_font_size: format!("{:.2}", s * 0.08),
+        amount_y: format!("{:.2}", s * 0.8),
+        achieved_amount: format!("{:.0}", config.total_raised()),
+        amount_font_size: format!("{:.2}", s * 0.095),
+        impact: crate::impact::funded(config).into_iter().next().map(|outcome| format!("Funds {}", outcome)),
+        impact_x: format!("{:.2}", s / 2.0),
+        impact_y: format!("{:.2}", s * 0.95),
+        impact_font_size: format!("{:.2}", s * 0.06),
+        background_color: colors.background,
+        text_primary_color: colors.text_primary,
+        text_secondary_color: colors.text_secondary,
+        tube_fill_color: colors.tube_fill,
+        tube_stroke_color: colors.tube_stroke,
+        fill_color: colors.fill_color_1,
+        achieved_text_color: colors.achieved_text,
     }
+    .render()
+}
+
+/// SVG user units are CSS pixels, at 96 to the inch
+pub const CSS_DPI: f32 = 96.0;
+/// Pixel densities accepted for PNGs, matching the 0.1 to 5.0 scale range
+pub const MIN_DPI: f32 = 10.0;
+pub const MAX_DPI: f32 = 480.0;
+
+/// How an SVG is rasterized to PNG
+#[derive(Debug, Clone, Copy)]
+pub struct PngOptions {
+    /// Output pixels per SVG pixel
+    pub scale: f32,
+    /// Pixel density written into the PNG, so print software sizes it correctly
+    pub dpi: Option<f32>,
+    /// Smooth the edges of shapes; off gives crisp, aliased edges
+    pub antialias: bool,
+    /// Hint text to the pixel grid for legibility at small sizes; off keeps exact glyph shapes
+    pub hinting: bool,
+    /// Painted behind the image, showing through any transparent areas
+    pub matte: Option<tiny_skia::Color>,
+}
+
+impl PngOptions {
+    /// Default rendering at `scale`
+    pub fn at_scale(scale: f32) -> Self {
+        Self {
+            scale,
+            dpi: None,
+            antialias: true,
+            hinting: true,
+            matte: None,
+        }
+    }
+}
+
+/// A matte color given as hex, with or without the `#` (e.g. `fff` or `f5f0e6`)
+pub fn parse_matte(value: &str) -> Option<tiny_skia::Color> {
+    let hex = value.trim().trim_start_matches('#');
+    if !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+        return None;
+    }
+    let channel = |i: usize, len: usize| u8::from_str_radix(&hex[i * len..(i + 1) * len], 16).ok();
+    let (r, g, b) = match hex.len() {
+        3 => (channel(0, 1)? * 17, channel(1, 1)? * 17, channel(2, 1)? * 17),
+        6 => (channel(0, 2)?, channel(1, 2)?, channel(2, 2)?),
+        _ => return None,
+    };
+    Some(tiny_skia::Color::from_rgba8(r, g, b, 255))
 }
 
 /// Convert SVG to PNG with the specified scale
 pub fn svg_to_png(svg_data: &str, scale: f32) -> Result<Vec<u8>, String> {
+    render_png(svg_data, &PngOptions::at_scale(scale))
+}
+
+/// Convert SVG to PNG with the given rendering options
+pub fn render_png(svg_data: &str, options: &PngOptions) -> Result<Vec<u8>, String> {
+    let pixmap = rasterize(&[svg_data], options)?.remove(0);
+
+    // Encode as PNG
+    let png = pixmap.encode_png()
+        .map_err(|e| format!("Failed to encode PNG: {}", e))?;
+    Ok(match options.dpi {
+        Some(dpi) => with_pixel_density(png, dpi),
+        None => png,
+    })
+}
+
+/// Encode SVG frames as a GIF that plays once and stays on the last frame. GIFs have no partial
+/// transparency, so `options.matte` is the way to choose what shows behind transparent areas.
+pub fn render_gif(frames: &[&str], options: &PngOptions) -> Result<Vec<u8>, String> {
+    let pixmaps = rasterize(frames, options)?;
+    let last = pixmaps.last().ok_or_else(|| "No frames to encode".to_string())?;
+    let (Ok(width), Ok(height)) = (u16::try_from(last.width()), u16::try_from(last.height())) else {
+        return Err("Image is too large for a GIF".to_string());
+    };
+
+    // Earlier frames only show less of the fill, so the last frame's palette covers them all and
+    // sharing it keeps colors from shifting between frames
+    let quantizer = color_quant::NeuQuant::new(GIF_QUANTIZER_SPEED, 256, &rgba(last));
+    let mut indices: std::collections::HashMap<[u8; 4], u8> = std::collections::HashMap::new();
+    let mut gif = Vec::new();
+    {
+        let mut encoder = gif::Encoder::new(&mut gif, width, height, &quantizer.color_map_rgb())
+            .map_err(|e| format!("Failed to encode GIF: {}", e))?;
+        for (i, pixmap) in pixmaps.iter().enumerate() {
+            let pixels: Vec<u8> = rgba(pixmap)
+                .chunks_exact(4)
+                .map(|p| *indices.entry([p[0], p[1], p[2], p[3]]).or_insert_with(|| quantizer.index_of(p) as u8))
+                .collect();
+            let mut frame = gif::Frame::from_indexed_pixels(width, height, pixels, None);
+            frame.delay = if i + 1 < pixmaps.len() { FRAME_DELAY } else { 0 };
+            encoder
+                .write_frame(&frame)
+                .map_err(|e| format!("Failed to encode GIF: {}", e))?;
+        }
+    }
+    Ok(gif)
+}
+
+/// Straight (not premultiplied) RGBA bytes of a pixmap
+fn rgba(pixmap: &tiny_skia::Pixmap) -> Vec<u8> {
+    pixmap
+        .pixels()
+        .iter()
+        .flat_map(|p| {
+            let c = p.demultiply();
+            [c.red(), c.green(), c.blue(), c.alpha()]
+        })
+        .collect()
+}
+
+/// Draw each SVG with the given rendering options, loading the fonts once for all of them
+fn rasterize(svgs: &[&str], options: &PngOptions) -> Result<Vec<tiny_skia::Pixmap>, String> {
     use resvg::usvg;
     use tiny_skia::Pixmap;
 
+    let scale = options.scale;
+
     // Create a font database and load system fonts
     let mut fontdb = usvg::fontdb::Database::new();
     fontdb.load_system_fonts();
@@ -290,29 +1200,78 @@ pub fn svg_to_png(svg_data: &str, scale: f32) -> Result<Vec<u8>, String> {
     // Parse the SVG with font database
     let mut opts = usvg::Options::default();
     opts.fontdb = std::sync::Arc::new(fontdb);
+    opts.shape_rendering = if options.antialias {
+        usvg::ShapeRendering::GeometricPrecision
+    } else {
+        usvg::ShapeRendering::CrispEdges
+    };
+    opts.text_rendering = if options.hinting {
+        usvg::TextRendering::OptimizeLegibility
+    } else {
+        usvg::TextRendering::GeometricPrecision
+    };
 
-    let tree = usvg::Tree::from_str(svg_data, &opts)
-        .map_err(|e| format!("Failed to parse SVG: {}", e))?;
+    svgs.iter()
+        .map(|svg_data| {
+            let tree = usvg::Tree::from_str(svg_data, &opts)
+                .map_err(|e| format!("Failed to parse SVG: {}", e))?;
 
-    // Get the SVG size
-    let size = tree.size();
-    let width = (size.width() * scale) as u32;
-    let height = (size.height() * scale) as u32;
+            // Get the SVG size
+            let size = tree.size();
+            let width = (size.width() * scale) as u32;
+            let height = (size.height() * scale) as u32;
 
-    // Create a pixmap
-    let mut pixmap = Pixmap::new(width, height)
-        .ok_or_else(|| "Failed to create pixmap".to_string())?;
+            // Create a pixmap
+            let mut pixmap = Pixmap::new(width, height)
+                .ok_or_else(|| "Failed to create pixmap".to_string())?;
+            if let Some(matte) = options.matte {
+                pixmap.fill(matte);
+            }
 
-    // Render the SVG
-    let transform = if scale != 1.0 {
-        tiny_skia::Transform::from_scale(scale, scale)
-    } else {
-        tiny_skia::Transform::identity()
-    };
+            // Render the SVG
+            let transform = if scale != 1.0 {
+                tiny_skia::Transform::from_scale(scale, scale)
+            } else {
+                tiny_skia::Transform::identity()
+            };
+
+            resvg::render(&tree, transform, &mut pixmap.as_mut());
+            Ok(pixmap)
+        })
+        .collect()
+}
+
+/// Add a `pHYs` chunk recording `dpi` right after the header chunk, where the PNG spec puts it
+fn with_pixel_density(png: Vec<u8>, dpi: f32) -> Vec<u8> {
+    // 8-byte signature, then IHDR: 4-byte length, 4-byte type, 13 bytes of data and a 4-byte CRC
+    const AFTER_HEADER: usize = 8 + 4 + 4 + 13 + 4;
+    if png.len() < AFTER_HEADER || &png[12..16] != b"IHDR" {
+        return png;
+    }
 
-    resvg::render(&tree, transform, &mut pixmap.as_mut());
+    let pixels_per_meter = (dpi / 0.0254).round() as u32;
+    let mut chunk = b"pHYs".to_vec();
+    chunk.extend_from_slice(&pixels_per_meter.to_be_bytes());
+    chunk.extend_from_slice(&pixels_per_meter.to_be_bytes());
+    chunk.push(1); // unit: meters
 
-    // Encode as PNG
-    pixmap.encode_png()
-        .map_err(|e| format!("Failed to encode PNG: {}", e))
+    let mut out = Vec::with_capacity(png.len() + 21);
+    out.extend_from_slice(&png[..AFTER_HEADER]);
+    out.extend_from_slice(&9u32.to_be_bytes());
+    out.extend_from_slice(&chunk);
+    out.extend_from_slice(&crc32(&chunk).to_be_bytes());
+    out.extend_from_slice(&png[AFTER_HEADER..]);
+    out
+}
+
+/// CRC-32 as used by PNG chunks
+fn crc32(bytes: &[u8]) -> u32 {
+    let mut crc = 0xFFFF_FFFFu32;
+    for &byte in bytes {
+        crc ^= byte as u32;
+        for _ in 0..8 {
+            crc = if crc & 1 == 1 { (crc >> 1) ^ 0xEDB8_8320 } else { crc >> 1 };
+        }
+    }
+    !crc
 }
@@ -0,0 +1,225 @@
+use axum::{
+    extract::{Query, State},
+    http::{HeaderMap, StatusCode},
+    response::{
+        sse::{Event, KeepAlive, Sse},
+        IntoResponse, Json, Response,
+    },
+};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::convert::Infallible;
+use std::time::Duration;
+use tokio::sync::{broadcast, mpsc};
+use tokio_stream::wrappers::ReceiverStream;
+use utoipa::{IntoParams, ToSchema};
+use uuid::Uuid;
+
+use crate::ledger::Donation;
+use crate::shutdown;
+use crate::storage::StorageError;
+use crate::{error_response, AppState, ErrorResponse, ThermometerConfig};
+
+/// Items in the rolling feed
+const FEED_SIZE: usize = 20;
+/// Team totals are announced each time they pass a multiple of this
+const TEAM_MILESTONE_STEP: f64 = 1000.0;
+/// Campaign progress is announced at these percentages of the goal
+const CAMPAIGN_MILESTONES: [f64; 4] = [25.0, 50.0, 75.0, 100.0];
+/// How often a stream checks the ledger for new items between momentum events
+const STREAM_REFRESH: Duration = Duration::from_secs(30);
+
+/// One line for the scrolling banner
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct TickerItem {
+    /// Stable across requests, so a display can skip items it has already shown
+    pub id: String,
+    /// e.g. `Hairball Wizards just passed $4,000!`
+    pub message: String,
+    pub created_at: String,
+}
+
+#[derive(Debug, Default, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct TickerQuery {
+    /// Stream items as Server-Sent Events (same as sending `Accept: text/event-stream`)
+    #[serde(default)]
+    stream: bool,
+}
+
+/// `$4,000`; cents are dropped
+fn dollars(amount: f64) -> String {
+    let digits = format!("{:.0}", amount.abs());
+    let mut grouped = String::new();
+    for (i, c) in digits.chars().enumerate() {
+        if i > 0 && (digits.len() - i) % 3 == 0 {
+            grouped.push(',');
+        }
+        grouped.push(c);
+    }
+    format!("{}${}", if amount < 0.0 { "-" } else { "" }, grouped)
+}
+
+/// The latest gifts and milestones from the ledger, newest first
+pub fn feed(config: &ThermometerConfig, donations: &[Donation]) -> Vec<TickerItem> {
+    let mut ledger: Vec<&Donation> = donations.iter().collect();
+    ledger.sort_by(|a, b| a.created_at.cmp(&b.created_at));
+
+    // Totals can include amounts that predate the ledger, so walk up from what the ledger doesn't explain
+    let mut running: HashMap<&str, f64> = config.active_teams().map(|t| (t.name.as_str(), t.total_raised)).collect();
+    for donation in &ledger {
+        if let Some(total) = running.get_mut(donation.team.as_str()) {
+            *total -= donation.amount;
+        }
+    }
+    let mut campaign: f64 = running.values().sum();
+
+    let mut items = Vec::new();
+    for donation in ledger {
+        let Some(total) = running.get_mut(donation.team.as_str()) else {
+            continue;
+        };
+        let before = *total;
+        *total += donation.amount;
+        let after = *total;
+        let campaign_before = campaign;
+        campaign += donation.amount;
+
+        if donation.source.is_gift() && donation.amount > 0.0 {
+            items.push(TickerItem {
+                id: format!("gift-{}", donation.id),
+                message: format!(
+                    "{} gave ${:.2} to {}",
+                    donation.public_name(&config.donors),
+                    donation.amount,
+                    donation.team
+                ),
+                created_at: donation.created_at.clone(),
+            });
+        }
+
+        let step = (after / TEAM_MILESTONE_STEP).floor();
+        if after > before && step >= 1.0 && step > (before / TEAM_MILESTONE_STEP).floor() {
+            items.push(TickerItem {
+                id: format!("team-{}", donation.id),
+                message: format!("{} just passed {}!", donation.team, dollars(step * TEAM_MILESTONE_STEP)),
+                created_at: donation.created_at.clone(),
+            });
+        }
+
+        if config.goal > 0.0 {
+            let reached = CAMPAIGN_MILESTONES
+                .iter()
+                .rev()
+                .find(|&&p| campaign >= config.goal * p / 100.0 && campaign_before < config.goal * p / 100.0);
+            if let Some(percent) = reached {
+                let message = if *percent >= 100.0 {
+                    format!("We reached our {} goal!", dollars(config.goal))
+                } else {
+                    format!("We're {:.0}% of the way to {}!", percent, dollars(config.goal))
+                };
+                items.push(TickerItem {
+                    id: format!("campaign-{}", donation.id),
+                    message,
+                    created_at: donation.created_at.clone(),
+                });
+            }
+        }
+    }
+
+    items.reverse();
+    items.truncate(FEED_SIZE);
+    items
+}
+
+async fn load_feed(state: &AppState) -> Result<Vec<TickerItem>, StorageError> {
+    let config = state.storage.load_config().await?;
+    let donations = state.storage.list_donations().await?;
+    Ok(feed(&config, &donations))
+}
+
+fn event(item: &TickerItem) -> Event {
+    Event::default()
+        .event("ticker")
+        .id(item.id.clone())
+        .json_data(item)
+        .unwrap_or_default()
+}
+
+/// Send the current feed oldest first, then momentum events as they happen and new feed items
+/// as the ledger grows, until the client disconnects or the server starts shutting down
+fn stream(state: AppState, initial: Vec<TickerItem>) -> Sse<ReceiverStream<Result<Event, Infallible>>> {
+    let (tx, rx) = mpsc::channel(FEED_SIZE);
+    let mut momentum = state.momentum.subscribe();
+    tokio::spawn(async move {
+        let mut seen: HashSet<String> = HashSet::new();
+        for item in initial.iter().rev() {
+            seen.insert(item.id.clone());
+            if tx.send(Ok(event(item))).await.is_err() {
+                return;
+            }
+        }
+
+        let mut refresh = tokio::time::interval(STREAM_REFRESH);
+        refresh.tick().await;
+        loop {
+            let items = tokio::select! {
+                received = momentum.recv() => match received {
+                    Ok(momentum_event) => vec![TickerItem {
+                        id: Uuid::new_v4().to_string(),
+                        message: momentum_event.message(),
+                        created_at: chrono::Utc::now().to_rfc3339(),
+                    }],
+                    Err(broadcast::error::RecvError::Lagged(_)) => continue,
+                    Err(broadcast::error::RecvError::Closed) => return,
+                },
+                _ = refresh.tick() => match load_feed(&state).await {
+                    Ok(feed) => feed.into_iter().rev().filter(|item| !seen.contains(&item.id)).collect(),
+                    Err(e) => {
+                        tracing::warn!("Failed to refresh ticker feed: {}", e);
+                        vec![]
+                    }
+                },
+                // Ending the stream lets the client reconnect to the next instance
+                _ = shutdown::draining() => return,
+            };
+            for item in items {
+                seen.insert(item.id.clone());
+                if tx.send(Ok(event(&item))).await.is_err() {
+                    return;
+                }
+            }
+        }
+    });
+    Sse::new(ReceiverStream::new(rx)).keep_alive(KeepAlive::default())
+}
+
+#[utoipa::path(
+    get,
+    path = "/events/ticker",
+    tag = "Public",
+    params(TickerQuery),
+    responses(
+        (status = 200, description = "Latest gifts and milestones as banner lines, newest first. With `stream=true` or `Accept: text/event-stream`, a stream of `ticker` events instead: the current feed, then new items as they happen", body = [TickerItem]),
+        (status = 500, description = "Failed to load the ledger", body = ErrorResponse)
+    )
+)]
+pub async fn get_ticker(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Query(query): Query<TickerQuery>,
+) -> Result<Response, (StatusCode, Json<ErrorResponse>)> {
+    let items = load_feed(&state).await.map_err(|e| {
+        error_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to load ticker feed: {}", e))
+    })?;
+
+    let wants_stream = query.stream
+        || headers
+            .get("accept")
+            .and_then(|h| h.to_str().ok())
+            .is_some_and(|accept| accept.contains("text/event-stream"));
+    if wants_stream {
+        return Ok(stream(state, items).into_response());
+    }
+    Ok(Json(items).into_response())
+}
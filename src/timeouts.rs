@@ -0,0 +1,95 @@
+//! Deadlines for reads, so a hung Firestore call or render gives up with a 504 instead of holding
+//! the Cloud Run connection until the platform kills the request.
+
+use axum::{
+    extract::{Request, State},
+    http::{header, Method, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Json, Response},
+};
+use serde::Serialize;
+use std::time::Duration;
+
+/// Default deadline for reads, in seconds
+const DEFAULT_REQUEST_TIMEOUT_SECS: u64 = 15;
+/// Default deadline for image renders, in seconds
+const DEFAULT_RENDER_TIMEOUT_SECS: u64 = 30;
+
+/// How long a read may take before it's answered with a 504
+#[derive(Debug, Clone, Copy)]
+pub struct RequestTimeouts {
+    /// `REQUEST_TIMEOUT_SECS`: pages and JSON
+    default: Duration,
+    /// `RENDER_TIMEOUT_SECS`: PNG, SVG and GIF images
+    render: Duration,
+}
+
+/// RFC 9457 problem details
+#[derive(Serialize)]
+struct Problem {
+    r#type: &'static str,
+    title: &'static str,
+    status: u16,
+    detail: String,
+}
+
+fn secs_from_env(name: &str, default: u64) -> Duration {
+    let secs = std::env::var(name)
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .filter(|secs| *secs > 0)
+        .unwrap_or(default);
+    Duration::from_secs(secs)
+}
+
+impl RequestTimeouts {
+    pub fn from_env() -> Self {
+        Self {
+            default: secs_from_env("REQUEST_TIMEOUT_SECS", DEFAULT_REQUEST_TIMEOUT_SECS),
+            render: secs_from_env("RENDER_TIMEOUT_SECS", DEFAULT_RENDER_TIMEOUT_SECS),
+        }
+    }
+
+    /// Deadline for a read of `path`, which may still carry a tenant or campaign prefix
+    fn for_path(&self, path: &str) -> Duration {
+        let is_image = path.ends_with(".png")
+            || path.ends_with(".svg")
+            || path.ends_with(".gif")
+            || path.contains("/img/");
+        if is_image {
+            self.render
+        } else {
+            self.default
+        }
+    }
+}
+
+/// Answer reads that take longer than their deadline with a 504. Writes are left to finish:
+/// abandoning one halfway could leave the ledger and the totals disagreeing. Streams only need
+/// their response head in time, so a live feed isn't cut off.
+pub async fn deadline(State(timeouts): State<RequestTimeouts>, request: Request, next: Next) -> Response {
+    if !matches!(*request.method(), Method::GET | Method::HEAD) {
+        return next.run(request).await;
+    }
+
+    let path = request.uri().path().to_string();
+    let timeout = timeouts.for_path(&path);
+    match tokio::time::timeout(timeout, next.run(request)).await {
+        Ok(response) => response,
+        Err(_) => {
+            tracing::error!("{} took longer than {}s, answering 504", path, timeout.as_secs());
+            let problem = Problem {
+                r#type: "about:blank",
+                title: "Gateway Timeout",
+                status: StatusCode::GATEWAY_TIMEOUT.as_u16(),
+                detail: format!("The request took longer than {} seconds", timeout.as_secs()),
+            };
+            (
+                StatusCode::GATEWAY_TIMEOUT,
+                [(header::CONTENT_TYPE, "application/problem+json")],
+                Json(problem),
+            )
+                .into_response()
+        }
+    }
+}
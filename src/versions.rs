@@ -0,0 +1,273 @@
+use axum::{
+    extract::{Path, State},
+    http::{HeaderMap, StatusCode},
+    response::Json,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use utoipa::{IntoParams, ToSchema};
+
+use crate::integrations;
+use crate::{error_response, require_admin, AppState, ErrorResponse, ThermometerConfig};
+
+/// Longest change note kept; anything past this is cut off
+const MAX_NOTE_LENGTH: usize = 500;
+
+/// Optional reason for an admin change, kept with the config version it creates
+#[derive(Debug, Default, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct ChangeNote {
+    /// Why the change was made, e.g. `Board raised the goal after the matching gift`
+    note: Option<String>,
+}
+
+impl ChangeNote {
+    /// The trimmed note, or `None` if it's missing or blank
+    pub fn text(&self) -> Option<&str> {
+        let note = self.note.as_deref()?.trim();
+        if note.is_empty() {
+            return None;
+        }
+        Some(match note.char_indices().nth(MAX_NOTE_LENGTH) {
+            Some((end, _)) => &note[..end],
+            None => note,
+        })
+    }
+}
+
+/// A config as it was saved, kept so changes can be compared later
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct ConfigVersion {
+    /// Increases by one with every save
+    pub version: u64,
+    pub config: ThermometerConfig,
+    pub created_at: String,
+    /// Reason the admin gave for the change
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub note: Option<String>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct VersionSummary {
+    pub version: u64,
+    pub created_at: String,
+    /// Reason the admin gave for the change
+    pub note: Option<String>,
+    pub total_raised: f64,
+    pub goal: f64,
+    pub team_count: usize,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ValueChange<T> {
+    pub before: T,
+    pub after: T,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct TeamTotal {
+    pub name: String,
+    pub total_raised: f64,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct TeamTotalChange {
+    pub name: String,
+    pub before: f64,
+    pub after: f64,
+    pub delta: f64,
+}
+
+/// What changed between two config versions
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ConfigDiff {
+    pub from: u64,
+    pub to: u64,
+    /// Note saved with version `to`
+    pub note: Option<String>,
+    #[schema(value_type = Option<Object>)]
+    pub organization_name: Option<ValueChange<String>>,
+    #[schema(value_type = Option<Object>)]
+    pub title: Option<ValueChange<String>>,
+    #[schema(value_type = Option<Object>)]
+    pub goal: Option<ValueChange<f64>>,
+    #[schema(value_type = Object)]
+    pub total_raised: ValueChange<f64>,
+    pub teams_added: Vec<TeamTotal>,
+    pub teams_removed: Vec<TeamTotal>,
+    pub totals_changed: Vec<TeamTotalChange>,
+    /// Other config sections that differ (e.g. `digest`, `donate`)
+    pub settings_changed: Vec<String>,
+}
+
+fn change<T: PartialEq + Clone>(before: &T, after: &T) -> Option<ValueChange<T>> {
+    (before != after).then(|| ValueChange {
+        before: before.clone(),
+        after: after.clone(),
+    })
+}
+
+/// Fields compared individually above; everything else is reported by section name
+const DIFFED_FIELDS: [&str; 5] = ["organization_name", "title", "goal", "teams", "last_updated"];
+
+pub fn diff(from: &ConfigVersion, to: &ConfigVersion) -> ConfigDiff {
+    // Deleting a team shows up as a removal and restoring it as an addition
+    let before: HashMap<&str, f64> = from.config.active_teams().map(|t| (t.name.as_str(), t.total_raised)).collect();
+    let after: HashMap<&str, f64> = to.config.active_teams().map(|t| (t.name.as_str(), t.total_raised)).collect();
+
+    let teams_added = to
+        .config
+        .active_teams()
+        .filter(|t| !before.contains_key(t.name.as_str()))
+        .map(|t| TeamTotal {
+            name: t.name.clone(),
+            total_raised: t.total_raised,
+        })
+        .collect();
+    let teams_removed = from
+        .config
+        .active_teams()
+        .filter(|t| !after.contains_key(t.name.as_str()))
+        .map(|t| TeamTotal {
+            name: t.name.clone(),
+            total_raised: t.total_raised,
+        })
+        .collect();
+    let totals_changed = to
+        .config
+        .active_teams()
+        .filter_map(|t| {
+            let previous = *before.get(t.name.as_str())?;
+            (previous != t.total_raised).then(|| TeamTotalChange {
+                name: t.name.clone(),
+                before: previous,
+                after: t.total_raised,
+                delta: t.total_raised - previous,
+            })
+        })
+        .collect();
+
+    let sections = |config: &ThermometerConfig| match serde_json::to_value(config) {
+        Ok(serde_json::Value::Object(map)) => map,
+        _ => serde_json::Map::new(),
+    };
+    let (old_sections, new_sections) = (sections(&from.config), sections(&to.config));
+    let mut settings_changed: Vec<String> = old_sections
+        .keys()
+        .chain(new_sections.keys())
+        .filter(|key| !DIFFED_FIELDS.contains(&key.as_str()))
+        .filter(|key| old_sections.get(*key) != new_sections.get(*key))
+        .cloned()
+        .collect();
+    settings_changed.sort();
+    settings_changed.dedup();
+
+    ConfigDiff {
+        from: from.version,
+        to: to.version,
+        note: to.note.clone(),
+        organization_name: change(&from.config.organization_name, &to.config.organization_name),
+        title: change(&from.config.title, &to.config.title),
+        goal: change(&from.config.goal, &to.config.goal),
+        total_raised: ValueChange {
+            before: from.config.total_raised(),
+            after: to.config.total_raised(),
+        },
+        teams_added,
+        teams_removed,
+        totals_changed,
+        settings_changed,
+    }
+}
+
+/// Store `config` as the next version with the admin's note, if any; failures are logged since the
+/// config itself is already saved
+pub async fn record(state: &AppState, config: &ThermometerConfig, note: Option<&str>) {
+    let latest = match state.storage.list_config_versions().await {
+        Ok(versions) => versions.iter().map(|v| v.version).max().unwrap_or(0),
+        Err(e) => {
+            tracing::error!("Failed to record config version: {}", e);
+            return;
+        }
+    };
+
+    let version = ConfigVersion {
+        version: latest + 1,
+        // History is readable by every admin, so it never keeps the secrets themselves
+        config: integrations::masked(config),
+        created_at: chrono::Utc::now().to_rfc3339(),
+        note: note.map(str::to_string),
+    };
+    if let Err(e) = state.storage.save_config_version(&version).await {
+        tracing::error!("Failed to record config version {}: {}", version.version, e);
+    }
+}
+
+fn storage_error(e: impl std::fmt::Display) -> (StatusCode, Json<ErrorResponse>) {
+    error_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to load config versions: {}", e))
+}
+
+#[utoipa::path(
+    get,
+    path = "/admin/versions",
+    tag = "Admin",
+    security(("edit_key" = [])),
+    responses(
+        (status = 200, description = "Saved config versions, newest first, with the note given for each change", body = [VersionSummary]),
+        (status = 401, description = "Unauthorized", body = ErrorResponse)
+    )
+)]
+pub async fn list_versions(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+) -> Result<Json<Vec<VersionSummary>>, (StatusCode, Json<ErrorResponse>)> {
+    require_admin(&headers, &state)?;
+
+    let mut versions = state.storage.list_config_versions().await.map_err(storage_error)?;
+    versions.sort_by_key(|v| std::cmp::Reverse(v.version));
+    Ok(Json(
+        versions
+            .into_iter()
+            .map(|v| VersionSummary {
+                version: v.version,
+                total_raised: v.config.total_raised(),
+                goal: v.config.goal,
+                team_count: v.config.active_teams().count(),
+                created_at: v.created_at,
+                note: v.note,
+            })
+            .collect(),
+    ))
+}
+
+#[utoipa::path(
+    get,
+    path = "/admin/versions/{a}/diff/{b}",
+    tag = "Admin",
+    security(("edit_key" = [])),
+    params(
+        ("a" = u64, Path, description = "Version to compare from"),
+        ("b" = u64, Path, description = "Version to compare to")
+    ),
+    responses(
+        (status = 200, description = "Changes from version `a` to version `b`", body = ConfigDiff),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 404, description = "Version not found", body = ErrorResponse)
+    )
+)]
+pub async fn diff_versions(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path((a, b)): Path<(u64, u64)>,
+) -> Result<Json<ConfigDiff>, (StatusCode, Json<ErrorResponse>)> {
+    require_admin(&headers, &state)?;
+
+    let versions = state.storage.list_config_versions().await.map_err(storage_error)?;
+    let find = |n: u64| {
+        versions
+            .iter()
+            .find(|v| v.version == n)
+            .ok_or_else(|| error_response(StatusCode::NOT_FOUND, format!("Version {} not found", n)))
+    };
+    Ok(Json(diff(find(a)?, find(b)?)))
+}
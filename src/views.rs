@@ -0,0 +1,111 @@
+//! The public face of the config. `/config`, `/preview/config` and `/ws` serialize it only through
+//! `PublicConfig`, which lists what anyone may see: the figures and what the pages, images and
+//! widgets draw. Everything else (captain contacts, recipients, integrations, webhook URLs,
+//! accounting, approvals) is only in the full config from `GET /admin/config`. A new config field
+//! stays private until it's added here.
+
+use serde::Serialize;
+use utoipa::ToSchema;
+
+use crate::announcement::Announcement;
+use crate::celebration::CelebrationSettings;
+use crate::impact::ImpactSettings;
+use crate::matching::MatchingRule;
+use crate::milestones::Milestone;
+use crate::stripe::DonateSettings;
+use crate::summary::LeaderboardSettings;
+use crate::theme::ThemeSettings;
+use crate::thermometer::ImageSettings;
+use crate::{Team, ThermometerConfig};
+
+/// The config as anyone may see it
+#[derive(Debug, Serialize, ToSchema)]
+pub struct PublicConfig {
+    pub organization_name: String,
+    pub title: String,
+    pub goal: f64,
+    pub teams: Vec<PublicTeam>,
+    pub last_updated: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub timezone: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[schema(value_type = Option<String>, format = Date)]
+    pub ends_on: Option<chrono::NaiveDate>,
+    pub donate: DonateSettings,
+    pub leaderboard: LeaderboardSettings,
+    pub image: ImageSettings,
+    pub theme: ThemeSettings,
+    pub impact: ImpactSettings,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub announcement: Option<Announcement>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub matching: Vec<MatchingRule>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub milestones: Vec<Milestone>,
+    pub celebration: CelebrationSettings,
+}
+
+/// A team as anyone may see it, without its captain's contact details
+#[derive(Debug, Serialize, ToSchema)]
+pub struct PublicTeam {
+    pub name: String,
+    pub image_url: Option<String>,
+    pub total_raised: f64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub deleted_at: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sort_order: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_changed: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub goal: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub group: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub members: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub score_multiplier: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub color: Option<String>,
+}
+
+impl From<&Team> for PublicTeam {
+    fn from(team: &Team) -> Self {
+        Self {
+            name: team.name.clone(),
+            image_url: team.image_url.clone(),
+            total_raised: team.total_raised,
+            deleted_at: team.deleted_at.clone(),
+            sort_order: team.sort_order,
+            last_changed: team.last_changed.clone(),
+            goal: team.goal,
+            group: team.group.clone(),
+            members: team.members,
+            score_multiplier: team.score_multiplier,
+            color: team.color.clone(),
+        }
+    }
+}
+
+impl From<&ThermometerConfig> for PublicConfig {
+    fn from(config: &ThermometerConfig) -> Self {
+        Self {
+            organization_name: config.organization_name.clone(),
+            title: config.title.clone(),
+            goal: config.goal,
+            teams: config.teams.iter().map(PublicTeam::from).collect(),
+            last_updated: config.last_updated.clone(),
+            timezone: config.timezone.clone(),
+            ends_on: config.ends_on,
+            donate: config.donate.clone(),
+            leaderboard: config.leaderboard.clone(),
+            image: config.image.clone(),
+            theme: config.theme.clone(),
+            impact: config.impact.clone(),
+            announcement: config.announcement.clone(),
+            matching: config.matching.clone(),
+            milestones: config.milestones.clone(),
+            celebration: config.celebration.clone(),
+        }
+    }
+}
@@ -0,0 +1,629 @@
+use axum::{
+    extract::{Path, Query, State},
+    http::{HeaderMap, StatusCode},
+    response::Json,
+};
+use hmac::{Hmac, Mac};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use sha2::Sha256;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Duration;
+use utoipa::{IntoParams, ToSchema};
+use uuid::Uuid;
+
+use crate::integrations;
+use crate::ledger::Donation;
+use crate::momentum::MomentumEvent;
+use crate::live_updates;
+use crate::scheduler::Job;
+use crate::storage::StorageError;
+use crate::views::PublicConfig;
+use crate::{error_response, require_admin, AppState, ErrorResponse, ThermometerConfig};
+
+/// Attempts before a delivery is moved to the dead-letter list
+const MAX_ATTEMPTS: u32 = 8;
+/// First retry delay; doubles after every failed attempt
+const BASE_BACKOFF_SECS: i64 = 30;
+const MAX_BACKOFF_SECS: i64 = 60 * 60;
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+/// Deliveries returned by the log endpoint
+const DELIVERY_LOG_LIMIT: usize = 100;
+/// Event name sent by `POST /admin/webhooks/{id}/test`
+const TEST_EVENT: &str = "webhook.test";
+
+/// Outgoing webhook settings. Each endpoint's URL is in `integrations.webhook_urls`. Payloads are
+/// signed with `integrations.webhook_signing_secret` (or `WEBHOOK_SIGNING_SECRET`) when it is set,
+/// and the URLs in `WEBHOOK_URLS` receive every event on top of these endpoints.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, ToSchema)]
+#[serde(default)]
+pub struct WebhookSettings {
+    pub endpoints: Vec<WebhookEndpoint>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct WebhookEndpoint {
+    /// Stable identifier shown in the delivery log, and the key of its URL in
+    /// `integrations.webhook_urls`
+    pub id: String,
+    /// Events to send; empty means all events
+    #[serde(default)]
+    pub events: Vec<WebhookEvent>,
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub enum WebhookEvent {
+    /// Any team total or the goal changed
+    #[serde(rename = "totals.updated")]
+    TotalsUpdated,
+    /// A donation was added to the ledger
+    #[serde(rename = "donation.recorded")]
+    DonationRecorded,
+    /// A team overtook another, made the biggest gift of the day, or extended a growth streak
+    #[serde(rename = "team.momentum")]
+    TeamMomentum,
+    /// The config was saved, whatever changed
+    #[serde(rename = "config.saved")]
+    ConfigSaved,
+}
+
+impl WebhookEvent {
+    fn as_str(&self) -> &'static str {
+        match self {
+            WebhookEvent::TotalsUpdated => "totals.updated",
+            WebhookEvent::DonationRecorded => "donation.recorded",
+            WebhookEvent::TeamMomentum => "team.momentum",
+            WebhookEvent::ConfigSaved => "config.saved",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum DeliveryStatus {
+    /// Waiting for its first attempt or a retry
+    Pending,
+    Delivered,
+    /// Gave up after `MAX_ATTEMPTS`; listed in the dead-letter queue until redelivered
+    Dead,
+}
+
+/// One event sent to one endpoint, with its retry state
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct WebhookDelivery {
+    pub id: String,
+    pub endpoint_id: String,
+    /// Masked to its last 4 characters in admin responses
+    pub url: String,
+    pub event: WebhookEvent,
+    /// JSON body sent to the endpoint
+    pub payload: String,
+    pub status: DeliveryStatus,
+    pub attempts: u32,
+    pub last_status_code: Option<u16>,
+    pub last_error: Option<String>,
+    /// When the next attempt is due (also used as a short lease while an attempt is running)
+    pub next_attempt_at: Option<String>,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct DeliveryQuery {
+    /// Only deliveries in this state
+    status: Option<DeliveryStatus>,
+}
+
+fn backoff(attempts: u32) -> chrono::Duration {
+    let secs = BASE_BACKOFF_SECS
+        .saturating_mul(1i64 << attempts.saturating_sub(1).min(20))
+        .min(MAX_BACKOFF_SECS);
+    chrono::Duration::seconds(secs)
+}
+
+fn is_due(delivery: &WebhookDelivery, now: chrono::DateTime<chrono::Utc>) -> bool {
+    delivery.status == DeliveryStatus::Pending
+        && delivery
+            .next_attempt_at
+            .as_deref()
+            .and_then(|t| chrono::DateTime::parse_from_rfc3339(t).ok())
+            .map(|t| t <= now)
+            .unwrap_or(true)
+}
+
+/// `t=<unix time>,v1=<hex HMAC-SHA256 of "t.payload">`, the same scheme Stripe uses
+fn sign(payload: &str, secret: &str) -> Option<String> {
+    let timestamp = chrono::Utc::now().timestamp();
+    let mut mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes()).ok()?;
+    mac.update(format!("{}.{}", timestamp, payload).as_bytes());
+    Some(format!("t={},v1={}", timestamp, hex::encode(mac.finalize().into_bytes())))
+}
+
+/// A POST of `payload` with the event headers, signed when there's a `secret`
+fn signed_request(
+    state: &AppState,
+    secret: Option<&str>,
+    url: &str,
+    event: &str,
+    id: &str,
+    payload: &str,
+) -> reqwest::RequestBuilder {
+    let mut request = state
+        .http_client
+        .post(url)
+        .timeout(REQUEST_TIMEOUT)
+        .header("Content-Type", "application/json")
+        .header("X-Thermometer-Event", event)
+        .header("X-Thermometer-Delivery", id)
+        .body(payload.to_string());
+    if let Some(signature) = secret.and_then(|secret| sign(payload, secret)) {
+        request = request.header("X-Thermometer-Signature", signature);
+    }
+    request
+}
+
+/// Make one attempt and record the outcome, scheduling a retry or dead-lettering on failure
+async fn attempt(state: &AppState, secret: Option<&str>, mut delivery: WebhookDelivery) -> WebhookDelivery {
+    let now = chrono::Utc::now();
+    // Lease the delivery so the sweeper doesn't pick it up while this attempt is in flight
+    delivery.next_attempt_at = Some((now + chrono::Duration::seconds(BASE_BACKOFF_SECS)).to_rfc3339());
+    if let Err(e) = state.storage.save_webhook_delivery(&delivery).await {
+        tracing::error!("Failed to save webhook delivery {}: {}", delivery.id, e);
+    }
+
+    let request = signed_request(state, secret, &delivery.url, delivery.event.as_str(), &delivery.id, &delivery.payload);
+
+    delivery.attempts += 1;
+    let result = match request.send().await {
+        Ok(response) if response.status().is_success() => {
+            delivery.last_status_code = Some(response.status().as_u16());
+            Ok(())
+        }
+        Ok(response) => {
+            delivery.last_status_code = Some(response.status().as_u16());
+            Err(format!("Endpoint returned {}", response.status()))
+        }
+        Err(e) => {
+            delivery.last_status_code = None;
+            // Without the URL, which can carry a token and ends up in the delivery log
+            Err(format!("Request failed: {}", e.without_url()))
+        }
+    };
+
+    let now = chrono::Utc::now();
+    match result {
+        Ok(()) => {
+            delivery.status = DeliveryStatus::Delivered;
+            delivery.last_error = None;
+            delivery.next_attempt_at = None;
+            tracing::info!("Delivered {} webhook {} to {}", delivery.event.as_str(), delivery.id, delivery.endpoint_id);
+        }
+        Err(e) if delivery.attempts >= MAX_ATTEMPTS => {
+            delivery.status = DeliveryStatus::Dead;
+            delivery.next_attempt_at = None;
+            tracing::error!(
+                "Webhook {} to {} failed after {} attempts, moved to dead letters: {}",
+                delivery.id,
+                delivery.endpoint_id,
+                delivery.attempts,
+                e
+            );
+            delivery.last_error = Some(e);
+        }
+        Err(e) => {
+            delivery.next_attempt_at = Some((now + backoff(delivery.attempts)).to_rfc3339());
+            tracing::warn!("Webhook {} to {} failed (attempt {}): {}", delivery.id, delivery.endpoint_id, delivery.attempts, e);
+            delivery.last_error = Some(e);
+        }
+    }
+    delivery.updated_at = now.to_rfc3339();
+
+    if let Err(e) = state.storage.save_webhook_delivery(&delivery).await {
+        tracing::error!("Failed to save webhook delivery {}: {}", delivery.id, e);
+    }
+    delivery
+}
+
+/// First attempts still running in the background, across the deployment and all tenants
+static IN_FLIGHT: AtomicUsize = AtomicUsize::new(0);
+
+/// Counts a dispatch as in flight until dropped
+struct InFlight;
+
+impl InFlight {
+    fn start() -> Self {
+        IN_FLIGHT.fetch_add(1, Ordering::SeqCst);
+        InFlight
+    }
+}
+
+impl Drop for InFlight {
+    fn drop(&mut self) {
+        IN_FLIGHT.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+/// Endpoints from the comma-separated `WEBHOOK_URLS`, subscribed to every event, with IDs `env-1`,
+/// `env-2`, ... in the order given
+fn env_endpoints() -> Vec<(WebhookEndpoint, String)> {
+    std::env::var("WEBHOOK_URLS")
+        .unwrap_or_default()
+        .split(',')
+        .map(str::trim)
+        .filter(|url| !url.is_empty())
+        .enumerate()
+        .map(|(i, url)| {
+            let endpoint = WebhookEndpoint {
+                id: format!("env-{}", i + 1),
+                events: Vec::new(),
+                enabled: true,
+            };
+            (endpoint, url.to_string())
+        })
+        .collect()
+}
+
+/// The endpoints of the `webhooks` config with their URLs, followed by those from `WEBHOOK_URLS`
+/// outside tenants. Endpoints without a URL in `integrations.webhook_urls` are left out.
+fn endpoints(state: &AppState, config: &ThermometerConfig) -> Vec<(WebhookEndpoint, String)> {
+    let mut endpoints: Vec<(WebhookEndpoint, String)> = config
+        .webhooks
+        .endpoints
+        .iter()
+        .filter_map(|endpoint| match config.integrations.webhook_urls.get(&endpoint.id) {
+            Some(url) => Some((endpoint.clone(), url.clone())),
+            None => {
+                tracing::warn!("Webhook endpoint {} has no URL in integrations.webhook_urls", endpoint.id);
+                None
+            }
+        })
+        .collect();
+    if state.env_integrations {
+        endpoints.extend(env_endpoints());
+    }
+    endpoints
+}
+
+/// `delivery` with its URL masked, for admin responses
+fn masked(mut delivery: WebhookDelivery) -> WebhookDelivery {
+    delivery.url = integrations::mask(&delivery.url);
+    delivery
+}
+
+/// Queue `event` for every enabled endpoint subscribed to it and make the first attempt right away
+fn dispatch(state: &AppState, config: &ThermometerConfig, event: WebhookEvent, data: serde_json::Value) {
+    let endpoints: Vec<(WebhookEndpoint, String)> = endpoints(state, config)
+        .into_iter()
+        .filter(|(e, _)| e.enabled && (e.events.is_empty() || e.events.contains(&event)))
+        .collect();
+    if endpoints.is_empty() {
+        return;
+    }
+
+    let state = state.clone();
+    let secret = config.integrations.webhook_signing_secret(&state);
+    let in_flight = InFlight::start();
+    tokio::spawn(async move {
+        let _in_flight = in_flight;
+        for (endpoint, url) in endpoints {
+            let id = Uuid::new_v4().to_string();
+            let now = chrono::Utc::now().to_rfc3339();
+            let payload = json!({
+                "id": id,
+                "event": event,
+                "created_at": now,
+                "data": data,
+            });
+
+            let delivery = WebhookDelivery {
+                id,
+                endpoint_id: endpoint.id,
+                url,
+                event,
+                payload: payload.to_string(),
+                status: DeliveryStatus::Pending,
+                attempts: 0,
+                last_status_code: None,
+                last_error: None,
+                next_attempt_at: None,
+                created_at: now.clone(),
+                updated_at: now,
+            };
+            attempt(&state, secret.as_deref(), delivery).await;
+        }
+    });
+}
+
+/// Send `config.saved` after every save, then `totals.updated` when any team total or the goal changed
+pub fn on_config_change(state: &AppState, previous: &ThermometerConfig, config: &ThermometerConfig) {
+    dispatch(
+        state,
+        config,
+        WebhookEvent::ConfigSaved,
+        json!({
+            "changes": live_updates::changes(previous, config),
+            "config": PublicConfig::from(config),
+        }),
+    );
+
+    let totals = |c: &ThermometerConfig| {
+        c.active_teams()
+            .map(|t| (t.name.clone(), t.total_raised))
+            .collect::<Vec<_>>()
+    };
+    if totals(previous) == totals(config) && previous.goal == config.goal {
+        return;
+    }
+
+    dispatch(
+        state,
+        config,
+        WebhookEvent::TotalsUpdated,
+        json!({
+            "total_raised": config.total_raised(),
+            "goal": config.goal,
+            "progress_percent": config.progress_percent(),
+            "teams": config.active_teams().map(|t| json!({
+                "name": t.name,
+                "total_raised": t.total_raised,
+            })).collect::<Vec<_>>(),
+        }),
+    );
+}
+
+/// Send `donation.recorded` with only the publicly displayable donor details
+pub fn on_donation(state: &AppState, config: &ThermometerConfig, donation: &Donation) {
+    dispatch(
+        state,
+        config,
+        WebhookEvent::DonationRecorded,
+        json!({
+            "id": donation.id,
+            "team": donation.team,
+            "amount": donation.amount,
+            "source": donation.source,
+            "donor": donation.public_name(&config.donors),
+            "created_at": donation.created_at,
+        }),
+    );
+}
+
+/// Send `team.momentum` with the typed event and its one-line description
+pub fn on_momentum(state: &AppState, config: &ThermometerConfig, event: &MomentumEvent) {
+    let mut data = json!(event);
+    data["message"] = json!(event.message());
+    dispatch(state, config, WebhookEvent::TeamMomentum, data);
+}
+
+/// Scheduled job that retries failed deliveries once their backoff has elapsed
+pub fn retry_job() -> Job {
+    Job::new("webhook-retry", "* * * * *", |state| async move {
+        let count = retry_due(&state).await?;
+        Ok(format!("Retried {} deliveries", count))
+    })
+}
+
+/// Make another attempt at every pending delivery whose backoff has elapsed
+async fn retry_due(state: &AppState) -> Result<usize, String> {
+    let deliveries = state
+        .storage
+        .list_webhook_deliveries()
+        .await
+        .map_err(|e| format!("Failed to load webhook deliveries for retry: {}", e))?;
+
+    let now = chrono::Utc::now();
+    let due: Vec<WebhookDelivery> = deliveries.into_iter().filter(|d| is_due(d, now)).collect();
+    let count = due.len();
+    if count == 0 {
+        return Ok(0);
+    }
+    let config = state
+        .storage
+        .load_config()
+        .await
+        .map_err(|e| format!("Failed to load config for webhook retries: {}", e))?;
+    let secret = config.integrations.webhook_signing_secret(state);
+    for delivery in due {
+        attempt(state, secret.as_deref(), delivery).await;
+    }
+    Ok(count)
+}
+
+/// Let first attempts still in flight finish, then retry whatever is due, so fewer deliveries
+/// wait for the next instance's retry sweep. Called during shutdown.
+pub async fn flush(state: &AppState) {
+    while IN_FLIGHT.load(Ordering::SeqCst) > 0 {
+        tokio::time::sleep(Duration::from_millis(50)).await;
+    }
+    match retry_due(state).await {
+        Ok(0) => {}
+        Ok(count) => tracing::info!("Flushed {} pending webhook deliveries", count),
+        Err(e) => tracing::error!("{}", e),
+    }
+}
+
+fn storage_error(e: StorageError) -> (StatusCode, Json<ErrorResponse>) {
+    error_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to load webhook deliveries: {}", e))
+}
+
+#[utoipa::path(
+    get,
+    path = "/admin/webhooks/deliveries",
+    tag = "Admin",
+    security(("edit_key" = [])),
+    params(DeliveryQuery),
+    responses(
+        (status = 200, description = "Most recent webhook deliveries, newest first", body = [WebhookDelivery]),
+        (status = 401, description = "Unauthorized", body = ErrorResponse)
+    )
+)]
+pub async fn list_deliveries(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Query(query): Query<DeliveryQuery>,
+) -> Result<Json<Vec<WebhookDelivery>>, (StatusCode, Json<ErrorResponse>)> {
+    require_admin(&headers, &state)?;
+
+    let mut deliveries = state.storage.list_webhook_deliveries().await.map_err(storage_error)?;
+    deliveries.retain(|d| query.status.is_none_or(|status| d.status == status));
+    deliveries.sort_by(|a, b| b.created_at.cmp(&a.created_at));
+    deliveries.truncate(DELIVERY_LOG_LIMIT);
+    Ok(Json(deliveries.into_iter().map(masked).collect()))
+}
+
+#[utoipa::path(
+    get,
+    path = "/admin/webhooks/dead-letter",
+    tag = "Admin",
+    security(("edit_key" = [])),
+    responses(
+        (status = 200, description = "Deliveries that exhausted their retries", body = [WebhookDelivery]),
+        (status = 401, description = "Unauthorized", body = ErrorResponse)
+    )
+)]
+pub async fn dead_letters(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+) -> Result<Json<Vec<WebhookDelivery>>, (StatusCode, Json<ErrorResponse>)> {
+    require_admin(&headers, &state)?;
+
+    let mut deliveries = state.storage.list_webhook_deliveries().await.map_err(storage_error)?;
+    deliveries.retain(|d| d.status == DeliveryStatus::Dead);
+    deliveries.sort_by(|a, b| b.created_at.cmp(&a.created_at));
+    Ok(Json(deliveries.into_iter().map(masked).collect()))
+}
+
+#[utoipa::path(
+    post,
+    path = "/admin/webhooks/{id}/redeliver",
+    tag = "Admin",
+    security(("edit_key" = [])),
+    params(("id" = String, Path, description = "Delivery ID")),
+    responses(
+        (status = 200, description = "Result of the redelivery attempt", body = WebhookDelivery),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 404, description = "Delivery not found", body = ErrorResponse),
+        (status = 500, description = "Failed to load config", body = ErrorResponse)
+    )
+)]
+pub async fn redeliver(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path(id): Path<String>,
+) -> Result<Json<WebhookDelivery>, (StatusCode, Json<ErrorResponse>)> {
+    require_admin(&headers, &state)?;
+
+    let mut delivery = state
+        .storage
+        .list_webhook_deliveries()
+        .await
+        .map_err(storage_error)?
+        .into_iter()
+        .find(|d| d.id == id)
+        .ok_or_else(|| error_response(StatusCode::NOT_FOUND, "Delivery not found"))?;
+
+    let config = state.storage.load_config().await.map_err(|e| {
+        error_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to load config: {}", e))
+    })?;
+
+    // A manual redelivery starts a fresh retry cycle, sending the original payload again
+    delivery.status = DeliveryStatus::Pending;
+    delivery.attempts = 0;
+    tracing::info!("Redelivering webhook {} to {}", delivery.id, delivery.endpoint_id);
+    let secret = config.integrations.webhook_signing_secret(&state);
+    Ok(Json(masked(attempt(&state, secret.as_deref(), delivery).await)))
+}
+
+/// What an endpoint did with a test payload
+#[derive(Debug, Serialize, ToSchema)]
+pub struct WebhookTestResult {
+    pub endpoint_id: String,
+    /// Masked to its last 4 characters
+    pub url: String,
+    /// The endpoint answered with a 2xx status
+    pub ok: bool,
+    /// The body isn't passed on: a tenant admin could otherwise point an endpoint at a service
+    /// only this server can reach and read what it answers
+    pub status_code: Option<u16>,
+    /// Set when the request didn't get a response (DNS, TLS, timeout)
+    pub error: Option<String>,
+    pub duration_ms: u64,
+}
+
+#[utoipa::path(
+    post,
+    path = "/admin/webhooks/{id}/test",
+    tag = "Admin",
+    security(("edit_key" = [])),
+    params(("id" = String, Path, description = "Endpoint ID from the `webhooks` config, or `env-1`, `env-2`, ... for `WEBHOOK_URLS`")),
+    responses(
+        (status = 200, description = "How the endpoint answered a sample `webhook.test` payload. Not logged or retried; disabled endpoints can be tested too", body = WebhookTestResult),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 404, description = "Endpoint not found, or it has no URL in `integrations.webhook_urls`", body = ErrorResponse),
+        (status = 500, description = "Failed to load config", body = ErrorResponse)
+    )
+)]
+pub async fn test_endpoint(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path(id): Path<String>,
+) -> Result<Json<WebhookTestResult>, (StatusCode, Json<ErrorResponse>)> {
+    require_admin(&headers, &state)?;
+
+    let config = state.storage.load_config().await.map_err(|e| {
+        error_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to load config: {}", e))
+    })?;
+    let (endpoint, url) = endpoints(&state, &config)
+        .into_iter()
+        .find(|(e, _)| e.id == id)
+        .ok_or_else(|| error_response(StatusCode::NOT_FOUND, format!("Webhook endpoint not found: {}", id)))?;
+
+    let delivery_id = Uuid::new_v4().to_string();
+    let payload = json!({
+        "id": delivery_id,
+        "event": TEST_EVENT,
+        "created_at": chrono::Utc::now().to_rfc3339(),
+        "data": {
+            "message": "This is a test from the donation thermometer.",
+            "total_raised": config.total_raised(),
+            "goal": config.goal,
+            "progress_percent": config.progress_percent(),
+        },
+    })
+    .to_string();
+
+    let started = std::time::Instant::now();
+    let secret = config.integrations.webhook_signing_secret(&state);
+    let response = signed_request(&state, secret.as_deref(), &url, TEST_EVENT, &delivery_id, &payload)
+        .send()
+        .await;
+    let mut result = WebhookTestResult {
+        endpoint_id: endpoint.id.clone(),
+        url: integrations::mask(&url),
+        ok: false,
+        status_code: None,
+        error: None,
+        duration_ms: 0,
+    };
+    match response {
+        Ok(response) => {
+            result.ok = response.status().is_success();
+            result.status_code = Some(response.status().as_u16());
+        }
+        Err(e) => result.error = Some(format!("Request failed: {}", e.without_url())),
+    }
+    result.duration_ms = started.elapsed().as_millis() as u64;
+
+    tracing::info!(
+        "Tested webhook endpoint {}: {:?}",
+        endpoint.id,
+        result.status_code.map_or_else(|| result.error.clone().unwrap_or_default(), |c| c.to_string())
+    );
+    Ok(Json(result))
+}
@@ -26,7 +26,7 @@ echo ""
 echo "4. Uploading sample CSV..."
 curl -s -X POST "$BASE_URL/admin/upload" \
   -H "Authorization: Bearer $EDIT_KEY" \
-  -F "file=@sample-teams.csv" | jq .
+  -F "file=@sample-teams.csv;type=text/csv" | jq .
 echo ""
 
 echo "5. Getting updated config..."
@@ -42,7 +42,7 @@ echo ""
 echo "7. Testing auth failure (wrong key)..."
 curl -s -X POST "$BASE_URL/admin/upload" \
   -H "Authorization: Bearer wrong-key" \
-  -F "file=@sample-teams.csv" || echo "Expected failure"
+  -F "file=@sample-teams.csv;type=text/csv" || echo "Expected failure"
 echo ""
 
 echo "8. Updating config via JSON..."